@@ -0,0 +1,49 @@
+/**
+ * FastDog → three.js 接入辅助
+ *
+ * WASM 模块的 decode_fastdog_three 输出 GLTFLoader.parse 可直接使用的 glTF JSON 字符串和资源表，
+ * 这里把资源名映射为 Blob URL 后交给 GLTFLoader 解析，现有 three.js 应用只需把
+ *   loader.load(url, onLoad)
+ * 换成
+ *   const gltf = await parseFastDogGLTF(wasmModule, loader, fastdogBytes);
+ */
+async function parseFastDogGLTF(wasmModule, loader, data, path = '') {
+    const bytes = data instanceof Uint8Array ? data : new Uint8Array(data);
+    const result = wasmModule.decode_fastdog_three(bytes);
+    if (!result.success) {
+        const error = new Error(`FastDog解码失败: ${result.error}`);
+        error.code = result.error_code;
+        throw error;
+    }
+
+    // GLTFLoader 按 path + uri 请求资源，通过 LoadingManager 的 URL 映射改为对应的 Blob URL
+    const resourceUrls = Object.entries(result.resources).map(([name, buffer]) => [
+        name,
+        URL.createObjectURL(new Blob([buffer]))
+    ]);
+    const manager = loader.manager;
+    const previousModifier = manager.urlModifier;
+    manager.setURLModifier((url) => {
+        const resource = resourceUrls.find(([name]) => url.endsWith(name));
+        if (resource) {
+            return resource[1];
+        }
+        return previousModifier ? previousModifier(url) : url;
+    });
+
+    try {
+        const gltf = await loader.parseAsync(result.json, path);
+        gltf.userData.fastdogStats = result.stats;
+        return gltf;
+    } finally {
+        manager.setURLModifier(previousModifier);
+        resourceUrls.forEach(([, url]) => URL.revokeObjectURL(url));
+    }
+}
+
+// 导出辅助函数
+if (typeof module !== 'undefined' && module.exports) {
+    module.exports = { parseFastDogGLTF };
+} else if (typeof window !== 'undefined') {
+    window.parseFastDogGLTF = parseFastDogGLTF;
+}
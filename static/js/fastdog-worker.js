@@ -0,0 +1,21 @@
+/**
+ * FastDog 解码 Worker
+ * 由 WASM 模块中的 DecoderPool 启动 (module 类型 Worker)
+ *
+ * 请求: { id, data: ArrayBuffer }
+ * 响应: { id, result } 或 { id, error }
+ */
+import init, { decode_fastdog_binary } from '/static/wasm/fastdog_decoder.js';
+
+const ready = init('/static/wasm/fastdog_decoder_bg.wasm');
+
+self.onmessage = async (event) => {
+    const { id, data } = event.data;
+    try {
+        await ready;
+        const result = decode_fastdog_binary(new Uint8Array(data));
+        self.postMessage({ id, result });
+    } catch (error) {
+        self.postMessage({ id, error: String(error) });
+    }
+};
@@ -311,12 +311,21 @@ class FastDogDecoder {
                 console.error('解码失败:', error);
             }
             
+            // WASM 实例发生 panic 后状态不再可靠
+            const poisoned = !this.usingJSFallback && typeof this.wasmModule?.is_poisoned === 'function' && this.wasmModule.is_poisoned();
+            
             // 如果 WASM 解码失败，尝试降级到 JS
             if (!this.usingJSFallback && this.jsDecoder) {
                 if (this.config.enableLogging) {
                     console.warn('🔄 WASM 解码失败，尝试使用 JavaScript 备选方案');
                 }
                 
+                // 之后的解码都使用 JavaScript 备选方案
+                if (poisoned) {
+                    console.error('❌ WASM 实例已损坏:', this.wasmModule.last_panic_message?.());
+                    this.usingJSFallback = true;
+                }
+                
                 try {
                     const result = await this._decodeWithJS(data, zeroCopy, options);
                     this.performanceStats.jsDecodes++;
@@ -392,40 +401,41 @@ class FastDogDecoder {
             // JavaScript备选方案，实际上还是会拷贝
             return await this.decodeBinary(data);
         } else {
-            if (!this.wasmModule || !this.wasmModule.decode_fastdog_binary_zero_copy) {
-                throw new Error('WASM解码器未初始化或decode_fastdog_binary_zero_copy方法不可用');
+            if (!this.wasmModule || !this.wasmModule.decode_fastdog_buffer) {
+                throw new Error('WASM解码器未初始化或decode_fastdog_buffer方法不可用');
             }
             
             // 确保数据是Uint8Array格式
             const uint8Data = data instanceof ArrayBuffer ? new Uint8Array(data) : data;
             
-            // 调用WASM零拷贝解码函数
+            // 调用WASM零拷贝解码函数，失败时抛出异常
             const startTime = performance.now();
-            const result = this.wasmModule.decode_fastdog_binary_zero_copy(uint8Data);
-            const endTime = performance.now();
-            
-            if (!result.success) {
-                throw new Error(`WASM零拷贝解码失败: ${result.error || '未知错误'}`);
+            let buffer;
+            try {
+                buffer = this.wasmModule.decode_fastdog_buffer(uint8Data);
+            } catch (error) {
+                throw new Error(`WASM零拷贝解码失败: ${error || '未知错误'}`);
             }
+            const endTime = performance.now();
             
             // 创建内存视图，直接访问WASM内存
-            const dataView = new Uint8Array(
-                this.wasmModule.memory.buffer,
-                result.data_ptr,
-                result.data_len
-            );
+            const dataView = buffer.view();
+            const stats = buffer.stats;
             
-            console.log('⚡ WASM零拷贝解码完成，数据长度:', result.data_len);
+            console.log('⚡ WASM零拷贝解码完成，数据长度:', buffer.len());
             
             return {
                 dataView: dataView,
+                handle: buffer.handle,
+                // 使用完 dataView 后调用以释放 WASM 中的缓冲区
+                release: () => buffer.free(),
                 stats: {
-                    originalSize: result.stats.original_size,
-                    compressedSize: result.stats.compressed_size,
-                    compressionRatio: result.stats.compression_ratio,
-                    decodeTimeMs: result.stats.decode_time_ms,
-                    formatVersion: result.stats.format_version,
-                    wasmDecodeTime: result.stats.decode_time_ms,
+                    originalSize: stats.original_size,
+                    compressedSize: stats.compressed_size,
+                    compressionRatio: stats.compression_ratio,
+                    decodeTimeMs: stats.decode_time_ms,
+                    formatVersion: stats.format_version,
+                    wasmDecodeTime: stats.decode_time_ms,
                     jsWrapperTime: endTime - startTime
                 }
             };
@@ -0,0 +1,370 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use fastdog_core::DecodeError;
+
+// fastdog 命令行工具
+//
+// 构建管线和美术人员在桌面上打包、解包和检查 .fastdog 文件，不需要浏览器。
+// 所有格式处理都调用 fastdog-core，与 WASM 模块的实现完全一致
+
+const USAGE: &str = "用法:
+  fastdog pack <输入文件> [输出文件 | -o 输出] [--kind 类型] [--profile 档案] [--codec 编码] [--meta 键=值]...
+  fastdog unpack <输入文件> [输出文件 | -o 输出]
+  fastdog inspect <输入文件> [--dump] [--max-bytes N]
+  fastdog verify <输入文件>...
+  fastdog transcode <输入文件> [输出文件 | -o 输出] --version N [--codec 编码] [--profile 档案]
+
+类型: json / glb / cbor / image / pointcloud / msgpack / protobuf / custom (省略时按扩展名推断)
+编码: zlib / brotli / lz4 / store / zstd
+档案: fast / balanced / max / reproducible (省略时为 balanced)";
+
+enum CliError {
+    // 参数错误，打印用法
+    Usage(String),
+    Io(String),
+    Decode(DecodeError),
+}
+
+impl From<DecodeError> for CliError {
+    fn from(error: DecodeError) -> CliError {
+        CliError::Decode(error)
+    }
+}
+
+// 解析后的命令行参数: 位置参数和 --选项 值
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+// 不带值的开关选项
+const SWITCHES: &[&str] = &["--dump"];
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Args, CliError> {
+        let mut parsed = Args { positional: Vec::new(), options: Vec::new() };
+        while let Some(arg) = args.next() {
+            if arg == "-o" || arg.starts_with("--") {
+                let name = if arg == "-o" { "--output".to_string() } else { arg };
+                let value = if SWITCHES.contains(&name.as_str()) {
+                    None
+                } else {
+                    Some(args.next().ok_or_else(|| CliError::Usage(format!("{} 缺少参数值", name)))?)
+                };
+                parsed.options.push((name, value));
+            } else {
+                parsed.positional.push(arg);
+            }
+        }
+        Ok(parsed)
+    }
+
+    // 检查只使用了命令支持的选项
+    fn expect(&self, allowed: &[&str]) -> Result<(), CliError> {
+        match self.options.iter().find(|(name, _)| !allowed.contains(&name.as_str())) {
+            Some((name, _)) => Err(CliError::Usage(format!("不支持的选项: {}", name))),
+            None => Ok(()),
+        }
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.iter().rev().find(|(option, _)| option == name).and_then(|(_, value)| value.as_deref())
+    }
+
+    fn all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.options.iter().filter(move |(option, _)| option == name).filter_map(|(_, value)| value.as_deref())
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.options.iter().any(|(option, _)| option == name)
+    }
+
+    // 恰好一个输入文件
+    fn input(&self) -> Result<&Path, CliError> {
+        match self.positional.as_slice() {
+            [input] => Ok(Path::new(input)),
+            [] => Err(CliError::Usage("缺少输入文件".to_string())),
+            _ => Err(CliError::Usage("只能指定一个输入文件".to_string())),
+        }
+    }
+
+    // 一个输入文件和可选的输出文件，输出文件可以作为第二个位置参数或用 -o 指定
+    fn input_output(&self) -> Result<(&Path, Option<PathBuf>), CliError> {
+        let (input, positional_output) = match self.positional.as_slice() {
+            [input] => (input, None),
+            [input, output] => (input, Some(output)),
+            [] => return Err(CliError::Usage("缺少输入文件".to_string())),
+            _ => return Err(CliError::Usage("只能指定一个输入文件和一个输出文件".to_string())),
+        };
+        let output = match (positional_output, self.option("--output")) {
+            (Some(_), Some(_)) => return Err(CliError::Usage("输出文件不能同时用位置参数和 -o 指定".to_string())),
+            (positional, option) => positional.map(String::as_str).or(option).map(PathBuf::from),
+        };
+        Ok((Path::new(input), output))
+    }
+
+    fn number(&self, name: &str) -> Result<Option<u32>, CliError> {
+        self.option(name)
+            .map(|value| value.parse().map_err(|_| CliError::Usage(format!("{} 必须是非负整数: {}", name, value))))
+            .transpose()
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, CliError> {
+    fs::read(path).map_err(|e| CliError::Io(format!("无法读取 {}: {}", path.display(), e)))
+}
+
+fn write(path: &Path, data: &[u8]) -> Result<(), CliError> {
+    fs::write(path, data).map_err(|e| CliError::Io(format!("无法写入 {}: {}", path.display(), e)))
+}
+
+// 按扩展名推断载荷类型
+fn kind_from_extension(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match extension.as_str() {
+        "json" => "json",
+        "glb" => "glb",
+        "cbor" => "cbor",
+        "msgpack" | "mpk" => "msgpack",
+        "pb" | "protobuf" => "protobuf",
+        "png" | "jpg" | "jpeg" | "webp" | "ktx2" => "image",
+        "pcd" | "ply" => "pointcloud",
+        _ => return None,
+    })
+}
+
+// 解包的默认输出: 去掉 .fastdog 后缀，没有该后缀时按载荷类型追加扩展名
+fn unpacked_path(input: &Path, kind: &str) -> PathBuf {
+    if input.extension().is_some_and(|extension| extension == "fastdog") {
+        return input.with_extension("");
+    }
+    let extension = match kind {
+        "json" | "glb" | "cbor" | "msgpack" => kind,
+        _ => "bin",
+    };
+    let mut path = input.as_os_str().to_owned();
+    path.push(format!(".{}", extension));
+    PathBuf::from(path)
+}
+
+fn with_suffix(input: &Path, suffix: &str) -> PathBuf {
+    let mut path = input.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+fn pack(args: &Args) -> Result<(), CliError> {
+    args.expect(&["--output", "--kind", "--profile", "--codec", "--meta"])?;
+    let (input, output) = args.input_output()?;
+    let kind = match args.option("--kind") {
+        Some(kind) => kind,
+        None => kind_from_extension(input)
+            .ok_or_else(|| CliError::Usage(format!("无法从扩展名推断 {} 的载荷类型，请用 --kind 指定", input.display())))?,
+    };
+    let mut metadata = BTreeMap::new();
+    for entry in args.all("--meta") {
+        let (key, value) =
+            entry.split_once('=').ok_or_else(|| CliError::Usage(format!("元数据必须是 键=值 形式: {}", entry)))?;
+        metadata.insert(key.to_string(), value.to_string());
+    }
+
+    let payload = read(input)?;
+    let container = fastdog_core::pack(&payload, kind, &metadata, args.option("--profile"), args.option("--codec"))?;
+    let output = output.unwrap_or_else(|| with_suffix(input, ".fastdog"));
+    write(&output, &container)?;
+    println!(
+        "{} -> {} ({} 字节 -> {} 字节)",
+        input.display(),
+        output.display(),
+        payload.len(),
+        container.len()
+    );
+    Ok(())
+}
+
+fn unpack(args: &Args) -> Result<(), CliError> {
+    args.expect(&["--output"])?;
+    let (input, output) = args.input_output()?;
+    let unpacked = fastdog_core::unpack(&read(input)?)?;
+    let output = output.unwrap_or_else(|| unpacked_path(input, unpacked.kind));
+    write(&output, &unpacked.payload)?;
+    println!(
+        "{} -> {} (版本 {}，{}，{} 载荷 {} 字节)",
+        input.display(),
+        output.display(),
+        unpacked.version,
+        unpacked.codec,
+        unpacked.kind,
+        unpacked.payload.len()
+    );
+    Ok(())
+}
+
+fn inspect(args: &Args) -> Result<(), CliError> {
+    args.expect(&["--dump", "--max-bytes"])?;
+    let data = read(args.input()?)?;
+    if args.flag("--dump") {
+        println!("{}", fastdog_core::debug_dump(&data, args.number("--max-bytes")?));
+    } else {
+        println!("{}", fastdog_core::inspect_json(&data)?);
+    }
+    Ok(())
+}
+
+// 逐个校验，全部通过时返回成功
+fn verify(args: &Args) -> Result<(), CliError> {
+    args.expect(&[])?;
+    if args.positional.is_empty() {
+        return Err(CliError::Usage("缺少输入文件".to_string()));
+    }
+    let mut failures = 0;
+    for input in &args.positional {
+        let result = read(Path::new(input)).and_then(|data| Ok(fastdog_core::verify(&data)?));
+        match result {
+            Ok(verified) => {
+                let checksums: Vec<&str> = [("crc32", verified.crc32), ("sha256", verified.sha256)]
+                    .into_iter()
+                    .filter_map(|(name, present)| present.then_some(name))
+                    .collect();
+                let checksums = if checksums.is_empty() { "无校验值".to_string() } else { checksums.join(" + ") };
+                println!(
+                    "OK   {} (版本 {}，{} 载荷 {} 字节，{})",
+                    input, verified.version, verified.kind, verified.original_size, checksums
+                );
+            }
+            Err(error) => {
+                failures += 1;
+                println!("FAIL {}: {}", input, describe(&error));
+            }
+        }
+    }
+    match failures {
+        0 => Ok(()),
+        count => Err(CliError::Io(format!("{} 个文件校验失败", count))),
+    }
+}
+
+fn transcode(args: &Args) -> Result<(), CliError> {
+    args.expect(&["--output", "--version", "--codec", "--profile"])?;
+    let (input, output) = args.input_output()?;
+    let version = args.number("--version")?.ok_or_else(|| CliError::Usage("缺少 --version".to_string()))?;
+    let data = read(input)?;
+    let container = fastdog_core::transcode(&data, version, args.option("--codec"), args.option("--profile"))?;
+    let output = output.unwrap_or_else(|| input.with_extension(format!("v{}.fastdog", version)));
+    write(&output, &container)?;
+    println!(
+        "{} -> {} (版本 {}，{} 字节 -> {} 字节)",
+        input.display(),
+        output.display(),
+        version,
+        data.len(),
+        container.len()
+    );
+    Ok(())
+}
+
+fn describe(error: &CliError) -> String {
+    match error {
+        CliError::Usage(message) | CliError::Io(message) => message.clone(),
+        CliError::Decode(error) => format!("[{}] {}", error.code.as_str(), error.message),
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let command = args.next();
+    let result = Args::parse(args).and_then(|args| match command.as_deref() {
+        Some("pack") => pack(&args),
+        Some("unpack") => unpack(&args),
+        Some("inspect") => inspect(&args),
+        Some("verify") => verify(&args),
+        Some("transcode") => transcode(&args),
+        Some("help" | "-h" | "--help") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(other) => Err(CliError::Usage(format!("未知命令: {}", other))),
+        None => Err(CliError::Usage("缺少命令".to_string())),
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("错误: {}", describe(&error));
+            if let CliError::Usage(_) = error {
+                eprintln!("\n{}", USAGE);
+                return ExitCode::from(2);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Args {
+        match Args::parse(args.iter().map(|arg| arg.to_string())) {
+            Ok(args) => args,
+            Err(error) => panic!("参数解析失败: {}", describe(&error)),
+        }
+    }
+
+    fn usage_message<T>(result: Result<T, CliError>) -> String {
+        match result {
+            Err(CliError::Usage(message)) => message,
+            Err(error) => panic!("预期参数错误，实际为: {}", describe(&error)),
+            Ok(_) => panic!("预期参数错误"),
+        }
+    }
+
+    #[test]
+    fn parse_separates_positionals_options_and_switches() {
+        let args = parse(&["in.json", "-o", "out.fastdog", "--meta", "a=1", "--dump", "--meta", "b=2"]);
+        assert_eq!(args.positional, ["in.json"]);
+        assert_eq!(args.option("--output"), Some("out.fastdog"));
+        assert_eq!(args.all("--meta").collect::<Vec<_>>(), ["a=1", "b=2"]);
+        assert!(args.flag("--dump"));
+        assert!(args.expect(&["--output", "--meta", "--dump"]).is_ok());
+        assert!(usage_message(args.expect(&["--output"])).contains("--meta"));
+
+        let message = usage_message(Args::parse(["in.json", "--kind"].iter().map(|arg| arg.to_string())));
+        assert!(message.contains("--kind"));
+    }
+
+    fn output_of(args: &[&str]) -> Option<PathBuf> {
+        match parse(args).input_output() {
+            Ok((input, output)) => {
+                assert_eq!(input, Path::new("in.json"));
+                output
+            }
+            Err(error) => panic!("输入输出解析失败: {}", describe(&error)),
+        }
+    }
+
+    #[test]
+    fn output_comes_from_positional_or_option() {
+        assert_eq!(output_of(&["in.json"]), None);
+        assert_eq!(output_of(&["in.json", "out.fastdog"]), Some(PathBuf::from("out.fastdog")));
+        assert_eq!(output_of(&["-o", "out.fastdog", "in.json"]), Some(PathBuf::from("out.fastdog")));
+    }
+
+    #[test]
+    fn conflicting_or_extra_outputs_are_rejected() {
+        let message = usage_message(parse(&["in.json", "a.fastdog", "-o", "b.fastdog"]).input_output());
+        assert!(message.contains("-o"));
+        usage_message(parse(&["in.json", "a.fastdog", "b.fastdog"]).input_output());
+        usage_message(parse(&[]).input_output());
+    }
+
+    #[test]
+    fn unpacked_path_strips_suffix_or_adds_extension() {
+        assert_eq!(unpacked_path(Path::new("dir/scene.glb.fastdog"), "glb"), PathBuf::from("dir/scene.glb"));
+        assert_eq!(unpacked_path(Path::new("scene.pack"), "glb"), PathBuf::from("scene.pack.glb"));
+        assert_eq!(unpacked_path(Path::new("cloud"), "pointcloud"), PathBuf::from("cloud.bin"));
+    }
+}
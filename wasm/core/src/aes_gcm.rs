@@ -0,0 +1,248 @@
+// AES-256-GCM (NIST SP 800-38D)，只支持 96 位 nonce 和 128 位认证标签
+//
+// 依赖中没有 AEAD 实现，这里按 FIPS-197 / SP 800-38D 直接实现。S 盒查表不是常数时间，
+// 不适合攻击者能精确测量解密耗时的服务端场景；GHASH 乘法和标签比较是常数时间的
+
+const ROUNDS: usize = 14;
+
+// GF(2^8) 乘法 (AES 的既约多项式 x^8 + x^4 + x^3 + x + 1)
+const fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        a = xtime(a);
+        b >>= 1;
+    }
+    product
+}
+
+const fn xtime(a: u8) -> u8 {
+    (a << 1) ^ if a & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+// S 盒: GF(2^8) 乘法逆元 (0 映射为 0) 再做仿射变换
+const fn build_sbox() -> [u8; 256] {
+    let mut sbox = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        // a^254 = a^-1
+        let mut inverse = 1u8;
+        let mut exponent = 0;
+        while exponent < 254 {
+            inverse = gf_mul(inverse, i as u8);
+            exponent += 1;
+        }
+        if i == 0 {
+            inverse = 0;
+        }
+        sbox[i] = inverse
+            ^ inverse.rotate_left(1)
+            ^ inverse.rotate_left(2)
+            ^ inverse.rotate_left(3)
+            ^ inverse.rotate_left(4)
+            ^ 0x63;
+        i += 1;
+    }
+    sbox
+}
+
+const SBOX: [u8; 256] = build_sbox();
+
+pub struct Aes256Gcm {
+    round_keys: [[u8; 16]; ROUNDS + 1],
+    // 哈希子密钥 H = E(K, 0^128)
+    hash_key: u128,
+}
+
+impl Aes256Gcm {
+    pub fn new(key: &[u8; 32]) -> Aes256Gcm {
+        let mut cipher = Aes256Gcm {
+            round_keys: expand_key(key),
+            hash_key: 0,
+        };
+        cipher.hash_key = u128::from_be_bytes(cipher.encrypt_block([0; 16]));
+        cipher
+    }
+
+    // 加密并返回认证标签，aad 为只认证不加密的附加数据
+    pub fn encrypt(&self, nonce: &[u8; 12], aad: &[u8], data: &mut [u8]) -> [u8; 16] {
+        self.apply_keystream(nonce, data);
+        self.tag(nonce, aad, data)
+    }
+
+    // 先校验认证标签再解密，标签不一致时返回 false 且数据保持不变
+    pub fn decrypt(&self, nonce: &[u8; 12], aad: &[u8], data: &mut [u8], tag: &[u8; 16]) -> bool {
+        let expected = self.tag(nonce, aad, data);
+        let difference = expected.iter().zip(tag).fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        if difference != 0 {
+            return false;
+        }
+        self.apply_keystream(nonce, data);
+        true
+    }
+
+    // 计数器模式: 第一个数据块使用计数器 2 (计数器 1 用于加密标签)
+    fn apply_keystream(&self, nonce: &[u8; 12], data: &mut [u8]) {
+        for (index, block) in data.chunks_mut(16).enumerate() {
+            let keystream = self.encrypt_block(counter_block(nonce, (index as u32).wrapping_add(2)));
+            for (byte, key) in block.iter_mut().zip(keystream) {
+                *byte ^= key;
+            }
+        }
+    }
+
+    fn tag(&self, nonce: &[u8; 12], aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
+        let mut hash = 0u128;
+        for input in [aad, ciphertext] {
+            for block in input.chunks(16) {
+                let mut padded = [0u8; 16];
+                padded[..block.len()].copy_from_slice(block);
+                hash = gf128_mul(hash ^ u128::from_be_bytes(padded), self.hash_key);
+            }
+        }
+        let lengths = ((aad.len() as u128 * 8) << 64) | (ciphertext.len() as u128 * 8);
+        hash = gf128_mul(hash ^ lengths, self.hash_key);
+
+        let mask = u128::from_be_bytes(self.encrypt_block(counter_block(nonce, 1)));
+        (hash ^ mask).to_be_bytes()
+    }
+
+    fn encrypt_block(&self, mut state: [u8; 16]) -> [u8; 16] {
+        add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1..=ROUNDS {
+            for byte in state.iter_mut() {
+                *byte = SBOX[*byte as usize];
+            }
+            shift_rows(&mut state);
+            if round != ROUNDS {
+                mix_columns(&mut state);
+            }
+            add_round_key(&mut state, &self.round_keys[round]);
+        }
+        state
+    }
+}
+
+fn counter_block(nonce: &[u8; 12], counter: u32) -> [u8; 16] {
+    let mut block = [0u8; 16];
+    block[..12].copy_from_slice(nonce);
+    block[12..].copy_from_slice(&counter.to_be_bytes());
+    block
+}
+
+// AES-256 密钥扩展: 8 个字的密钥扩展为 15 个轮密钥
+fn expand_key(key: &[u8; 32]) -> [[u8; 16]; ROUNDS + 1] {
+    let mut words = [[0u8; 4]; 4 * (ROUNDS + 1)];
+    for (word, bytes) in words.iter_mut().zip(key.chunks(4)) {
+        word.copy_from_slice(bytes);
+    }
+    let mut rcon = 1u8;
+    for i in 8..words.len() {
+        let mut temp = words[i - 1];
+        if i % 8 == 0 {
+            temp.rotate_left(1);
+            temp = temp.map(|byte| SBOX[byte as usize]);
+            temp[0] ^= rcon;
+            rcon = xtime(rcon);
+        } else if i % 8 == 4 {
+            temp = temp.map(|byte| SBOX[byte as usize]);
+        }
+        for j in 0..4 {
+            words[i][j] = words[i - 8][j] ^ temp[j];
+        }
+    }
+
+    let mut round_keys = [[0u8; 16]; ROUNDS + 1];
+    for (round_key, words) in round_keys.iter_mut().zip(words.chunks(4)) {
+        for (bytes, word) in round_key.chunks_mut(4).zip(words) {
+            bytes.copy_from_slice(word);
+        }
+    }
+    round_keys
+}
+
+fn add_round_key(state: &mut [u8; 16], round_key: &[u8; 16]) {
+    for (byte, key) in state.iter_mut().zip(round_key) {
+        *byte ^= key;
+    }
+}
+
+// 状态按列存放: state[row + 4 * column]，第 row 行循环左移 row 个字节
+fn shift_rows(state: &mut [u8; 16]) {
+    let original = *state;
+    for row in 1..4 {
+        for column in 0..4 {
+            state[row + 4 * column] = original[row + 4 * ((column + row) % 4)];
+        }
+    }
+}
+
+fn mix_columns(state: &mut [u8; 16]) {
+    for column in state.chunks_mut(4) {
+        let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+        column[0] = xtime(a0) ^ xtime(a1) ^ a1 ^ a2 ^ a3;
+        column[1] = a0 ^ xtime(a1) ^ xtime(a2) ^ a2 ^ a3;
+        column[2] = a0 ^ a1 ^ xtime(a2) ^ xtime(a3) ^ a3;
+        column[3] = xtime(a0) ^ a0 ^ a1 ^ a2 ^ xtime(a3);
+    }
+}
+
+// GF(2^128) 乘法 (GCM 的位序: 最高位对应 x^0)，按位处理且不依赖数据分支
+fn gf128_mul(x: u128, y: u128) -> u128 {
+    const R: u128 = 0xe1 << 120;
+    let mut product = 0u128;
+    let mut v = y;
+    for i in 0..128 {
+        let bit = (x >> (127 - i)) & 1;
+        product ^= v & bit.wrapping_neg();
+        let carry = v & 1;
+        v = (v >> 1) ^ (R & carry.wrapping_neg());
+    }
+    product
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrity::to_hex;
+
+    // GCM 规范 (McGrew & Viega) 附录 B 的 AES-256 测试用例 13、14
+    #[test]
+    fn matches_gcm_spec_vectors() {
+        let cipher = Aes256Gcm::new(&[0; 32]);
+        assert_eq!(to_hex(&cipher.encrypt(&[0; 12], &[], &mut [])), "530f8afbc74536b9a963b4f1c4cb738b");
+
+        let mut data = [0u8; 16];
+        let tag = cipher.encrypt(&[0; 12], &[], &mut data);
+        assert_eq!(to_hex(&data), "cea7403d4d606b6e074ec5d3baf39d18");
+        assert_eq!(to_hex(&tag), "d0d1c8a799996bf0265b98b5d48ab919");
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let cipher = Aes256Gcm::new(&[7; 32]);
+        let nonce = [1; 12];
+        let plaintext = b"fastdog payload spanning more than one block".to_vec();
+        let mut data = plaintext.clone();
+        let tag = cipher.encrypt(&nonce, b"header", &mut data);
+
+        let mut decrypted = data.clone();
+        assert!(cipher.decrypt(&nonce, b"header", &mut decrypted, &tag));
+        assert_eq!(decrypted, plaintext);
+
+        // 密文、附加数据、nonce、标签或密钥任一改变都校验失败，数据保持不变
+        let mut flipped = data.clone();
+        flipped[20] ^= 1;
+        assert!(!cipher.decrypt(&nonce, b"header", &mut flipped.clone(), &tag));
+        assert!(!cipher.decrypt(&nonce, b"Header", &mut data.clone(), &tag));
+        assert!(!cipher.decrypt(&[2; 12], b"header", &mut data.clone(), &tag));
+        let mut bad_tag = tag;
+        bad_tag[15] ^= 0x80;
+        assert!(!cipher.decrypt(&nonce, b"header", &mut data.clone(), &bad_tag));
+        let mut unchanged = data.clone();
+        assert!(!Aes256Gcm::new(&[8; 32]).decrypt(&nonce, b"header", &mut unchanged, &tag));
+        assert_eq!(unchanged, data);
+    }
+}
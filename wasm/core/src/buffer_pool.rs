@@ -0,0 +1,174 @@
+use std::sync::Mutex;
+
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// 解压输出缓冲区的复用池
+//
+// 连续解码成千上万个小瓦片时，每次解码分配一个新的输出 Vec、用完即释放，分配器反复切分合并内存块。
+// 用完的缓冲区按容量放回 2 的幂分桶，下次解码从不小于所需容量的桶中取出复用。
+// 池中的空闲缓冲区不计入内存配额，总量受 set_limits (JS 接口 set_pool_limits) 限制
+const MIN_CAPACITY: usize = 4 * 1024;
+
+struct BufferPool {
+    // 第 i 个桶存放容量在 [MIN_CAPACITY << i, MIN_CAPACITY << (i + 1)) 之间的缓冲区
+    buckets: Vec<Vec<Vec<u8>>>,
+    pooled_bytes: usize,
+    // 池中空闲缓冲区的总容量上限
+    max_pooled_bytes: usize,
+    // 超过此容量的缓冲区不进入池，按需精确分配
+    max_buffer_bytes: usize,
+}
+
+const DEFAULT_MAX_POOLED_BYTES: usize = 16 * 1024 * 1024;
+const DEFAULT_MAX_BUFFER_BYTES: usize = 4 * 1024 * 1024;
+
+static POOL: Mutex<BufferPool> = Mutex::new(BufferPool {
+    buckets: Vec::new(),
+    pooled_bytes: 0,
+    max_pooled_bytes: DEFAULT_MAX_POOLED_BYTES,
+    max_buffer_bytes: DEFAULT_MAX_BUFFER_BYTES,
+});
+
+fn with_pool<T>(f: impl FnOnce(&mut BufferPool) -> T) -> T {
+    let mut pool = POOL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut pool)
+}
+
+// 容量所在的桶
+fn bucket_index(capacity: usize) -> usize {
+    (capacity.ilog2() - MIN_CAPACITY.ilog2()) as usize
+}
+
+// 取出空缓冲区，容量通常至少为 capacity。capacity 只是预分配的提示 (通常由不可信的头部推算)，
+// 超过单个缓冲区上限的请求不经过池，分配失败时返回空缓冲区
+pub fn take(capacity: usize) -> Vec<u8> {
+    let Some(rounded) = capacity.max(MIN_CAPACITY).checked_next_power_of_two() else {
+        return allocate_unpooled(capacity);
+    };
+    let reused = with_pool(|pool| {
+        if rounded > pool.max_buffer_bytes {
+            return None;
+        }
+        Some(
+            pool.buckets
+                .get_mut(bucket_index(rounded))
+                .and_then(Vec::pop)
+                .inspect(|buffer| pool.pooled_bytes -= buffer.capacity()),
+        )
+    });
+    match reused {
+        Some(Some(buffer)) => buffer,
+        // 未命中时按桶容量分配，放回后才能被同样大小的请求复用
+        Some(None) => Vec::with_capacity(rounded),
+        None => allocate_unpooled(capacity),
+    }
+}
+
+// 不经过池的分配。分配失败时 (如声明数 GB 的原始长度) 不中止进程，返回的空缓冲区随写入增长
+fn allocate_unpooled(capacity: usize) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    // 失败时 buffer 保持为空
+    let _ = buffer.try_reserve_exact(capacity);
+    buffer
+}
+
+// 容量为 capacity 的空缓冲区，分配失败时返回 QuotaExceeded 而不是中止进程
+// (release 构建为 panic = "abort"，分配失败无法在之后捕获)。用于长度来自不可信数据、无法预先确定上限的分配
+pub fn try_allocate(capacity: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut buffer = Vec::new();
+    buffer.try_reserve_exact(capacity).map_err(|_| {
+        DecodeError::new(DecodeErrorCode::QuotaExceeded, format!("无法分配 {} 字节的缓冲区 (内存不足)", capacity))
+    })?;
+    Ok(buffer)
+}
+
+// 长度为 len 的零填充缓冲区，分配失败时返回 QuotaExceeded
+pub fn try_zeroed(len: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut buffer = try_allocate(len)?;
+    buffer.resize(len, 0);
+    Ok(buffer)
+}
+
+// 放回用完的缓冲区，过小、过大或池已满时直接释放
+pub fn recycle(mut buffer: Vec<u8>) {
+    let capacity = buffer.capacity();
+    if capacity < MIN_CAPACITY {
+        return;
+    }
+    buffer.clear();
+    let rejected = with_pool(|pool| {
+        if capacity > pool.max_buffer_bytes || pool.pooled_bytes + capacity > pool.max_pooled_bytes {
+            return Some(buffer);
+        }
+        let index = bucket_index(capacity);
+        if pool.buckets.len() <= index {
+            pool.buckets.resize_with(index + 1, Vec::new);
+        }
+        pool.buckets[index].push(buffer);
+        pool.pooled_bytes += capacity;
+        None
+    });
+    // 在锁外释放
+    drop(rejected);
+}
+
+// 池中空闲缓冲区的数量和总容量
+pub fn pooled() -> (u32, usize) {
+    with_pool(|pool| (pool.buckets.iter().map(Vec::len).sum::<usize>() as u32, pool.pooled_bytes))
+}
+
+// 设置池中空闲缓冲区的总容量上限和单个缓冲区的容量上限 (字节)，None 恢复默认值 (16MB / 4MB)。
+// 收紧上限时立即释放超出的缓冲区
+pub fn set_limits(max_pooled_bytes: Option<usize>, max_buffer_bytes: Option<usize>) {
+    let evicted = with_pool(|pool| {
+        pool.max_pooled_bytes = max_pooled_bytes.unwrap_or(DEFAULT_MAX_POOLED_BYTES);
+        pool.max_buffer_bytes = max_buffer_bytes.unwrap_or(DEFAULT_MAX_BUFFER_BYTES);
+        // 从大到小淘汰，直到满足两个上限
+        let mut evicted = Vec::new();
+        for bucket in pool.buckets.iter_mut().rev() {
+            for buffer in std::mem::take(bucket) {
+                if buffer.capacity() > pool.max_buffer_bytes || pool.pooled_bytes > pool.max_pooled_bytes {
+                    pool.pooled_bytes -= buffer.capacity();
+                    evicted.push(buffer);
+                } else {
+                    bucket.push(buffer);
+                }
+            }
+        }
+        evicted
+    });
+    // 在锁外释放
+    drop(evicted);
+}
+
+// 释放池中所有空闲缓冲区 (如切换场景后归还内存)
+pub fn clear() {
+    let buckets = with_pool(|pool| {
+        pool.pooled_bytes = 0;
+        std::mem::take(&mut pool.buckets)
+    });
+    drop(buckets);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 无法满足的容量提示不会中止进程
+    #[test]
+    fn impossible_capacity_hints_return_empty_buffers() {
+        for capacity in [usize::MAX, isize::MAX as usize] {
+            let mut buffer = take(capacity);
+            assert!(buffer.capacity() < capacity);
+            buffer.extend_from_slice(b"grows on demand");
+            assert_eq!(buffer, b"grows on demand");
+        }
+        assert!(take(DEFAULT_MAX_BUFFER_BYTES * 4).capacity() >= DEFAULT_MAX_BUFFER_BYTES * 4);
+    }
+
+    #[test]
+    fn failed_allocations_are_errors() {
+        assert_eq!(try_zeroed(isize::MAX as usize).unwrap_err().code, DecodeErrorCode::QuotaExceeded);
+        assert_eq!(try_zeroed(16).unwrap(), [0; 16]);
+    }
+}
@@ -0,0 +1,383 @@
+use serde::{Deserialize, Serialize};
+
+use crate::byte_reader::ByteReader;
+use crate::codec::{self, Codec};
+use crate::container::payload_len;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::profiles::EncoderProfile;
+
+// FastDog 打包格式 (一个文件包含多个具名条目)
+//
+// 魔数 "FASTDOGB"(8) | 版本(4) | 清单长度(4) | 清单 (UTF-8 JSON) | 条目数据区
+//
+// 清单记录每个条目的名称、内容类型、编码、在数据区中的偏移、压缩/原始大小和 CRC32，
+// 每个条目独立压缩，因此可以单独读取或替换。
+pub const BUNDLE_MAGIC: &[u8; 8] = b"FASTDOGB";
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleEntry {
+    pub name: String,
+    pub content_type: String,
+    pub codec: Codec,
+    // 相对数据区起始位置的偏移
+    pub offset: u64,
+    pub compressed_size: u64,
+    pub original_size: u64,
+    // 未压缩数据的 CRC32
+    pub crc32: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    entries: Vec<BundleEntry>,
+}
+
+// 解析后的打包文件，条目数据仍引用原始输入
+pub struct Bundle<'a> {
+    pub entries: Vec<BundleEntry>,
+    data: &'a [u8],
+}
+
+impl<'a> Bundle<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Bundle<'a>, DecodeError> {
+        if data.len() < 16 {
+            return Err(DecodeError::new(DecodeErrorCode::TooShort, "数据太短，不是有效的 FastDog 打包文件"));
+        }
+        if &data[0..8] != BUNDLE_MAGIC {
+            return Err(DecodeError::new(
+                DecodeErrorCode::BadMagic,
+                format!("无效的打包文件魔数: {:?}", &data[0..8]),
+            ));
+        }
+        let header = ByteReader::new(data, "打包文件头部");
+        let version = header.u32_at(8)?;
+        if version != BUNDLE_VERSION {
+            return Err(DecodeError::new(
+                DecodeErrorCode::UnsupportedVersion,
+                format!("不支持的打包文件版本: {}", version),
+            ));
+        }
+
+        let manifest_len = header.u32_at(12)? as usize;
+        let data_start = 16usize.checked_add(manifest_len).filter(|&end| end <= data.len()).ok_or_else(|| {
+            DecodeError::new(
+                DecodeErrorCode::Truncated,
+                format!("清单长度 {} 超出文件范围 ({} 字节)", manifest_len, data.len()),
+            )
+        })?;
+
+        let manifest: Manifest = serde_json::from_slice(&data[16..data_start])
+            .map_err(|e| DecodeError::new(DecodeErrorCode::InvalidInput, format!("清单解析失败: {}", e)))?;
+
+        Ok(Bundle {
+            entries: manifest.entries,
+            data: &data[data_start..],
+        })
+    }
+
+    // 条目的压缩数据
+    pub fn raw_entry(&self, entry: &BundleEntry) -> Result<&'a [u8], DecodeError> {
+        let start = usize::try_from(entry.offset).ok();
+        let end = start.and_then(|start| start.checked_add(usize::try_from(entry.compressed_size).ok()?));
+        match (start, end) {
+            (Some(start), Some(end)) if end <= self.data.len() => Ok(&self.data[start..end]),
+            _ => Err(DecodeError::new(
+                DecodeErrorCode::Truncated,
+                format!("条目 {} 的数据超出范围", entry.name),
+            )),
+        }
+    }
+
+    // 解压条目并校验长度和 CRC32。on_slice 在每个解压分片边界以目前的输出长度调用 (内存配额)，
+    // 输出超出清单声明的原始大小时立即中止
+    pub fn decode_entry<F>(&self, entry: &BundleEntry, mut on_slice: F) -> Result<Vec<u8>, DecodeError>
+    where
+        F: FnMut(usize) -> Result<(), DecodeError>,
+    {
+        let context = format!("条目 {}", entry.name);
+        let compressed = self.raw_entry(entry)?;
+        let original_len = payload_len(entry.original_size).map_err(|e| e.context(&context))?;
+        let data = codec::decompress_sliced(entry.codec, compressed, original_len, |len| {
+            if len > original_len {
+                return Err(codec::length_exceeded(original_len));
+            }
+            on_slice(len)
+        })
+        .map_err(|e| e.context(&context))?;
+
+        if data.len() != original_len {
+            return Err(DecodeError::new(
+                DecodeErrorCode::LengthMismatch,
+                format!("条目 {} 解压后长度不匹配: 期望 {}, 实际 {}", entry.name, original_len, data.len()),
+            ));
+        }
+        let crc32 = crc32fast::hash(&data);
+        if crc32 != entry.crc32 {
+            return Err(DecodeError::new(
+                DecodeErrorCode::ChecksumMismatch,
+                format!("条目 {} CRC32 校验失败: 期望 {:08x}, 实际 {:08x}", entry.name, entry.crc32, crc32),
+            ));
+        }
+        Ok(data)
+    }
+}
+
+// 打包文件写入器
+#[derive(Default)]
+pub struct BundleWriter {
+    entries: Vec<BundleEntry>,
+    data: Vec<u8>,
+}
+
+impl BundleWriter {
+    // 直接写入已压缩的条目数据 (复用原文件中未修改的条目)
+    pub fn add_compressed(&mut self, entry: &BundleEntry, compressed: &[u8]) {
+        self.entries.push(BundleEntry {
+            offset: self.data.len() as u64,
+            compressed_size: compressed.len() as u64,
+            ..entry.clone()
+        });
+        self.data.extend_from_slice(compressed);
+    }
+
+    // 按档案压缩并写入条目
+    pub fn add(
+        &mut self,
+        name: &str,
+        content_type: &str,
+        payload: &[u8],
+        profile: &EncoderProfile,
+    ) -> Result<(), DecodeError> {
+        let compressed = codec::compress(profile.codec, payload, profile.level, profile.block_size)
+            .map_err(|e| DecodeError::new(DecodeErrorCode::InvalidInput, format!("条目 {} 压缩失败: {}", name, e)))?;
+        let entry = BundleEntry {
+            name: name.to_string(),
+            content_type: content_type.to_string(),
+            codec: profile.codec,
+            offset: 0,
+            compressed_size: 0,
+            original_size: payload.len() as u64,
+            crc32: crc32fast::hash(payload),
+        };
+        self.add_compressed(&entry, &compressed);
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<Vec<u8>, DecodeError> {
+        let manifest = serde_json::to_vec(&Manifest { entries: self.entries })
+            .map_err(|e| DecodeError::new(DecodeErrorCode::Internal, format!("清单无法序列化: {}", e)))?;
+        let manifest_len = u32::try_from(manifest.len())
+            .map_err(|_| DecodeError::new(DecodeErrorCode::InvalidInput, "清单超过 4GB，无法写入打包文件"))?;
+        let mut output = Vec::with_capacity(16 + manifest.len() + self.data.len());
+        output.extend_from_slice(BUNDLE_MAGIC);
+        output.extend_from_slice(&BUNDLE_VERSION.to_le_bytes());
+        output.extend_from_slice(&manifest_len.to_le_bytes());
+        output.extend_from_slice(&manifest);
+        output.extend_from_slice(&self.data);
+        Ok(output)
+    }
+}
+
+// 打包时写入的条目，content_type 省略时为 "binary"
+pub struct EntryData<'a> {
+    pub name: &'a str,
+    pub data: &'a [u8],
+    pub content_type: Option<&'a str>,
+}
+
+// 增量更新的修改条目: data 为 None 表示删除该条目，content_type 省略时沿用原条目的类型
+pub struct EntryChange<'a> {
+    pub name: &'a str,
+    pub data: Option<&'a [u8]>,
+    pub content_type: Option<&'a str>,
+}
+
+// 把多个载荷打包成一个文件，每个条目按档案独立压缩，名称不能重复
+pub fn encode(entries: &[EntryData], profile: &EncoderProfile) -> Result<Vec<u8>, DecodeError> {
+    let mut writer = BundleWriter::default();
+    for (index, entry) in entries.iter().enumerate() {
+        if entries[..index].iter().any(|earlier| earlier.name == entry.name) {
+            return Err(DecodeError::new(DecodeErrorCode::InvalidInput, format!("条目名称重复: {}", entry.name)));
+        }
+        writer.add(entry.name, entry.content_type.unwrap_or("binary"), entry.data, profile)?;
+    }
+    writer.finish()
+}
+
+// 增量更新打包文件: 未修改的条目直接复用原有压缩数据，只重新压缩修改过的条目；新名称的条目追加到末尾。
+// 同名的多个修改以最后一个为准
+pub fn update(original: &[u8], changes: &[EntryChange], profile: &EncoderProfile) -> Result<Vec<u8>, DecodeError> {
+    let bundle = Bundle::parse(original)?;
+    let mut writer = BundleWriter::default();
+
+    for entry in &bundle.entries {
+        match changes.iter().rfind(|change| change.name == entry.name) {
+            None => writer.add_compressed(entry, bundle.raw_entry(entry)?),
+            Some(EntryChange { data: None, .. }) => {}
+            Some(EntryChange {
+                data: Some(data),
+                content_type,
+                ..
+            }) => {
+                let content_type = content_type.unwrap_or(&entry.content_type);
+                writer.add(&entry.name, content_type, data, profile)?;
+            }
+        }
+    }
+
+    // 原文件中不存在的条目
+    for (index, change) in changes.iter().enumerate() {
+        let Some(data) = change.data else {
+            continue;
+        };
+        let exists = bundle.entries.iter().any(|entry| entry.name == change.name);
+        let superseded = changes[index + 1..].iter().any(|later| later.name == change.name);
+        if !exists && !superseded {
+            writer.add(change.name, change.content_type.unwrap_or("binary"), data, profile)?;
+        }
+    }
+
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::resolve_profile;
+    use crate::transcode::target_profile;
+
+    fn entry<'a>(name: &'a str, data: &'a [u8]) -> EntryData<'a> {
+        EntryData {
+            name,
+            data,
+            content_type: None,
+        }
+    }
+
+    #[test]
+    fn update_reuses_unchanged_entries() {
+        let profile = resolve_profile(None).unwrap();
+        let scene = br#"{"nodes":[]}"#.repeat(50);
+        let original = encode(&[entry("scene.json", &scene), entry("model.glb", b"glTF"), entry("old", b"x")], &profile)
+            .unwrap();
+        assert!(encode(&[entry("a", b"1"), entry("a", b"2")], &profile).is_err());
+
+        let changes = [
+            EntryChange {
+                name: "model.glb",
+                data: Some(b"glTF v2"),
+                content_type: Some("glb"),
+            },
+            EntryChange {
+                name: "old",
+                data: None,
+                content_type: None,
+            },
+            EntryChange {
+                name: "extra.bin",
+                data: Some(b"\x00\x01"),
+                content_type: None,
+            },
+        ];
+        let updated = update(&original, &changes, &profile).unwrap();
+        let bundle = Bundle::parse(&updated).unwrap();
+        let names: Vec<&str> = bundle.entries.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, ["scene.json", "model.glb", "extra.bin"]);
+        assert_eq!(bundle.entries[1].content_type, "glb");
+        assert_eq!(bundle.entries[2].content_type, "binary");
+
+        let original_bundle = Bundle::parse(&original).unwrap();
+        let scene_entry = &bundle.entries[0];
+        let original_scene = original_bundle.raw_entry(&original_bundle.entries[0]).unwrap();
+        assert_eq!(bundle.raw_entry(scene_entry).unwrap(), original_scene);
+        assert_eq!(bundle.decode_entry(scene_entry, |_| Ok(())).unwrap(), scene);
+        assert_eq!(bundle.decode_entry(&bundle.entries[1], |_| Ok(())).unwrap(), b"glTF v2");
+    }
+
+    #[test]
+    fn every_codec_round_trips() {
+        let scene = br#"{"nodes":[{"mesh":0}]}"#.repeat(200);
+        let mesh: Vec<u8> = (0..5000u32).flat_map(|i| (i % 97).to_le_bytes()).collect();
+        for codec in Codec::ALL {
+            let profile = target_profile(None, Some(codec)).unwrap();
+            let data = encode(&[entry("scene.json", &scene), entry("mesh.bin", &mesh), entry("empty", b"")], &profile)
+                .unwrap();
+            let bundle = Bundle::parse(&data).unwrap();
+            assert_eq!(bundle.entries.len(), 3);
+            for (entry, expected) in bundle.entries.iter().zip([&scene[..], &mesh[..], b""]) {
+                assert_eq!(entry.codec, codec);
+                assert_eq!(bundle.decode_entry(entry, |_| Ok(())).unwrap(), expected, "{}", codec.name());
+            }
+        }
+    }
+
+    #[test]
+    fn corrupt_bundles_are_rejected() {
+        let profile = target_profile(None, Some(Codec::Store)).unwrap();
+        let data = encode(&[entry("a.json", b"{\"a\":1}"), entry("b.bin", b"0123456789")], &profile).unwrap();
+        let parse_code = |data: &[u8]| Bundle::parse(data).err().map(|e| e.code);
+
+        assert_eq!(parse_code(&data[..12]), Some(DecodeErrorCode::TooShort));
+        let mut magic = data.clone();
+        magic[7] = b'X';
+        assert_eq!(parse_code(&magic), Some(DecodeErrorCode::BadMagic));
+        let mut version = data.clone();
+        version[8] = 2;
+        assert_eq!(parse_code(&version), Some(DecodeErrorCode::UnsupportedVersion));
+        let mut manifest_len = data.clone();
+        manifest_len[12..16].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(parse_code(&manifest_len), Some(DecodeErrorCode::Truncated));
+        let mut manifest = data.clone();
+        manifest[16] = b'[';
+        assert_eq!(parse_code(&manifest), Some(DecodeErrorCode::InvalidInput));
+
+        // 条目数据被截断或改动
+        let decode_code = |data: &[u8], index: usize| {
+            let bundle = Bundle::parse(data).unwrap();
+            bundle.decode_entry(&bundle.entries[index], |_| Ok(())).err().map(|e| e.code)
+        };
+        assert_eq!(decode_code(&data[..data.len() - 1], 1), Some(DecodeErrorCode::Truncated));
+        let mut flipped = data.clone();
+        let last = flipped.len() - 1;
+        flipped[last] ^= 1;
+        assert_eq!(decode_code(&flipped, 1), Some(DecodeErrorCode::ChecksumMismatch));
+        assert_eq!(decode_code(&flipped, 0), None);
+
+        // 清单中的原始大小与数据不一致
+        let bundle = Bundle::parse(&data).unwrap();
+        let mut wrong_len = bundle.entries[1].clone();
+        wrong_len.original_size = 11;
+        let result = bundle.decode_entry(&wrong_len, |_| Ok(()));
+        assert_eq!(result.err().map(|e| e.code), Some(DecodeErrorCode::LengthMismatch));
+    }
+
+    // 清单声明的原始大小很小而压缩数据解压出大量输出时，解压在超出声明大小时立即中止
+    #[test]
+    fn entry_output_is_capped_at_the_declared_size() {
+        let bomb = vec![0u8; 8 << 20];
+        let entry = BundleEntry {
+            name: "bomb".to_string(),
+            content_type: "binary".to_string(),
+            codec: Codec::Zlib,
+            offset: 0,
+            compressed_size: 0,
+            original_size: 10,
+            crc32: 0,
+        };
+        let mut writer = BundleWriter::default();
+        writer.add_compressed(&entry, &codec::compress(Codec::Zlib, &bomb, 6, 0).unwrap());
+        let data = writer.finish().unwrap();
+
+        let bundle = Bundle::parse(&data).unwrap();
+        let mut largest = 0;
+        let error = bundle
+            .decode_entry(&bundle.entries[0], |len| {
+                largest = largest.max(len);
+                Ok(())
+            })
+            .unwrap_err();
+        assert_eq!(error.code, DecodeErrorCode::LengthMismatch);
+        assert_eq!(largest, 0);
+    }
+}
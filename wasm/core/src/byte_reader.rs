@@ -0,0 +1,117 @@
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::format::{Field, FieldKind};
+
+// 容器头部、元数据区等小端二进制结构的安全读取
+//
+// 所有读取都先检查范围，偏移和长度的加法使用 checked_add，恶意构造的长度字段只会得到 Truncated 错误，
+// 不会越界切片导致 panic。既支持按游标顺序读取，也支持按 format 模块的字段表在绝对偏移处读取。
+// MessagePack / CBOR 等大端格式使用 structured::Reader
+pub struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    // 出错时说明正在读取的结构，如 "容器头部"
+    context: &'static str,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(data: &'a [u8], context: &'static str) -> ByteReader<'a> {
+        ByteReader { data, pos: 0, context }
+    }
+
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    fn truncated(&self, offset: usize, len: usize) -> DecodeError {
+        DecodeError::new(
+            DecodeErrorCode::Truncated,
+            format!(
+                "{}不完整: 偏移 {} 处需要 {} 字节，数据只有 {} 字节",
+                self.context,
+                offset,
+                len,
+                self.data.len()
+            ),
+        )
+    }
+
+    // [offset, offset + len) 区间，不移动游标
+    pub fn slice(&self, offset: usize, len: usize) -> Result<&'a [u8], DecodeError> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or_else(|| self.truncated(offset, len))
+    }
+
+    // offset 之后的全部数据
+    pub fn rest_from(&self, offset: usize) -> Result<&'a [u8], DecodeError> {
+        self.data.get(offset..).ok_or_else(|| self.truncated(offset, 0))
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let bytes = self.slice(self.pos, len)?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    // offset 处的小端 u32 / u64，不移动游标
+    pub fn u32_at(&self, offset: usize) -> Result<u32, DecodeError> {
+        Ok(le_uint(self.slice(offset, 4)?) as u32)
+    }
+
+    pub fn u64_at(&self, offset: usize) -> Result<u64, DecodeError> {
+        Ok(le_uint(self.slice(offset, 8)?))
+    }
+
+    // 1 / 2 / 4 / 8 字节的小端无符号整数
+    pub fn uint(&mut self, size: usize) -> Result<u64, DecodeError> {
+        Ok(le_uint(self.take(size)?))
+    }
+
+    // 按字段表读取字段的原始字节 (字段偏移相对数据开头)
+    pub fn field_bytes(&self, field: &Field) -> Result<&'a [u8], DecodeError> {
+        self.slice(field.offset, field.size)
+    }
+
+    // 按字段表读取整数字段 (不超过 32 位)
+    pub fn field(&self, field: &Field) -> Result<u32, DecodeError> {
+        match field.kind {
+            FieldKind::U8 | FieldKind::U16 | FieldKind::U32 => Ok(le_uint(self.field_bytes(field)?) as u32),
+            FieldKind::U64 | FieldKind::Bytes => Err(DecodeError::new(
+                DecodeErrorCode::Internal,
+                format!("字段 {} 不能按 u32 读取", field.name),
+            )),
+        }
+    }
+
+    // 按字段表读取整数字段，支持 u64
+    pub fn field_u64(&self, field: &Field) -> Result<u64, DecodeError> {
+        match field.kind {
+            FieldKind::U64 => Ok(le_uint(self.field_bytes(field)?)),
+            _ => self.field(field).map(u64::from),
+        }
+    }
+}
+
+fn le_uint(bytes: &[u8]) -> u64 {
+    bytes.iter().rev().fold(0, |value, &byte| (value << 8) | byte as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn absolute_reads_are_bounds_checked() {
+        let data = [1, 0, 0, 0, 2, 0, 0, 0, 3];
+        let reader = ByteReader::new(&data, "测试数据");
+        assert_eq!(reader.u32_at(4).unwrap(), 2);
+        assert_eq!(reader.u64_at(0).unwrap(), 2 << 32 | 1);
+        assert_eq!(reader.u32_at(6).unwrap_err().code, DecodeErrorCode::Truncated);
+        assert_eq!(reader.u64_at(usize::MAX - 2).unwrap_err().code, DecodeErrorCode::Truncated);
+    }
+}
@@ -0,0 +1,322 @@
+use crate::error::DecodeError;
+use crate::structured::{Reader, Value, MAX_DEPTH};
+
+// CBOR 载荷解码 (RFC 8949)
+//
+// 载荷为单个 CBOR 数据项，支持不定长的字节串、文本、数组和映射。标签按以下方式处理:
+// 2 / 3 (大整数) 转为数字，自描述标签 55799 和日期、URI 等语义标签输出其内容，
+// 其他标签输出为 { type: 标签号, data: 内容 }。undefined 按 null 输出。
+//
+// 没有使用 ciborium: 它的 Value 保留全部标签而不区分语义，大整数也要另行从字节串转换，
+// 输出约定仍需在其上再实现一遍；这里直接产生 structured::Value，长度字段在分配之前按剩余数据
+// 检查，嵌套层数上限与 MessagePack 相同。测试用 RFC 8949 附录 A 的示例和 ciborium
+// 编码的数据对照解码结果
+const TAG_POSITIVE_BIGNUM: u64 = 2;
+const TAG_NEGATIVE_BIGNUM: u64 = 3;
+const TAG_SELF_DESCRIBE: u64 = 55799;
+// 内容本身即可表达含义的标签: 日期时间 (0 / 1)、编码提示 (21-23)、URI 等 (32-36)
+const TRANSPARENT_TAGS: &[u64] = &[0, 1, 21, 22, 23, 32, 33, 34, 35, 36, TAG_SELF_DESCRIBE];
+
+const MAJOR_UINT: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const MAJOR_SIMPLE: u8 = 7;
+
+const INDEFINITE: u8 = 31;
+const BREAK: u8 = 0xff;
+
+pub fn decode(payload: &[u8]) -> Result<Value, DecodeError> {
+    let mut reader = Reader::new(payload, "CBOR");
+    let value = read_value(&mut reader, 0)?;
+    if reader.remaining() > 0 {
+        return Err(reader.error(format!("数据项之后有 {} 字节多余数据", reader.remaining())));
+    }
+    Ok(value)
+}
+
+// 附加信息对应的参数值，不定长 (31) 返回 None
+fn read_argument(reader: &mut Reader, info: u8) -> Result<Option<u64>, DecodeError> {
+    match info {
+        0..=23 => Ok(Some(info as u64)),
+        24..=27 => Ok(Some(reader.uint(1 << (info - 24))?)),
+        INDEFINITE => Ok(None),
+        _ => Err(reader.error(format!("保留的附加信息 {}", info))),
+    }
+}
+
+fn read_value(reader: &mut Reader, depth: usize) -> Result<Value, DecodeError> {
+    if depth > MAX_DEPTH {
+        return Err(reader.error(format!("嵌套超过 {} 层", MAX_DEPTH)));
+    }
+    let initial = reader.u8()?;
+    if initial == BREAK {
+        return Err(reader.error("不定长数据项之外出现结束标记"));
+    }
+    let (major, info) = (initial >> 5, initial & 0x1f);
+
+    if major == MAJOR_SIMPLE {
+        return read_simple(reader, info);
+    }
+    let argument = read_argument(reader, info)?;
+    match (major, argument) {
+        (MAJOR_UINT, Some(value)) => Ok(Value::UInt(value)),
+        (MAJOR_NEGATIVE, Some(value)) => Ok(match i64::try_from(value) {
+            Ok(value) => Value::Int(-1 - value),
+            Err(_) => Value::Float(-1.0 - value as f64),
+        }),
+        (MAJOR_BYTES, _) => Ok(Value::Bytes(read_string_bytes(reader, MAJOR_BYTES, argument)?)),
+        (MAJOR_TEXT, _) => {
+            let bytes = read_string_bytes(reader, MAJOR_TEXT, argument)?;
+            String::from_utf8(bytes).map(Value::Str).map_err(|e| reader.utf8_error(e))
+        }
+        (MAJOR_ARRAY, Some(count)) => {
+            let count = reader.length(count, 1)?;
+            let mut items = Vec::with_capacity(count);
+            for _ in 0..count {
+                items.push(read_value(reader, depth + 1)?);
+            }
+            Ok(Value::Array(items))
+        }
+        (MAJOR_ARRAY, None) => {
+            let mut items = Vec::new();
+            while !read_break(reader)? {
+                items.push(read_value(reader, depth + 1)?);
+            }
+            Ok(Value::Array(items))
+        }
+        (MAJOR_MAP, Some(count)) => {
+            let count = reader.length(count, 2)?;
+            let mut entries = Vec::with_capacity(count);
+            for _ in 0..count {
+                entries.push((read_value(reader, depth + 1)?, read_value(reader, depth + 1)?));
+            }
+            Ok(Value::Map(entries))
+        }
+        (MAJOR_MAP, None) => {
+            let mut entries = Vec::new();
+            while !read_break(reader)? {
+                entries.push((read_value(reader, depth + 1)?, read_value(reader, depth + 1)?));
+            }
+            Ok(Value::Map(entries))
+        }
+        // 标签内容经 read_tagged 再递归，每层占两个栈帧，按两层计入嵌套深度
+        (MAJOR_TAG, Some(tag)) => read_tagged(reader, tag, depth + 1),
+        _ => Err(reader.error(format!("主类型 {} 不能使用不定长编码", major))),
+    }
+}
+
+// 不定长数据项中下一个字节为结束标记时消费它并返回 true
+fn read_break(reader: &mut Reader) -> Result<bool, DecodeError> {
+    if reader.peek()? == BREAK {
+        reader.u8()?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+// 字节串 / 文本的内容，不定长时拼接各个同类型的定长分段
+fn read_string_bytes(reader: &mut Reader, major: u8, argument: Option<u64>) -> Result<Vec<u8>, DecodeError> {
+    if let Some(len) = argument {
+        let len = reader.length(len, 1)?;
+        return Ok(reader.take(len)?.to_vec());
+    }
+    let mut bytes = Vec::new();
+    while !read_break(reader)? {
+        let initial = reader.u8()?;
+        let len = match read_argument(reader, initial & 0x1f)? {
+            Some(len) if initial >> 5 == major => reader.length(len, 1)?,
+            _ => return Err(reader.error("不定长字符串的分段类型无效")),
+        };
+        bytes.extend_from_slice(reader.take(len)?);
+    }
+    Ok(bytes)
+}
+
+fn read_simple(reader: &mut Reader, info: u8) -> Result<Value, DecodeError> {
+    match info {
+        20 => Ok(Value::Bool(false)),
+        21 => Ok(Value::Bool(true)),
+        22 | 23 => Ok(Value::Null),
+        25 => Ok(Value::Float(f16_to_f64(reader.uint(2)? as u16))),
+        26 => Ok(Value::Float(f32::from_bits(reader.uint(4)? as u32) as f64)),
+        27 => Ok(Value::Float(f64::from_bits(reader.uint(8)?))),
+        24 => {
+            let value = reader.u8()?;
+            Err(reader.error(format!("不支持的简单值 {}", value)))
+        }
+        _ => Err(reader.error(format!("不支持的简单值 {}", info))),
+    }
+}
+
+fn read_tagged(reader: &mut Reader, tag: u64, depth: usize) -> Result<Value, DecodeError> {
+    let content = read_value(reader, depth + 1)?;
+    match (tag, content) {
+        (TAG_POSITIVE_BIGNUM | TAG_NEGATIVE_BIGNUM, Value::Bytes(bytes)) => {
+            let magnitude = bignum(&bytes);
+            Ok(match (tag, magnitude) {
+                (TAG_POSITIVE_BIGNUM, Some(value)) => Value::UInt(value),
+                (TAG_NEGATIVE_BIGNUM, Some(value)) if i64::try_from(value).is_ok() => Value::Int(-1 - value as i64),
+                _ => {
+                    let value = bytes.iter().fold(0.0, |value, &byte| value * 256.0 + byte as f64);
+                    Value::Float(if tag == TAG_POSITIVE_BIGNUM { value } else { -1.0 - value })
+                }
+            })
+        }
+        (tag, content) if TRANSPARENT_TAGS.contains(&tag) => Ok(content),
+        (tag, content) => match i64::try_from(tag) {
+            Ok(tag) => Ok(Value::Ext(tag, Box::new(content))),
+            Err(_) => Ok(content),
+        },
+    }
+}
+
+// 大整数字节串 (大端) 能放入 u64 时返回其值
+fn bignum(bytes: &[u8]) -> Option<u64> {
+    let start = bytes.iter().position(|&byte| byte != 0).unwrap_or(bytes.len());
+    let significant = &bytes[start..];
+    (significant.len() <= 8).then(|| significant.iter().fold(0, |value, &byte| (value << 8) | byte as u64))
+}
+
+// IEEE 754 半精度浮点
+fn f16_to_f64(bits: u16) -> f64 {
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = (bits & 0x3ff) as f64;
+    let magnitude = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        0x1f if mantissa == 0.0 => f64::INFINITY,
+        0x1f => f64::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f64.powi(exponent as i32 - 15),
+    };
+    if bits & 0x8000 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DecodeErrorCode;
+
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len()).step_by(2).map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap()).collect()
+    }
+
+    fn value(text: &str) -> Value {
+        decode(&hex(text)).unwrap_or_else(|e| panic!("{}: {}", text, e.message))
+    }
+
+    fn code(text: &str) -> Option<DecodeErrorCode> {
+        decode(&hex(text)).err().map(|e| e.code)
+    }
+
+    // RFC 8949 附录 A
+    #[test]
+    fn matches_rfc_examples() {
+        let text = |s: &str| Value::Str(s.to_string());
+        let cases = [
+            ("17", Value::UInt(23)),
+            ("1903e8", Value::UInt(1000)),
+            ("1bffffffffffffffff", Value::UInt(u64::MAX)),
+            ("c249010000000000000000", Value::Float(18446744073709551616.0)),
+            ("3bffffffffffffffff", Value::Float(-18446744073709551616.0)),
+            ("c349010000000000000000", Value::Float(-18446744073709551617.0)),
+            ("3903e7", Value::Int(-1000)),
+            ("f93c00", Value::Float(1.0)),
+            ("f97bff", Value::Float(65504.0)),
+            ("f90001", Value::Float(5.960464477539063e-8)),
+            ("f9c400", Value::Float(-4.0)),
+            ("f97c00", Value::Float(f64::INFINITY)),
+            ("fa47c35000", Value::Float(100000.0)),
+            ("fb3ff199999999999a", Value::Float(1.1)),
+            ("f4", Value::Bool(false)),
+            ("f7", Value::Null),
+            ("c074323031332d30332d32315432303a30343a30305a", text("2013-03-21T20:04:00Z")),
+            ("d82076687474703a2f2f7777772e6578616d706c652e636f6d", text("http://www.example.com")),
+            ("d818456449455446", Value::Ext(24, Box::new(Value::Bytes(b"dIETF".to_vec())))),
+            ("5f42010243030405ff", Value::Bytes(vec![1, 2, 3, 4, 5])),
+            ("7f657374726561646d696e67ff", text("streaming")),
+            (
+                "9f018202039f0405ffff",
+                Value::Array(vec![
+                    Value::UInt(1),
+                    Value::Array(vec![Value::UInt(2), Value::UInt(3)]),
+                    Value::Array(vec![Value::UInt(4), Value::UInt(5)]),
+                ]),
+            ),
+            (
+                "bf61610161629f0203ffff",
+                Value::Map(vec![
+                    (text("a"), Value::UInt(1)),
+                    (text("b"), Value::Array(vec![Value::UInt(2), Value::UInt(3)])),
+                ]),
+            ),
+        ];
+        for (encoded, expected) in cases {
+            assert_eq!(value(encoded), expected, "{}", encoded);
+        }
+        assert!(matches!(value("f97e00"), Value::Float(nan) if nan.is_nan()));
+        assert!(matches!(value("f98000"), Value::Float(zero) if zero == 0.0 && zero.is_sign_negative()));
+    }
+
+    // ciborium 编码的 JSON 值解码后与原值一致
+    #[test]
+    fn matches_reference_encoder() {
+        let document = serde_json::json!({
+            "name": "scene",
+            "nodes": [0, 23, 24, 255, 256, 65535, 65536, 4294967296u64],
+            "negative": [-1, -24, -25, -256, -257, -4294967297i64],
+            "floats": [0.5, -1.25, 1e300],
+            "flags": [true, false, null],
+            "long": "x".repeat(300),
+            "nested": { "a": { "b": { "c": [] } } },
+        });
+        let mut encoded = Vec::new();
+        ciborium::into_writer(&document, &mut encoded).unwrap();
+        assert_eq!(serde_json::to_value(decode(&encoded).unwrap()).unwrap(), document);
+    }
+
+    #[test]
+    fn malformed_items_are_rejected() {
+        for encoded in [
+            "", "19", "1a0000", "636162", "830102", "a16161", "5f4101", "c2",
+            // 保留的附加信息、不定长整数、多余的结束标记、分段类型不一致、未知简单值
+            "1c", "1f", "ff", "5f6161ff", "f0", "f818",
+            // 数据项之后的多余数据
+            "0101",
+        ] {
+            assert_eq!(code(encoded), Some(DecodeErrorCode::InvalidInput), "{}", encoded);
+        }
+        assert_eq!(code("62c328"), Some(DecodeErrorCode::Utf8Error));
+        assert_eq!(code("7f61c36128ff"), Some(DecodeErrorCode::Utf8Error));
+    }
+
+    // 声明的长度超过剩余数据时在分配之前报错
+    #[test]
+    fn oversized_lengths_are_rejected() {
+        for encoded in ["9bffffffffffffffff", "bbffffffffffffffff", "5bffffffffffffffff", "7a7fffffff00", "9a00010000", "a301020304"] {
+            assert_eq!(code(encoded), Some(DecodeErrorCode::InvalidInput), "{}", encoded);
+        }
+    }
+
+    #[test]
+    fn nesting_depth_is_limited() {
+        let nested = |prefix: u8, depth: usize| {
+            let mut payload = vec![prefix; depth];
+            payload.push(0xf6);
+            payload
+        };
+        assert!(decode(&nested(0x81, MAX_DEPTH)).is_ok());
+        assert!(decode(&nested(0x81, MAX_DEPTH + 1)).is_err());
+        // 不定长数组同样计入层数
+        // 标签每层按两层计 (0xd8 0xd8 为标签 216)
+        assert!(decode(&nested(0xd8, MAX_DEPTH)).is_ok());
+        assert!(decode(&nested(0xd8, MAX_DEPTH + 2)).is_err());
+        assert!(decode(&nested(0x9f, 100_000)).is_err());
+    }
+}
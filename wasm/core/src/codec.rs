@@ -0,0 +1,1348 @@
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use flate2::write::ZlibEncoder;
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress, Status};
+use serde::{Deserialize, Serialize};
+use std::io::{Cursor, Read, Write};
+use std::rc::Rc;
+
+use crate::buffer_pool;
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// 压缩编解码器标识 (对应扩展头部中的 codec 字节)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Codec {
+    Zlib = 0,
+    Brotli = 1,
+    // LZ4 帧格式: 压缩率低于 zlib，但解压速度快数倍，适合对延迟敏感的场景 (如 WebXR 流式加载)
+    Lz4 = 2,
+    // 不压缩直接存储: 小 JSON 载荷经 zlib 压缩后反而变大
+    Store = 3,
+    // Zstandard 帧格式: 压缩率接近 brotli，解压速度接近 lz4，支持压缩字典
+    Zstd = 4,
+}
+
+impl Codec {
+    pub const ALL: [Codec; 5] = [Codec::Zlib, Codec::Brotli, Codec::Lz4, Codec::Store, Codec::Zstd];
+
+    pub fn from_byte(byte: u8) -> Result<Codec, DecodeError> {
+        match byte {
+            0 => Ok(Codec::Zlib),
+            1 => Ok(Codec::Brotli),
+            2 => Ok(Codec::Lz4),
+            3 => Ok(Codec::Store),
+            4 => Ok(Codec::Zstd),
+            other => Err(DecodeError::new(
+                DecodeErrorCode::UnsupportedCodec,
+                format!("不支持的压缩编码: {}", other),
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Codec::Zlib => "zlib",
+            Codec::Brotli => "brotli",
+            Codec::Lz4 => "lz4",
+            Codec::Store => "store",
+            Codec::Zstd => "zstd",
+        }
+    }
+
+    // 每字节压缩数据最多能解压出的字节数: deflate 为 1032 (258 字节的匹配最少占 2 位)，
+    // lz4 为 255 (匹配长度每个扩展字节加 255)，zstd 为 32768 (每块最多解压出 128KB，最短的 RLE 块占 4 字节)。
+    // brotli 的单个元块可以用几个字节描述 16MB 的重复，没有实用的上界
+    pub fn max_ratio(&self) -> Option<usize> {
+        match self {
+            Codec::Zlib => Some(1032),
+            Codec::Brotli => None,
+            Codec::Lz4 => Some(255),
+            Codec::Store => Some(1),
+            Codec::Zstd => Some(32768),
+        }
+    }
+}
+
+// 解压输出缓冲区预分配的上限，更大的载荷在解压过程中按实际输出增长
+const MAX_PREALLOCATION: usize = 64 * 1024 * 1024;
+
+// 解压输出缓冲区的初始容量。头部声明的原始长度不可信 (可能声明数 GB 而只带几个字节的压缩数据)，
+// 取声明长度、压缩数据按编码最大压缩比能解压出的长度和固定上限三者中的最小值
+pub fn initial_capacity(codec: Codec, compressed_len: usize, original_len: usize) -> usize {
+    let possible = codec.max_ratio().map_or(usize::MAX, |ratio| compressed_len.saturating_mul(ratio));
+    original_len.min(possible).min(MAX_PREALLOCATION)
+}
+
+// 检查原始长度是否可能由 compressed_len 字节的压缩数据解压得到
+pub fn check_expansion(codec: Codec, compressed_len: usize, original_len: u64) -> Result<(), DecodeError> {
+    match codec.max_ratio() {
+        Some(ratio) if original_len > (compressed_len as u64).saturating_mul(ratio as u64) => Err(DecodeError::new(
+            DecodeErrorCode::LengthMismatch,
+            format!(
+                "原始数据长度 {} 字节超出 {} 字节的 {} 压缩数据可能解压出的大小",
+                original_len,
+                compressed_len,
+                codec.name()
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+// 解压输出超出头部声明的原始长度
+pub fn length_exceeded(original_len: usize) -> DecodeError {
+    DecodeError::new(
+        DecodeErrorCode::LengthMismatch,
+        format!("解压后数据超出声明的长度 {} 字节", original_len),
+    )
+}
+
+// 解压失败的错误
+pub fn inflate_error(codec: Codec, error: impl std::fmt::Display) -> DecodeError {
+    DecodeError::new(
+        DecodeErrorCode::InflateError,
+        format!("解压缩失败 ({}): {}", codec.name(), error),
+    )
+}
+
+// DEFLATE 数据的封装格式
+//
+// 部分旧管线在容器内写入的是 gzip 而非 zlib，因此 zlib 编码下先嗅探封装格式:
+// zlib 头部、gzip 魔数，都不匹配时按原始 deflate 流处理
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DeflateWrapper {
+    Zlib,
+    Gzip,
+    Raw,
+}
+
+impl DeflateWrapper {
+    fn detect(data: &[u8]) -> DeflateWrapper {
+        if data.len() >= 2 {
+            if data[0] == 0x1f && data[1] == 0x8b {
+                return DeflateWrapper::Gzip;
+            }
+
+            // zlib 头部: CM = 8 (deflate), CINFO <= 7, 且 CMF/FLG 组成的 16 位值是 31 的倍数
+            let cmf = data[0];
+            let flg = data[1];
+            if cmf & 0x0f == 8 && cmf >> 4 <= 7 && ((cmf as u16) << 8 | flg as u16).is_multiple_of(31) {
+                return DeflateWrapper::Zlib;
+            }
+        }
+        DeflateWrapper::Raw
+    }
+}
+
+// 每个解压分片的大小，分片边界处调用回调 (用于超时检查等协作式控制)
+const SLICE_SIZE: u64 = 64 * 1024;
+
+enum SliceError {
+    Io(std::io::Error),
+    Aborted(DecodeError),
+}
+
+// 按编码分片解压数据, original_len 用于预分配输出缓冲区 (见 initial_capacity)。
+// 每解压出一个分片调用一次 on_slice(已解压字节数)，回调返回错误时立即中止
+pub fn decompress_sliced<F>(
+    codec: Codec,
+    compressed: &[u8],
+    original_len: usize,
+    mut on_slice: F,
+) -> Result<Vec<u8>, DecodeError>
+where
+    F: FnMut(usize) -> Result<(), DecodeError>,
+{
+    let mut decompressed = buffer_pool::take(initial_capacity(codec, compressed.len(), original_len));
+    let out = &mut decompressed;
+
+    let wrapper = DeflateWrapper::detect(compressed);
+    let result = match read_sliced(open_reader(codec, wrapper, compressed), out, &mut on_slice) {
+        // 原始 deflate 流的前两个字节也可能碰巧满足 zlib 头部校验，失败时再按原始流重试
+        Err(SliceError::Io(_)) if codec == Codec::Zlib && wrapper == DeflateWrapper::Zlib => {
+            out.clear();
+            read_sliced(open_reader(codec, DeflateWrapper::Raw, compressed), out, &mut on_slice)
+        }
+        other => other,
+    };
+
+    match result {
+        Ok(()) => Ok(decompressed),
+        Err(SliceError::Io(e)) => Err(inflate_error(codec, e)),
+        Err(SliceError::Aborted(error)) => Err(error),
+    }
+}
+
+// 只解压开头最多 limit 字节 (用于轻量校验等探测场景)
+pub fn decompress_prefix(codec: Codec, compressed: &[u8], limit: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut prefix = Vec::with_capacity(initial_capacity(codec, compressed.len(), limit));
+    let wrapper = DeflateWrapper::detect(compressed);
+    let result = match open_reader(codec, wrapper, compressed).take(limit as u64).read_to_end(&mut prefix) {
+        Err(_) if codec == Codec::Zlib && wrapper == DeflateWrapper::Zlib => {
+            prefix.clear();
+            open_reader(codec, DeflateWrapper::Raw, compressed)
+                .take(limit as u64)
+                .read_to_end(&mut prefix)
+        }
+        other => other,
+    };
+    result
+        .map(|_| prefix)
+        .map_err(|e| inflate_error(codec, e))
+}
+
+// 推送式解压器
+//
+// 压缩数据按到达顺序分块写入，立即解压到内部输出缓冲区，无需先缓存完整的压缩数据
+// (流式解码时峰值内存约为解压后大小，而不是压缩 + 解压后大小)。
+// 输出在写入缓冲区之前检查累计长度上限和剩余内存预算，超出时立即中止，不会先解压整个数据块。
+pub struct StreamInflater {
+    codec: Codec,
+    state: PushState,
+    // 引用压缩字典的载荷缓存到结束时一次性解压
+    dictionary: Option<Rc<[u8]>>,
+    limit: Option<usize>,
+    budget: Option<usize>,
+}
+
+// 疑似 zlib 头部的数据在解压出内容前保留原始字节，失败时可按原始 deflate 流重试
+const ZLIB_RETRY_LIMIT: usize = 64 * 1024;
+
+enum PushState {
+    // zlib 编码在收到前两个字节之前无法判断封装格式
+    Sniffing(Vec<u8>),
+    Zlib {
+        decoder: flate2::write::ZlibDecoder<OutputSink>,
+        retained: Option<Vec<u8>>,
+    },
+    Gzip(flate2::write::GzDecoder<OutputSink>),
+    Deflate(flate2::write::DeflateDecoder<OutputSink>),
+    Brotli(Box<brotli::DecompressorWriter<OutputSink>>),
+    Zstd(Box<ZstdWriter>),
+    // lz4 帧没有推送式解码器，先缓存压缩数据，结束时一次性解压
+    Buffered(Vec<u8>),
+    Store(OutputSink),
+}
+
+// 推送式解码器的输出缓冲区
+//
+// 解码器按内部缓冲区大小 (几 KB 到几十 KB) 分批写入输出，每批写入前检查累计输出长度和剩余预算，
+// 超出时记录错误并让解码器的写入失败。
+struct OutputSink {
+    buffer: Vec<u8>,
+    // 累计输出的字节数 (包括已取出的部分)
+    written: usize,
+    limit: Option<usize>,
+    budget: Option<usize>,
+    exceeded: Option<DecodeError>,
+}
+
+impl OutputSink {
+    fn new(limit: Option<usize>, budget: Option<usize>) -> OutputSink {
+        OutputSink {
+            buffer: Vec::new(),
+            written: 0,
+            limit,
+            budget,
+            exceeded: None,
+        }
+    }
+
+    fn check(&self, len: usize) -> Result<(), DecodeError> {
+        if let Some(limit) = self.limit.filter(|&limit| self.written.saturating_add(len) > limit) {
+            return Err(length_exceeded(limit));
+        }
+        match self.budget {
+            Some(budget) if len > budget => Err(budget_exceeded(budget)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Write for OutputSink {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        if let Err(error) = self.check(data.len()) {
+            self.exceeded = Some(error);
+            return Err(std::io::Error::other("解压输出超出上限"));
+        }
+        self.buffer.extend_from_slice(data);
+        self.written += data.len();
+        if let Some(budget) = self.budget.as_mut() {
+            *budget -= data.len();
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+// 解压输出超出调用方给出的剩余内存预算
+fn budget_exceeded(budget: usize) -> DecodeError {
+    DecodeError::new(
+        DecodeErrorCode::QuotaExceeded,
+        format!("内存配额不足: 解压输出超出剩余的 {} bytes 配额", budget),
+    )
+}
+
+impl StreamInflater {
+    pub fn new(codec: Codec) -> StreamInflater {
+        let state = match codec {
+            Codec::Zlib => PushState::Sniffing(Vec::new()),
+            Codec::Brotli => {
+                PushState::Brotli(Box::new(brotli::DecompressorWriter::new(OutputSink::new(None, None), 4096)))
+            }
+            Codec::Lz4 => PushState::Buffered(Vec::new()),
+            Codec::Store => PushState::Store(OutputSink::new(None, None)),
+            // 无法创建解压上下文 (内存不足) 时退回到缓存后一次性解压，错误在 finish 时报告
+            Codec::Zstd => match zstd::stream::raw::Decoder::new() {
+                Ok(decoder) => PushState::Zstd(Box::new(ZstdWriter::new(OutputSink::new(None, None), decoder))),
+                Err(_) => PushState::Buffered(Vec::new()),
+            },
+        };
+        StreamInflater {
+            codec,
+            state,
+            dictionary: None,
+            limit: None,
+            budget: None,
+        }
+    }
+
+    pub fn with_dictionary(codec: Codec, dictionary: Rc<[u8]>) -> StreamInflater {
+        StreamInflater {
+            codec,
+            state: PushState::Buffered(Vec::new()),
+            dictionary: Some(dictionary),
+            limit: None,
+            budget: None,
+        }
+    }
+
+    // 累计解压输出的上限 (声明的原始长度或压缩数据可能解压出的最大长度)，超出时以 LengthMismatch 失败
+    pub fn set_output_limit(&mut self, limit: usize) {
+        self.limit = Some(limit);
+        if let Some(sink) = self.sink_mut() {
+            sink.limit = Some(limit);
+        }
+    }
+
+    // 之后最多还能输出的字节数 (剩余内存配额)，超出时以 QuotaExceeded 失败，None 表示不限制
+    pub fn set_output_budget(&mut self, budget: Option<usize>) {
+        self.budget = budget;
+        if let Some(sink) = self.sink_mut() {
+            sink.budget = budget;
+        }
+    }
+
+    fn sink_mut(&mut self) -> Option<&mut OutputSink> {
+        match &mut self.state {
+            PushState::Zlib { decoder, .. } => Some(decoder.get_mut()),
+            PushState::Gzip(decoder) => Some(decoder.get_mut()),
+            PushState::Deflate(decoder) => Some(decoder.get_mut()),
+            PushState::Brotli(decoder) => Some(decoder.get_mut()),
+            PushState::Zstd(decoder) => Some(decoder.writer_mut()),
+            PushState::Store(sink) => Some(sink),
+            PushState::Sniffing(_) | PushState::Buffered(_) => None,
+        }
+    }
+
+    fn new_sink(&self) -> OutputSink {
+        OutputSink::new(self.limit, self.budget)
+    }
+
+    // 输出超出上限或预算时返回记录的错误，其他失败按解压错误报告
+    fn check_output(&mut self, result: std::io::Result<()>) -> Result<(), DecodeError> {
+        if let Some(error) = self.sink_mut().and_then(|sink| sink.exceeded.take()) {
+            return Err(error);
+        }
+        result.map_err(|e| inflate_error(self.codec, e))
+    }
+
+    pub fn write(&mut self, chunk: &[u8]) -> Result<(), DecodeError> {
+        let result = self.push(chunk);
+        self.check_output(result)
+    }
+
+    fn push(&mut self, chunk: &[u8]) -> std::io::Result<()> {
+        match &mut self.state {
+            PushState::Sniffing(pending) => {
+                pending.extend_from_slice(chunk);
+                if pending.len() < 2 {
+                    return Ok(());
+                }
+                let pending = std::mem::take(pending);
+                self.state = match DeflateWrapper::detect(&pending) {
+                    DeflateWrapper::Zlib => PushState::Zlib {
+                        decoder: flate2::write::ZlibDecoder::new(self.new_sink()),
+                        retained: Some(Vec::new()),
+                    },
+                    DeflateWrapper::Gzip => PushState::Gzip(flate2::write::GzDecoder::new(self.new_sink())),
+                    DeflateWrapper::Raw => PushState::Deflate(flate2::write::DeflateDecoder::new(self.new_sink())),
+                };
+                self.push(&pending)
+            }
+            PushState::Zlib { decoder, retained } => {
+                if let Some(retained) = retained.as_mut() {
+                    retained.extend_from_slice(chunk);
+                }
+                match decoder.write_all(chunk) {
+                    Ok(()) => {
+                        // 已解压出内容或保留数据过多时不再支持重试
+                        let too_large = retained.as_ref().is_some_and(|r| r.len() > ZLIB_RETRY_LIMIT);
+                        if decoder.get_ref().written > 0 || too_large {
+                            *retained = None;
+                        }
+                        Ok(())
+                    }
+                    // 输出超出上限不是格式错误，不按原始流重试
+                    Err(e) if decoder.get_ref().exceeded.is_some() => Err(e),
+                    Err(e) => match retained.take() {
+                        // 原始 deflate 流的前两个字节也可能碰巧满足 zlib 头部校验，按原始流重新开始
+                        Some(retained) => {
+                            let mut decoder = flate2::write::DeflateDecoder::new(OutputSink::new(self.limit, self.budget));
+                            let result = decoder.write_all(&retained);
+                            self.state = PushState::Deflate(decoder);
+                            result
+                        }
+                        None => Err(e),
+                    },
+                }
+            }
+            PushState::Gzip(decoder) => decoder.write_all(chunk),
+            PushState::Deflate(decoder) => decoder.write_all(chunk),
+            PushState::Brotli(decoder) => decoder.write_all(chunk),
+            PushState::Zstd(decoder) => decoder.write_all(chunk),
+            PushState::Store(sink) => sink.write_all(chunk),
+            PushState::Buffered(buffer) => {
+                buffer.extend_from_slice(chunk);
+                Ok(())
+            }
+        }
+    }
+
+    // 当前在内部缓存的字节数 (解压输出和尚未解压的压缩数据)
+    pub fn buffered_len(&self) -> usize {
+        match &self.state {
+            PushState::Sniffing(pending) => pending.len(),
+            PushState::Zlib { decoder, retained } => {
+                decoder.get_ref().buffer.len() + retained.as_ref().map_or(0, |retained| retained.len())
+            }
+            PushState::Gzip(decoder) => decoder.get_ref().buffer.len(),
+            PushState::Deflate(decoder) => decoder.get_ref().buffer.len(),
+            PushState::Brotli(decoder) => decoder.get_ref().buffer.len(),
+            PushState::Zstd(decoder) => decoder.writer().buffer.len(),
+            PushState::Store(sink) => sink.buffer.len(),
+            PushState::Buffered(buffer) => buffer.len(),
+        }
+    }
+
+    // 取出目前已解压的输出 (lz4 在 finish 之前没有输出)
+    pub fn take_output(&mut self) -> Vec<u8> {
+        self.sink_mut().map(|sink| std::mem::take(&mut sink.buffer)).unwrap_or_default()
+    }
+
+    // 结束输入并返回尚未取出的解压输出。结束时才一次性解压的数据按 original_len (尚未取出的输出长度) 限制
+    pub fn finish(mut self, original_len: usize) -> Result<Vec<u8>, DecodeError> {
+        let codec = self.codec;
+        let io_error = |e: std::io::Error| inflate_error(codec, e);
+        let finished = match &mut self.state {
+            PushState::Zlib { decoder, .. } => decoder.try_finish(),
+            PushState::Gzip(decoder) => decoder.try_finish(),
+            PushState::Deflate(decoder) => decoder.try_finish(),
+            PushState::Brotli(decoder) => decoder.close(),
+            // finish 在最后一帧不完整时返回错误
+            PushState::Zstd(decoder) => decoder.finish(),
+            _ => Ok(()),
+        };
+        if let Some(error) = self.sink_mut().and_then(|sink| sink.exceeded.take()) {
+            return Err(error);
+        }
+
+        let budget = self.budget;
+        let cap = |len: usize| {
+            if len > original_len {
+                return Err(length_exceeded(original_len));
+            }
+            match budget {
+                Some(budget) if len > budget => Err(budget_exceeded(budget)),
+                _ => Ok(()),
+            }
+        };
+        match self.state {
+            PushState::Sniffing(pending) => decompress_sliced(codec, &pending, original_len, cap),
+            PushState::Zlib { mut decoder, retained } => match (finished, retained) {
+                (Ok(()), _) => Ok(std::mem::take(&mut decoder.get_mut().buffer)),
+                (Err(_), Some(retained)) => decompress_sliced(codec, &retained, original_len, cap),
+                (Err(e), None) => Err(io_error(e)),
+            },
+            PushState::Gzip(mut decoder) => finished.map(|_| std::mem::take(&mut decoder.get_mut().buffer)).map_err(io_error),
+            PushState::Deflate(mut decoder) => {
+                finished.map(|_| std::mem::take(&mut decoder.get_mut().buffer)).map_err(io_error)
+            }
+            PushState::Brotli(decoder) => {
+                finished.map_err(io_error)?;
+                decoder.into_inner().map(|sink| sink.buffer).map_err(|_| inflate_error(codec, "数据不完整"))
+            }
+            PushState::Zstd(decoder) => {
+                finished.map_err(io_error)?;
+                Ok(decoder.into_inner().0.buffer)
+            }
+            PushState::Buffered(buffer) => match &self.dictionary {
+                // 字典解压按 original_len 限制输出，预算不足以容纳声明的长度时直接失败
+                Some(dictionary) => {
+                    cap(original_len)?;
+                    decompress_with_dictionary(codec, &buffer, original_len, dictionary)
+                }
+                None => decompress_sliced(codec, &buffer, original_len, cap),
+            },
+            PushState::Store(sink) => Ok(sink.buffer),
+        }
+    }
+}
+
+// zstd 推送式解码器 (write::Decoder 不报告末尾不完整的帧，直接使用底层的 zio::Writer)
+type ZstdWriter = zstd::stream::zio::Writer<OutputSink, zstd::stream::raw::Decoder<'static>>;
+
+// 按编码创建解压读取器，wrapper 仅对 zlib 编码有效
+fn open_reader<'a, R: Read + 'a>(codec: Codec, wrapper: DeflateWrapper, input: R) -> Box<dyn Read + 'a> {
+    match codec {
+        Codec::Zlib => match wrapper {
+            DeflateWrapper::Zlib => Box::new(ZlibDecoder::new(input)),
+            DeflateWrapper::Gzip => Box::new(GzDecoder::new(input)),
+            DeflateWrapper::Raw => Box::new(DeflateDecoder::new(input)),
+        },
+        Codec::Brotli => Box::new(brotli::Decompressor::new(input, 4096)),
+        Codec::Lz4 => Box::new(Lz4Frames::new(input)),
+        // 存储模式无需解压，按分片复制以保持回调语义一致
+        Codec::Store => Box::new(input),
+        // 多个连续的帧 (分块编码) 依次解压；无法创建解压上下文时在读取时报告错误
+        Codec::Zstd => match zstd::stream::read::Decoder::new(input) {
+            Ok(decoder) => Box::new(decoder),
+            Err(e) => Box::new(FailedReader(Some(e))),
+        },
+    }
+}
+
+// 创建失败的读取器，第一次读取时返回创建时的错误
+struct FailedReader(Option<std::io::Error>);
+
+impl Read for FailedReader {
+    fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+        Err(self.0.take().unwrap_or_else(|| std::io::Error::other("读取器不可用")))
+    }
+}
+
+// 共享所有权的数据区间，供持有输入数据的解压器使用
+#[derive(Clone)]
+struct SharedRange {
+    data: Rc<Vec<u8>>,
+    start: usize,
+    end: usize,
+}
+
+impl AsRef<[u8]> for SharedRange {
+    fn as_ref(&self) -> &[u8] {
+        &self.data[self.start..self.end]
+    }
+}
+
+// 可逐片推进的解压器
+//
+// 持有输入数据的所有权，每次 step() 解压一个分片后返回，调用方可以在分片之间
+// 让出事件循环 (异步解码、ChunkedDecoder 等协作式场景)。
+pub struct SliceDecoder {
+    codec: Codec,
+    wrapper: DeflateWrapper,
+    input: SharedRange,
+    reader: Box<dyn Read>,
+    output: Vec<u8>,
+    // 头部声明的原始长度，输出超出时中止
+    original_len: usize,
+    slice_size: u64,
+    finished: bool,
+}
+
+impl SliceDecoder {
+    // data 为完整输入，压缩数据位于 [start, end) 区间
+    pub fn new(codec: Codec, data: Rc<Vec<u8>>, start: usize, end: usize, original_len: usize) -> SliceDecoder {
+        let input = SharedRange { data, start, end };
+        let wrapper = DeflateWrapper::detect(input.as_ref());
+        SliceDecoder {
+            codec,
+            wrapper,
+            reader: open_reader(codec, wrapper, Cursor::new(input.clone())),
+            output: buffer_pool::take(initial_capacity(codec, end - start, original_len)),
+            input,
+            original_len,
+            slice_size: SLICE_SIZE,
+            finished: false,
+        }
+    }
+
+    // 已经解压完成的解码器 (一次性解压的载荷，如引用压缩字典的容器)
+    pub fn decoded(output: Vec<u8>) -> SliceDecoder {
+        let input = SharedRange {
+            data: Rc::new(Vec::new()),
+            start: 0,
+            end: 0,
+        };
+        SliceDecoder {
+            codec: Codec::Store,
+            wrapper: DeflateWrapper::Raw,
+            input,
+            reader: Box::new(std::io::empty()),
+            original_len: output.len(),
+            output,
+            slice_size: SLICE_SIZE,
+            finished: true,
+        }
+    }
+
+    // 设置每次 step() 解压的最大字节数，0 表示使用默认分片大小
+    pub fn set_slice_size(&mut self, size: u32) {
+        self.slice_size = if size == 0 { SLICE_SIZE } else { size as u64 };
+    }
+
+    // 解压下一个分片，返回 true 表示已全部解压完成
+    pub fn step(&mut self) -> Result<bool, DecodeError> {
+        if self.finished {
+            return Ok(true);
+        }
+        match (&mut self.reader).take(self.slice_size).read_to_end(&mut self.output) {
+            Ok(0) => {
+                self.finished = true;
+                Ok(true)
+            }
+            Ok(_) if self.output.len() > self.original_len => Err(length_exceeded(self.original_len)),
+            Ok(_) => Ok(false),
+            // 与 decompress_sliced 相同: 疑似 zlib 头部解压失败时按原始 deflate 流重新开始
+            Err(_) if self.codec == Codec::Zlib && self.wrapper == DeflateWrapper::Zlib => {
+                self.wrapper = DeflateWrapper::Raw;
+                self.reader = open_reader(self.codec, self.wrapper, Cursor::new(self.input.clone()));
+                self.output.clear();
+                Ok(false)
+            }
+            Err(e) => Err(inflate_error(self.codec, e)),
+        }
+    }
+
+    // 已解压的字节数
+    pub fn output_len(&self) -> usize {
+        self.output.len()
+    }
+
+    pub fn into_output(self) -> Vec<u8> {
+        self.output
+    }
+}
+
+// 连续多个 LZ4 帧的读取器 (分块编码每块为一个帧)
+//
+// FrameDecoder 在每个帧结束时返回 0，输入尚未读完时继续读取下一帧
+struct Lz4Frames<R: Read> {
+    decoder: lz4_flex::frame::FrameDecoder<TrackEof<R>>,
+}
+
+impl<R: Read> Lz4Frames<R> {
+    fn new(input: R) -> Lz4Frames<R> {
+        Lz4Frames {
+            decoder: lz4_flex::frame::FrameDecoder::new(TrackEof { inner: input, eof: false }),
+        }
+    }
+}
+
+impl<R: Read> Read for Lz4Frames<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            let read = self.decoder.read(buf)?;
+            if read > 0 || buf.is_empty() || self.decoder.get_ref().eof {
+                return Ok(read);
+            }
+        }
+    }
+}
+
+// 记录底层输入是否已读到末尾
+struct TrackEof<R: Read> {
+    inner: R,
+    eof: bool,
+}
+
+impl<R: Read> Read for TrackEof<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read == 0 && !buf.is_empty() {
+            self.eof = true;
+        }
+        Ok(read)
+    }
+}
+
+fn read_sliced<R, F>(mut reader: R, out: &mut Vec<u8>, on_slice: &mut F) -> Result<(), SliceError>
+where
+    R: Read,
+    F: FnMut(usize) -> Result<(), DecodeError>,
+{
+    loop {
+        let read = (&mut reader)
+            .take(SLICE_SIZE)
+            .read_to_end(out)
+            .map_err(SliceError::Io)?;
+        if read == 0 {
+            return Ok(());
+        }
+        on_slice(out.len()).map_err(SliceError::Aborted)?;
+    }
+}
+
+// 可复用的 deflate 解压状态
+//
+// 解压器内部状态约几十 KB，连续解码大量小载荷时反复分配的开销远大于解压本身，
+// 因此由调用方持有一个实例，在每次解码前重置复用。
+pub struct Inflater {
+    state: Decompress,
+}
+
+impl Inflater {
+    pub fn new() -> Inflater {
+        Inflater {
+            state: Decompress::new(true),
+        }
+    }
+
+    // 追加解压到 out，输出超出 original_len 时立即中止。缓冲区写满时按分片增长，
+    // 每次最多增长到比 original_len 多 1 字节 (用于发现超出)
+    fn inflate(
+        &mut self,
+        compressed: &[u8],
+        zlib_header: bool,
+        original_len: usize,
+        out: &mut Vec<u8>,
+    ) -> Result<(), SliceError> {
+        self.state.reset(zlib_header);
+        let limit = out.len().saturating_add(original_len);
+        loop {
+            if out.len() > limit {
+                return Err(SliceError::Aborted(length_exceeded(original_len)));
+            }
+            if out.len() == out.capacity() {
+                out.reserve_exact((SLICE_SIZE as usize).min(limit + 1 - out.len()));
+            }
+            let consumed = self.state.total_in() as usize;
+            let produced = out.len();
+            let status = self
+                .state
+                .decompress_vec(&compressed[consumed..], out, FlushDecompress::None)
+                .map_err(|e| SliceError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+            match status {
+                Status::StreamEnd if out.len() > limit => return Err(SliceError::Aborted(length_exceeded(original_len))),
+                Status::StreamEnd => return Ok(()),
+                // 输入已用完、输出仍有空间却没有进展: 数据在流结束前截断
+                _ if self.state.total_in() as usize == consumed && out.len() == produced && out.len() < out.capacity() => {
+                    return Err(SliceError::Io(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "压缩数据不完整")));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Default for Inflater {
+    fn default() -> Inflater {
+        Inflater::new()
+    }
+}
+
+// 解压到调用方提供的缓冲区 (追加写入)，zlib/原始 deflate 复用 inflater 的状态，
+// 其他编码走常规解压流程。与 decompress_sliced 相同，输出超出头部声明的 original_len 时立即中止
+pub fn decompress_into(
+    codec: Codec,
+    compressed: &[u8],
+    original_len: usize,
+    out: &mut Vec<u8>,
+    inflater: &mut Inflater,
+) -> Result<(), DecodeError> {
+    let start = out.len();
+    let result = match (codec, DeflateWrapper::detect(compressed)) {
+        (Codec::Zlib, DeflateWrapper::Zlib) => match inflater.inflate(compressed, true, original_len, out) {
+            Err(SliceError::Io(_)) => {
+                out.truncate(start);
+                inflater.inflate(compressed, false, original_len, out)
+            }
+            other => other,
+        },
+        (Codec::Zlib, DeflateWrapper::Raw) => inflater.inflate(compressed, false, original_len, out),
+        (Codec::Store, _) if compressed.len() > original_len => Err(SliceError::Aborted(length_exceeded(original_len))),
+        (Codec::Store, _) => {
+            out.extend_from_slice(compressed);
+            Ok(())
+        }
+        _ => {
+            // decompress_sliced 的错误信息已包含编码名称
+            let decompressed = decompress_sliced(codec, compressed, original_len, |len| {
+                if len > original_len {
+                    return Err(length_exceeded(original_len));
+                }
+                Ok(())
+            })?;
+            out.extend_from_slice(&decompressed);
+            buffer_pool::recycle(decompressed);
+            return Ok(());
+        }
+    };
+    match result {
+        Ok(()) => Ok(()),
+        Err(SliceError::Io(e)) => Err(inflate_error(codec, e)),
+        Err(SliceError::Aborted(error)) => Err(error),
+    }
+}
+
+// 解压到调用方提供的定长缓冲区，返回写入的字节数。
+// 解压输出超出缓冲区长度时返回错误 (调用方按头部声明的原始长度划定缓冲区)
+pub fn decompress_to_slice(codec: Codec, compressed: &[u8], out: &mut [u8]) -> Result<usize, DecodeError> {
+    let wrapper = DeflateWrapper::detect(compressed);
+    match fill_slice(open_reader(codec, wrapper, compressed), out) {
+        // 与 decompress_sliced 相同: 疑似 zlib 头部解压失败时按原始 deflate 流重试
+        Err(_) if codec == Codec::Zlib && wrapper == DeflateWrapper::Zlib => {
+            fill_slice(open_reader(codec, DeflateWrapper::Raw, compressed), out)
+        }
+        other => other,
+    }
+    .map_err(|e| inflate_error(codec, e))?
+    .ok_or_else(|| length_exceeded(out.len()))
+}
+
+// 读满缓冲区或读到末尾，读满后仍有数据时返回 None
+fn fill_slice(mut reader: impl Read, out: &mut [u8]) -> std::io::Result<Option<usize>> {
+    let mut filled = 0;
+    while filled < out.len() {
+        match reader.read(&mut out[filled..]) {
+            Ok(0) => return Ok(Some(filled)),
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    let mut probe = [0u8; 1];
+    match reader.read(&mut probe)? {
+        0 => Ok(Some(filled)),
+        _ => Ok(None),
+    }
+}
+
+// 按编码压缩数据
+//
+// level: zlib 为 0-9, brotli 为 0-11, zstd 为 1-22 (0 表示默认级别 3), lz4/store 忽略
+// block_size: brotli 窗口大小 / lz4 帧块大小 (字节)，0 表示使用编码默认值，zlib/zstd/store 忽略
+pub fn compress(codec: Codec, data: &[u8], level: u32, block_size: u32) -> Result<Vec<u8>, String> {
+    let mut deflater = StreamDeflater::new(codec, level, block_size)?;
+    deflater.write(data)?;
+    deflater.finish()
+}
+
+// 推送式压缩器
+//
+// 载荷分块写入，压缩输出可随时取出，用于不预先知道载荷长度的单遍流式编码。
+// 参数含义与 compress 相同，一次写入全部数据时输出与 compress 逐字节一致
+pub struct StreamDeflater {
+    codec: Codec,
+    state: DeflateState,
+}
+
+enum DeflateState {
+    Zlib(ZlibEncoder<Vec<u8>>),
+    Brotli(Box<brotli::CompressorWriter<Vec<u8>>>),
+    Lz4(lz4_flex::frame::FrameEncoder<Vec<u8>>),
+    Store(Vec<u8>),
+    Zstd(zstd::stream::write::Encoder<'static, Vec<u8>>),
+}
+
+impl StreamDeflater {
+    pub fn new(codec: Codec, level: u32, block_size: u32) -> Result<StreamDeflater, String> {
+        let state = match codec {
+            Codec::Zlib => DeflateState::Zlib(ZlibEncoder::new(Vec::new(), Compression::new(level.min(9)))),
+            Codec::Brotli => {
+                // 窗口大小取 log2，brotli 允许 10-24
+                let lgwin = match block_size {
+                    0 => 22,
+                    size => (32 - size.leading_zeros()).saturating_sub(1).clamp(10, 24),
+                };
+                DeflateState::Brotli(Box::new(brotli::CompressorWriter::new(Vec::new(), 4096, level.min(11), lgwin)))
+            }
+            Codec::Lz4 => {
+                let frame_block_size = match block_size {
+                    0 => lz4_flex::frame::BlockSize::Auto,
+                    size if size <= 64 * 1024 => lz4_flex::frame::BlockSize::Max64KB,
+                    size if size <= 256 * 1024 => lz4_flex::frame::BlockSize::Max256KB,
+                    size if size <= 1024 * 1024 => lz4_flex::frame::BlockSize::Max1MB,
+                    _ => lz4_flex::frame::BlockSize::Max4MB,
+                };
+                // 帧参数全部显式指定，不依赖 lz4_flex 的默认值 (确定性编码要求输出不随库版本变化)；
+                // 独立块模式同时是并行解压的前提
+                let frame_info = lz4_flex::frame::FrameInfo::new()
+                    .block_size(frame_block_size)
+                    .block_mode(lz4_flex::frame::BlockMode::Independent)
+                    .block_checksums(false)
+                    .content_checksum(false)
+                    .content_size(None);
+                DeflateState::Lz4(lz4_flex::frame::FrameEncoder::with_frame_info(frame_info, Vec::new()))
+            }
+            Codec::Store => DeflateState::Store(Vec::new()),
+            Codec::Zstd => DeflateState::Zstd(zstd_encoder(level, &[])?),
+        };
+        Ok(StreamDeflater { codec, state })
+    }
+
+    pub fn write(&mut self, chunk: &[u8]) -> Result<(), String> {
+        let result = match &mut self.state {
+            DeflateState::Zlib(encoder) => encoder.write_all(chunk),
+            DeflateState::Brotli(encoder) => encoder.write_all(chunk),
+            DeflateState::Lz4(encoder) => encoder.write_all(chunk),
+            DeflateState::Zstd(encoder) => encoder.write_all(chunk),
+            DeflateState::Store(buffer) => {
+                buffer.extend_from_slice(chunk);
+                Ok(())
+            }
+        };
+        result.map_err(|e| format!("压缩失败 ({}): {}", self.codec.name(), e))
+    }
+
+    // 取出目前已产生的压缩输出 (编码器内部缓存的部分在之后的写入或 finish 时输出)
+    pub fn take_output(&mut self) -> Vec<u8> {
+        match &mut self.state {
+            DeflateState::Zlib(encoder) => std::mem::take(encoder.get_mut()),
+            DeflateState::Brotli(encoder) => std::mem::take(encoder.get_mut()),
+            DeflateState::Lz4(encoder) => std::mem::take(encoder.get_mut()),
+            DeflateState::Zstd(encoder) => std::mem::take(encoder.get_mut()),
+            DeflateState::Store(buffer) => std::mem::take(buffer),
+        }
+    }
+
+    // 结束数据流并返回尚未取出的压缩输出
+    pub fn finish(self) -> Result<Vec<u8>, String> {
+        let result = match self.state {
+            DeflateState::Zlib(encoder) => encoder.finish(),
+            DeflateState::Brotli(encoder) => Ok(encoder.into_inner()),
+            DeflateState::Lz4(encoder) => encoder.finish().map_err(std::io::Error::other),
+            DeflateState::Zstd(encoder) => encoder.finish(),
+            DeflateState::Store(buffer) => Ok(buffer),
+        };
+        result.map_err(|e| format!("压缩失败 ({}): {}", self.codec.name(), e))
+    }
+}
+
+// zstd 压缩器。帧参数全部显式指定 (同 lz4)：不写校验和、字典 ID 和内容长度，
+// 内容长度在流式编码时未知，写入与否会使一次性压缩和分块写入的输出不一致
+fn zstd_encoder(level: u32, dictionary: &[u8]) -> Result<zstd::stream::write::Encoder<'static, Vec<u8>>, String> {
+    let level = match level {
+        0 => zstd::DEFAULT_COMPRESSION_LEVEL,
+        level => level.min(22) as i32,
+    };
+    let error = |e: std::io::Error| format!("压缩失败 (zstd): {}", e);
+    let mut encoder = zstd::stream::write::Encoder::with_dictionary(Vec::new(), level, dictionary).map_err(error)?;
+    encoder.include_checksum(false).map_err(error)?;
+    encoder.include_dictid(false).map_err(error)?;
+    encoder.include_contentsize(false).map_err(error)?;
+    Ok(encoder)
+}
+
+// 可随机访问的分块压缩
+//
+// 载荷按 block_size 切块，每块独立压缩 (不引用之前块的数据)，拼接后仍是该编码的合法数据流，
+// 常规解码流程不受影响: zlib 在块边界做 full flush (重置压缩字典)，lz4/zstd 每块为一个独立的帧，
+// store 直接拼接。brotli 数据流无法这样切分。返回压缩数据和每块压缩数据的结束偏移
+pub fn compress_blocks(
+    codec: Codec,
+    data: &[u8],
+    level: u32,
+    block_size: usize,
+) -> Result<(Vec<u8>, Vec<u32>), String> {
+    if block_size == 0 {
+        return Err("块大小必须大于 0".to_string());
+    }
+    // 空载荷也写入一个空块，zlib 需要完整的数据流
+    let blocks: Vec<&[u8]> = if data.is_empty() {
+        vec![data]
+    } else {
+        data.chunks(block_size).collect()
+    };
+
+    let mut output = Vec::new();
+    let mut ends = Vec::with_capacity(blocks.len());
+    let mut deflate = Compress::new(Compression::new(level.min(9)), true);
+    for (index, block) in blocks.iter().enumerate() {
+        match codec {
+            Codec::Zlib => {
+                let last = index + 1 == blocks.len();
+                deflate_block(&mut deflate, block, last, &mut output)?;
+            }
+            Codec::Lz4 | Codec::Zstd => output.extend_from_slice(&compress(codec, block, level, block_size as u32)?),
+            Codec::Store => output.extend_from_slice(block),
+            Codec::Brotli => return Err("brotli 编码不支持块索引".to_string()),
+        }
+        ends.push(u32::try_from(output.len()).map_err(|_| "压缩数据超过 4GB".to_string())?);
+    }
+    Ok((output, ends))
+}
+
+// 压缩一个块: 中间的块以 full flush 结束，最后一块结束整个 zlib 数据流
+fn deflate_block(state: &mut Compress, input: &[u8], last: bool, out: &mut Vec<u8>) -> Result<(), String> {
+    let flush = if last { FlushCompress::Finish } else { FlushCompress::Full };
+    let start = state.total_in();
+    loop {
+        out.reserve(input.len() / 2 + 1024);
+        let consumed = (state.total_in() - start) as usize;
+        let status = state
+            .compress_vec(&input[consumed..], out, flush)
+            .map_err(|e| format!("压缩失败 (zlib): {}", e))?;
+        let all_consumed = (state.total_in() - start) as usize == input.len();
+        // 输出缓冲区没有写满说明 flush 已完成
+        let flushed = out.len() < out.capacity();
+        match status {
+            Status::StreamEnd => return Ok(()),
+            _ if !last && all_consumed && flushed => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+// 解压 compress_blocks 生成的单个块，block_len 为该块的未压缩长度。
+// zlib 的第一块带有 2 字节 zlib 头部，之后的块从字节边界开始，按原始 deflate 解压
+pub fn decompress_block(codec: Codec, block: &[u8], first: bool, block_len: usize) -> Result<Vec<u8>, DecodeError> {
+    let output = match codec {
+        Codec::Zlib => {
+            let input = if first { block.get(2..).unwrap_or_default() } else { block };
+            inflate_block(input, block_len, initial_capacity(codec, input.len(), block_len))?
+        }
+        Codec::Lz4 | Codec::Zstd => decompress_sliced(codec, block, block_len, |len| {
+            if len > block_len {
+                return Err(length_exceeded(block_len));
+            }
+            Ok(())
+        })?,
+        Codec::Store => block.to_vec(),
+        Codec::Brotli => {
+            return Err(DecodeError::new(DecodeErrorCode::UnsupportedCodec, "brotli 编码不支持块索引"));
+        }
+    };
+
+    if output.len() != block_len {
+        return Err(DecodeError::new(
+            DecodeErrorCode::LengthMismatch,
+            format!("块解压后长度不匹配: 期望 {}, 实际 {}", block_len, output.len()),
+        ));
+    }
+    Ok(output)
+}
+
+// 按原始 deflate 解压一个 zlib 块，最多输出 block_len 字节。初始容量 capacity 受预分配上限限制，
+// 可能小于 block_len，缓冲区写满时继续增长 (不超过 block_len)
+fn inflate_block(input: &[u8], block_len: usize, capacity: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut state = Decompress::new(false);
+    let mut out = Vec::with_capacity(capacity.min(block_len));
+    while out.len() < block_len {
+        if out.len() == out.capacity() {
+            out.reserve_exact((block_len - out.len()).min(out.capacity().max(SLICE_SIZE as usize)));
+        }
+        let consumed = state.total_in() as usize;
+        let produced = out.len();
+        let status = state
+            .decompress_vec(&input[consumed..], &mut out, FlushDecompress::None)
+            .map_err(|e| inflate_error(Codec::Zlib, e))?;
+        let stalled = state.total_in() as usize == consumed && out.len() == produced;
+        if status == Status::StreamEnd || stalled {
+            break;
+        }
+    }
+    Ok(out)
+}
+
+// 使用外部字典压缩: 字典作为压缩历史，载荷中与字典重复的片段编码为对字典的引用。
+// zstd 为带字典的 zstd 帧 (字典按原始内容使用，不要求 zstd 训练的字典格式)，lz4 为 lz4 块格式 (不带帧头)，
+// 其他编码返回错误。level 含义与 compress 相同
+pub fn compress_with_dictionary(codec: Codec, data: &[u8], level: u32, dictionary: &[u8]) -> Result<Vec<u8>, String> {
+    match codec {
+        Codec::Zstd => {
+            let mut encoder = zstd_encoder(level, dictionary)?;
+            encoder.write_all(data).map_err(|e| format!("压缩失败 (zstd): {}", e))?;
+            encoder.finish().map_err(|e| format!("压缩失败 (zstd): {}", e))
+        }
+        Codec::Lz4 => Ok(lz4_flex::block::compress_with_dict(data, dictionary)),
+        other => Err(format!("{} 编码不支持压缩字典 (请使用 zstd 或 lz4 编码的档案)", other.name())),
+    }
+}
+
+// 带字典的 zstd 解压读取器
+fn zstd_dictionary_reader<'a>(compressed: &'a [u8], dictionary: &[u8]) -> std::io::Result<impl Read + 'a> {
+    zstd::stream::read::Decoder::with_dictionary(std::io::BufReader::new(compressed), dictionary)
+}
+
+// 解压 compress_with_dictionary 生成的数据，original_len 为未压缩长度
+pub fn decompress_with_dictionary(
+    codec: Codec,
+    compressed: &[u8],
+    original_len: usize,
+    dictionary: &[u8],
+) -> Result<Vec<u8>, DecodeError> {
+    match codec {
+        // 块格式按 original_len 一次分配完整输出，先排除压缩数据不可能解压出的长度
+        Codec::Lz4 => {
+            check_expansion(codec, compressed.len(), original_len as u64)?;
+            lz4_flex::block::decompress_with_dict(compressed, original_len, dictionary).map_err(|e| inflate_error(codec, e))
+        }
+        // 帧格式按实际输出增长，多读一个字节判断输出是否超出 original_len
+        Codec::Zstd => {
+            check_expansion(codec, compressed.len(), original_len as u64)?;
+            let mut output = Vec::with_capacity(initial_capacity(codec, compressed.len(), original_len));
+            zstd_dictionary_reader(compressed, dictionary)
+                .and_then(|reader| reader.take(original_len as u64 + 1).read_to_end(&mut output))
+                .map_err(|e| inflate_error(codec, e))?;
+            if output.len() > original_len {
+                return Err(length_exceeded(original_len));
+            }
+            Ok(output)
+        }
+        other => Err(DecodeError::new(
+            DecodeErrorCode::UnsupportedCodec,
+            format!("{} 编码不支持压缩字典", other.name()),
+        )),
+    }
+}
+
+// 只解压 compress_with_dictionary 生成的数据开头最多 limit 字节 (用于轻量校验、区间读取等探测场景)，
+// 不解压之后的数据
+pub fn decompress_prefix_with_dictionary(
+    codec: Codec,
+    compressed: &[u8],
+    original_len: usize,
+    limit: usize,
+    dictionary: &[u8],
+) -> Result<Vec<u8>, DecodeError> {
+    check_expansion(codec, compressed.len(), original_len as u64)?;
+    let limit = limit.min(original_len);
+    match codec {
+        Codec::Lz4 => lz4_block_prefix(compressed, limit, dictionary).map_err(|e| inflate_error(codec, e)),
+        Codec::Zstd => {
+            let mut output = Vec::with_capacity(initial_capacity(codec, compressed.len(), limit));
+            zstd_dictionary_reader(compressed, dictionary)
+                .and_then(|reader| reader.take(limit as u64).read_to_end(&mut output))
+                .map_err(|e| inflate_error(codec, e))?;
+            Ok(output)
+        }
+        other => Err(DecodeError::new(
+            DecodeErrorCode::UnsupportedCodec,
+            format!("{} 编码不支持压缩字典", other.name()),
+        )),
+    }
+}
+
+// 解码 lz4 块格式的开头最多 limit 字节。lz4_flex 只能按完整长度解码整个块，这里逐个序列解码到 limit 为止:
+// 每个序列为 标记字节 (高 4 位字面量长度、低 4 位匹配长度 - 4，15 表示后续字节继续累加)、字面量、
+// 2 字节小端偏移和匹配。偏移超出已输出的数据时引用字典末尾 (字典视为输出之前的历史)
+fn lz4_block_prefix(compressed: &[u8], limit: usize, dictionary: &[u8]) -> Result<Vec<u8>, &'static str> {
+    fn length(compressed: &[u8], pos: &mut usize, base: usize) -> Result<usize, &'static str> {
+        let mut len = base;
+        if base == 15 {
+            loop {
+                let byte = *compressed.get(*pos).ok_or("长度字段不完整")?;
+                *pos += 1;
+                len = len.checked_add(byte as usize).ok_or("长度溢出")?;
+                if byte != 255 {
+                    break;
+                }
+            }
+        }
+        Ok(len)
+    }
+
+    let mut out = Vec::with_capacity(limit.min(MAX_PREALLOCATION));
+    let mut pos = 0;
+    while out.len() < limit {
+        let token = *compressed.get(pos).ok_or("压缩数据不完整")?;
+        pos += 1;
+        let literal_len = length(compressed, &mut pos, (token >> 4) as usize)?;
+        let literals = compressed.get(pos..pos.saturating_add(literal_len)).ok_or("字面量超出压缩数据")?;
+        out.extend_from_slice(&literals[..literals.len().min(limit - out.len())]);
+        pos += literal_len;
+        // 最后一个序列只有字面量
+        if pos == compressed.len() || out.len() >= limit {
+            break;
+        }
+
+        let offset = compressed.get(pos..pos + 2).ok_or("匹配偏移不完整")?;
+        let offset = u16::from_le_bytes([offset[0], offset[1]]) as usize;
+        pos += 2;
+        let match_len = length(compressed, &mut pos, (token & 0x0f) as usize)?.saturating_add(4);
+        if offset == 0 || offset > out.len() + dictionary.len() {
+            return Err("匹配偏移无效");
+        }
+        for _ in 0..match_len.min(limit - out.len()) {
+            let byte = match out.len().checked_sub(offset) {
+                Some(index) => out[index],
+                None => dictionary[dictionary.len() + out.len() - offset],
+            };
+            out.push(byte);
+        }
+    }
+    Ok(out)
+}
+
+// decompress_with_dictionary 的定长缓冲区版本，返回写入的字节数
+pub fn decompress_to_slice_with_dictionary(
+    codec: Codec,
+    compressed: &[u8],
+    out: &mut [u8],
+    dictionary: &[u8],
+) -> Result<usize, DecodeError> {
+    match codec {
+        Codec::Lz4 => {
+            lz4_flex::block::decompress_into_with_dict(compressed, out, dictionary).map_err(|e| inflate_error(codec, e))
+        }
+        Codec::Zstd => zstd_dictionary_reader(compressed, dictionary)
+            .and_then(|reader| fill_slice(reader, out))
+            .map_err(|e| inflate_error(codec, e))?
+            .ok_or_else(|| length_exceeded(out.len())),
+        other => Err(DecodeError::new(
+            DecodeErrorCode::UnsupportedCodec,
+            format!("{} 编码不支持压缩字典", other.name()),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 % 251) as u8 ^ (i >> 10) as u8).collect()
+    }
+
+    fn raw_deflate(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), Compression::new(6));
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    // 输出超出初始容量时缓冲区按需增长，复用的 inflater 每次都从头开始
+    #[test]
+    fn inflater_grows_past_initial_capacity() {
+        let mut inflater = Inflater::new();
+        for len in [0, 1, 65_535, 65_536, 70_000, 1 << 20] {
+            let payload = sample(len);
+            let zlib = compress(Codec::Zlib, &payload, 6, 0).unwrap();
+            for (compressed, capacity) in [(&zlib, 0), (&zlib, 64 * 1024), (&raw_deflate(&payload), 0)] {
+                let mut out = Vec::with_capacity(capacity);
+                decompress_into(Codec::Zlib, compressed, len, &mut out, &mut inflater).unwrap();
+                assert_eq!(out, payload, "{} 字节, 初始容量 {}", len, capacity);
+            }
+        }
+
+        // 追加写入: 已有内容保留，长度上限按本次输出计算
+        let payload = sample(70_000);
+        let mut out = b"prefix".to_vec();
+        decompress_into(Codec::Zlib, &compress(Codec::Zlib, &payload, 6, 0).unwrap(), payload.len(), &mut out, &mut inflater)
+            .unwrap();
+        assert_eq!((&out[..6], &out[6..]), (&b"prefix"[..], &payload[..]));
+    }
+
+    #[test]
+    fn truncated_deflate_streams_are_rejected() {
+        let payload = sample(100_000);
+        let compressed = compress(Codec::Zlib, &payload, 6, 0).unwrap();
+        let mut out = Vec::new();
+        let error = decompress_into(Codec::Zlib, &compressed[..compressed.len() / 2], payload.len(), &mut out, &mut Inflater::new())
+            .unwrap_err();
+        assert_eq!(error.code, DecodeErrorCode::InflateError);
+    }
+
+    // 块大于预分配上限时 (这里用很小的初始容量模拟) 缓冲区继续增长，而不是报告长度不匹配
+    #[test]
+    fn blocks_larger_than_the_preallocation_cap_inflate_fully() {
+        let payload = sample(300_000);
+        let (compressed, ends) = compress_blocks(Codec::Zlib, &payload, 6, 200_000).unwrap();
+        let first = &compressed[..ends[0] as usize];
+        for capacity in [0, 1, 4096, 199_999] {
+            assert_eq!(inflate_block(&first[2..], 200_000, capacity).unwrap(), &payload[..200_000], "{}", capacity);
+        }
+        let second = &compressed[ends[0] as usize..ends[1] as usize];
+        assert_eq!(decompress_block(Codec::Zlib, second, false, 100_000).unwrap(), &payload[200_000..]);
+        // zlib 块最多读出声明的长度，lz4 / zstd 块输出超出声明的长度时立即中止
+        assert_eq!(decompress_block(Codec::Zlib, first, true, 150_000).unwrap(), &payload[..150_000]);
+        for codec in [Codec::Lz4, Codec::Zstd] {
+            let (compressed, ends) = compress_blocks(codec, &payload, 1, 200_000).unwrap();
+            let error = decompress_block(codec, &compressed[..ends[0] as usize], true, 1000).unwrap_err();
+            assert_eq!(error.code, DecodeErrorCode::LengthMismatch, "{}", codec.name());
+        }
+    }
+
+    // 字典压缩数据的前缀解码与完整解码的对应部分一致
+    #[test]
+    fn dictionary_prefixes_match_full_decode() {
+        let dictionary = sample(20_000);
+        let mut payload = sample(50_000);
+        payload.extend_from_slice(&dictionary[5_000..15_000]);
+        payload.extend(std::iter::repeat_n(b'x', 1000));
+        for codec in [Codec::Lz4, Codec::Zstd] {
+            let compressed = compress_with_dictionary(codec, &payload, 3, &dictionary).unwrap();
+            for limit in [0, 1, 15, 4096, 50_000, 55_555, payload.len(), payload.len() + 10] {
+                let prefix = decompress_prefix_with_dictionary(codec, &compressed, payload.len(), limit, &dictionary).unwrap();
+                assert_eq!(prefix, &payload[..limit.min(payload.len())], "{} {}", codec.name(), limit);
+            }
+            let truncated = &compressed[..compressed.len() / 2];
+            let error =
+                decompress_prefix_with_dictionary(codec, truncated, payload.len(), payload.len(), &dictionary).unwrap_err();
+            assert_eq!(error.code, DecodeErrorCode::InflateError, "{}", codec.name());
+        }
+        // 偏移超出字典和已输出的数据
+        let error = decompress_prefix_with_dictionary(Codec::Lz4, &[0x10, b'a', 0x09, 0x00], 100, 100, b"dict").unwrap_err();
+        assert_eq!(error.code, DecodeErrorCode::InflateError);
+    }
+
+    // 声明的原始长度很小而实际解压出大量数据 (解压炸弹) 时，输出只比声明长度多出有限的字节就中止
+    #[test]
+    fn small_declared_length_caps_output() {
+        let bomb = vec![0u8; 8 << 20];
+        let mut inflater = Inflater::new();
+        for codec in Codec::ALL {
+            let compressed = compress(codec, &bomb, 1, 0).unwrap();
+            let mut out = Vec::new();
+            let error = decompress_into(codec, &compressed, 10, &mut out, &mut inflater).unwrap_err();
+            assert_eq!(error.code, DecodeErrorCode::LengthMismatch, "{}", codec.name());
+            assert!(out.capacity() <= 2 * SLICE_SIZE as usize, "{}: {}", codec.name(), out.capacity());
+        }
+    }
+
+    // 推送式解压在输出写入缓冲区之前检查上限和预算，不会先解压完整个数据块
+    #[test]
+    fn stream_output_stops_at_limit_and_budget() {
+        let bomb = vec![0u8; 8 << 20];
+        for codec in Codec::ALL {
+            let compressed = compress(codec, &bomb, 1, 0).unwrap();
+
+            let mut inflater = StreamInflater::new(codec);
+            inflater.set_output_limit(10);
+            let error = inflater.write(&compressed).and_then(|_| inflater.finish(10).map(|_| ())).unwrap_err();
+            assert_eq!(error.code, DecodeErrorCode::LengthMismatch, "{}", codec.name());
+
+            let mut inflater = StreamInflater::new(codec);
+            inflater.set_output_budget(Some(100_000));
+            let written = inflater.write(&compressed);
+            assert!(inflater.buffered_len() <= 100_000 + compressed.len(), "{}", codec.name());
+            let error = written.and_then(|_| inflater.finish(bomb.len()).map(|_| ())).unwrap_err();
+            assert_eq!(error.code, DecodeErrorCode::QuotaExceeded, "{}", codec.name());
+
+            // 上限等于实际长度时正常解压
+            let mut inflater = StreamInflater::new(codec);
+            inflater.set_output_limit(bomb.len());
+            let mut output = Vec::new();
+            for chunk in compressed.chunks(4096) {
+                inflater.write(chunk).unwrap();
+                output.append(&mut inflater.take_output());
+            }
+            output.extend(inflater.finish(bomb.len() - output.len()).unwrap());
+            assert_eq!(output.len(), bomb.len(), "{}", codec.name());
+        }
+    }
+}
@@ -0,0 +1,814 @@
+use crate::byte_reader::ByteReader;
+use crate::codec::{self, Codec};
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::format::{
+    self, v3_header_len, FIXED_HEADER_LEN, FLAG_BLOCK_INDEX, FLAG_DICTIONARY, FLAG_ENCRYPTED, FLAG_FOOTER,
+    FLAG_LONG_LENGTHS, FLAG_PASSWORD, FLAG_PROVENANCE, FOOTER_EXCLUSIVE_FLAGS, SUPPORTED_FLAGS,
+};
+use crate::payload_kind::PayloadKind;
+use crate::{dictionary, encryption, metadata, password, provenance, seek};
+
+// 容器解析
+//
+// 头部、扩展字段、块偏移表、元数据区和原始长度字段的解析，以及载荷的定位、解压和校验。
+// 这里不登记内存配额也不做错误注入，WASM 模块在此基础上包装配额和混沌测试逻辑
+
+// 容器头部信息 (压缩数据之前的固定字段)
+#[derive(Clone)]
+pub struct ContainerHeader {
+    pub version: u32,
+    pub codec: Codec,
+    // 载荷类型 (版本 1/2 由版本号决定)
+    pub kind: PayloadKind,
+    pub payload_offset: usize,
+    pub compressed_len: usize,
+    // 未压缩载荷的 CRC32 (版本 3 且设置了 FLAG_CRC32 时存在)
+    pub crc32: Option<u32>,
+    // 未压缩载荷的 SHA-256 摘要 (版本 3 且设置了 FLAG_SHA256 时存在)
+    pub sha256: Option<[u8; 32]>,
+    // 块索引 (版本 3 且设置了 FLAG_BLOCK_INDEX 时存在)
+    pub block_index: Option<seek::BlockIndex>,
+    // 压缩字典 ID (版本 3 且设置了 FLAG_DICTIONARY 时存在)，解压时才查找字典
+    pub dict_id: Option<u32>,
+    // 64 位长度 (版本 3 且设置了 FLAG_LONG_LENGTHS 时): 原始长度字段为 u64
+    pub long_lengths: bool,
+    // 键值元数据 (版本 3 且设置了 FLAG_METADATA 时非空)
+    pub metadata: metadata::Metadata,
+    // 创建时间和生成工具 (版本 3 且设置了 FLAG_PROVENANCE 时存在)
+    pub provenance: Option<provenance::Provenance>,
+    // 尾部帧格式 (版本 3 且设置了 FLAG_FOOTER 时): 压缩长度、原始长度和 CRC32 位于压缩数据之后的尾部，
+    // 找到尾部之前 compressed_len 为 0
+    pub footer: bool,
+    // 载荷加密参数 (版本 3 且设置了 FLAG_ENCRYPTED 时存在)
+    pub encryption: Option<encryption::Encryption>,
+    // 口令的密钥派生参数 (版本 3 且设置了 FLAG_PASSWORD 时存在)
+    pub kdf: Option<password::KdfParams>,
+}
+
+impl ContainerHeader {
+    // 完整容器的预期大小 (头部 + 压缩数据 + 原始长度字段)，解析头部时已确认不会溢出
+    pub fn total_len(&self) -> usize {
+        self.payload_offset + self.compressed_len + self.trailer_len()
+    }
+
+    // 压缩数据之后的原始长度字段 (或尾部) 的大小
+    pub fn trailer_len(&self) -> usize {
+        if self.footer {
+            format::FOOTER_LEN
+        } else if self.long_lengths {
+            format::ORIGINAL_LEN_LONG.size
+        } else {
+            format::ORIGINAL_LEN.size
+        }
+    }
+
+    // 读取原始长度字段，trailer 从字段起始位置开始。
+    // 压缩数据按编码最大压缩比不可能解压出的长度在此报错，不会传到解压代码
+    pub fn read_original_len(&self, trailer: &[u8]) -> Result<u64, DecodeError> {
+        let reader = ByteReader::new(trailer, "原始长度字段");
+        let original_len = if self.footer {
+            reader.field_u64(&format::FOOTER_ORIGINAL_LEN)?
+        } else if self.long_lengths {
+            reader.field_u64(&format::ORIGINAL_LEN_LONG)?
+        } else {
+            reader.field_u64(&format::ORIGINAL_LEN)?
+        };
+        codec::check_expansion(self.codec, self.compressed_len, original_len)?;
+        Ok(original_len)
+    }
+    
+    pub fn compression_ratio(&self, original_len: u64) -> f32 {
+        // 存储模式下载荷未压缩，压缩比恒为 1.0 (包括空载荷)
+        if self.codec == Codec::Store {
+            return 1.0;
+        }
+        self.compressed_len as f32 / original_len as f32
+    }
+}
+
+// 解压到内存前把头部声明的原始长度转换为 usize，超出平台寻址范围 (wasm32 为 4GB) 时报错，
+// 这样的载荷只能流式解码或按区间读取
+pub fn payload_len(original_len: u64) -> Result<usize, DecodeError> {
+    usize::try_from(original_len).map_err(|_| {
+        DecodeError::new(
+            DecodeErrorCode::QuotaExceeded,
+            format!("原始数据长度 {} 字节超出当前平台的内存寻址范围", original_len),
+        )
+    })
+}
+
+// 判断数据是否已包含完整头部 (流式解码时用于等待扩展字段到达)
+pub fn header_available(data: &[u8]) -> bool {
+    data.len() >= required_header_len(data)
+}
+
+// 按已到达的数据计算完整头部的长度: 固定部分到达后才知道扩展字段的长度，
+// 扩展字段到达后才知道块偏移表和元数据区的长度
+pub fn required_header_len(data: &[u8]) -> usize {
+    let reader = ByteReader::new(data, "容器头部");
+    if data.len() < FIXED_HEADER_LEN || reader.field(&format::VERSION).ok() != Some(3) {
+        return FIXED_HEADER_LEN;
+    }
+    let flags = reader.field(&format::FLAGS).unwrap_or(0) as u16;
+    let extensions_end = v3_header_len(flags);
+    match (format::v3_block_table_len(&reader, flags), format::v3_metadata_len(&reader, flags)) {
+        (Ok(table_len), Ok(metadata_len)) => extensions_end.saturating_add(table_len).saturating_add(metadata_len),
+        // 扩展字段尚未完整到达
+        _ => extensions_end,
+    }
+}
+
+// 解析容器头部，字段布局见 format 模块中的字段表 (describe_format 输出同一组定义)
+//
+// 版本 1/2: 魔数(8) | 版本(4) | 压缩长度(4) | 压缩数据 | 原始长度(4)
+// 版本 3:   魔数(8) | 版本(4) | 编码(1) | 内容类型(1) | 标志(2) | 压缩长度(4) | [扩展字段] | [块偏移表] | [元数据区] | 压缩数据 | 原始长度(4，FLAG_LONG_LENGTHS 时为 8)
+//
+// 尾部帧格式的容器需要在完整数据中查找尾部才能得到压缩长度，只有头部时使用 parse_header_fields
+pub fn parse_container_header(data: &[u8]) -> Result<ContainerHeader, DecodeError> {
+    let header = parse_header_fields(data)?;
+    if header.footer {
+        locate_footer(header, data)
+    } else {
+        Ok(header)
+    }
+}
+
+// 尾部帧格式: 在压缩数据之后查找第一个有效的尾部，补全压缩长度和 CRC32
+pub fn locate_footer(mut header: ContainerHeader, data: &[u8]) -> Result<ContainerHeader, DecodeError> {
+    let payload = ByteReader::new(data, "容器").rest_from(header.payload_offset)?;
+    let position = format::find_footer(payload, 0).ok_or_else(|| {
+        DecodeError::new(DecodeErrorCode::Truncated, "找不到容器尾部 (尾部帧格式的容器需要完整接收)")
+    })?;
+    let footer = ByteReader::new(&payload[position..], "容器尾部");
+    header.compressed_len = position;
+    header.crc32 = Some(footer.field(&format::FOOTER_CRC32)?);
+    Ok(header)
+}
+
+// 只解析头部 (含扩展字段、块偏移表和元数据区)，不需要压缩数据。
+// 尾部帧格式的容器此时还不知道压缩长度和 CRC32，compressed_len 为 0
+pub fn parse_header_fields(data: &[u8]) -> Result<ContainerHeader, DecodeError> {
+    if data.len() < FIXED_HEADER_LEN {
+        return Err(DecodeError::new(DecodeErrorCode::TooShort, "数据太短，不是有效的 FastDog 格式"));
+    }
+    let reader = ByteReader::new(data, "容器头部");
+
+    // 1. 验证魔数 (8字节)
+    let magic = reader.field_bytes(&format::MAGIC)?;
+    if magic != b"FASTDOG1" {
+        return Err(DecodeError::new(DecodeErrorCode::BadMagic, format!("无效的魔数: {:?}", magic)));
+    }
+
+    // 2. 读取版本号 (4字节)
+    let version = reader.field(&format::VERSION)?;
+
+    match version {
+        1 | 2 => Ok(ContainerHeader {
+            version,
+            codec: Codec::Zlib,
+            kind: if version == 1 { PayloadKind::Json } else { PayloadKind::Glb },
+            payload_offset: format::LEGACY_COMPRESSED_LEN.offset + format::LEGACY_COMPRESSED_LEN.size,
+            compressed_len: reader.field(&format::LEGACY_COMPRESSED_LEN)? as usize,
+            crc32: None,
+            sha256: None,
+            block_index: None,
+            dict_id: None,
+            long_lengths: false,
+            metadata: metadata::Metadata::new(),
+            provenance: None,
+            footer: false,
+            encryption: None,
+            kdf: None,
+        }),
+        3 => {
+            // 3. 读取扩展头部: 编码、内容类型、标志
+            let codec = Codec::from_byte(reader.field(&format::CODEC)? as u8)?;
+            let kind = PayloadKind::from_byte(reader.field(&format::CONTENT_TYPE)? as u8)?;
+            let flags = reader.field(&format::FLAGS)? as u16;
+            if flags & !SUPPORTED_FLAGS != 0 {
+                return Err(DecodeError::new(
+                    DecodeErrorCode::UnsupportedFlags,
+                    format!("不支持的头部标志: {:#06x}", flags),
+                ));
+            }
+            // 字典压缩的载荷是一个整体，无法按块随机访问
+            if flags & FLAG_BLOCK_INDEX != 0 && flags & FLAG_DICTIONARY != 0 {
+                return Err(DecodeError::new(DecodeErrorCode::UnsupportedFlags, "块索引与压缩字典不能同时使用"));
+            }
+            // 加密载荷只能整体认证后解密，同样无法按块随机访问
+            if flags & FLAG_BLOCK_INDEX != 0 && flags & FLAG_ENCRYPTED != 0 {
+                return Err(DecodeError::new(DecodeErrorCode::UnsupportedFlags, "块索引与加密不能同时使用"));
+            }
+            if flags & FLAG_PASSWORD != 0 && flags & FLAG_ENCRYPTED == 0 {
+                return Err(DecodeError::new(DecodeErrorCode::UnsupportedFlags, "密码派生参数必须与加密标志同时使用"));
+            }
+            let footer = flags & FLAG_FOOTER != 0;
+            if footer && flags & FOOTER_EXCLUSIVE_FLAGS != 0 {
+                return Err(DecodeError::new(
+                    DecodeErrorCode::UnsupportedFlags,
+                    format!("尾部帧格式不能与 CRC32 / SHA-256 / 块索引 / 64 位长度 / 加密标志同时使用: {:#06x}", flags),
+                ));
+            }
+
+            // 4. 按标志位读取扩展字段
+            let extensions_end = v3_header_len(flags);
+            if data.len() < extensions_end {
+                return Err(DecodeError::new(DecodeErrorCode::Truncated, "头部扩展字段不完整"));
+            }
+            // 尾部帧格式的头部压缩长度字段写为 0，实际长度在尾部
+            let mut compressed_len = if footer { 0 } else { reader.field(&format::COMPRESSED_LEN)? as u64 };
+            let mut crc32 = None;
+            let mut sha256 = None;
+            let mut block_size = 0;
+            let mut dict_id = None;
+            for field in format::v3_extension_fields(flags) {
+                match field.name {
+                    "crc32" => crc32 = Some(reader.field(&field)?),
+                    "sha256" => {
+                        let mut digest = [0u8; 32];
+                        digest.copy_from_slice(reader.field_bytes(&field)?);
+                        sha256 = Some(digest);
+                    }
+                    "block_size" => block_size = reader.field(&field)?,
+                    "dict_id" => dict_id = Some(reader.field(&field)?),
+                    "compressed_len_high" => compressed_len |= (reader.field(&field)? as u64) << 32,
+                    _ => {}
+                }
+            }
+            let provenance = if flags & FLAG_PROVENANCE != 0 {
+                Some(provenance::Provenance::parse(&reader, flags)?)
+            } else {
+                None
+            };
+            let encryption = if flags & FLAG_ENCRYPTED != 0 {
+                Some(encryption::Encryption::parse(&reader, flags)?)
+            } else {
+                None
+            };
+            let kdf = if flags & FLAG_PASSWORD != 0 {
+                Some(password::KdfParams::parse(&reader, flags)?)
+            } else {
+                None
+            };
+            // 5. 块偏移表紧跟扩展字段
+            let table_len = format::v3_block_table_len(&reader, flags)?;
+            let table = reader
+                .slice(extensions_end, table_len)
+                .map_err(|e| e.context("块偏移表不完整"))?;
+            let table_end = extensions_end + table_len;
+
+            // 6. 元数据区紧跟块偏移表
+            let metadata_len = format::v3_metadata_len(&reader, flags)?;
+            let section = reader
+                .slice(table_end, metadata_len)
+                .map_err(|e| e.context("元数据区不完整"))?;
+            let payload_offset = table_end + metadata_len;
+            let metadata = metadata::parse(section)?;
+
+            // 完整容器的长度必须能用 usize 表示，之后的偏移计算不会溢出
+            let long_lengths = flags & FLAG_LONG_LENGTHS != 0;
+            let trailer_len = if long_lengths { format::ORIGINAL_LEN_LONG.size } else { format::ORIGINAL_LEN.size };
+            let compressed_len = usize::try_from(compressed_len)
+                .ok()
+                .filter(|len| len.checked_add(payload_offset + trailer_len).is_some())
+                .ok_or_else(|| {
+                    DecodeError::new(
+                        DecodeErrorCode::QuotaExceeded,
+                        format!("压缩数据长度 {} 字节超出当前平台的内存寻址范围", compressed_len),
+                    )
+                })?;
+            let block_index = if flags & FLAG_BLOCK_INDEX != 0 {
+                Some(seek::BlockIndex::parse(block_size, table, compressed_len)?)
+            } else {
+                None
+            };
+
+            Ok(ContainerHeader {
+                version,
+                codec,
+                kind,
+                payload_offset,
+                compressed_len,
+                crc32,
+                sha256,
+                block_index,
+                dict_id,
+                long_lengths,
+                metadata,
+                provenance,
+                footer,
+                encryption,
+                kdf,
+            })
+        }
+        _ => Err(DecodeError::new(DecodeErrorCode::UnsupportedVersion, format!("不支持的版本: {}", version))),
+    }
+}
+
+// 解压容器载荷: 引用压缩字典的载荷一次性解压 (字典用于小载荷)，其他载荷分片解压。
+// on_slice 在分配或解压出每个分片后以输出长度调用，返回错误即中止
+pub fn decompress_payload<F>(
+    header: &ContainerHeader,
+    compressed: &[u8],
+    original_len: u64,
+    mut on_slice: F,
+) -> Result<Vec<u8>, DecodeError>
+where
+    F: FnMut(usize) -> Result<(), DecodeError>,
+{
+    let original_len = payload_len(original_len)?;
+    match header.dict_id {
+        Some(id) => {
+            let dictionary = dictionary::lookup(id)?;
+            // 字典解压一次分配完整输出，分配前按声明的长度调用回调 (输出限制、内存配额)
+            on_slice(original_len)?;
+            codec::decompress_with_dictionary(header.codec, compressed, original_len, &dictionary)
+        }
+        // 输出超出声明的长度时立即中止，而不是解压完整个炸弹再由 verify_payload 发现
+        None => codec::decompress_sliced(header.codec, compressed, original_len, |len| {
+            if len > original_len {
+                return Err(codec::length_exceeded(original_len));
+            }
+            on_slice(len)
+        }),
+    }
+}
+
+// 只解压载荷开头最多 limit 字节
+pub fn decompress_payload_prefix(
+    header: &ContainerHeader,
+    compressed: &[u8],
+    original_len: u64,
+    limit: usize,
+) -> Result<Vec<u8>, DecodeError> {
+    match header.dict_id {
+        Some(id) => {
+            let dictionary = dictionary::lookup(id)?;
+            codec::decompress_prefix_with_dictionary(header.codec, compressed, payload_len(original_len)?, limit, &dictionary)
+        }
+        None => codec::decompress_prefix(header.codec, compressed, limit),
+    }
+}
+
+// 分片解码器 (异步解码和协作式分块解码使用)，引用压缩字典的载荷在创建时一次性解压
+pub fn open_slice_decoder(
+    header: &ContainerHeader,
+    data: std::rc::Rc<Vec<u8>>,
+    original_len: u64,
+) -> Result<codec::SliceDecoder, DecodeError> {
+    let payload_end = header.payload_offset + header.compressed_len;
+    if header.dict_id.is_some() {
+        let compressed = &data[header.payload_offset..payload_end];
+        let decompressed = decompress_payload(header, compressed, original_len, |_| Ok(()))?;
+        return Ok(codec::SliceDecoder::decoded(decompressed));
+    }
+    Ok(codec::SliceDecoder::new(
+        header.codec,
+        data,
+        header.payload_offset,
+        payload_end,
+        payload_len(original_len)?,
+    ))
+}
+
+// 流式解码的推送式解压器
+pub fn open_stream_inflater(header: &ContainerHeader) -> Result<codec::StreamInflater, DecodeError> {
+    if header.encryption.is_some() {
+        return Err(encrypted_error());
+    }
+    match header.dict_id {
+        Some(id) => Ok(codec::StreamInflater::with_dictionary(header.codec, dictionary::lookup(id)?)),
+        None => Ok(codec::StreamInflater::new(header.codec)),
+    }
+}
+
+// 流式解码时限制推送式解压器的累计输出: 原始长度字段 (或尾部) 已到达时按声明的原始长度，
+// 否则按压缩数据可能解压出的最大长度。尾部帧格式在找到尾部之前不知道压缩长度，不设上限
+pub fn bound_stream_output(
+    header: &ContainerHeader,
+    trailer: &[u8],
+    inflater: &mut codec::StreamInflater,
+) -> Result<(), DecodeError> {
+    if trailer.len() >= header.trailer_len() {
+        let original_len = header.read_original_len(trailer)?;
+        inflater.set_output_limit(payload_len(original_len)?);
+    } else if let Some(ratio) = header.codec.max_ratio().filter(|_| !header.footer) {
+        inflater.set_output_limit(header.compressed_len.saturating_mul(ratio));
+    }
+    Ok(())
+}
+
+// 尾部帧格式的流式输入: pending 保存尚不能确定是否属于尾部的末尾字节 (最多 FOOTER_LEN - 1 字节)，
+// 返回可以送入解压器的压缩数据。找到尾部时 pending 变为完整的尾部，并补全头部的压缩长度和 CRC32，
+// 之后的数据全部忽略
+pub fn split_footer_stream(
+    header: &mut ContainerHeader,
+    pending: &mut Vec<u8>,
+    compressed_received: usize,
+    bytes: &[u8],
+) -> Vec<u8> {
+    if pending.len() == format::FOOTER_LEN {
+        return Vec::new();
+    }
+    let mut window = std::mem::take(pending);
+    window.extend_from_slice(bytes);
+    match format::find_footer(&window, compressed_received) {
+        Some(position) => {
+            let footer = window.split_off(position);
+            header.compressed_len = compressed_received + position;
+            header.crc32 = Some(format::FOOTER_CRC32.read(&footer));
+            *pending = footer[..format::FOOTER_LEN].to_vec();
+            window
+        }
+        None => {
+            let keep = window.len().min(format::FOOTER_LEN - 1);
+            *pending = window.split_off(window.len() - keep);
+            window
+        }
+    }
+}
+
+// 解析头部并定位压缩数据，返回头部、压缩数据和原始长度。加密容器返回 KeyRequired
+pub fn locate_payload(data: &[u8]) -> Result<(ContainerHeader, &[u8], u64), DecodeError> {
+    let located = locate_raw_payload(data)?;
+    if located.0.encryption.is_some() {
+        return Err(encrypted_error());
+    }
+    Ok(located)
+}
+
+pub fn encrypted_error() -> DecodeError {
+    DecodeError::new(DecodeErrorCode::KeyRequired, "容器已加密，请使用 decode_encrypted 并提供密钥")
+}
+
+// 定位压缩数据，加密容器返回的是密文
+pub fn locate_raw_payload(data: &[u8]) -> Result<(ContainerHeader, &[u8], u64), DecodeError> {
+    let header = parse_container_header(data)?;
+    let reader = ByteReader::new(data, "容器");
+
+    // 读取压缩数据
+    let compressed_data = reader
+        .slice(header.payload_offset, header.compressed_len)
+        .map_err(|e| e.context("压缩数据长度超出范围"))?;
+
+    // 读取原始数据长度 (4 或 8 字节) - 用于验证
+    let trailer = reader
+        .slice(header.payload_offset + header.compressed_len, header.trailer_len())
+        .map_err(|e| e.context("缺少原始数据长度字段"))?;
+    let original_len = header.read_original_len(trailer)?;
+    // 整体解码需要把载荷放入内存，超出平台寻址范围的长度在解析时报错；
+    // 带块索引的容器仍可按区间读取
+    if header.block_index.is_none() {
+        payload_len(original_len)?;
+    }
+    Ok((header, compressed_data, original_len))
+}
+
+// 校验解压结果的长度和 CRC32
+pub fn verify_payload(header: &ContainerHeader, original_len: u64, decompressed: &[u8]) -> Result<(), DecodeError> {
+    // 验证解压后的数据长度
+    if decompressed.len() as u64 != original_len {
+        return Err(DecodeError::new(
+            DecodeErrorCode::LengthMismatch,
+            format!("解压后数据长度不匹配: 期望 {}, 实际 {}", original_len, decompressed.len()),
+        ));
+    }
+    
+    // 校验 CRC32，发现载荷中间字节的损坏
+    if let Some(expected) = header.crc32 {
+        let actual = crc32fast::hash(decompressed);
+        if actual != expected {
+            return Err(DecodeError::new(
+                DecodeErrorCode::ChecksumMismatch,
+                format!("CRC32 校验失败: 期望 {:08x}, 实际 {:08x}", expected, actual),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// 解析并解压完整容器 (含长度和 CRC32 核对)，返回头部、原始长度和解压后的数据。加密容器返回 KeyRequired
+pub fn decode(data: &[u8]) -> Result<(ContainerHeader, u64, Vec<u8>), DecodeError> {
+    let (header, compressed, original_len) = locate_payload(data)?;
+    let decompressed = decompress_payload(&header, compressed, original_len, |_| Ok(()))?;
+    verify_payload(&header, original_len, &decompressed)?;
+    Ok((header, original_len, decompressed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder::{encode_container, encode_dictionary_container, encode_legacy_container};
+    use crate::profiles::resolve_profile;
+    use crate::transcode::target_profile;
+
+    // 手工写出的版本 3 存储模式容器 (64 位长度)，原始长度字段可以声明任意值
+    fn long_store_container(payload: &[u8], original_len: u64) -> Vec<u8> {
+        let mut data = b"FASTDOG1".to_vec();
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&[Codec::Store as u8, PayloadKind::Json as u8]);
+        data.extend_from_slice(&FLAG_LONG_LENGTHS.to_le_bytes());
+        data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(payload);
+        data.extend_from_slice(&original_len.to_le_bytes());
+        data
+    }
+
+    // 把容器末尾 4 字节的原始长度字段改为 original_len
+    fn declare_len(mut container: Vec<u8>, original_len: u32) -> Vec<u8> {
+        let at = container.len() - 4;
+        container[at..].copy_from_slice(&original_len.to_le_bytes());
+        container
+    }
+
+    // 44 字节的文件声明 2^62 字节的原始长度: 解码返回错误，而不是按声明长度分配内存后中止进程
+    #[test]
+    fn huge_declared_length_is_not_preallocated() {
+        let data = long_store_container(br#"{"a":[1,23]}"#, 1 << 62);
+        assert_eq!(data.len(), 44);
+        assert_eq!(decode(&data).err().map(|e| e.code), Some(DecodeErrorCode::LengthMismatch));
+        assert_eq!(crate::verify(&data).err().map(|e| e.code), Some(DecodeErrorCode::LengthMismatch));
+    }
+
+    #[test]
+    fn legacy_declared_length_is_not_preallocated() {
+        let container = encode_legacy_container(br#"{"scene":[]}"#, PayloadKind::Json, 6).unwrap();
+        let data = declare_len(container, u32::MAX);
+        assert_eq!(decode(&data).err().map(|e| e.code), Some(DecodeErrorCode::LengthMismatch));
+    }
+
+    // 解压输出超出声明长度时在第一个分片处中止，不解压完整个载荷
+    #[test]
+    fn output_beyond_declared_length_aborts() {
+        let profile = resolve_profile(Some("balanced")).unwrap();
+        let container = encode_container(&vec![0u8; 4 << 20], PayloadKind::Custom, &profile).unwrap();
+        let data = declare_len(container, 100);
+        let (header, compressed, original_len) = locate_payload(&data).unwrap();
+        let mut slices = 0;
+        let result = decompress_payload(&header, compressed, original_len, |_| {
+            slices += 1;
+            Ok(())
+        });
+        assert_eq!(result.err().map(|e| e.code), Some(DecodeErrorCode::LengthMismatch));
+        assert_eq!(slices, 0);
+    }
+
+    // 字典解压一次分配完整输出: 分配前先以声明长度调用回调，不可能的长度直接报错
+    #[test]
+    fn dictionary_length_is_checked_before_allocation() {
+        let dict_id = dictionary::register(b"{\"position\":[0,0,0],\"rotation\":[0,0,0,1]}".to_vec()).unwrap();
+        let profile = resolve_profile(Some("fast")).unwrap();
+        let payload = br#"{"position":[1,2,3],"rotation":[0,0,0,1]}"#;
+        let container = encode_dictionary_container(payload, PayloadKind::Json, dict_id, &profile).unwrap();
+        assert_eq!(decode(&container).unwrap().2, payload);
+
+        // 按 lz4 最大压缩比仍然可能的长度能通过头部解析
+        let (_, compressed, _) = locate_payload(&container).unwrap();
+        let declared = compressed.len() as u32 * 255;
+        let data = declare_len(container.clone(), declared);
+        let (header, compressed, original_len) = locate_payload(&data).unwrap();
+        let mut requested = Vec::new();
+        let quota = |len| {
+            requested.push(len);
+            Err(DecodeError::new(DecodeErrorCode::QuotaExceeded, "配额不足"))
+        };
+        let result = decompress_payload(&header, compressed, original_len, quota);
+        assert_eq!(result.err().map(|e| e.code), Some(DecodeErrorCode::QuotaExceeded));
+        assert_eq!(requested, [declared as usize]);
+        assert_eq!(decode(&data).err().map(|e| e.code), Some(DecodeErrorCode::LengthMismatch));
+        let data = declare_len(container, u32::MAX);
+        assert_eq!(locate_payload(&data).err().map(|e| e.code), Some(DecodeErrorCode::LengthMismatch));
+        dictionary::remove(dict_id);
+    }
+
+    #[test]
+    fn long_lengths_round_trip() {
+        let payload = br#"{"a":[1,23]}"#;
+        let data = long_store_container(payload, payload.len() as u64);
+        let (header, compressed, original_len) = locate_payload(&data).unwrap();
+        assert!(header.long_lengths);
+        assert_eq!(header.total_len(), data.len());
+        assert_eq!((compressed, original_len), (&payload[..], payload.len() as u64));
+        assert_eq!(decode(&data).unwrap().2, payload);
+    }
+
+    // 原始长度字段不完整、压缩长度高 32 位超出数据、64 位长度与尾部帧格式同时设置
+    #[test]
+    fn malformed_long_lengths_are_rejected() {
+        let payload = br#"{"a":[1,23]}"#;
+        let data = long_store_container(payload, payload.len() as u64);
+        let code = |data: &[u8]| locate_payload(data).err().map(|e| e.code);
+
+        assert_eq!(code(&data[..data.len() - 4]), Some(DecodeErrorCode::Truncated));
+
+        let mut high = data.clone();
+        high[20..24].copy_from_slice(&1u32.to_le_bytes());
+        assert!(matches!(code(&high), Some(DecodeErrorCode::Truncated | DecodeErrorCode::QuotaExceeded)));
+
+        let mut footer = data.clone();
+        footer[14..16].copy_from_slice(&(FLAG_LONG_LENGTHS | FLAG_FOOTER).to_le_bytes());
+        assert_eq!(code(&footer), Some(DecodeErrorCode::UnsupportedFlags));
+    }
+
+    // 压缩数据不可能解压出的原始长度在解析头部时报错，不进入解压
+    #[test]
+    fn implausible_long_lengths_are_rejected_at_parse_time() {
+        let payload = br#"{"a":[1,23]}"#;
+        let data = long_store_container(payload, payload.len() as u64 + 1);
+        assert_eq!(locate_payload(&data).err().map(|e| e.code), Some(DecodeErrorCode::LengthMismatch));
+        let inspection = crate::inspect::inspect_container(&data);
+        assert_eq!(inspection.error_code, Some(DecodeErrorCode::LengthMismatch));
+
+        let mut data = long_store_container(payload, u64::MAX);
+        data[12] = Codec::Zlib as u8;
+        assert_eq!(locate_payload(&data).err().map(|e| e.code), Some(DecodeErrorCode::LengthMismatch));
+        assert_eq!(decode(&data).err().map(|e| e.code), Some(DecodeErrorCode::LengthMismatch));
+    }
+
+    // 与 StreamEncoder 输出相同的尾部帧格式容器
+    fn footer_container(codec: Codec, payload: &[u8]) -> Vec<u8> {
+        let compressed = codec::compress(codec, payload, 6, 0).unwrap();
+        let mut data = Vec::new();
+        crate::encoder::write_fixed_header(&mut data, codec, PayloadKind::Json, FLAG_FOOTER, 0);
+        data.extend_from_slice(&compressed);
+        data.extend_from_slice(&(compressed.len() as u64).to_le_bytes());
+        data.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+        data.extend_from_slice(&crc32fast::hash(payload).to_le_bytes());
+        data.extend_from_slice(format::END_MAGIC);
+        data
+    }
+
+    #[test]
+    fn footer_round_trip() {
+        let payload = br#"{"scene":{"nodes":[0,1,2]}}"#;
+        for codec in Codec::ALL {
+            let data = footer_container(codec, payload);
+            let (header, compressed, original_len) = locate_payload(&data).unwrap();
+            assert!(header.footer);
+            assert_eq!(header.total_len(), data.len());
+            assert_eq!(header.crc32, Some(crc32fast::hash(payload)));
+            assert_eq!(compressed.len() + FIXED_HEADER_LEN + format::FOOTER_LEN, data.len());
+            assert_eq!(original_len, payload.len() as u64);
+            assert_eq!(decode(&data).unwrap().2, payload, "{}", codec.name());
+        }
+    }
+
+    // 压缩数据中偶然出现的结束标记: 记录的压缩长度与位置不符，不会被当作尾部
+    #[test]
+    fn end_magic_inside_payload_is_not_a_footer() {
+        let mut payload = br#"{"note":""#.to_vec();
+        payload.extend_from_slice(&[b'x'; 20]);
+        payload.extend_from_slice(format::END_MAGIC);
+        payload.extend_from_slice(br#""}"#);
+        let data = footer_container(Codec::Store, &payload);
+        let compressed = &data[FIXED_HEADER_LEN..data.len() - format::FOOTER_LEN];
+        assert_eq!(format::find_footer(compressed, 0), None);
+        assert_eq!(format::find_footer(&data[FIXED_HEADER_LEN..], 0), Some(payload.len()));
+        assert_eq!(decode(&data).unwrap().2, payload);
+    }
+
+    // 按任意大小分块送入时，尾部跨越块边界也能识别，尾部之后的数据被忽略
+    #[test]
+    fn split_footer_stream_handles_any_chunking() {
+        let payload = br#"{"scene":{"nodes":[0,1,2]}}"#;
+        let mut data = footer_container(Codec::Zlib, payload);
+        let footer = data[data.len() - format::FOOTER_LEN..].to_vec();
+        let compressed = data[FIXED_HEADER_LEN..data.len() - format::FOOTER_LEN].to_vec();
+        data.extend_from_slice(b"trailing");
+        for chunk_size in [1, 3, 27, 28, 29, 1000] {
+            let mut header = parse_header_fields(&data[..FIXED_HEADER_LEN]).unwrap();
+            assert_eq!(header.compressed_len, 0);
+            let (mut pending, mut received) = (Vec::new(), Vec::new());
+            for chunk in data[FIXED_HEADER_LEN..].chunks(chunk_size) {
+                let bytes = split_footer_stream(&mut header, &mut pending, received.len(), chunk);
+                received.extend_from_slice(&bytes);
+            }
+            assert_eq!(received, compressed, "chunk size {}", chunk_size);
+            assert_eq!(pending, footer);
+            assert_eq!(header.compressed_len, compressed.len());
+            assert_eq!(header.crc32, Some(crc32fast::hash(payload)));
+            assert_eq!(header.read_original_len(&pending).unwrap(), payload.len() as u64);
+        }
+    }
+
+    #[test]
+    fn malformed_footers_are_rejected() {
+        let payload = br#"{"scene":{"nodes":[0,1,2]}}"#;
+        let data = footer_container(Codec::Zlib, payload);
+        let code = |data: &[u8]| decode(data).err().map(|e| e.code);
+        let footer_at = data.len() - format::FOOTER_LEN;
+
+        assert_eq!(code(&data[..data.len() - 1]), Some(DecodeErrorCode::Truncated));
+        assert_eq!(code(&data[..FIXED_HEADER_LEN]), Some(DecodeErrorCode::Truncated));
+
+        // 尾部记录的压缩长度与位置不符时找不到尾部
+        let mut moved = data.clone();
+        moved[footer_at] ^= 1;
+        assert_eq!(code(&moved), Some(DecodeErrorCode::Truncated));
+
+        let mut crc = data.clone();
+        crc[footer_at + 16] ^= 1;
+        assert_eq!(code(&crc), Some(DecodeErrorCode::ChecksumMismatch));
+
+        let mut original_len = data.clone();
+        original_len[footer_at + 8] += 1;
+        assert_eq!(code(&original_len), Some(DecodeErrorCode::LengthMismatch));
+
+        let mut flags = data.clone();
+        flags[14..16].copy_from_slice(&(FLAG_FOOTER | format::FLAG_CRC32).to_le_bytes());
+        assert_eq!(code(&flags), Some(DecodeErrorCode::UnsupportedFlags));
+    }
+
+    fn zstd_payload() -> Vec<u8> {
+        (0..2000).flat_map(|i| format!("{{\"id\":{},\"name\":\"node-{}\"}},", i, i % 7).into_bytes()).collect()
+    }
+
+    #[test]
+    fn zstd_round_trip() {
+        let payload = zstd_payload();
+        let profile = target_profile(None, Some(Codec::Zstd)).unwrap();
+        let container = encode_container(&payload, PayloadKind::Json, &profile).unwrap();
+        let (header, compressed, original_len) = locate_payload(&container).unwrap();
+        assert_eq!(header.codec, Codec::Zstd);
+        assert!(compressed.len() < payload.len() / 4);
+        assert_eq!(decode(&container).unwrap().2, payload);
+
+        // 推送式解压逐字节写入与一次性解压结果相同
+        let mut inflater = codec::StreamInflater::new(Codec::Zstd);
+        let mut output = Vec::new();
+        for byte in compressed.chunks(1) {
+            inflater.write(byte).unwrap();
+            output.append(&mut inflater.take_output());
+        }
+        output.extend(inflater.finish(original_len as usize).unwrap());
+        assert_eq!(output, payload);
+    }
+
+    // 原始长度字段与压缩数据一起到达时，流式解压按声明的长度中止
+    #[test]
+    fn stream_output_is_bounded_by_declared_length() {
+        let payload = zstd_payload();
+        let profile = target_profile(None, Some(Codec::Zstd)).unwrap();
+        let container = declare_len(encode_container(&payload, PayloadKind::Json, &profile).unwrap(), 10);
+        let header = parse_header_fields(&container).unwrap();
+        let payload_end = header.payload_offset + header.compressed_len;
+        let mut inflater = open_stream_inflater(&header).unwrap();
+        bound_stream_output(&header, &container[payload_end..], &mut inflater).unwrap();
+        let error = inflater.write(&container[header.payload_offset..payload_end]).unwrap_err();
+        assert_eq!(error.code, DecodeErrorCode::LengthMismatch);
+        assert!(inflater.buffered_len() <= 10);
+
+        // 原始长度字段尚未到达时按最大压缩比限制
+        let mut inflater = open_stream_inflater(&header).unwrap();
+        bound_stream_output(&header, &[], &mut inflater).unwrap();
+        inflater.write(&container[header.payload_offset..payload_end]).unwrap();
+        let mut output = inflater.take_output();
+        output.extend(inflater.finish(payload.len() - output.len()).unwrap());
+        assert_eq!(output, payload);
+    }
+
+    #[test]
+    fn truncated_zstd_frame_is_rejected() {
+        let payload = zstd_payload();
+        let compressed = codec::compress(Codec::Zstd, &payload, 3, 0).unwrap();
+        let truncated = &compressed[..compressed.len() - 3];
+        let result = codec::decompress_sliced(Codec::Zstd, truncated, payload.len(), |_| Ok(()));
+        assert_eq!(result.err().map(|e| e.code), Some(DecodeErrorCode::InflateError));
+
+        let mut inflater = codec::StreamInflater::new(Codec::Zstd);
+        inflater.write(truncated).unwrap();
+        assert_eq!(inflater.finish(payload.len()).err().map(|e| e.code), Some(DecodeErrorCode::InflateError));
+    }
+
+    #[test]
+    fn zstd_dictionary_round_trip() {
+        let dictionary = b"{\"position\":[0,0,0],\"rotation\":[0,0,0,1],\"scale\":[1,1,1]}".to_vec();
+        let dict_id = dictionary::register(dictionary.clone()).unwrap();
+        let payload = br#"{"position":[1,2,3],"rotation":[0,0,0,1],"scale":[1,1,1]}"#;
+        let profile = target_profile(None, Some(Codec::Zstd)).unwrap();
+        let container = encode_dictionary_container(payload, PayloadKind::Json, dict_id, &profile).unwrap();
+        assert_eq!(decode(&container).unwrap().2, payload);
+        // 前缀探测只解压开头的数据
+        let (header, compressed, original_len) = locate_payload(&container).unwrap();
+        let prefix = decompress_payload_prefix(&header, compressed, original_len, 12).unwrap();
+        assert_eq!(prefix, &payload[..12]);
+
+        let with_dictionary = codec::compress_with_dictionary(Codec::Zstd, payload, 3, &dictionary).unwrap();
+        let without = codec::compress(Codec::Zstd, payload, 3, 0).unwrap();
+        assert!(with_dictionary.len() < without.len());
+
+        let mut out = vec![0u8; payload.len()];
+        let written = codec::decompress_to_slice_with_dictionary(Codec::Zstd, &with_dictionary, &mut out, &dictionary);
+        assert_eq!(written.unwrap(), payload.len());
+        assert_eq!(out, payload);
+
+        // 声明长度小于实际输出、字典不同时都报错
+        let data = declare_len(container, 10);
+        assert_eq!(decode(&data).err().map(|e| e.code), Some(DecodeErrorCode::LengthMismatch));
+        let other = codec::decompress_with_dictionary(Codec::Zstd, &with_dictionary, payload.len(), b"other dictionary");
+        assert_ne!(other.ok().as_deref(), Some(&payload[..]));
+        dictionary::remove(dict_id);
+    }
+}
@@ -0,0 +1,41 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// 压缩字典注册表
+//
+// 大量结构相似的小 JSON 瓦片单独压缩时几乎没有可引用的历史数据，压缩率很低。
+// 用同一份字典 (典型瓦片的拼接) 作为压缩历史，每个瓦片只需编码与字典不同的部分。
+// 容器头部只记录字典 ID (字典内容的 CRC32)，字典本身由应用下载一次后注册。
+thread_local! {
+    static DICTIONARIES: RefCell<HashMap<u32, Rc<[u8]>>> = RefCell::new(HashMap::new());
+}
+
+// 字典 ID 对应的字典，未注册时返回 MissingDictionary 错误码
+pub fn lookup(id: u32) -> Result<Rc<[u8]>, DecodeError> {
+    DICTIONARIES
+        .with(|dictionaries| dictionaries.borrow().get(&id).cloned())
+        .ok_or_else(|| {
+            DecodeError::new(
+                DecodeErrorCode::MissingDictionary,
+                format!("压缩字典 {:08x} 未注册，请先调用 set_dictionary", id),
+            )
+        })
+}
+
+// 注册压缩字典，返回字典 ID (字典内容的 CRC32)。可以同时注册多个字典，解码时按容器头部的字典 ID 选择
+pub fn register(dictionary: Vec<u8>) -> Result<u32, String> {
+    if dictionary.is_empty() {
+        return Err("压缩字典不能为空".to_string());
+    }
+    let id = crc32fast::hash(&dictionary);
+    DICTIONARIES.with(|dictionaries| dictionaries.borrow_mut().insert(id, dictionary.into()));
+    Ok(id)
+}
+
+// 注销压缩字典，返回该字典是否已注册
+pub fn remove(id: u32) -> bool {
+    DICTIONARIES.with(|dictionaries| dictionaries.borrow_mut().remove(&id).is_some())
+}
@@ -0,0 +1,473 @@
+use sha2::{Digest, Sha256};
+
+use crate::codec::{self, Codec};
+use crate::format::{
+    FLAG_BLOCK_INDEX, FLAG_CRC32, FLAG_DICTIONARY, FLAG_ENCRYPTED, FLAG_LONG_LENGTHS, FLAG_METADATA,
+    FLAG_PASSWORD, FLAG_PROVENANCE, FLAG_SHA256,
+};
+use crate::metadata::{self, Metadata};
+use crate::password::{self, KdfParams};
+use crate::payload_kind::PayloadKind;
+use crate::profiles::EncoderProfile;
+use crate::provenance::Provenance;
+use crate::seek::BlockIndex;
+use crate::{dictionary, encryption, platform};
+
+// 按档案把载荷编码为版本 3 容器
+pub fn encode_container(
+    payload: &[u8],
+    kind: PayloadKind,
+    profile: &EncoderProfile,
+) -> Result<Vec<u8>, String> {
+    let compressed = codec::compress(profile.codec, payload, profile.level, profile.block_size)?;
+    write_container(payload, &compressed, kind, profile, ContainerExtras::default())
+}
+
+// 编码并在头部写入键值元数据
+pub fn encode_metadata_container(
+    payload: &[u8],
+    kind: PayloadKind,
+    metadata: &Metadata,
+    profile: &EncoderProfile,
+) -> Result<Vec<u8>, String> {
+    let compressed = codec::compress(profile.codec, payload, profile.level, profile.block_size)?;
+    let extras = ContainerExtras { metadata: Some(metadata), ..ContainerExtras::default() };
+    write_container(payload, &compressed, kind, profile, extras)
+}
+
+// 使用已注册的压缩字典编码，档案的编码必须支持字典 (zstd 或 lz4)
+pub fn encode_dictionary_container(
+    payload: &[u8],
+    kind: PayloadKind,
+    dict_id: u32,
+    profile: &EncoderProfile,
+) -> Result<Vec<u8>, String> {
+    let dictionary = dictionary::lookup(dict_id).map_err(|e| e.message)?;
+    let compressed = codec::compress_with_dictionary(profile.codec, payload, profile.level, &dictionary)?;
+    let extras = ContainerExtras { dict_id: Some(dict_id), ..ContainerExtras::default() };
+    write_container(payload, &compressed, kind, profile, extras)
+}
+
+// 按档案分块压缩并写入块索引，用于 decode_range 随机访问
+pub fn encode_seekable_container(
+    payload: &[u8],
+    kind: PayloadKind,
+    block_size: u32,
+    profile: &EncoderProfile,
+) -> Result<Vec<u8>, String> {
+    let (compressed, ends) = codec::compress_blocks(profile.codec, payload, profile.level, block_size as usize)?;
+    let index = BlockIndex { block_size, ends };
+    let extras = ContainerExtras { block_index: Some(&index), ..ContainerExtras::default() };
+    write_container(payload, &compressed, kind, profile, extras)
+}
+
+// 按档案编码并用 AES-256-GCM 加密压缩数据
+pub fn encode_encrypted_container(
+    payload: &[u8],
+    kind: PayloadKind,
+    key: &[u8; 32],
+    profile: &EncoderProfile,
+) -> Result<Vec<u8>, String> {
+    let compressed = codec::compress(profile.codec, payload, profile.level, profile.block_size)?;
+    let extras = ContainerExtras { key: Some(key), ..ContainerExtras::default() };
+    write_container(payload, &compressed, kind, profile, extras)
+}
+
+// 由口令派生密钥后加密，派生参数写入头部。确定性档案的盐由载荷派生，其余档案使用随机盐
+pub fn encode_password_container(
+    payload: &[u8],
+    kind: PayloadKind,
+    password: &str,
+    profile: &EncoderProfile,
+) -> Result<Vec<u8>, String> {
+    if password.is_empty() {
+        return Err("口令不能为空".to_string());
+    }
+    let salt = if profile.deterministic {
+        let digest = Sha256::new().chain_update(b"fastdog-salt").chain_update(payload).finalize();
+        digest[..password::SALT_LEN].try_into().unwrap()
+    } else {
+        platform::random_bytes()?
+    };
+    let kdf = KdfParams { iterations: password::DEFAULT_ITERATIONS, salt };
+    let key = kdf.derive_key(password);
+
+    let compressed = codec::compress(profile.codec, payload, profile.level, profile.block_size)?;
+    let extras = ContainerExtras { key: Some(&key), kdf: Some(&kdf), ..ContainerExtras::default() };
+    write_container(payload, &compressed, kind, profile, extras)
+}
+
+// 转码时重新编码: 保留原容器的元数据、来源信息和块索引的块大小 (block_size 为 None 时不分块)
+pub fn encode_transcoded_container(
+    payload: &[u8],
+    kind: PayloadKind,
+    metadata: &Metadata,
+    provenance: Option<&Provenance>,
+    block_size: Option<u32>,
+    profile: &EncoderProfile,
+) -> Result<Vec<u8>, String> {
+    let (compressed, index) = match block_size {
+        Some(block_size) => {
+            let (compressed, ends) = codec::compress_blocks(profile.codec, payload, profile.level, block_size as usize)?;
+            (compressed, Some(BlockIndex { block_size, ends }))
+        }
+        None => (codec::compress(profile.codec, payload, profile.level, profile.block_size)?, None),
+    };
+    let extras = ContainerExtras {
+        block_index: index.as_ref(),
+        metadata: Some(metadata),
+        provenance,
+        ..ContainerExtras::default()
+    };
+    write_container(payload, &compressed, kind, profile, extras)
+}
+
+// 编码为版本 1 (JSON) / 版本 2 (GLB) 的旧格式容器: zlib 压缩，没有扩展头部，供尚未升级的读取器使用
+pub fn encode_legacy_container(payload: &[u8], kind: PayloadKind, level: u32) -> Result<Vec<u8>, String> {
+    let version: u32 = match kind {
+        PayloadKind::Json => 1,
+        PayloadKind::Glb => 2,
+        other => return Err(format!("版本 1/2 只能存放 JSON / GLB 载荷，不支持 {}", other.name())),
+    };
+    let compressed = codec::compress(Codec::Zlib, payload, level, 0)?;
+    let too_long = || "版本 1/2 的长度字段为 32 位，载荷不能超过 4GB".to_string();
+    let compressed_len = u32::try_from(compressed.len()).map_err(|_| too_long())?;
+    let original_len = u32::try_from(payload.len()).map_err(|_| too_long())?;
+
+    let mut output = Vec::with_capacity(compressed.len() + 20);
+    output.extend_from_slice(b"FASTDOG1");
+    output.extend_from_slice(&version.to_le_bytes());
+    output.extend_from_slice(&compressed_len.to_le_bytes());
+    output.extend_from_slice(&compressed);
+    output.extend_from_slice(&original_len.to_le_bytes());
+    Ok(output)
+}
+
+// 档案之外的可选头部内容
+#[derive(Default)]
+struct ContainerExtras<'a> {
+    block_index: Option<&'a BlockIndex>,
+    dict_id: Option<u32>,
+    metadata: Option<&'a Metadata>,
+    // 保留已有的来源信息 (转码时)，未设置时按档案写入
+    provenance: Option<&'a Provenance>,
+    // 加密密钥，设置时压缩数据以密文写入
+    key: Option<&'a [u8; 32]>,
+    // 口令的密钥派生参数 (key 由此派生)
+    kdf: Option<&'a KdfParams>,
+}
+
+fn write_container(
+    payload: &[u8],
+    compressed: &[u8],
+    kind: PayloadKind,
+    profile: &EncoderProfile,
+    extras: ContainerExtras,
+) -> Result<Vec<u8>, String> {
+    let ContainerExtras { block_index, dict_id, metadata, provenance, key, kdf } = extras;
+    let empty = Metadata::new();
+    let metadata = metadata.unwrap_or(&empty);
+    let mut flags = 0u16;
+    if profile.checksum.crc32() {
+        flags |= FLAG_CRC32;
+    }
+    if profile.checksum.sha256() {
+        flags |= FLAG_SHA256;
+    }
+    if block_index.is_some() {
+        flags |= FLAG_BLOCK_INDEX;
+    }
+    if dict_id.is_some() {
+        flags |= FLAG_DICTIONARY;
+    }
+    // 长度超过 u32 时才使用 64 位长度字段，其余容器保持旧读取器可读的格式
+    let long_lengths = compressed.len() as u64 > u32::MAX as u64 || payload.len() as u64 > u32::MAX as u64;
+    if long_lengths {
+        flags |= FLAG_LONG_LENGTHS;
+    }
+    let metadata_len = metadata::encoded_len(metadata);
+    if metadata_len > 0 {
+        flags |= FLAG_METADATA;
+    }
+    let provenance = provenance.cloned().or_else(|| profile_provenance(profile));
+    if provenance.is_some() {
+        flags |= FLAG_PROVENANCE;
+    }
+    if key.is_some() {
+        flags |= FLAG_ENCRYPTED;
+    }
+    if kdf.is_some() {
+        flags |= FLAG_PASSWORD;
+    }
+
+    let mut output = Vec::with_capacity(compressed.len() + metadata_len + 64);
+    // 64 位长度时压缩长度字段为低 32 位
+    write_fixed_header(&mut output, profile.codec, kind, flags, compressed.len() as u64 as u32);
+
+    // 扩展字段按标志位顺序写入
+    if flags & FLAG_CRC32 != 0 {
+        output.extend_from_slice(&crc32fast::hash(payload).to_le_bytes());
+    }
+    if flags & FLAG_SHA256 != 0 {
+        output.extend_from_slice(&Sha256::digest(payload));
+    }
+    if let Some(index) = block_index {
+        output.extend_from_slice(&index.block_size.to_le_bytes());
+        output.extend_from_slice(&(index.ends.len() as u32).to_le_bytes());
+    }
+    if let Some(dict_id) = dict_id {
+        output.extend_from_slice(&dict_id.to_le_bytes());
+    }
+    if long_lengths {
+        output.extend_from_slice(&((compressed.len() as u64 >> 32) as u32).to_le_bytes());
+    }
+    if metadata_len > 0 {
+        let metadata_len = u32::try_from(metadata_len).map_err(|_| "元数据超过 4GB".to_string())?;
+        output.extend_from_slice(&metadata_len.to_le_bytes());
+    }
+    if let Some(provenance) = &provenance {
+        provenance.write(&mut output);
+    }
+    // nonce 和认证标签先写为 0，写入密文时回填
+    if key.is_some() {
+        output.extend_from_slice(&[0; 12 + 16]);
+    }
+    if let Some(kdf) = kdf {
+        kdf.write(&mut output);
+    }
+    // 块偏移表位于全部扩展字段之后，元数据区位于块偏移表之后
+    if let Some(index) = block_index {
+        index.write(&mut output);
+    }
+    metadata::write(metadata, &mut output)?;
+
+    match key {
+        Some(key) => encryption::seal(&mut output, flags, key, profile.deterministic, compressed)?,
+        None => output.extend_from_slice(compressed),
+    }
+    if long_lengths {
+        output.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    } else {
+        output.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    }
+    Ok(output)
+}
+
+// 写入版本 3 的固定头部 (魔数、版本、编码、内容类型、标志和压缩长度)
+pub fn write_fixed_header(output: &mut Vec<u8>, codec: Codec, kind: PayloadKind, flags: u16, compressed_len: u32) {
+    output.extend_from_slice(b"FASTDOG1");
+    output.extend_from_slice(&3u32.to_le_bytes());
+    output.push(codec as u8);
+    output.push(kind as u8);
+    output.extend_from_slice(&flags.to_le_bytes());
+    output.extend_from_slice(&compressed_len.to_le_bytes());
+}
+
+// 档案设置了生成工具标识时写入来源信息，确定性编码的创建时间固定为 0
+pub fn profile_provenance(profile: &EncoderProfile) -> Option<Provenance> {
+    profile.producer.as_ref().map(|producer| Provenance {
+        created_at: if profile.deterministic { 0 } else { platform::now_ms() },
+        producer: producer.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container;
+    use crate::error::{DecodeError, DecodeErrorCode};
+    use crate::profiles::resolve_profile;
+
+    // 固定的测试载荷: 结构化 JSON 加一段伪随机字节，覆盖可压缩与不可压缩的数据
+    fn payload() -> Vec<u8> {
+        let mut payload = Vec::new();
+        for i in 0..200u32 {
+            payload.extend_from_slice(format!(r#"{{"id":{},"name":"node-{}","scale":[1.0,{}.5,1.0]}},"#, i, i % 7, i % 3).as_bytes());
+        }
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        for _ in 0..4096 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            payload.push(state as u8);
+        }
+        payload
+    }
+
+    fn deterministic_profile(codec: Codec, level: u32) -> EncoderProfile {
+        EncoderProfile {
+            name: "test".to_string(),
+            codec,
+            level,
+            block_size: 0,
+            checksum: crate::profiles::ChecksumMode::All,
+            deterministic: true,
+            producer: None,
+        }
+    }
+
+    fn digest(container: &[u8]) -> String {
+        crate::integrity::to_hex(&Sha256::digest(container))
+    }
+
+    #[test]
+    fn deterministic_encoding_is_repeatable() {
+        let payload = payload();
+        for (codec, level) in [(Codec::Zlib, 6), (Codec::Brotli, 9), (Codec::Lz4, 0), (Codec::Store, 0)] {
+            let profile = deterministic_profile(codec, level).pinned();
+            let first = encode_container(&payload, PayloadKind::Json, &profile).unwrap();
+            let second = encode_container(&payload, PayloadKind::Json, &profile).unwrap();
+            assert_eq!(first, second, "{} 两次编码输出不同", codec.name());
+        }
+    }
+
+    // 固定输出的 SHA-256: 编码库升级导致输出变化时此测试失败，需要确认后更新
+    #[test]
+    fn reproducible_output_is_pinned() {
+        let payload = payload();
+        let reproducible = resolve_profile(Some("reproducible")).unwrap();
+        assert_eq!(
+            digest(&encode_container(&payload, PayloadKind::Json, &reproducible).unwrap()),
+            "01994152f9d43aa6a37145d16f21c8f18fd7d713e41fd9a3058ef3c930ef7757"
+        );
+
+        let golden = [
+            (Codec::Zlib, 6, "e5eb28f92392cda5b14d785d83e9133108e188ed5c21735b8b2200481994c7ca"),
+            (Codec::Brotli, 9, "5f34e07278dbb2abd000467b225aaa00c5199a8b1fff2a01c4551d88d51dd20d"),
+            (Codec::Lz4, 0, "1ab06f77a0ec870cc1dc35bc69d8c1a6986390b3dbd4de984a14d57863130adb"),
+            (Codec::Store, 0, "437070ce6adfe8d30bb17e592352966b504f9a1bc98bf7378db666657e7af468"),
+        ];
+        for (codec, level, expected) in golden {
+            let profile = deterministic_profile(codec, level).pinned();
+            assert_eq!(digest(&encode_container(&payload, PayloadKind::Json, &profile).unwrap()), expected, "{}", codec.name());
+        }
+    }
+
+    // 解密、解压并校验加密容器
+    fn open_encrypted(data: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, DecodeError> {
+        let (header, ciphertext, original_len) = container::locate_raw_payload(data)?;
+        let compressed = encryption::decrypt_payload(&header, data, ciphertext, key)?;
+        let payload = container::decompress_payload(&header, &compressed, original_len, |_| Ok(()))?;
+        container::verify_payload(&header, original_len, &payload)?;
+        Ok(payload)
+    }
+
+    #[test]
+    fn encrypted_round_trip() {
+        let payload = payload();
+        let key = [0x42; 32];
+        for codec in Codec::ALL {
+            let profile = deterministic_profile(codec, 0).pinned();
+            let data = encode_encrypted_container(&payload, PayloadKind::Json, &key, &profile).unwrap();
+            assert_eq!(open_encrypted(&data, &key).unwrap(), payload, "{}", codec.name());
+            // 确定性档案的 nonce 由内容派生，相同输入得到相同密文
+            assert_eq!(encode_encrypted_container(&payload, PayloadKind::Json, &key, &profile).unwrap(), data);
+            assert_eq!(container::decode(&data).err().map(|e| e.code), Some(DecodeErrorCode::KeyRequired));
+        }
+    }
+
+    // 非确定性档案每次使用随机 nonce (js 特性的随机数取自 JS 全局对象，只在 WASM 中可用)
+    #[cfg(not(feature = "js"))]
+    #[test]
+    fn random_nonces_differ() {
+        let payload = payload();
+        let key = [0x42; 32];
+        let profile = resolve_profile(Some("balanced")).unwrap();
+        let first = encode_encrypted_container(&payload, PayloadKind::Json, &key, &profile).unwrap();
+        let second = encode_encrypted_container(&payload, PayloadKind::Json, &key, &profile).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(open_encrypted(&second, &key).unwrap(), payload);
+    }
+
+    #[test]
+    fn tampered_encrypted_containers_are_rejected() {
+        let payload = payload();
+        let key = [0x42; 32];
+        let profile = deterministic_profile(Codec::Zlib, 6).pinned();
+        let data = encode_encrypted_container(&payload, PayloadKind::Json, &key, &profile).unwrap();
+        let (header, _, _) = container::locate_raw_payload(&data).unwrap();
+        let code = |data: &[u8], key: &[u8; 32]| open_encrypted(data, key).err().map(|e| e.code);
+
+        assert_eq!(code(&data, &[0x43; 32]), Some(DecodeErrorCode::DecryptionFailed));
+        // 头部 (CRC32、nonce、认证标签) 和密文都受认证保护
+        let flags = u16::from_le_bytes([data[14], data[15]]);
+        let field = |name| crate::format::v3_extension_field(flags, name).unwrap().offset;
+        for offset in [field("crc32"), field("nonce"), field("auth_tag"), header.payload_offset, data.len() - 5] {
+            let mut tampered = data.clone();
+            tampered[offset] ^= 1;
+            assert_eq!(code(&tampered, &key), Some(DecodeErrorCode::DecryptionFailed), "偏移 {}", offset);
+        }
+        let mut kind = data.clone();
+        kind[13] = PayloadKind::Glb as u8;
+        assert_eq!(code(&kind, &key), Some(DecodeErrorCode::DecryptionFailed));
+        assert_eq!(code(&data[..data.len() - 1], &key), Some(DecodeErrorCode::Truncated));
+        assert_eq!(code(&data[..header.payload_offset - 1], &key), Some(DecodeErrorCode::Truncated));
+
+        // 加密与尾部帧格式不能同时使用
+        let mut flags = data.clone();
+        flags[14..16].copy_from_slice(&(FLAG_ENCRYPTED | crate::format::FLAG_FOOTER).to_le_bytes());
+        assert_eq!(code(&flags, &key), Some(DecodeErrorCode::UnsupportedFlags));
+
+        assert_eq!(encryption::parse_key(&[0; 31]).err().map(|e| e.code), Some(DecodeErrorCode::InvalidInput));
+    }
+
+    // 口令容器: 迭代次数取较小值以缩短测试时间，其余与 encode_password_container 相同
+    fn password_container(payload: &[u8], password: &str, iterations: u32) -> Vec<u8> {
+        let kdf = KdfParams { iterations, salt: [9; password::SALT_LEN] };
+        let key = kdf.derive_key(password);
+        let profile = deterministic_profile(Codec::Zlib, 6).pinned();
+        let compressed = codec::compress(profile.codec, payload, profile.level, profile.block_size).unwrap();
+        let extras = ContainerExtras { key: Some(&key), kdf: Some(&kdf), ..ContainerExtras::default() };
+        write_container(payload, &compressed, PayloadKind::Json, &profile, extras).unwrap()
+    }
+
+    fn open_password(data: &[u8], password: &str) -> Result<Vec<u8>, DecodeError> {
+        let (header, _, _) = container::locate_raw_payload(data)?;
+        let key = header.kdf.as_ref().expect("缺少密钥派生参数").derive_key(password);
+        open_encrypted(data, &key)
+    }
+
+    #[test]
+    fn password_round_trip() {
+        let payload = payload();
+        let data = password_container(&payload, "correct horse", 1000);
+        let (header, _, _) = container::locate_raw_payload(&data).unwrap();
+        let kdf = header.kdf.as_ref().unwrap();
+        assert_eq!((kdf.iterations, kdf.salt), (1000, [9; password::SALT_LEN]));
+        assert_eq!(open_password(&data, "correct horse").unwrap(), payload);
+        assert_eq!(open_password(&data, "wrong horse").err().map(|e| e.code), Some(DecodeErrorCode::DecryptionFailed));
+        assert!(encode_password_container(&payload, PayloadKind::Json, "", &deterministic_profile(Codec::Zlib, 6)).is_err());
+    }
+
+    #[test]
+    fn malformed_kdf_params_are_rejected() {
+        let data = password_container(b"{}", "secret", 10);
+        let flags = u16::from_le_bytes([data[14], data[15]]);
+        let field = |name| crate::format::v3_extension_field(flags, name).unwrap().offset;
+        let code = |data: &[u8]| open_password(data, "secret").err().map(|e| e.code);
+
+        // 派生参数属于认证头部，改动后派生出的密钥不同或认证失败
+        let mut salt = data.clone();
+        salt[field("kdf_salt")] ^= 1;
+        assert_eq!(code(&salt), Some(DecodeErrorCode::DecryptionFailed));
+        let mut iterations = data.clone();
+        iterations[field("kdf_iterations")] = 11;
+        assert_eq!(code(&iterations), Some(DecodeErrorCode::DecryptionFailed));
+
+        for value in [0, password::MAX_ITERATIONS + 1] {
+            let mut invalid = data.clone();
+            let at = field("kdf_iterations");
+            invalid[at..at + 4].copy_from_slice(&value.to_le_bytes());
+            assert_eq!(code(&invalid), Some(DecodeErrorCode::InvalidInput), "迭代次数 {}", value);
+        }
+        let mut algorithm = data.clone();
+        algorithm[field("kdf")] = 2;
+        assert_eq!(code(&algorithm), Some(DecodeErrorCode::InvalidInput));
+
+        // 密码标志必须与加密标志同时使用
+        let mut flags_only = data.clone();
+        flags_only[14..16].copy_from_slice(&(flags & !FLAG_ENCRYPTED).to_le_bytes());
+        assert_eq!(code(&flags_only), Some(DecodeErrorCode::UnsupportedFlags));
+        assert_eq!(code(&data[..field("kdf_salt") + 4]), Some(DecodeErrorCode::Truncated));
+    }
+}
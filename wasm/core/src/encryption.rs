@@ -0,0 +1,103 @@
+use sha2::{Digest, Sha256};
+
+use crate::aes_gcm::Aes256Gcm;
+use crate::byte_reader::ByteReader;
+use crate::container::ContainerHeader;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::{format, platform};
+
+// 载荷加密 (版本 3，FLAG_ENCRYPTED)
+//
+// 压缩后的数据用 AES-256-GCM 加密 (先压缩后加密)，头部记录 nonce 和认证标签。附加认证数据为
+// 压缩数据之前的完整头部 (认证标签字段按 0 计算)，头部被改动同样会使解密失败。原始长度和 CRC32
+// 仍针对明文载荷，解密、解压后照常校验。加密容器不能用普通解码接口读取 (返回 KeyRequired)
+#[derive(Clone)]
+pub struct Encryption {
+    pub nonce: [u8; 12],
+    pub tag: [u8; 16],
+    // 认证标签字段在容器中的偏移
+    tag_offset: usize,
+}
+
+impl Encryption {
+    // 由扩展字段读取 (调用方保证 FLAG_ENCRYPTED 已置位)
+    pub fn parse(reader: &ByteReader, flags: u16) -> Result<Encryption, DecodeError> {
+        let (nonce, tag) = (format::v3_extension_field(flags, "nonce")?, format::v3_extension_field(flags, "auth_tag")?);
+        let mut encryption = Encryption {
+            nonce: [0u8; 12],
+            tag: [0u8; 16],
+            tag_offset: tag.offset,
+        };
+        encryption.nonce.copy_from_slice(reader.field_bytes(&nonce)?);
+        encryption.tag.copy_from_slice(reader.field_bytes(&tag)?);
+        Ok(encryption)
+    }
+}
+
+// 检查密钥长度 (AES-256 需要 32 字节)
+pub fn parse_key(key: &[u8]) -> Result<[u8; 32], DecodeError> {
+    key.try_into().map_err(|_| {
+        DecodeError::new(DecodeErrorCode::InvalidInput, format!("密钥长度必须为 32 字节, 实际 {} 字节", key.len()))
+    })
+}
+
+// 加密参数和附加认证数据 (压缩数据之前的头部，认证标签字段置 0)
+pub fn authenticated_header<'a>(header: &'a ContainerHeader, data: &[u8]) -> Result<(&'a Encryption, Vec<u8>), DecodeError> {
+    let Some(encryption) = &header.encryption else {
+        return Err(DecodeError::new(DecodeErrorCode::InvalidInput, "容器未加密"));
+    };
+    let mut aad = data[..header.payload_offset].to_vec();
+    aad[encryption.tag_offset..encryption.tag_offset + 16].fill(0);
+    Ok((encryption, aad))
+}
+
+pub fn decryption_failed() -> DecodeError {
+    DecodeError::new(DecodeErrorCode::DecryptionFailed, "解密失败: 密钥错误或数据已被篡改")
+}
+
+// 解密压缩数据，密钥错误或数据被篡改时返回 DecryptionFailed
+pub fn decrypt_payload(
+    header: &ContainerHeader,
+    data: &[u8],
+    ciphertext: &[u8],
+    key: &[u8; 32],
+) -> Result<Vec<u8>, DecodeError> {
+    let (encryption, aad) = authenticated_header(header, data)?;
+    let mut payload = ciphertext.to_vec();
+    if !Aes256Gcm::new(key).decrypt(&encryption.nonce, &aad, &mut payload, &encryption.tag) {
+        return Err(decryption_failed());
+    }
+    Ok(payload)
+}
+
+// 加密写入: output 为完整头部 (nonce 和认证标签字段为 0)，写入 nonce、追加密文并回填认证标签。
+// 确定性编码的 nonce 由密钥、头部和压缩数据派生，相同输入得到相同密文，内容不同时 nonce 不会重复
+pub fn seal(
+    output: &mut Vec<u8>,
+    flags: u16,
+    key: &[u8; 32],
+    deterministic: bool,
+    compressed: &[u8],
+) -> Result<(), String> {
+    let offset = |name: &str| format::v3_extension_fields(flags).find(|field| field.name == name).unwrap().offset;
+    let (nonce_offset, tag_offset) = (offset("nonce"), offset("auth_tag"));
+    let nonce = if deterministic {
+        let digest = Sha256::new()
+            .chain_update(b"fastdog-nonce")
+            .chain_update(key)
+            .chain_update(&output[..])
+            .chain_update(compressed)
+            .finalize();
+        digest[..12].try_into().unwrap()
+    } else {
+        platform::random_bytes()?
+    };
+    output[nonce_offset..nonce_offset + 12].copy_from_slice(&nonce);
+
+    let header_len = output.len();
+    output.extend_from_slice(compressed);
+    let (aad, ciphertext) = output.split_at_mut(header_len);
+    let tag = Aes256Gcm::new(key).encrypt(&nonce, aad, ciphertext);
+    output[tag_offset..tag_offset + 16].copy_from_slice(&tag);
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+use crate::locale::{self, Locale};
+
+// 解码错误码
+//
+// 与错误信息一起序列化到各结果结构的 error_code 字段 (按变体名序列化为字符串)，
+// 调用方据此分支处理，不需要匹配错误信息文本。抛出异常的接口抛出带 code 属性的 Error 对象。
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeErrorCode {
+    // 数据不足以包含头部
+    TooShort,
+    // 魔数不是 FASTDOG1
+    BadMagic,
+    UnsupportedVersion,
+    UnsupportedCodec,
+    UnsupportedContentType,
+    UnsupportedFlags,
+    // 数据不完整: 压缩数据或长度字段超出范围，数据流在解码完成前结束
+    Truncated,
+    // 解压后长度与头部声明不一致
+    LengthMismatch,
+    // CRC32 / SHA-256 校验失败
+    ChecksumMismatch,
+    // 压缩数据损坏或无法解压
+    InflateError,
+    // 容器引用的压缩字典尚未通过 set_dictionary 注册
+    MissingDictionary,
+    // 容器已加密，需要通过 decode_encrypted 提供密钥
+    KeyRequired,
+    // 密钥错误或加密数据 / 头部被篡改 (认证标签校验失败)
+    DecryptionFailed,
+    // JSON 载荷不是有效的 UTF-8
+    Utf8Error,
+    // 解码转换钩子执行失败
+    TransformError,
+    // 超出模块级内存配额，或内存不足无法分配所需的缓冲区
+    QuotaExceeded,
+    // 超出 DecoderOptions 的 max_output_bytes / max_ratio 限制
+    OutputLimitExceeded,
+    // 超出 DecoderOptions 的 max_decode_ms 限制
+    Timeout,
+    // 调用方通过 AbortSignal 取消
+    Aborted,
+    // 参数或输入数据无效 (如 base64 解码失败)
+    InvalidInput,
+    // 对象状态不允许该操作 (如流式解码已结束后继续写入)
+    InvalidState,
+    // 内部错误 (如结果无法转换为 JS 值)，属于模块缺陷
+    Internal,
+}
+
+impl DecodeErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DecodeErrorCode::TooShort => "TooShort",
+            DecodeErrorCode::BadMagic => "BadMagic",
+            DecodeErrorCode::UnsupportedVersion => "UnsupportedVersion",
+            DecodeErrorCode::UnsupportedCodec => "UnsupportedCodec",
+            DecodeErrorCode::UnsupportedContentType => "UnsupportedContentType",
+            DecodeErrorCode::UnsupportedFlags => "UnsupportedFlags",
+            DecodeErrorCode::Truncated => "Truncated",
+            DecodeErrorCode::LengthMismatch => "LengthMismatch",
+            DecodeErrorCode::ChecksumMismatch => "ChecksumMismatch",
+            DecodeErrorCode::InflateError => "InflateError",
+            DecodeErrorCode::MissingDictionary => "MissingDictionary",
+            DecodeErrorCode::KeyRequired => "KeyRequired",
+            DecodeErrorCode::DecryptionFailed => "DecryptionFailed",
+            DecodeErrorCode::Utf8Error => "Utf8Error",
+            DecodeErrorCode::TransformError => "TransformError",
+            DecodeErrorCode::QuotaExceeded => "QuotaExceeded",
+            DecodeErrorCode::OutputLimitExceeded => "OutputLimitExceeded",
+            DecodeErrorCode::Timeout => "Timeout",
+            DecodeErrorCode::Aborted => "Aborted",
+            DecodeErrorCode::InvalidInput => "InvalidInput",
+            DecodeErrorCode::InvalidState => "InvalidState",
+            DecodeErrorCode::Internal => "Internal",
+        }
+    }
+}
+
+// 带错误码的解码错误
+#[derive(Clone, Debug)]
+pub struct DecodeError {
+    pub code: DecodeErrorCode,
+    pub message: String,
+}
+
+impl DecodeError {
+    pub fn new(code: DecodeErrorCode, message: impl Into<String>) -> DecodeError {
+        DecodeError {
+            code,
+            message: locale::localize(code, message.into()),
+        }
+    }
+
+    // 在错误信息前加上上下文，错误码不变。英文信息为固定说明，不加中文上下文
+    pub fn context(self, context: &str) -> DecodeError {
+        if locale::current() == Locale::En {
+            return self;
+        }
+        DecodeError {
+            code: self.code,
+            message: format!("{}: {}", context, self.message),
+        }
+    }
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+// 只关心错误信息的调用方 (编码、打包等工具函数) 可以直接用 ? 转换
+impl From<DecodeError> for String {
+    fn from(error: DecodeError) -> String {
+        error.message
+    }
+}
+
+// 抛给 JS 的 Error 对象，code 属性为错误码字符串
+#[cfg(feature = "js")]
+impl From<DecodeError> for wasm_bindgen::JsValue {
+    fn from(error: DecodeError) -> wasm_bindgen::JsValue {
+        let js_error = js_sys::Error::new(&error.message);
+        // 对新建的 Error 对象设置属性不会失败
+        let _ = js_sys::Reflect::set(&js_error, &"code".into(), &error.code.as_str().into());
+        js_error.into()
+    }
+}
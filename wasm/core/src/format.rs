@@ -0,0 +1,454 @@
+use serde::Serialize;
+
+use crate::byte_reader::ByteReader;
+use crate::codec::Codec;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::payload_kind::PayloadKind;
+
+// 容器格式的字段定义表
+//
+// 头部解析器按这些表读取字段，describe (JS 接口 describe_format) 也由同一组表生成格式说明，
+// 因此对外发布的格式描述与解码器的实际行为始终一致。所有整数均为小端序。
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    Bytes,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+#[derive(Clone, Copy, Serialize)]
+pub struct Field {
+    pub name: &'static str,
+    pub offset: usize,
+    pub size: usize,
+    #[serde(rename = "type")]
+    pub kind: FieldKind,
+    pub description: &'static str,
+}
+
+impl Field {
+    // 字段的原始字节，数据不足以包含该字段时为空
+    pub fn bytes<'a>(&self, data: &'a [u8]) -> &'a [u8] {
+        data.get(self.offset..self.offset + self.size).unwrap_or_default()
+    }
+
+    // 读取整数字段 (小端)。调用方应先检查数据长度，数据不足时返回 0 而不是 panic；
+    // 超出 u32 的 u64 字段值饱和为 u32::MAX
+    pub fn read(&self, data: &[u8]) -> u32 {
+        u32::try_from(self.read_u64(data)).unwrap_or(u32::MAX)
+    }
+
+    // 读取整数字段 (小端)，支持 u64。字节串字段返回 0
+    pub fn read_u64(&self, data: &[u8]) -> u64 {
+        match self.kind {
+            FieldKind::U8 | FieldKind::U16 | FieldKind::U32 | FieldKind::U64 => {
+                self.bytes(data).iter().rev().fold(0, |value, &byte| (value << 8) | byte as u64)
+            }
+            FieldKind::Bytes => 0,
+        }
+    }
+}
+
+pub const MAGIC: Field = Field {
+    name: "magic",
+    offset: 0,
+    size: 8,
+    kind: FieldKind::Bytes,
+    description: "魔数，固定为 ASCII \"FASTDOG1\"",
+};
+
+pub const VERSION: Field = Field {
+    name: "version",
+    offset: 8,
+    size: 4,
+    kind: FieldKind::U32,
+    description: "容器版本: 1 = JSON, 2 = GLB (旧格式), 3 = 扩展头部",
+};
+
+pub const LEGACY_COMPRESSED_LEN: Field = Field {
+    name: "compressed_len",
+    offset: 12,
+    size: 4,
+    kind: FieldKind::U32,
+    description: "压缩数据长度 (字节)，压缩数据为 zlib 流 (兼容 gzip / 原始 deflate)",
+};
+
+pub const CODEC: Field = Field {
+    name: "codec",
+    offset: 12,
+    size: 1,
+    kind: FieldKind::U8,
+    description: "压缩编码，取值见 codecs",
+};
+
+pub const CONTENT_TYPE: Field = Field {
+    name: "content_type",
+    offset: 13,
+    size: 1,
+    kind: FieldKind::U8,
+    description: "载荷内容类型，取值见 content_types",
+};
+
+pub const FLAGS: Field = Field {
+    name: "flags",
+    offset: 14,
+    size: 2,
+    kind: FieldKind::U16,
+    description: "头部标志位，置位的扩展字段按位序依次跟在 compressed_len 之后，未知标志位视为错误",
+};
+
+pub const COMPRESSED_LEN: Field = Field {
+    name: "compressed_len",
+    offset: 16,
+    size: 4,
+    kind: FieldKind::U32,
+    description: "压缩数据长度 (字节)",
+};
+
+pub const LEGACY_HEADER: &[Field] = &[MAGIC, VERSION, LEGACY_COMPRESSED_LEN];
+pub const V3_HEADER: &[Field] = &[MAGIC, VERSION, CODEC, CONTENT_TYPE, FLAGS, COMPRESSED_LEN];
+
+// 固定头部长度 (各版本解析前都至少需要这么多字节)
+pub const FIXED_HEADER_LEN: usize = 20;
+
+// 版本 3 头部标志位
+pub const FLAG_CRC32: u16 = 0x0001;
+pub const FLAG_SHA256: u16 = 0x0002;
+pub const FLAG_BLOCK_INDEX: u16 = 0x0004;
+pub const FLAG_DICTIONARY: u16 = 0x0008;
+pub const FLAG_LONG_LENGTHS: u16 = 0x0010;
+pub const FLAG_METADATA: u16 = 0x0020;
+pub const FLAG_PROVENANCE: u16 = 0x0040;
+pub const FLAG_FOOTER: u16 = 0x0080;
+pub const FLAG_ENCRYPTED: u16 = 0x0100;
+pub const FLAG_PASSWORD: u16 = 0x0200;
+
+// 标志位名称 (inspect 等诊断输出使用)
+pub const FLAG_NAMES: &[(u16, &str)] = &[
+    (FLAG_CRC32, "crc32"),
+    (FLAG_SHA256, "sha256"),
+    (FLAG_BLOCK_INDEX, "block_index"),
+    (FLAG_DICTIONARY, "dictionary"),
+    (FLAG_LONG_LENGTHS, "long_lengths"),
+    (FLAG_METADATA, "metadata"),
+    (FLAG_PROVENANCE, "provenance"),
+    (FLAG_FOOTER, "footer"),
+    (FLAG_ENCRYPTED, "encrypted"),
+    (FLAG_PASSWORD, "password"),
+];
+
+// 版本 3 扩展字段，按标志位顺序排列
+#[derive(Clone, Copy, Serialize)]
+pub struct Extension {
+    pub flag: u16,
+    pub name: &'static str,
+    pub size: usize,
+    #[serde(rename = "type")]
+    pub kind: FieldKind,
+    pub description: &'static str,
+}
+
+pub const V3_EXTENSIONS: &[Extension] = &[
+    Extension {
+        flag: FLAG_CRC32,
+        name: "crc32",
+        size: 4,
+        kind: FieldKind::U32,
+        description: "未压缩载荷的 CRC32，解码时校验",
+    },
+    Extension {
+        flag: FLAG_SHA256,
+        name: "sha256",
+        size: 32,
+        kind: FieldKind::Bytes,
+        description: "未压缩载荷的 SHA-256 摘要，由 verify_integrity 校验",
+    },
+    Extension {
+        flag: FLAG_BLOCK_INDEX,
+        name: "block_size",
+        size: 4,
+        kind: FieldKind::U32,
+        description: "块索引: 载荷按此大小 (未压缩字节) 切块并独立压缩，支持 decode_range 随机访问",
+    },
+    Extension {
+        flag: FLAG_BLOCK_INDEX,
+        name: "block_count",
+        size: 4,
+        kind: FieldKind::U32,
+        description: "块索引: 块数。扩展字段之后紧跟 block_count 个 u32，依次为各块压缩数据的结束偏移 (相对压缩数据起始)",
+    },
+    Extension {
+        flag: FLAG_DICTIONARY,
+        name: "dict_id",
+        size: 4,
+        kind: FieldKind::U32,
+        description: "压缩字典 ID (字典内容的 CRC32)，解码前需通过 set_dictionary 注册同一字典",
+    },
+    Extension {
+        flag: FLAG_LONG_LENGTHS,
+        name: "compressed_len_high",
+        size: 4,
+        kind: FieldKind::U32,
+        description: "64 位长度: 压缩数据长度的高 32 位 (compressed_len 为低 32 位)，原始长度字段扩展为 u64",
+    },
+    Extension {
+        flag: FLAG_METADATA,
+        name: "metadata_len",
+        size: 4,
+        kind: FieldKind::U32,
+        description: "元数据区长度。元数据区位于块偏移表之后、压缩数据之前，由若干条记录组成: \
+                      键长度 u16 | 键 (UTF-8) | 值长度 u32 | 值 (UTF-8)，键按字节序排列且不重复",
+    },
+    Extension {
+        flag: FLAG_PROVENANCE,
+        name: "created_at",
+        size: 8,
+        kind: FieldKind::U64,
+        description: "来源信息: 容器创建时间 (Unix 毫秒时间戳)，0 表示未知，确定性编码总是写 0",
+    },
+    Extension {
+        flag: FLAG_PROVENANCE,
+        name: "producer",
+        size: 16,
+        kind: FieldKind::Bytes,
+        description: "来源信息: 生成工具 / 流水线标识 (UTF-8)，不足 16 字节时以 0 填充",
+    },
+    Extension {
+        flag: FLAG_ENCRYPTED,
+        name: "nonce",
+        size: 12,
+        kind: FieldKind::Bytes,
+        description: "加密: AES-256-GCM 的 96 位 nonce，压缩数据为加密后的密文 (长度不变)",
+    },
+    Extension {
+        flag: FLAG_ENCRYPTED,
+        name: "auth_tag",
+        size: 16,
+        kind: FieldKind::Bytes,
+        description: "加密: AES-256-GCM 认证标签。附加认证数据为完整头部 (含块偏移表和元数据区，本字段按 0 计算)",
+    },
+    Extension {
+        flag: FLAG_PASSWORD,
+        name: "kdf",
+        size: 1,
+        kind: FieldKind::U8,
+        description: "密码加密: 密钥派生算法，1 = PBKDF2-HMAC-SHA256。必须与加密标志同时使用",
+    },
+    Extension {
+        flag: FLAG_PASSWORD,
+        name: "kdf_iterations",
+        size: 4,
+        kind: FieldKind::U32,
+        description: "密码加密: 密钥派生的迭代次数",
+    },
+    Extension {
+        flag: FLAG_PASSWORD,
+        name: "kdf_salt",
+        size: 16,
+        kind: FieldKind::Bytes,
+        description: "密码加密: 密钥派生的盐",
+    },
+];
+
+pub const SUPPORTED_FLAGS: u16 = FLAG_CRC32
+    | FLAG_SHA256
+    | FLAG_BLOCK_INDEX
+    | FLAG_DICTIONARY
+    | FLAG_LONG_LENGTHS
+    | FLAG_METADATA
+    | FLAG_PROVENANCE
+    | FLAG_FOOTER
+    | FLAG_ENCRYPTED
+    | FLAG_PASSWORD;
+
+// 尾部帧格式 (FLAG_FOOTER) 不能与这些标志同时使用: 它们要求在写出头部时已知道完整的载荷或压缩数据
+pub const FOOTER_EXCLUSIVE_FLAGS: u16 =
+    FLAG_CRC32 | FLAG_SHA256 | FLAG_BLOCK_INDEX | FLAG_LONG_LENGTHS | FLAG_ENCRYPTED;
+
+// 按标志位列出存在的扩展字段及其偏移
+pub fn v3_extension_fields(flags: u16) -> impl Iterator<Item = Field> {
+    let mut offset = FIXED_HEADER_LEN;
+    V3_EXTENSIONS
+        .iter()
+        .filter(move |extension| flags & extension.flag != 0)
+        .map(move |extension| {
+            let field = Field {
+                name: extension.name,
+                offset,
+                size: extension.size,
+                kind: extension.kind,
+                description: extension.description,
+            };
+            offset += extension.size;
+            field
+        })
+}
+
+// 版本 3 头部固定部分 (含扩展字段，不含块偏移表) 的长度
+pub fn v3_header_len(flags: u16) -> usize {
+    FIXED_HEADER_LEN + v3_extension_fields(flags).map(|field| field.size).sum::<usize>()
+}
+
+// 扩展字段之后的块偏移表长度，未设置 FLAG_BLOCK_INDEX 时为 0
+pub fn v3_block_table_len(reader: &ByteReader, flags: u16) -> Result<usize, DecodeError> {
+    match v3_extension_fields(flags).find(|field| field.name == "block_count") {
+        Some(field) => Ok((reader.field(&field)? as usize).saturating_mul(4)),
+        None => Ok(0),
+    }
+}
+
+// 块偏移表之后的元数据区长度，未设置 FLAG_METADATA 时为 0
+pub fn v3_metadata_len(reader: &ByteReader, flags: u16) -> Result<usize, DecodeError> {
+    match v3_extension_fields(flags).find(|field| field.name == "metadata_len") {
+        Some(field) => Ok(reader.field(&field)? as usize),
+        None => Ok(0),
+    }
+}
+
+// 按名称查找置位的扩展字段 (调用方保证对应标志已置位)
+pub fn v3_extension_field(flags: u16, name: &str) -> Result<Field, DecodeError> {
+    v3_extension_fields(flags).find(|field| field.name == name).ok_or_else(|| {
+        DecodeError::new(DecodeErrorCode::Internal, format!("头部缺少扩展字段 {}", name))
+    })
+}
+
+pub const ORIGINAL_LEN: Field = Field {
+    name: "original_len",
+    offset: 0,
+    size: 4,
+    kind: FieldKind::U32,
+    description: "未压缩载荷长度 (字节)，位于压缩数据之后，解码时校验",
+};
+
+pub const ORIGINAL_LEN_LONG: Field = Field {
+    name: "original_len",
+    offset: 0,
+    size: 8,
+    kind: FieldKind::U64,
+    description: "未压缩载荷长度 (字节)，版本 3 设置 FLAG_LONG_LENGTHS 时使用此 u64 字段",
+};
+
+// 尾部帧格式 (版本 3，FLAG_FOOTER)
+//
+// 流式编码的服务端在写出头部时还不知道压缩数据的长度: 头部的 compressed_len 写为 0，
+// 压缩数据之后以尾部代替原始长度字段，尾部以结束标记收尾。偏移相对尾部起始
+pub const FOOTER_COMPRESSED_LEN: Field = Field {
+    name: "compressed_len",
+    offset: 0,
+    size: 8,
+    kind: FieldKind::U64,
+    description: "压缩数据长度 (字节)，必须等于头部与尾部之间的字节数",
+};
+
+pub const FOOTER_ORIGINAL_LEN: Field = Field {
+    name: "original_len",
+    offset: 8,
+    size: 8,
+    kind: FieldKind::U64,
+    description: "未压缩载荷长度 (字节)，解码时校验",
+};
+
+pub const FOOTER_CRC32: Field = Field {
+    name: "crc32",
+    offset: 16,
+    size: 4,
+    kind: FieldKind::U32,
+    description: "未压缩载荷的 CRC32，解码时校验",
+};
+
+pub const FOOTER_MAGIC: Field = Field {
+    name: "end_magic",
+    offset: 20,
+    size: 8,
+    kind: FieldKind::Bytes,
+    description: "结束标记，固定为 ASCII \"FDOGEND1\"",
+};
+
+pub const FOOTER: &[Field] = &[FOOTER_COMPRESSED_LEN, FOOTER_ORIGINAL_LEN, FOOTER_CRC32, FOOTER_MAGIC];
+pub const FOOTER_LEN: usize = 28;
+pub const END_MAGIC: &[u8; 8] = b"FDOGEND1";
+
+// 在 bytes 中查找第一个有效的尾部，consumed 为 bytes 之前已有的压缩数据长度。
+// 尾部记录的压缩长度必须恰好等于它之前的压缩数据长度，压缩数据中偶然出现的结束标记不会被误认
+pub fn find_footer(bytes: &[u8], consumed: usize) -> Option<usize> {
+    (0..(bytes.len() + 1).saturating_sub(FOOTER_LEN)).find(|&position| {
+        let footer = &bytes[position..position + FOOTER_LEN];
+        FOOTER_MAGIC.bytes(footer) == END_MAGIC
+            && FOOTER_COMPRESSED_LEN.read_u64(footer) == (consumed + position) as u64
+    })
+}
+
+#[derive(Serialize)]
+struct CodecDescription {
+    id: u8,
+    name: &'static str,
+}
+
+#[derive(Serialize)]
+struct ContentTypeDescription {
+    id: u8,
+    name: &'static str,
+}
+
+#[derive(Serialize)]
+pub struct FormatDescription {
+    version: u32,
+    byte_order: &'static str,
+    header: &'static [Field],
+    // 仅版本 3: 扩展字段从偏移 extensions_offset 开始按顺序排列
+    extensions: &'static [Extension],
+    extensions_offset: Option<usize>,
+    payload: &'static str,
+    // 偏移相对压缩数据的末尾，版本 3 按 FLAG_LONG_LENGTHS 二选一
+    trailer: Vec<Field>,
+    // 尾部帧格式 (FLAG_FOOTER) 下代替 trailer 的尾部，仅版本 3
+    footer: &'static [Field],
+    codecs: Vec<CodecDescription>,
+    content_types: Vec<ContentTypeDescription>,
+}
+
+// 指定容器版本的机器可读格式说明 (字段名、偏移、大小、类型和语义)
+pub fn describe(version: u32) -> Result<FormatDescription, String> {
+    let (header, extensions, extensions_offset, trailer, codecs, content_types): (_, &[Extension], _, _, &[Codec], &[PayloadKind]) =
+        match version {
+            1 => (LEGACY_HEADER, &[], None, vec![ORIGINAL_LEN], &[Codec::Zlib], &[PayloadKind::Json]),
+            2 => (LEGACY_HEADER, &[], None, vec![ORIGINAL_LEN], &[Codec::Zlib], &[PayloadKind::Glb]),
+            3 => (
+                V3_HEADER,
+                V3_EXTENSIONS,
+                Some(FIXED_HEADER_LEN),
+                vec![ORIGINAL_LEN, ORIGINAL_LEN_LONG],
+                &Codec::ALL,
+                &PayloadKind::ALL,
+            ),
+            other => return Err(format!("不支持的版本: {}", other)),
+        };
+
+    Ok(FormatDescription {
+        version,
+        byte_order: "little-endian",
+        header,
+        extensions,
+        extensions_offset,
+        payload: "压缩数据，紧跟在头部 (含扩展字段、块偏移表和元数据区) 之后，长度为 compressed_len",
+        trailer,
+        footer: if version == 3 { FOOTER } else { &[] },
+        codecs: codecs
+            .iter()
+            .map(|codec| CodecDescription {
+                id: *codec as u8,
+                name: codec.name(),
+            })
+            .collect(),
+        content_types: content_types
+            .iter()
+            .map(|kind| ContentTypeDescription {
+                id: *kind as u8,
+                name: kind.name(),
+            })
+            .collect(),
+    })
+}
@@ -0,0 +1,406 @@
+use serde::Serialize;
+
+use crate::byte_reader::ByteReader;
+use crate::container::{parse_container_header, ContainerHeader};
+use crate::error::DecodeErrorCode;
+use crate::format::{self, v3_header_len, Field, FieldKind, FIXED_HEADER_LEN};
+use crate::integrity::to_hex;
+use crate::metadata;
+
+// 容器结构检查
+//
+// 只读取头部、块偏移表、元数据区和原始长度字段，不解压载荷，逐段列出各部分的位置和内容，
+// 支持全部容器版本。数据损坏时仍尽量给出已读到的字段，error 为发现的第一个问题
+#[derive(Serialize)]
+struct HeaderField {
+    name: &'static str,
+    offset: usize,
+    size: usize,
+    // 字段值: 整数为十进制 (标志为十六进制)，魔数为 ASCII，其他字节串为十六进制; 数据不完整时缺省
+    value: Option<String>,
+}
+
+#[derive(Serialize, Default)]
+struct HeaderInfo {
+    magic: Option<String>,
+    version: Option<u32>,
+    // 完整头部长度 (含扩展字段、块偏移表和元数据区)，即压缩数据的起始偏移
+    size: Option<usize>,
+    flags: Option<u16>,
+    flag_names: Vec<String>,
+    fields: Vec<HeaderField>,
+}
+
+#[derive(Serialize)]
+struct PayloadInfo {
+    offset: usize,
+    compressed_size: f64,
+    // 原始长度字段缺失时缺省
+    original_size: Option<f64>,
+    compression_ratio: Option<f32>,
+    encrypted: bool,
+    kdf_iterations: Option<u32>,
+    dictionary_id: Option<u32>,
+    block_size: Option<u32>,
+    block_count: Option<usize>,
+}
+
+#[derive(Serialize, Default)]
+struct ChecksumInfo {
+    crc32: Option<String>,
+    sha256: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ProvenanceInfo {
+    created_at: f64,
+    producer: String,
+}
+
+#[derive(Serialize)]
+struct Section {
+    name: &'static str,
+    offset: usize,
+    size: usize,
+}
+
+#[derive(Serialize, Default)]
+pub struct Inspection {
+    // 头部有效且容器完整 (压缩数据和原始长度字段都在数据范围内)
+    pub valid: bool,
+    pub error: Option<String>,
+    pub error_code: Option<DecodeErrorCode>,
+    pub total_size: f64,
+    header: HeaderInfo,
+    pub codec: Option<&'static str>,
+    content_type: Option<&'static str>,
+    payload: Option<PayloadInfo>,
+    checksums: ChecksumInfo,
+    metadata: metadata::Metadata,
+    provenance: Option<ProvenanceInfo>,
+    // 原始长度字段为 "original_len"，尾部帧格式为 "footer"
+    trailer_kind: Option<&'static str>,
+    // 容器结束之后多余的字节数
+    trailing_bytes: f64,
+    // 按偏移排列的各段: header / extensions / block_table / metadata / payload / trailer / trailing
+    sections: Vec<Section>,
+}
+
+impl Inspection {
+    pub fn magic(&self) -> Option<&str> {
+        self.header.magic.as_deref()
+    }
+
+    pub fn version(&self) -> Option<u32> {
+        self.header.version
+    }
+
+    pub fn compressed_size(&self) -> Option<f64> {
+        self.payload.as_ref().map(|payload| payload.compressed_size)
+    }
+
+    pub fn original_size(&self) -> Option<f64> {
+        self.payload.as_ref().and_then(|payload| payload.original_size)
+    }
+
+    pub fn created_at(&self) -> Option<f64> {
+        self.provenance.as_ref().map(|provenance| provenance.created_at)
+    }
+
+    pub fn producer(&self) -> Option<&str> {
+        self.provenance.as_ref().map(|provenance| provenance.producer.as_str())
+    }
+
+    // 头部字段的名称和值 (按偏移排列)
+    pub fn header_fields(&self) -> impl Iterator<Item = (&'static str, Option<&str>)> {
+        self.header.fields.iter().map(|field| (field.name, field.value.as_deref()))
+    }
+}
+
+// 检查容器结构，返回各段的位置和内容。不解压载荷，因此不校验 CRC32 / SHA-256
+pub fn inspect_container(data: &[u8]) -> Inspection {
+    let reader = ByteReader::new(data, "容器头部");
+    let mut inspection = Inspection {
+        total_size: data.len() as f64,
+        ..Inspection::default()
+    };
+    inspection.header.magic = reader
+        .field_bytes(&format::MAGIC)
+        .ok()
+        .map(|magic| String::from_utf8_lossy(magic).into_owned());
+    inspection.header.version = reader.field(&format::VERSION).ok();
+    inspection.header.fields = layout(&reader, inspection.header.version)
+        .into_iter()
+        .map(|field| HeaderField {
+            name: field.name,
+            offset: field.offset,
+            size: field.size,
+            value: field_value(&reader, &field),
+        })
+        .collect();
+    if inspection.header.version == Some(3) {
+        if let Ok(flags) = reader.field(&format::FLAGS) {
+            inspection.header.flags = Some(flags as u16);
+            inspection.header.flag_names = flag_names(flags as u16);
+        }
+    }
+
+    let header = match parse_container_header(data) {
+        Ok(header) => header,
+        Err(error) => {
+            inspection.error = Some(error.message);
+            inspection.error_code = Some(error.code);
+            return inspection;
+        }
+    };
+    describe_sections(&mut inspection, &header, data.len());
+    inspection.header.size = Some(header.payload_offset);
+    inspection.codec = Some(header.codec.name());
+    inspection.content_type = Some(header.kind.name());
+    inspection.checksums = ChecksumInfo {
+        crc32: header.crc32.map(|crc32| format!("{:08x}", crc32)),
+        sha256: header.sha256.map(|digest| to_hex(&digest)),
+    };
+    inspection.provenance = header.provenance.as_ref().map(|provenance| ProvenanceInfo {
+        created_at: provenance.created_at as f64,
+        producer: provenance.producer.clone(),
+    });
+    inspection.trailer_kind = Some(if header.footer { "footer" } else { "original_len" });
+
+    let trailer_offset = header.payload_offset + header.compressed_len;
+    let original_len = ByteReader::new(data, "容器")
+        .slice(trailer_offset, header.trailer_len())
+        .and_then(|trailer| header.read_original_len(trailer))
+        .map_err(|e| e.context("缺少原始数据长度字段"));
+    if let Err(error) = &original_len {
+        inspection.error = Some(error.message.clone());
+        inspection.error_code = Some(error.code);
+    }
+    let original_len = original_len.ok();
+    inspection.payload = Some(PayloadInfo {
+        offset: header.payload_offset,
+        compressed_size: header.compressed_len as f64,
+        original_size: original_len.map(|len| len as f64),
+        compression_ratio: original_len.map(|len| header.compression_ratio(len)),
+        encrypted: header.encryption.is_some(),
+        kdf_iterations: header.kdf.as_ref().map(|kdf| kdf.iterations),
+        dictionary_id: header.dict_id,
+        block_size: header.block_index.as_ref().map(|index| index.block_size),
+        block_count: header.block_index.as_ref().map(|index| index.ends.len()),
+    });
+    inspection.trailing_bytes = data.len().saturating_sub(header.total_len()) as f64;
+    inspection.valid = inspection.error.is_none();
+    inspection.metadata = header.metadata;
+    inspection
+}
+
+// 按版本列出头部字段 (扩展字段按声明的标志列出，即使数据不完整)
+fn layout(reader: &ByteReader, version: Option<u32>) -> Vec<Field> {
+    match version {
+        Some(1 | 2) => format::LEGACY_HEADER.to_vec(),
+        Some(3) => {
+            let mut fields = format::V3_HEADER.to_vec();
+            if let Ok(flags) = reader.field(&format::FLAGS) {
+                fields.extend(format::v3_extension_fields(flags as u16));
+            }
+            fields
+        }
+        _ => vec![format::MAGIC, format::VERSION],
+    }
+}
+
+fn field_value(reader: &ByteReader, field: &Field) -> Option<String> {
+    match field.kind {
+        FieldKind::Bytes if field.name == format::MAGIC.name => {
+            reader.field_bytes(field).ok().map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+        }
+        FieldKind::Bytes => reader.field_bytes(field).ok().map(to_hex),
+        FieldKind::U16 if field.name == format::FLAGS.name => {
+            reader.field(field).ok().map(|flags| format!("{:#06x}", flags))
+        }
+        _ => reader.field_u64(field).ok().map(|value| value.to_string()),
+    }
+}
+
+// 置位的标志名称，未知标志位以十六进制列出
+fn flag_names(flags: u16) -> Vec<String> {
+    let mut names: Vec<String> = format::FLAG_NAMES
+        .iter()
+        .filter(|(flag, _)| flags & flag != 0)
+        .map(|(_, name)| name.to_string())
+        .collect();
+    let known = format::FLAG_NAMES.iter().fold(0, |known, (flag, _)| known | flag);
+    for bit in 0..16 {
+        let flag = 1u16 << bit;
+        if flags & flag != 0 && known & flag == 0 {
+            names.push(format!("{:#06x}", flag));
+        }
+    }
+    names
+}
+
+// 头部解析成功后按偏移列出各段 (超出数据范围的部分按声明的长度列出)
+fn describe_sections(inspection: &mut Inspection, header: &ContainerHeader, data_len: usize) {
+    let sections = &mut inspection.sections;
+    let mut push = |name: &'static str, offset: usize, size: usize| {
+        if size > 0 {
+            sections.push(Section { name, offset, size });
+        }
+    };
+    if header.version < 3 {
+        push("header", 0, header.payload_offset);
+    } else {
+        let flags = inspection.header.flags.unwrap_or(0);
+        let extensions_end = v3_header_len(flags);
+        let table_len = header.block_index.as_ref().map_or(0, |index| index.ends.len() * 4);
+        push("header", 0, FIXED_HEADER_LEN);
+        push("extensions", FIXED_HEADER_LEN, extensions_end - FIXED_HEADER_LEN);
+        push("block_table", extensions_end, table_len);
+        push("metadata", extensions_end + table_len, header.payload_offset - extensions_end - table_len);
+    }
+    push("payload", header.payload_offset, header.compressed_len);
+    push("trailer", header.payload_offset + header.compressed_len, header.trailer_len());
+    push("trailing", header.total_len(), data_len.saturating_sub(header.total_len()));
+}
+
+// debug_dump 默认每段最多显示的字节数
+const DUMP_DEFAULT_BYTES: usize = 64;
+const DUMP_ROW: usize = 16;
+
+// 带注释的十六进制转储: 按 inspect_container 的结果逐段输出，头部和尾部逐字段标注名称和值，
+// 其余各段每段最多显示 max_bytes 字节 (默认 64)
+pub fn debug_dump(data: &[u8], max_bytes: Option<u32>) -> String {
+    let max_bytes = max_bytes.map_or(DUMP_DEFAULT_BYTES, |bytes| bytes as usize);
+    let inspection = inspect_container(data);
+    let mut lines = summary_lines(&inspection);
+    let fields = &inspection.header.fields;
+
+    if inspection.sections.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("[header] 0x{:08x}", 0));
+        lines.extend(fields.iter().map(|field| field_line(data, field)));
+        let end = fields
+            .iter()
+            .filter(|field| field.value.is_some())
+            .map(|field| field.offset + field.size)
+            .max()
+            .unwrap_or(0);
+        dump_bytes(&mut lines, "raw", data, end, data.len().saturating_sub(end), max_bytes);
+        return lines.join("\n");
+    }
+
+    for section in &inspection.sections {
+        let in_section =
+            |field: &&HeaderField| field.offset >= section.offset && field.offset < section.offset + section.size;
+        match section.name {
+            "header" | "extensions" => {
+                lines.push(String::new());
+                lines.push(section_title(section));
+                lines.extend(fields.iter().filter(in_section).map(|field| field_line(data, field)));
+            }
+            "trailer" => {
+                lines.push(String::new());
+                lines.push(section_title(section));
+                let trailer = match (inspection.trailer_kind, section.size) {
+                    (Some("footer"), _) => format::FOOTER,
+                    (_, size) if size == format::ORIGINAL_LEN_LONG.size => &[format::ORIGINAL_LEN_LONG],
+                    _ => &[format::ORIGINAL_LEN],
+                };
+                let reader = ByteReader::new(data.get(section.offset..).unwrap_or_default(), "容器尾部");
+                for field in trailer {
+                    let field = HeaderField {
+                        name: field.name,
+                        offset: section.offset + field.offset,
+                        size: field.size,
+                        value: field_value(&reader, field),
+                    };
+                    lines.push(field_line(data, &field));
+                }
+            }
+            name => dump_bytes(&mut lines, name, data, section.offset, section.size, max_bytes),
+        }
+    }
+    lines.join("\n")
+}
+
+fn summary_lines(inspection: &Inspection) -> Vec<String> {
+    let mut lines = vec![format!("FastDog 容器调试转储: 共 {} 字节", inspection.total_size)];
+    let mut summary = vec![format!("版本 {}", inspection.version().map_or("?".to_string(), |v| v.to_string()))];
+    if let (Some(codec), Some(content_type)) = (inspection.codec, inspection.content_type) {
+        summary.push(format!("编码 {}", codec));
+        summary.push(format!("内容类型 {}", content_type));
+    }
+    if !inspection.header.flag_names.is_empty() {
+        summary.push(format!("标志 {}", inspection.header.flag_names.join(", ")));
+    }
+    if let Some(payload) = &inspection.payload {
+        summary.push(format!("压缩数据 {} 字节", payload.compressed_size));
+        if let Some(original_size) = payload.original_size {
+            summary.push(format!("原始数据 {} 字节", original_size));
+        }
+    }
+    lines.push(summary.join("，"));
+    lines.push(match (&inspection.error, inspection.error_code) {
+        (Some(error), Some(code)) => format!("状态: 无效 ({}) {}", code.as_str(), error),
+        _ if inspection.trailing_bytes > 0.0 => format!("状态: 有效，容器之后有 {} 字节多余数据", inspection.trailing_bytes),
+        _ => "状态: 有效".to_string(),
+    });
+    if !inspection.metadata.is_empty() {
+        let entries: Vec<String> =
+            inspection.metadata.iter().map(|(key, value)| format!("{}={:?}", key, value)).collect();
+        lines.push(format!("元数据: {}", entries.join(", ")));
+    }
+    lines.push("标记 * 的字段为魔数、版本和长度字段".to_string());
+    lines
+}
+
+fn section_title(section: &Section) -> String {
+    format!("[{}] 0x{:08x} +{} 字节", section.name, section.offset, section.size)
+}
+
+// 魔数、版本和长度字段
+fn is_highlighted(name: &str) -> bool {
+    name == format::MAGIC.name || name == format::VERSION.name || name.contains("len") || name == "block_count"
+}
+
+// 一个字段一行: 偏移、原始字节 (超过一行时截断) 和解析后的值
+fn field_line(data: &[u8], field: &HeaderField) -> String {
+    let marker = if is_highlighted(field.name) { '*' } else { ' ' };
+    let end = field.offset.saturating_add(field.size).min(data.len());
+    let bytes = data.get(field.offset..end).unwrap_or_default();
+    let mut hex = hex_bytes(&bytes[..bytes.len().min(DUMP_ROW)]);
+    if bytes.len() > DUMP_ROW {
+        hex.push_str(" …");
+    }
+    let value = field.value.as_deref().unwrap_or("<数据不完整>");
+    format!("{} 0x{:08x}  {:<50} {} = {}", marker, field.offset, hex, field.name, value)
+}
+
+// 按每行 16 字节输出十六进制和 ASCII，最多 max_bytes 字节
+fn dump_bytes(lines: &mut Vec<String>, name: &str, data: &[u8], offset: usize, size: usize, max_bytes: usize) {
+    if size == 0 {
+        return;
+    }
+    lines.push(String::new());
+    lines.push(format!("[{}] 0x{:08x} +{} 字节", name, offset, size));
+    let available = data.get(offset..).unwrap_or_default();
+    let shown = &available[..size.min(max_bytes).min(available.len())];
+    for (row, chunk) in shown.chunks(DUMP_ROW).enumerate() {
+        let ascii: String = chunk
+            .iter()
+            .map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' })
+            .collect();
+        lines.push(format!("  0x{:08x}  {:<50} |{}|", offset + row * DUMP_ROW, hex_bytes(chunk), ascii));
+    }
+    let present = size.min(available.len());
+    if shown.len() < present {
+        lines.push(format!("  ... 省略 {} 字节", present - shown.len()));
+    }
+    if present < size {
+        lines.push(format!("  ... 数据在 0x{:08x} 处截断，缺少 {} 字节", data.len(), size - present));
+    }
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<_>>().join(" ")
+}
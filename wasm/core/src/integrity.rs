@@ -0,0 +1,22 @@
+use sha2::{Digest, Sha256};
+
+use crate::container::ContainerHeader;
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// 校验 SHA-256 摘要 (头部带摘要时)
+pub fn verify_sha256(header: &ContainerHeader, decompressed: &[u8]) -> Result<(), DecodeError> {
+    if let Some(expected) = header.sha256 {
+        let actual: [u8; 32] = Sha256::digest(decompressed).into();
+        if actual != expected {
+            return Err(DecodeError::new(
+                DecodeErrorCode::ChecksumMismatch,
+                format!("SHA-256 校验失败: 期望 {}, 实际 {}", to_hex(&expected), to_hex(&actual)),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,131 @@
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// 顶层数组的流式 JSON 解析
+//
+// 只扫描顶层数组的结构 (字符串、转义和括号深度)，每当一个元素的字节完整到达就单独解析该元素，
+// 不必等整个文档解压完成。元素内部由 serde_json 完整解析，扫描器只负责找出元素边界
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum State {
+    // 等待顶层的 '['
+    #[default]
+    Start,
+    // 顶层数组内部
+    Array,
+    // 顶层数组已结束，之后只允许空白
+    Done,
+}
+
+#[derive(Default)]
+pub struct JsonArrayStream {
+    state: State,
+    // 下一个待扫描的字节位置
+    cursor: usize,
+    // 当前元素的起始位置
+    element_start: Option<usize>,
+    // 上一个顶层逗号之后还没有出现元素
+    expect_element: bool,
+    // 相对顶层数组的括号深度，元素内部为 1 以上
+    depth: u32,
+    in_string: bool,
+    escaped: bool,
+    elements: u32,
+}
+
+const UTF8_BOM: &[u8; 3] = b"\xEF\xBB\xBF";
+
+fn invalid(message: impl Into<String>) -> DecodeError {
+    DecodeError::new(DecodeErrorCode::InvalidInput, message)
+}
+
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+impl JsonArrayStream {
+    // payload 为目前已解压的全部数据，每个元素完整到达时以 (序号, 元素) 调用一次 on_element
+    pub fn advance(
+        &mut self,
+        payload: &[u8],
+        mut on_element: impl FnMut(u32, serde_json::Value),
+    ) -> Result<(), DecodeError> {
+        while self.cursor < payload.len() {
+            let position = self.cursor;
+            let byte = payload[position];
+            self.cursor += 1;
+
+            match self.state {
+                State::Start => {
+                    // 允许 UTF-8 BOM 和前导空白
+                    let bom = position < UTF8_BOM.len() && byte == UTF8_BOM[position];
+                    if byte == b'[' {
+                        self.state = State::Array;
+                    } else if !bom && !is_whitespace(byte) {
+                        return Err(invalid("流式 JSON 解析要求载荷的顶层为数组"));
+                    }
+                }
+                State::Done => {
+                    if !is_whitespace(byte) {
+                        return Err(invalid(format!("JSON 顶层数组之后有多余内容 (位置 {})", position)));
+                    }
+                }
+                State::Array if self.in_string => {
+                    if self.escaped {
+                        self.escaped = false;
+                    } else if byte == b'\\' {
+                        self.escaped = true;
+                    } else if byte == b'"' {
+                        self.in_string = false;
+                    }
+                }
+                State::Array => match byte {
+                    b',' | b']' if self.depth == 0 => {
+                        match self.element_start.take() {
+                            Some(start) => {
+                                let value = serde_json::from_slice(&payload[start..position]).map_err(|e| {
+                                    invalid(format!("JSON 数组第 {} 个元素解析失败: {}", self.elements, e))
+                                })?;
+                                on_element(self.elements, value);
+                                self.elements += 1;
+                            }
+                            None if byte == b',' || self.expect_element => {
+                                return Err(invalid(format!("JSON 数组在位置 {} 缺少元素", position)));
+                            }
+                            None => {}
+                        }
+                        self.expect_element = byte == b',';
+                        if byte == b']' {
+                            self.state = State::Done;
+                        }
+                    }
+                    _ if is_whitespace(byte) => {}
+                    _ => {
+                        if self.element_start.is_none() {
+                            self.element_start = Some(position);
+                        }
+                        match byte {
+                            b'"' => self.in_string = true,
+                            b'[' | b'{' => self.depth += 1,
+                            b']' | b'}' => {
+                                self.depth = self
+                                    .depth
+                                    .checked_sub(1)
+                                    .ok_or_else(|| invalid(format!("JSON 括号不匹配 (位置 {})", position)))?;
+                            }
+                            _ => {}
+                        }
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+
+    // 载荷结束时检查顶层数组已完整闭合
+    pub fn check_complete(&self) -> Result<(), DecodeError> {
+        match self.state {
+            State::Done => Ok(()),
+            State::Start => Err(invalid("流式 JSON 解析要求载荷的顶层为数组")),
+            State::Array => Err(invalid(format!("JSON 顶层数组不完整: 已解析 {} 个元素", self.elements))),
+        }
+    }
+}
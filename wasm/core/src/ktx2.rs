@@ -0,0 +1,256 @@
+use crate::byte_reader::ByteReader;
+use crate::codec::{self, Codec};
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// KTX2 纹理容器解析
+//
+// 布局: 12 字节标识 | 头部 9 个 u32 (vkFormat、尺寸、层/面/级别数、超压缩方式) | 索引
+// (DFD / KVD 偏移长度 u32，SGD 偏移长度 u64) | 每个 mip 级别的 偏移 u64 | 长度 u64 | 解压后长度 u64。
+// vkFormat 为 0 时数据是 Basis Universal (ETC1S 或 UASTC)，具体类型由 DFD 的颜色模型给出
+pub const KTX2_IDENTIFIER: &[u8; 12] = b"\xABKTX 20\xBB\r\n\x1A\n";
+const HEADER_LEN: usize = 80;
+const LEVEL_INDEX_ENTRY_LEN: usize = 24;
+
+const SUPERCOMPRESSION_NONE: u32 = 0;
+const SUPERCOMPRESSION_BASIS_LZ: u32 = 1;
+const SUPERCOMPRESSION_ZSTD: u32 = 2;
+const SUPERCOMPRESSION_ZLIB: u32 = 3;
+
+// DFD 颜色模型 (KHR_DF_MODEL_*)
+const COLOR_MODEL_ETC1S: u8 = 163;
+const COLOR_MODEL_UASTC: u8 = 166;
+
+// 本模块可以直接输出的 GPU 格式: (vkFormat, 目标名, 是否 sRGB)
+const NATIVE_FORMATS: &[(u32, &str, bool)] = &[
+    (37, "rgba8", false),
+    (43, "rgba8", true),
+    (145, "bc7", false),
+    (146, "bc7", true),
+    (157, "astc", false),
+    (158, "astc", true),
+];
+
+pub struct Ktx2<'a> {
+    pub vk_format: u32,
+    pub width: u32,
+    pub height: u32,
+    pub layer_count: u32,
+    pub face_count: u32,
+    pub supercompression: u32,
+    pub color_model: Option<u8>,
+    levels: Vec<Level<'a>>,
+}
+
+struct Level<'a> {
+    data: &'a [u8],
+    uncompressed_len: u64,
+}
+
+fn invalid(message: impl Into<String>) -> DecodeError {
+    DecodeError::new(DecodeErrorCode::InvalidInput, message)
+}
+
+fn unsupported(message: impl Into<String>) -> DecodeError {
+    DecodeError::new(DecodeErrorCode::UnsupportedContentType, message)
+}
+
+pub fn is_ktx2(data: &[u8]) -> bool {
+    data.starts_with(KTX2_IDENTIFIER)
+}
+
+// 解析 KTX2 头部和级别索引并检查各级别的范围
+pub fn parse(data: &[u8]) -> Result<Ktx2<'_>, DecodeError> {
+    if !is_ktx2(data) {
+        return Err(invalid("数据不是 KTX2 纹理 (标识不匹配)"));
+    }
+    if data.len() < HEADER_LEN {
+        return Err(invalid("KTX2 头部不完整"));
+    }
+    let reader = ByteReader::new(data, "KTX2 头部");
+    let field = |index: usize| reader.u32_at(12 + index * 4);
+    let level_count = field(7)?.max(1) as usize;
+    let index_end = level_count
+        .checked_mul(LEVEL_INDEX_ENTRY_LEN)
+        .and_then(|len| len.checked_add(HEADER_LEN))
+        .filter(|&end| end <= data.len())
+        .ok_or_else(|| invalid("KTX2 级别索引不完整"))?;
+
+    let mut levels = Vec::with_capacity(level_count);
+    for entry in (HEADER_LEN..index_end).step_by(LEVEL_INDEX_ENTRY_LEN) {
+        let (offset, len) = (reader.u64_at(entry)?, reader.u64_at(entry + 8)?);
+        let level = offset
+            .checked_add(len)
+            .filter(|&end| end <= data.len() as u64)
+            .map(|end| &data[offset as usize..end as usize])
+            .ok_or_else(|| invalid(format!("KTX2 级别 {} 超出数据范围", levels.len())))?;
+        levels.push(Level {
+            data: level,
+            uncompressed_len: reader.u64_at(entry + 16)?,
+        });
+    }
+
+    // DFD: 总长度 u32 之后是基本描述块，颜色模型位于块内第 8 字节
+    let (dfd_offset, dfd_len) = (reader.u32_at(48)? as usize, reader.u32_at(52)? as usize);
+    let color_model = (dfd_len >= 13)
+        .then(|| data.get(dfd_offset + 12).copied())
+        .flatten();
+
+    Ok(Ktx2 {
+        vk_format: field(0)?,
+        width: field(2)?,
+        height: field(3)?,
+        layer_count: field(5)?,
+        face_count: field(6)?,
+        supercompression: field(8)?,
+        color_model,
+        levels,
+    })
+}
+
+impl Ktx2<'_> {
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    // Basis Universal 编码类型 ("etc1s" / "uastc")，普通 GPU 格式为 None
+    pub fn basis(&self) -> Option<&'static str> {
+        if self.vk_format != 0 {
+            return None;
+        }
+        match (self.supercompression, self.color_model) {
+            (SUPERCOMPRESSION_BASIS_LZ, _) | (_, Some(COLOR_MODEL_ETC1S)) => Some("etc1s"),
+            (_, Some(COLOR_MODEL_UASTC)) => Some("uastc"),
+            _ => None,
+        }
+    }
+
+    // 纹理本身的格式对应的目标名和是否 sRGB
+    pub fn native_format(&self) -> Option<(&'static str, bool)> {
+        NATIVE_FORMATS
+            .iter()
+            .find(|&&(vk_format, _, _)| vk_format == self.vk_format)
+            .map(|&(_, name, srgb)| (name, srgb))
+    }
+
+    pub fn supercompression_name(&self) -> &'static str {
+        match self.supercompression {
+            SUPERCOMPRESSION_NONE => "none",
+            SUPERCOMPRESSION_BASIS_LZ => "basislz",
+            SUPERCOMPRESSION_ZSTD => "zstd",
+            SUPERCOMPRESSION_ZLIB => "zlib",
+            _ => "unknown",
+        }
+    }
+
+    // 按目标格式输出全部 mip 级别 (级别 0 为最大尺寸)。
+    // 纹理格式与目标一致时只做超压缩 (zstd / zlib) 的解压; Basis Universal 转码不在本模块中
+    pub fn transcode(&self, target: &str) -> Result<Vec<Vec<u8>>, DecodeError> {
+        if !NATIVE_FORMATS.iter().any(|&(_, name, _)| name == target) {
+            return Err(invalid(format!("不支持的纹理目标格式: {} (可选 rgba8 / bc7 / astc)", target)));
+        }
+        if let Some(basis) = self.basis() {
+            return Err(unsupported(format!(
+                "KTX2 纹理为 Basis Universal ({}) 编码，本模块尚不包含 Basis 转码器，请使用 KTX2Loader 等转码",
+                basis
+            )));
+        }
+        match self.native_format() {
+            Some((name, _)) if name == target => {}
+            _ => {
+                return Err(unsupported(format!(
+                    "KTX2 纹理格式 (vkFormat {}) 不能转换为 {}",
+                    self.vk_format, target
+                )));
+            }
+        }
+        self.levels.iter().enumerate().map(|(index, level)| self.level_data(index, level)).collect()
+    }
+
+    fn level_data(&self, index: usize, level: &Level) -> Result<Vec<u8>, DecodeError> {
+        match self.supercompression {
+            SUPERCOMPRESSION_NONE => Ok(level.data.to_vec()),
+            SUPERCOMPRESSION_ZSTD | SUPERCOMPRESSION_ZLIB => {
+                let codec = if self.supercompression == SUPERCOMPRESSION_ZSTD { Codec::Zstd } else { Codec::Zlib };
+                let len = usize::try_from(level.uncompressed_len)
+                    .map_err(|_| invalid(format!("KTX2 级别 {} 的解压后长度过大", index)))?;
+                codec::check_expansion(codec, level.data.len(), level.uncompressed_len)?;
+                let output = codec::decompress_sliced(codec, level.data, len, |produced| {
+                    if produced > len {
+                        return Err(codec::length_exceeded(len));
+                    }
+                    Ok(())
+                })?;
+                if output.len() != len {
+                    return Err(DecodeError::new(
+                        DecodeErrorCode::LengthMismatch,
+                        format!("KTX2 级别 {} 解压后长度不匹配: 期望 {}, 实际 {}", index, len, output.len()),
+                    ));
+                }
+                Ok(output)
+            }
+            _ => Err(unsupported(format!(
+                "不支持的 KTX2 超压缩方式: {} ({})",
+                self.supercompression,
+                self.supercompression_name()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 单个级别的 rgba8 纹理 (没有 DFD)，levels 为该级别按超压缩方式存放的数据
+    fn rgba8_texture(supercompression: u32, level: &[u8], uncompressed_len: u64) -> Vec<u8> {
+        let mut data = KTX2_IDENTIFIER.to_vec();
+        for value in [37, 1, 2, 2, 0, 0, 1, 1, supercompression] {
+            data.extend_from_slice(&u32::to_le_bytes(value));
+        }
+        data.extend_from_slice(&[0; 32]);
+        let offset = (HEADER_LEN + LEVEL_INDEX_ENTRY_LEN) as u64;
+        for value in [offset, level.len() as u64, uncompressed_len] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(level);
+        data
+    }
+
+    #[test]
+    fn supercompressed_levels_are_inflated() {
+        let pixels: Vec<u8> = (0..16).collect();
+        for (scheme, codec) in [(SUPERCOMPRESSION_ZSTD, Codec::Zstd), (SUPERCOMPRESSION_ZLIB, Codec::Zlib)] {
+            let compressed = codec::compress(codec, &pixels, 0, 0).unwrap();
+            let data = rgba8_texture(scheme, &compressed, pixels.len() as u64);
+            let texture = parse(&data).unwrap();
+            assert_eq!(texture.basis(), None);
+            assert_eq!(texture.transcode("rgba8").unwrap(), std::slice::from_ref(&pixels), "{}", texture.supercompression_name());
+            assert_eq!(texture.transcode("bc7").err().map(|e| e.code), Some(DecodeErrorCode::UnsupportedContentType));
+
+            // 声明的解压后长度与实际不符
+            for wrong in [pixels.len() as u64 - 1, pixels.len() as u64 + 1] {
+                let data = rgba8_texture(scheme, &compressed, wrong);
+                let result = parse(&data).unwrap().transcode("rgba8");
+                assert_eq!(result.err().map(|e| e.code), Some(DecodeErrorCode::LengthMismatch));
+            }
+        }
+        let raw = rgba8_texture(SUPERCOMPRESSION_NONE, &pixels, 0);
+        assert_eq!(parse(&raw).unwrap().transcode("rgba8").unwrap(), [pixels]);
+    }
+
+    #[test]
+    fn basis_textures_and_bad_indexes_are_rejected() {
+        let mut data = rgba8_texture(SUPERCOMPRESSION_BASIS_LZ, &[0; 16], 0);
+        data[12..16].copy_from_slice(&0u32.to_le_bytes());
+        let texture = parse(&data).unwrap();
+        assert_eq!(texture.basis(), Some("etc1s"));
+        assert_eq!(texture.transcode("rgba8").err().map(|e| e.code), Some(DecodeErrorCode::UnsupportedContentType));
+
+        let data = rgba8_texture(SUPERCOMPRESSION_NONE, &[0; 16], 0);
+        assert_eq!(parse(&data[..data.len() - 1]).err().map(|e| e.code), Some(DecodeErrorCode::InvalidInput));
+        assert_eq!(parse(&data[..HEADER_LEN + 8]).err().map(|e| e.code), Some(DecodeErrorCode::InvalidInput));
+        let mut levels = data.clone();
+        levels[12 + 7 * 4..12 + 8 * 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(parse(&levels).err().map(|e| e.code), Some(DecodeErrorCode::InvalidInput));
+    }
+}
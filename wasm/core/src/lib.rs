@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+
+// FastDog 容器格式的平台无关实现
+//
+// 容器解析、编解码、校验、加密和转码都在这里，不依赖浏览器或 JS 运行时，服务端和命令行工具
+// 可以直接使用。WASM 绑定 (fastdog-decoder) 在此基础上增加内存配额、JS 回调和 Web Crypto 等
+// 浏览器相关的接口，并以 js 特性构建本 crate
+
+pub mod aes_gcm;
+pub mod buffer_pool;
+pub mod bundle;
+pub mod byte_reader;
+pub mod cbor;
+pub mod codec;
+pub mod container;
+pub mod dictionary;
+pub mod encoder;
+pub mod encryption;
+pub mod error;
+pub mod format;
+pub mod inspect;
+pub mod integrity;
+pub mod json_stream;
+pub mod ktx2;
+pub mod locale;
+pub mod meshopt;
+pub mod metadata;
+pub mod msgpack;
+pub mod password;
+pub mod patch;
+pub mod payload_kind;
+pub mod platform;
+pub mod profiles;
+pub mod provenance;
+pub mod seek;
+pub mod shard;
+pub mod structured;
+pub mod transcode;
+pub mod validate;
+
+use encoder::{encode_container, encode_metadata_container};
+use payload_kind::PayloadKind;
+use transcode::{parse_codec, target_profile};
+
+pub use error::{DecodeError, DecodeErrorCode};
+
+fn invalid(message: String) -> DecodeError {
+    DecodeError::new(DecodeErrorCode::InvalidInput, message)
+}
+
+// 解码后的载荷
+pub struct Unpacked {
+    pub version: u32,
+    pub codec: &'static str,
+    // 载荷类型名称 ("json"、"glb" 等)
+    pub kind: &'static str,
+    pub payload: Vec<u8>,
+}
+
+// 校验通过的容器摘要
+pub struct Verified {
+    pub version: u32,
+    pub kind: &'static str,
+    pub original_size: u64,
+    // 头部或尾部带有并已核对的校验值
+    pub crc32: bool,
+    pub sha256: bool,
+}
+
+// 按档案把载荷编码为版本 3 容器。kind 为载荷类型名称，codec 覆盖档案的编码 (使用该编码的默认级别)，
+// metadata 非空时写入头部元数据区
+pub fn pack(
+    payload: &[u8],
+    kind: &str,
+    metadata: &BTreeMap<String, String>,
+    profile: Option<&str>,
+    codec: Option<&str>,
+) -> Result<Vec<u8>, DecodeError> {
+    let kind = PayloadKind::from_name(kind).map_err(invalid)?;
+    let codec = codec.map(parse_codec).transpose().map_err(invalid)?;
+    let profile = target_profile(profile, codec).map_err(invalid)?;
+    let output = if metadata.is_empty() {
+        encode_container(payload, kind, &profile)
+    } else {
+        encode_metadata_container(payload, kind, metadata, &profile)
+    };
+    output.map_err(invalid)
+}
+
+// 解码容器 (含长度和校验值核对)。加密容器返回 KeyRequired
+pub fn unpack(data: &[u8]) -> Result<Unpacked, DecodeError> {
+    let (header, _, payload) = container::decode(data)?;
+    integrity::verify_sha256(&header, &payload)?;
+    Ok(Unpacked {
+        version: header.version,
+        codec: header.codec.name(),
+        kind: header.kind.name(),
+        payload,
+    })
+}
+
+// 完整解码并核对长度、CRC32 和 SHA-256，不保留载荷
+pub fn verify(data: &[u8]) -> Result<Verified, DecodeError> {
+    let (header, original_len, payload) = container::decode(data)?;
+    let checked = integrity::verify_sha256(&header, &payload);
+    buffer_pool::recycle(payload);
+    checked?;
+    Ok(Verified {
+        version: header.version,
+        kind: header.kind.name(),
+        original_size: original_len,
+        crc32: header.crc32.is_some(),
+        sha256: header.sha256.is_some(),
+    })
+}
+
+// 与 JS 接口 inspect 相同的结构检查结果，格式化为 JSON 文本
+pub fn inspect_json(data: &[u8]) -> Result<String, DecodeError> {
+    serde_json::to_string_pretty(&inspect::inspect_container(data))
+        .map_err(|e| DecodeError::new(DecodeErrorCode::Internal, format!("检查结果无法转换为 JSON: {}", e)))
+}
+
+// 带注释的十六进制转储，与 JS 接口 debug_dump 相同
+pub fn debug_dump(data: &[u8], max_bytes: Option<u32>) -> String {
+    inspect::debug_dump(data, max_bytes)
+}
+
+// 转码为目标版本和编码，参数含义与 JS 接口 transcode 相同
+pub fn transcode(
+    data: &[u8],
+    target_version: u32,
+    target_codec: Option<&str>,
+    profile: Option<&str>,
+) -> Result<Vec<u8>, DecodeError> {
+    transcode::transcode(data, target_version, target_codec, profile)
+}
@@ -0,0 +1,77 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::error::DecodeErrorCode;
+
+// 错误信息语言
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Locale {
+    Zh,
+    En,
+}
+
+impl Locale {
+    pub fn from_name(name: &str) -> Option<Locale> {
+        match name.to_ascii_lowercase().as_str() {
+            "zh" | "zh-cn" | "zh-hans" => Some(Locale::Zh),
+            "en" | "en-us" | "en-gb" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::Zh => "zh",
+            Locale::En => "en",
+        }
+    }
+}
+
+// 全局设置 (多线程解压时工作线程也读取同一设置)
+static LOCALE: AtomicU8 = AtomicU8::new(0);
+
+pub fn current() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::En,
+        _ => Locale::Zh,
+    }
+}
+
+// 设置错误信息语言，之后产生的解码错误使用该语言
+pub fn set(locale: Locale) {
+    LOCALE.store(if locale == Locale::En { 1 } else { 0 }, Ordering::Relaxed);
+}
+
+// 按当前语言转换错误信息，中文时原样返回
+pub fn localize(code: DecodeErrorCode, message: String) -> String {
+    match current() {
+        Locale::Zh => message,
+        Locale::En => english_message(code).to_string(),
+    }
+}
+
+fn english_message(code: DecodeErrorCode) -> &'static str {
+    match code {
+        DecodeErrorCode::TooShort => "Data is too short to be a FastDog container",
+        DecodeErrorCode::BadMagic => "Invalid magic bytes, not a FastDog container",
+        DecodeErrorCode::UnsupportedVersion => "Unsupported container version",
+        DecodeErrorCode::UnsupportedCodec => "Unsupported compression codec",
+        DecodeErrorCode::UnsupportedContentType => "Unsupported content type",
+        DecodeErrorCode::UnsupportedFlags => "Unsupported header flags",
+        DecodeErrorCode::Truncated => "Container data is truncated",
+        DecodeErrorCode::LengthMismatch => "Decompressed length does not match the header",
+        DecodeErrorCode::ChecksumMismatch => "Checksum verification failed",
+        DecodeErrorCode::InflateError => "Failed to decompress payload",
+        DecodeErrorCode::MissingDictionary => "Compression dictionary is not registered",
+        DecodeErrorCode::KeyRequired => "Container is encrypted, a key is required",
+        DecodeErrorCode::DecryptionFailed => "Decryption failed: wrong key or tampered data",
+        DecodeErrorCode::Utf8Error => "Payload is not valid UTF-8",
+        DecodeErrorCode::TransformError => "Decode transform failed",
+        DecodeErrorCode::QuotaExceeded => "Memory quota exceeded",
+        DecodeErrorCode::OutputLimitExceeded => "Output exceeds the configured size or ratio limit",
+        DecodeErrorCode::Timeout => "Decoding exceeded the configured time limit",
+        DecodeErrorCode::Aborted => "Decoding was aborted",
+        DecodeErrorCode::InvalidInput => "Invalid input",
+        DecodeErrorCode::InvalidState => "Invalid decoder state",
+        DecodeErrorCode::Internal => "Internal error",
+    }
+}
@@ -0,0 +1,412 @@
+use serde::Deserialize;
+
+use crate::buffer_pool;
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// meshoptimizer 压缩 (glTF 扩展 EXT_meshopt_compression) 解码
+//
+// gltfpack 等工具把 bufferView 的数据用 meshoptimizer 的编码压缩后存入 BIN 块，扩展字段记录压缩
+// 数据的位置、元素数、元素大小和编码方式，原 bufferView 指向没有数据的回退缓冲区。
+// 三种编码: ATTRIBUTES (顶点数据，按字节分组的差分编码)、TRIANGLES (三角形索引，边/顶点 FIFO 编码)、
+// INDICES (一般索引序列，变长差分编码)。顶点数据解码后还可能需要按 filter 还原 (八面体法线、四元数、指数)
+pub const EXT_MESHOPT_COMPRESSION: &str = "EXT_meshopt_compression";
+
+const VERTEX_HEADER: u8 = 0xa0;
+const INDEX_HEADER: u8 = 0xe0;
+const SEQUENCE_HEADER: u8 = 0xd0;
+
+const VERTEX_BLOCK_SIZE_BYTES: usize = 8192;
+const VERTEX_BLOCK_MAX_SIZE: usize = 256;
+const BYTE_GROUP_SIZE: usize = 16;
+const TAIL_MAX_SIZE: usize = 32;
+
+// 三角形编码数据末尾的顶点组合查找表
+const CODEAUX_TABLE_LEN: usize = 16;
+
+// bufferView 上的 EXT_meshopt_compression 扩展
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeshoptCompression {
+    pub buffer: usize,
+    #[serde(default)]
+    pub byte_offset: usize,
+    pub byte_length: usize,
+    pub byte_stride: usize,
+    pub count: usize,
+    pub mode: String,
+    #[serde(default)]
+    pub filter: Option<String>,
+}
+
+fn malformed(message: impl Into<String>) -> DecodeError {
+    DecodeError::new(DecodeErrorCode::InvalidInput, message)
+}
+
+fn truncated() -> DecodeError {
+    malformed("meshopt 压缩数据不完整")
+}
+
+// 按扩展字段解码一个 bufferView，source 为扩展引用的压缩数据，返回 count * byteStride 字节
+pub fn decode_buffer_view(ext: &MeshoptCompression, source: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let (count, stride) = (ext.count, ext.byte_stride);
+    if count.checked_mul(stride).is_none_or(|len| len > u32::MAX as usize) {
+        return Err(malformed(format!("meshopt bufferView 的元素数 {} 过大", count)));
+    }
+    match ext.mode.as_str() {
+        "ATTRIBUTES" => {
+            let mut output = decode_vertex_buffer(count, stride, source)?;
+            apply_filter(ext.filter.as_deref().unwrap_or("NONE"), &mut output, stride)?;
+            Ok(output)
+        }
+        "TRIANGLES" | "INDICES" => {
+            if stride != 2 && stride != 4 {
+                return Err(malformed(format!("meshopt 索引的 byteStride 必须为 2 或 4: {}", stride)));
+            }
+            if ext.filter.as_deref().is_some_and(|filter| filter != "NONE") {
+                return Err(malformed("meshopt 索引数据不能使用 filter"));
+            }
+            let indices = if ext.mode == "TRIANGLES" {
+                decode_index_buffer(count, source)?
+            } else {
+                decode_index_sequence(count, source)?
+            };
+            Ok(match stride {
+                2 => indices.iter().flat_map(|&index| (index as u16).to_le_bytes()).collect(),
+                _ => indices.iter().flat_map(|&index| index.to_le_bytes()).collect(),
+            })
+        }
+        mode => Err(malformed(format!("不支持的 meshopt 编码方式: {}", mode))),
+    }
+}
+
+fn unzigzag8(value: u8) -> u8 {
+    0u8.wrapping_sub(value & 1) ^ (value >> 1)
+}
+
+// 顶点数据: 每块最多 256 个顶点，块内逐字节位置解码一组差分值 (相对上一个顶点的同一字节)，
+// 数据末尾是第一个块差分的基准顶点 (不足 32 字节时前面补 0)
+pub fn decode_vertex_buffer(count: usize, vertex_size: usize, source: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    if vertex_size == 0 || vertex_size > 256 || !vertex_size.is_multiple_of(4) {
+        return Err(malformed(format!("meshopt 顶点大小必须为 4 的倍数且不超过 256: {}", vertex_size)));
+    }
+    let tail = vertex_size.max(TAIL_MAX_SIZE);
+    if source.len() < 1 + tail {
+        return Err(truncated());
+    }
+    if source[0] != VERTEX_HEADER {
+        return Err(malformed(format!("不支持的 meshopt 顶点编码版本: {:#04x}", source[0])));
+    }
+
+    // count 来自 glTF 扩展字段，最多可达 4GB，不能假设分配一定成功
+    let len = count.checked_mul(vertex_size).ok_or_else(|| malformed(format!("meshopt 顶点数 {} 过大", count)))?;
+    let mut output = buffer_pool::try_zeroed(len)?;
+    let mut last_vertex = source[source.len() - vertex_size..].to_vec();
+    let block_size = ((VERTEX_BLOCK_SIZE_BYTES / vertex_size) & !(BYTE_GROUP_SIZE - 1)).min(VERTEX_BLOCK_MAX_SIZE);
+    let end = source.len() - tail;
+    let mut offset = 1;
+    for block in output.chunks_mut(block_size * vertex_size) {
+        offset = decode_vertex_block(&source[..end], offset, block, vertex_size, &mut last_vertex)?;
+    }
+    // 全部块之后应恰好剩下基准顶点所在的尾部
+    if offset != end {
+        return Err(malformed("meshopt 顶点数据长度与元素数不符"));
+    }
+    Ok(output)
+}
+
+fn decode_vertex_block(
+    source: &[u8],
+    mut offset: usize,
+    block: &mut [u8],
+    vertex_size: usize,
+    last_vertex: &mut [u8],
+) -> Result<usize, DecodeError> {
+    let count = block.len() / vertex_size;
+    let count_aligned = count.next_multiple_of(BYTE_GROUP_SIZE);
+    let mut deltas = [0u8; VERTEX_BLOCK_MAX_SIZE];
+    for k in 0..vertex_size {
+        offset = decode_bytes(source, offset, &mut deltas[..count_aligned])?;
+        let mut previous = last_vertex[k];
+        for (vertex, &delta) in block.chunks_exact_mut(vertex_size).zip(&deltas[..count]) {
+            previous = unzigzag8(delta).wrapping_add(previous);
+            vertex[k] = previous;
+        }
+    }
+    last_vertex.copy_from_slice(&block[block.len() - vertex_size..]);
+    Ok(offset)
+}
+
+// 一组字节 (16 的倍数): 头部每个 16 字节分组用 2 位记录位宽 (0 / 2 / 4 / 8 位)，
+// 2 位和 4 位分组中取最大值的元素表示实际值存放在分组之后的字节中
+fn decode_bytes(source: &[u8], offset: usize, output: &mut [u8]) -> Result<usize, DecodeError> {
+    let header_len = (output.len() / BYTE_GROUP_SIZE).div_ceil(4);
+    let header = source.get(offset..offset + header_len).ok_or_else(truncated)?;
+    let mut offset = offset + header_len;
+    for (group, values) in output.chunks_exact_mut(BYTE_GROUP_SIZE).enumerate() {
+        let bits_log2 = (header[group / 4] >> ((group % 4) * 2)) & 3;
+        offset = match bits_log2 {
+            0 => {
+                values.fill(0);
+                offset
+            }
+            1 => decode_bytes_group(source, offset, values, 2)?,
+            2 => decode_bytes_group(source, offset, values, 4)?,
+            _ => {
+                let raw = source.get(offset..offset + BYTE_GROUP_SIZE).ok_or_else(truncated)?;
+                values.copy_from_slice(raw);
+                offset + BYTE_GROUP_SIZE
+            }
+        };
+    }
+    Ok(offset)
+}
+
+fn decode_bytes_group(source: &[u8], offset: usize, values: &mut [u8], bits: usize) -> Result<usize, DecodeError> {
+    let selector_len = BYTE_GROUP_SIZE * bits / 8;
+    let selectors = source.get(offset..offset + selector_len).ok_or_else(truncated)?;
+    let escape = (1u8 << bits) - 1;
+    let mut rest = offset + selector_len;
+    for (index, value) in values.iter_mut().enumerate() {
+        let bit = index * bits;
+        let encoded = (selectors[bit / 8] >> (8 - bits - bit % 8)) & escape;
+        *value = if encoded == escape {
+            let byte = *source.get(rest).ok_or_else(truncated)?;
+            rest += 1;
+            byte
+        } else {
+            encoded
+        };
+    }
+    Ok(rest)
+}
+
+// 变长整数 (每字节 7 位，最多 5 字节)
+fn decode_vbyte(source: &[u8], offset: &mut usize) -> Result<u32, DecodeError> {
+    let mut result = 0u32;
+    for shift in (0..35).step_by(7) {
+        let byte = *source.get(*offset).ok_or_else(truncated)?;
+        *offset += 1;
+        result |= ((byte & 127) as u32) << shift;
+        if byte < 128 {
+            break;
+        }
+    }
+    Ok(result)
+}
+
+fn unzigzag32(value: u32) -> u32 {
+    (value >> 1) ^ 0u32.wrapping_sub(value & 1)
+}
+
+// 三角形索引: 每个三角形一个编码字节，引用最近的 16 条边和 16 个顶点，
+// 无法引用的顶点以相对上一个显式顶点的差分存放在数据区，数据末尾是 16 字节的组合查找表
+pub fn decode_index_buffer(count: usize, source: &[u8]) -> Result<Vec<u32>, DecodeError> {
+    if !count.is_multiple_of(3) {
+        return Err(malformed(format!("meshopt 三角形索引数必须为 3 的倍数: {}", count)));
+    }
+    if source.len() < 1 + count / 3 + CODEAUX_TABLE_LEN {
+        return Err(truncated());
+    }
+    let version = match source[0] {
+        INDEX_HEADER => 0,
+        header if header == INDEX_HEADER + 1 => 1,
+        header => return Err(malformed(format!("不支持的 meshopt 索引编码版本: {:#04x}", header))),
+    };
+    // 版本 1 中 13 / 14 表示上一个显式顶点 -1 / +1
+    let fec_max = if version == 0 { 15 } else { 13 };
+
+    let mut edge_fifo = [[u32::MAX; 2]; 16];
+    let mut vertex_fifo = [u32::MAX; 16];
+    let (mut edge_offset, mut vertex_offset) = (0usize, 0usize);
+    let (mut next, mut last) = (0u32, 0u32);
+
+    let codes = &source[1..1 + count / 3];
+    let data_end = source.len() - CODEAUX_TABLE_LEN;
+    let codeaux_table = &source[data_end..];
+    let data = &source[..data_end];
+    let mut offset = 1 + count / 3;
+    let mut output = Vec::with_capacity(count);
+
+    let push_vertex = |fifo: &mut [u32; 16], offset: &mut usize, vertex: u32, cond: bool| {
+        fifo[*offset] = vertex;
+        *offset = (*offset + cond as usize) & 15;
+    };
+    let push_edge = |fifo: &mut [[u32; 2]; 16], offset: &mut usize, a: u32, b: u32| {
+        fifo[*offset] = [a, b];
+        *offset = (*offset + 1) & 15;
+    };
+    let decode_index = |offset: &mut usize, last: &mut u32| -> Result<u32, DecodeError> {
+        let value = decode_vbyte(data, offset)?;
+        *last = last.wrapping_add(unzigzag32(value));
+        Ok(*last)
+    };
+
+    for &code in codes {
+        if offset > data_end {
+            return Err(truncated());
+        }
+        if code < 0xf0 {
+            // 复用一条最近的边
+            let fe = (code >> 4) as usize;
+            let [a, b] = edge_fifo[(edge_offset.wrapping_sub(1 + fe)) & 15];
+            let fec = (code & 15) as u32;
+            let c = if fec < fec_max {
+                let c = if fec == 0 { next } else { vertex_fifo[(vertex_offset.wrapping_sub(1 + fec as usize)) & 15] };
+                if fec == 0 {
+                    next += 1;
+                }
+                push_vertex(&mut vertex_fifo, &mut vertex_offset, c, fec == 0);
+                c
+            } else {
+                let c = if fec != 15 {
+                    last = if fec == 13 { last.wrapping_sub(1) } else { last.wrapping_add(1) };
+                    last
+                } else {
+                    decode_index(&mut offset, &mut last)?
+                };
+                push_vertex(&mut vertex_fifo, &mut vertex_offset, c, true);
+                c
+            };
+            output.extend_from_slice(&[a, b, c]);
+            push_edge(&mut edge_fifo, &mut edge_offset, c, b);
+            push_edge(&mut edge_fifo, &mut edge_offset, a, c);
+        } else {
+            // 三个顶点都不在边 FIFO 中。0xf0..0xfd 的组合取自查找表，不含显式顶点；
+            // 0xfe / 0xff 的组合字节在数据区，15 表示显式顶点
+            let explicit = code >= 0xfe;
+            let codeaux = if explicit {
+                let byte = *data.get(offset).ok_or_else(truncated)?;
+                offset += 1;
+                byte
+            } else {
+                codeaux_table[(code & 15) as usize]
+            };
+            let feb = (codeaux >> 4) as usize;
+            let fec = (codeaux & 15) as usize;
+
+            let a = if code == 0xff { 0 } else { next };
+            if code != 0xff {
+                next += 1;
+            }
+            let reference = |fe: usize, next: &mut u32| {
+                if fe == 0 {
+                    *next += 1;
+                    *next - 1
+                } else {
+                    vertex_fifo[(vertex_offset.wrapping_sub(fe)) & 15]
+                }
+            };
+            let b = reference(feb, &mut next);
+            let c = reference(fec, &mut next);
+            let a = if code == 0xff { decode_index(&mut offset, &mut last)? } else { a };
+            let (b_explicit, c_explicit) = (explicit && feb == 15, explicit && fec == 15);
+            let b = if b_explicit { decode_index(&mut offset, &mut last)? } else { b };
+            let c = if c_explicit { decode_index(&mut offset, &mut last)? } else { c };
+            output.extend_from_slice(&[a, b, c]);
+            push_vertex(&mut vertex_fifo, &mut vertex_offset, a, true);
+            push_vertex(&mut vertex_fifo, &mut vertex_offset, b, feb == 0 || b_explicit);
+            push_vertex(&mut vertex_fifo, &mut vertex_offset, c, fec == 0 || c_explicit);
+            push_edge(&mut edge_fifo, &mut edge_offset, b, a);
+            push_edge(&mut edge_fifo, &mut edge_offset, c, b);
+            push_edge(&mut edge_fifo, &mut edge_offset, a, c);
+        }
+    }
+    // 数据区应恰好在查找表之前结束
+    if offset != data_end {
+        return Err(malformed("meshopt 索引数据长度与元素数不符"));
+    }
+    Ok(output)
+}
+
+// 一般索引序列 (线、点或非三角形列表): 每个索引相对两个基准之一的变长差分，数据末尾有 4 字节填充
+pub fn decode_index_sequence(count: usize, source: &[u8]) -> Result<Vec<u32>, DecodeError> {
+    if source.len() < 1 + count + 4 {
+        return Err(truncated());
+    }
+    if source[0] & 0xf0 != SEQUENCE_HEADER || source[0] & 0x0f > 1 {
+        return Err(malformed(format!("不支持的 meshopt 索引序列编码版本: {:#04x}", source[0])));
+    }
+    let data_end = source.len() - 4;
+    let data = &source[..data_end];
+    let mut offset = 1;
+    let mut last = [0u32; 2];
+    let mut output = Vec::with_capacity(count);
+    for _ in 0..count {
+        let value = decode_vbyte(data, &mut offset)?;
+        let baseline = (value & 1) as usize;
+        last[baseline] = last[baseline].wrapping_add(unzigzag32(value >> 1));
+        output.push(last[baseline]);
+    }
+    if offset != data_end {
+        return Err(malformed("meshopt 索引序列长度与元素数不符"));
+    }
+    Ok(output)
+}
+
+// 顶点数据的还原 filter，按元素原地处理
+pub fn apply_filter(filter: &str, data: &mut [u8], stride: usize) -> Result<(), DecodeError> {
+    match (filter, stride) {
+        ("NONE", _) => {}
+        ("OCTAHEDRAL", 4) => data.chunks_exact_mut(4).for_each(|element| {
+            let values: [f32; 4] = std::array::from_fn(|i| element[i] as i8 as f32);
+            let [x, y, z] = octahedral(values, 127.0);
+            element[..3].copy_from_slice(&[x as i8 as u8, y as i8 as u8, z as i8 as u8]);
+        }),
+        ("OCTAHEDRAL", 8) => data.chunks_exact_mut(8).for_each(|element| {
+            let values = read_i16x4(element).map(|value| value as f32);
+            let [x, y, z] = octahedral(values, 32767.0);
+            write_i16(element, &[(0, x as i16), (1, y as i16), (2, z as i16)]);
+        }),
+        ("QUATERNION", 8) => data.chunks_exact_mut(8).for_each(|element| {
+            let values = read_i16x4(element);
+            // 第 4 个分量: 高位为缩放，低 2 位为省略的 (最大的) 分量的位置
+            let scale = std::f32::consts::FRAC_1_SQRT_2 / (values[3] | 3) as f32;
+            let [x, y, z] = [values[0], values[1], values[2]].map(|value| value as f32 * scale);
+            let w = (1.0 - x * x - y * y - z * z).max(0.0).sqrt();
+            let [x, y, z, w] = [x, y, z, w].map(|value| round_signed(value * 32767.0) as i16);
+            let position = (values[3] & 3) as usize;
+            write_i16(
+                element,
+                &[((position + 1) & 3, x), ((position + 2) & 3, y), ((position + 3) & 3, z), (position, w)],
+            );
+        }),
+        ("EXPONENTIAL", stride) if stride.is_multiple_of(4) => data.chunks_exact_mut(4).for_each(|element| {
+            // 高 8 位为有符号指数，低 24 位为有符号尾数
+            let value = u32::from_le_bytes(element.try_into().unwrap());
+            let mantissa = ((value << 8) as i32) >> 8;
+            let exponent = (value as i32) >> 24;
+            let decoded = f32::from_bits(((exponent + 127) as u32) << 23) * mantissa as f32;
+            element.copy_from_slice(&decoded.to_le_bytes());
+        }),
+        ("OCTAHEDRAL" | "QUATERNION" | "EXPONENTIAL", _) => {
+            return Err(malformed(format!("meshopt filter {} 不支持 byteStride {}", filter, stride)));
+        }
+        _ => return Err(malformed(format!("不支持的 meshopt filter: {}", filter))),
+    }
+    Ok(())
+}
+
+fn round_signed(value: f32) -> i32 {
+    (value + if value >= 0.0 { 0.5 } else { -0.5 }) as i32
+}
+
+// 八面体编码的单位向量: 前两个分量为投影坐标，第三个分量为 1.0 的定点表示，第四个分量保持不变
+fn octahedral(values: [f32; 4], max: f32) -> [i32; 3] {
+    let (mut x, mut y) = (values[0], values[1]);
+    let z = values[2] - x.abs() - y.abs();
+    let t = z.min(0.0);
+    x += if x >= 0.0 { t } else { -t };
+    y += if y >= 0.0 { t } else { -t };
+    let scale = max / (x * x + y * y + z * z).sqrt();
+    [x, y, z].map(|value| round_signed(value * scale))
+}
+
+fn read_i16x4(element: &[u8]) -> [i16; 4] {
+    std::array::from_fn(|i| i16::from_le_bytes([element[i * 2], element[i * 2 + 1]]))
+}
+
+fn write_i16(element: &mut [u8], values: &[(usize, i16)]) {
+    for &(index, value) in values {
+        element[index * 2..index * 2 + 2].copy_from_slice(&value.to_le_bytes());
+    }
+}
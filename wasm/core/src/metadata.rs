@@ -0,0 +1,51 @@
+use std::collections::BTreeMap;
+
+use crate::byte_reader::ByteReader;
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// 容器元数据 (版本 3，FLAG_METADATA)
+//
+// 生产方附加的键值属性 (场景名称、作者、坐标系、应用版本等)，位于块偏移表之后、压缩数据之前，
+// 读取时不需要解压载荷。每条记录为 键长度 u16 | 键 | 值长度 u32 | 值，键和值均为 UTF-8。
+// 记录按键排序写入，相同的元数据总是得到相同的字节
+pub type Metadata = BTreeMap<String, String>;
+
+// 解析元数据区，记录不完整、不是 UTF-8 或键重复时报错
+pub fn parse(section: &[u8]) -> Result<Metadata, DecodeError> {
+    let invalid = |message: String| DecodeError::new(DecodeErrorCode::InvalidInput, message);
+    let mut reader = ByteReader::new(section, "元数据区");
+    let mut metadata = Metadata::new();
+    while reader.remaining() > 0 {
+        let offset = reader.position();
+        let key = read_string(&mut reader, 2).ok_or_else(|| invalid(format!("元数据记录无效 (偏移 {})", offset)))?;
+        let value = read_string(&mut reader, 4).ok_or_else(|| invalid(format!("元数据 {} 的值无效", key)))?;
+        if metadata.insert(key.clone(), value).is_some() {
+            return Err(invalid(format!("元数据键重复: {}", key)));
+        }
+    }
+    Ok(metadata)
+}
+
+// 读取一个带长度前缀 (len_size 字节) 的 UTF-8 字符串
+fn read_string(reader: &mut ByteReader, len_size: usize) -> Option<String> {
+    let len = usize::try_from(reader.uint(len_size).ok()?).ok()?;
+    String::from_utf8(reader.take(len).ok()?.to_vec()).ok()
+}
+
+// 写入元数据区，键超过 65535 字节或值超过 4GB 时报错
+pub fn write(metadata: &Metadata, output: &mut Vec<u8>) -> Result<(), String> {
+    for (key, value) in metadata {
+        let key_len = u16::try_from(key.len()).map_err(|_| format!("元数据键过长: {} 字节", key.len()))?;
+        let value_len = u32::try_from(value.len()).map_err(|_| format!("元数据 {} 的值过长", key))?;
+        output.extend_from_slice(&key_len.to_le_bytes());
+        output.extend_from_slice(key.as_bytes());
+        output.extend_from_slice(&value_len.to_le_bytes());
+        output.extend_from_slice(value.as_bytes());
+    }
+    Ok(())
+}
+
+// 元数据区写入后的长度
+pub fn encoded_len(metadata: &Metadata) -> usize {
+    metadata.iter().map(|(key, value)| 2 + key.len() + 4 + value.len()).sum()
+}
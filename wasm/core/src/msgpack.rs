@@ -0,0 +1,183 @@
+use crate::error::DecodeError;
+use crate::structured::{Reader, Value, MAX_DEPTH};
+
+// MessagePack 载荷解码
+//
+// 载荷为单个 MessagePack 值，解码为结构化值后直接转为 JS 值。扩展类型 (包括 -1 时间戳)
+// 不做解释，输出为 { type, data: Uint8Array }。
+//
+// 没有使用 rmp-serde: 经 serde 数据模型后扩展类型只能按 rmp-serde 约定的私有结构体名取出，
+// 而这里需要在解码时就按剩余数据检查每个长度字段 (数组和映射不会按声明的元素数预分配)，
+// 并与 CBOR 共用 structured::Value 和嵌套层数上限。格式本身只有约 30 种类型标记，
+// 测试用 rmp-serde 编码的数据对照解码结果
+pub fn decode(payload: &[u8]) -> Result<Value, DecodeError> {
+    let mut reader = Reader::new(payload, "MessagePack");
+    let value = read_value(&mut reader, 0)?;
+    if reader.remaining() > 0 {
+        return Err(reader.error(format!("值之后有 {} 字节多余数据", reader.remaining())));
+    }
+    Ok(value)
+}
+
+fn read_value(reader: &mut Reader, depth: usize) -> Result<Value, DecodeError> {
+    if depth > MAX_DEPTH {
+        return Err(reader.error(format!("嵌套超过 {} 层", MAX_DEPTH)));
+    }
+    let marker = reader.u8()?;
+    let value = match marker {
+        0x00..=0x7f => Value::UInt(marker as u64),
+        0x80..=0x8f => read_map(reader, (marker & 0x0f) as u64, depth)?,
+        0x90..=0x9f => read_array(reader, (marker & 0x0f) as u64, depth)?,
+        0xa0..=0xbf => Value::Str(reader.string((marker & 0x1f) as usize)?),
+        0xc0 => Value::Null,
+        0xc2 => Value::Bool(false),
+        0xc3 => Value::Bool(true),
+        0xc4..=0xc6 => {
+            let len = read_len(reader, 1 << (marker - 0xc4))?;
+            Value::Bytes(reader.take(len)?.to_vec())
+        }
+        0xc7..=0xc9 => {
+            let len = read_len(reader, 1 << (marker - 0xc7))?;
+            read_ext(reader, len)?
+        }
+        0xca => Value::Float(f32::from_bits(reader.uint(4)? as u32) as f64),
+        0xcb => Value::Float(f64::from_bits(reader.uint(8)?)),
+        0xcc..=0xcf => Value::UInt(reader.uint(1 << (marker - 0xcc))?),
+        0xd0..=0xd3 => {
+            let size = 1 << (marker - 0xd0);
+            let bits = reader.uint(size)?;
+            // 按位宽做符号扩展
+            let shift = 64 - size * 8;
+            Value::Int(((bits << shift) as i64) >> shift)
+        }
+        0xd4..=0xd8 => read_ext(reader, 1 << (marker - 0xd4))?,
+        0xd9..=0xdb => {
+            let len = read_len(reader, 1 << (marker - 0xd9))?;
+            Value::Str(reader.string(len)?)
+        }
+        0xdc | 0xdd => {
+            let count = reader.uint(if marker == 0xdc { 2 } else { 4 })?;
+            read_array(reader, count, depth)?
+        }
+        0xde | 0xdf => {
+            let count = reader.uint(if marker == 0xde { 2 } else { 4 })?;
+            read_map(reader, count, depth)?
+        }
+        0xe0..=0xff => Value::Int(marker as i8 as i64),
+        0xc1 => return Err(reader.error("保留的类型标记 0xc1")),
+    };
+    Ok(value)
+}
+
+fn read_len(reader: &mut Reader, size: usize) -> Result<usize, DecodeError> {
+    let len = reader.uint(size)?;
+    reader.length(len, 1)
+}
+
+fn read_ext(reader: &mut Reader, len: usize) -> Result<Value, DecodeError> {
+    let kind = reader.u8()? as i8;
+    let data = reader.take(len)?.to_vec();
+    Ok(Value::Ext(kind as i64, Box::new(Value::Bytes(data))))
+}
+
+fn read_array(reader: &mut Reader, count: u64, depth: usize) -> Result<Value, DecodeError> {
+    let count = reader.length(count, 1)?;
+    let mut items = Vec::with_capacity(count);
+    for _ in 0..count {
+        items.push(read_value(reader, depth + 1)?);
+    }
+    Ok(Value::Array(items))
+}
+
+fn read_map(reader: &mut Reader, count: u64, depth: usize) -> Result<Value, DecodeError> {
+    let count = reader.length(count, 2)?;
+    let mut entries = Vec::with_capacity(count);
+    for _ in 0..count {
+        let key = read_value(reader, depth + 1)?;
+        let value = read_value(reader, depth + 1)?;
+        entries.push((key, value));
+    }
+    Ok(Value::Map(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DecodeErrorCode;
+
+    fn code(payload: &[u8]) -> Option<DecodeErrorCode> {
+        decode(payload).err().map(|e| e.code)
+    }
+
+    // rmp-serde 编码的 JSON 值解码后与原值一致
+    #[test]
+    fn matches_reference_encoder() {
+        let document = serde_json::json!({
+            "name": "scene",
+            "nodes": [0, 1, 127, 128, 255, 256, 65535, 65536, 4294967296u64],
+            "negative": [-1, -32, -33, -128, -129, -32768, -32769, -4294967297i64],
+            "floats": [0.5, -1.25, 1e300],
+            "flags": [true, false, null],
+            "long": "x".repeat(300),
+            "nested": { "a": { "b": { "c": [] } } },
+        });
+        let encoded = rmp_serde::to_vec(&document).unwrap();
+        assert_eq!(serde_json::to_value(decode(&encoded).unwrap()).unwrap(), document);
+
+        // 超出安全整数范围的值在转为 JS 值时才变为浮点数，解码结果保留原值
+        let extremes = rmp_serde::to_vec(&(u64::MAX, i64::MIN)).unwrap();
+        assert_eq!(decode(&extremes).unwrap(), Value::Array(vec![Value::UInt(u64::MAX), Value::Int(i64::MIN)]));
+
+        let map = rmp_serde::to_vec(&(0..20).map(|i| (i.to_string(), i)).collect::<std::collections::BTreeMap<_, _>>());
+        assert!(matches!(decode(&map.unwrap()).unwrap(), Value::Map(entries) if entries.len() == 20));
+    }
+
+    #[test]
+    fn binary_and_extension_types() {
+        assert_eq!(decode(&[0xc4, 2, 1, 2]).unwrap(), Value::Bytes(vec![1, 2]));
+        assert_eq!(decode(&[0xd4, 0x05, 0xaa]).unwrap(), Value::Ext(5, Box::new(Value::Bytes(vec![0xaa]))));
+        // -1 时间戳 (fixext 4) 和 ext 8
+        assert_eq!(decode(&[0xd6, 0xff, 0, 0, 0, 1]).unwrap(), Value::Ext(-1, Box::new(Value::Bytes(vec![0, 0, 0, 1]))));
+        assert_eq!(decode(&[0xc7, 1, 0x10, 7]).unwrap(), Value::Ext(16, Box::new(Value::Bytes(vec![7]))));
+        assert_eq!(decode(&[0xca, 0x3f, 0xc0, 0, 0]).unwrap(), Value::Float(1.5));
+        assert_eq!(decode(&[0xd1, 0xff, 0x7f]).unwrap(), Value::Int(-129));
+    }
+
+    #[test]
+    fn truncated_input_is_rejected() {
+        for payload in [&[][..], &[0xcd, 0x01], &[0xa3, b'a', b'b'], &[0x92, 0x01], &[0x81, 0xa1, b'k'], &[0xd6, 0xff, 0, 0]] {
+            assert_eq!(code(payload), Some(DecodeErrorCode::InvalidInput), "{:02x?}", payload);
+        }
+        assert_eq!(code(&[0x01, 0x02]), Some(DecodeErrorCode::InvalidInput));
+        assert_eq!(code(&[0xc1]), Some(DecodeErrorCode::InvalidInput));
+        assert_eq!(code(&[0xa2, 0xc3, 0x28]), Some(DecodeErrorCode::Utf8Error));
+    }
+
+    // 声明的长度超过剩余数据时在分配之前报错
+    #[test]
+    fn oversized_lengths_are_rejected() {
+        for payload in [
+            &[0xdd, 0xff, 0xff, 0xff, 0xff][..],
+            &[0xdf, 0xff, 0xff, 0xff, 0xff],
+            &[0xdb, 0xff, 0xff, 0xff, 0xff],
+            &[0xc6, 0xff, 0xff, 0xff, 0xff],
+            &[0xc9, 0xff, 0xff, 0xff, 0xff, 0x01],
+            // 映射的每个条目至少 2 字节
+            &[0x82, 0x01, 0x02, 0x03],
+        ] {
+            assert_eq!(code(payload), Some(DecodeErrorCode::InvalidInput), "{:02x?}", payload);
+        }
+    }
+
+    #[test]
+    fn nesting_depth_is_limited() {
+        let nested = |depth: usize| {
+            let mut payload = vec![0x91; depth];
+            payload.push(0xc0);
+            payload
+        };
+        assert!(decode(&nested(MAX_DEPTH)).is_ok());
+        assert_eq!(code(&nested(MAX_DEPTH + 1)), Some(DecodeErrorCode::InvalidInput));
+        assert_eq!(code(&nested(100_000)), Some(DecodeErrorCode::InvalidInput));
+    }
+}
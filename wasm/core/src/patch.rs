@@ -0,0 +1,293 @@
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use crate::buffer_pool;
+use crate::byte_reader::ByteReader;
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// FastDog 二进制补丁格式
+//
+// 魔数 "FASTDOGP"(8) | 版本(4) | 基准长度(4) | 基准 CRC32(4) | 目标长度(4) | 目标 CRC32(4)
+// | 指令流压缩长度(4) | zlib 压缩的指令流
+//
+// 指令流由以下指令依次组成:
+//   COPY   (1) | 基准偏移(4) | 长度(4)   从基准数据复制
+//   INSERT (2) | 长度(4) | 数据          插入新数据
+pub const PATCH_MAGIC: &[u8; 8] = b"FASTDOGP";
+pub const PATCH_VERSION: u32 = 1;
+const OP_COPY: u8 = 1;
+const OP_INSERT: u8 = 2;
+const PATCH_HEADER_LEN: usize = 32;
+
+// 匹配块大小: 基准数据按此大小切块建立索引
+const BLOCK_SIZE: usize = 32;
+const HASH_BASE: u64 = 257;
+
+// 生成从 base 到 updated 的二进制补丁
+pub fn diff(base: &[u8], updated: &[u8]) -> Result<Vec<u8>, String> {
+    let ops = diff_ops(base, updated);
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(&ops)
+        .and_then(|_| encoder.finish())
+        .map(|compressed_ops| {
+            let mut patch = Vec::with_capacity(PATCH_HEADER_LEN + compressed_ops.len());
+            patch.extend_from_slice(PATCH_MAGIC);
+            patch.extend_from_slice(&PATCH_VERSION.to_le_bytes());
+            patch.extend_from_slice(&(base.len() as u32).to_le_bytes());
+            patch.extend_from_slice(&crc32fast::hash(base).to_le_bytes());
+            patch.extend_from_slice(&(updated.len() as u32).to_le_bytes());
+            patch.extend_from_slice(&crc32fast::hash(updated).to_le_bytes());
+            patch.extend_from_slice(&(compressed_ops.len() as u32).to_le_bytes());
+            patch.extend_from_slice(&compressed_ops);
+            patch
+        })
+        .map_err(|e| format!("补丁压缩失败: {}", e))
+}
+
+// 补丁头部声明的目标长度 (应用前按此登记内存)
+pub fn target_len(patch: &[u8]) -> Result<usize, DecodeError> {
+    Ok(read_header(patch)?.u32_at(20)? as usize)
+}
+
+// 检查魔数和版本，返回头部的读取器
+fn read_header(patch: &[u8]) -> Result<ByteReader<'_>, DecodeError> {
+    if patch.len() < PATCH_HEADER_LEN {
+        return Err(DecodeError::new(DecodeErrorCode::TooShort, "数据太短，不是有效的 FastDog 补丁"));
+    }
+    if &patch[0..8] != PATCH_MAGIC {
+        return Err(DecodeError::new(DecodeErrorCode::BadMagic, format!("无效的补丁魔数: {:?}", &patch[0..8])));
+    }
+    let header = ByteReader::new(patch, "补丁头部");
+    let version = header.u32_at(8)?;
+    if version != PATCH_VERSION {
+        return Err(DecodeError::new(
+            DecodeErrorCode::UnsupportedVersion,
+            format!("不支持的补丁版本: {}", version),
+        ));
+    }
+    Ok(header)
+}
+
+// 把 diff 生成的补丁应用到 base，返回更新后的数据。
+// base 与生成补丁时的基准不一致时返回 ChecksumMismatch 错误码
+pub fn apply(base: &[u8], patch: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let header = read_header(patch)?;
+    let base_len = header.u32_at(12)? as usize;
+    let base_crc32 = header.u32_at(16)?;
+    let target_len = header.u32_at(20)? as usize;
+    let target_crc32 = header.u32_at(24)?;
+    let ops_len = header.u32_at(28)? as usize;
+    if base.len() != base_len || crc32fast::hash(base) != base_crc32 {
+        return Err(DecodeError::new(
+            DecodeErrorCode::ChecksumMismatch,
+            format!("基准数据与补丁不匹配: 期望 {} 字节 (CRC32 {:08x})", base_len, base_crc32),
+        ));
+    }
+    if patch.len() - PATCH_HEADER_LEN != ops_len {
+        return Err(DecodeError::new(
+            DecodeErrorCode::LengthMismatch,
+            format!("补丁指令流长度不匹配: 期望 {}, 实际 {}", ops_len, patch.len() - PATCH_HEADER_LEN),
+        ));
+    }
+
+    // 每条指令至少产生 1 字节输出，指令流不会超过 9 * 目标长度 (防止解压炸弹)
+    let ops_limit = target_len.saturating_mul(9).saturating_add(9);
+    let mut ops = Vec::new();
+    ZlibDecoder::new(&patch[PATCH_HEADER_LEN..])
+        .take(ops_limit as u64 + 1)
+        .read_to_end(&mut ops)
+        .map_err(|e| DecodeError::new(DecodeErrorCode::InflateError, format!("补丁指令流解压失败: {}", e)))?;
+    if ops.len() > ops_limit {
+        return Err(DecodeError::new(DecodeErrorCode::InvalidInput, "补丁指令流过长"));
+    }
+
+    let mut output = buffer_pool::try_allocate(target_len)?;
+    let operands = ByteReader::new(&ops, "补丁指令");
+    let mut pos = 0;
+    while pos < ops.len() {
+        let op = ops[pos];
+        let (start, len) = match op {
+            OP_COPY => (operands.u32_at(pos + 1)? as usize, operands.u32_at(pos + 5)? as usize),
+            OP_INSERT => (pos + 5, operands.u32_at(pos + 1)? as usize),
+            _ => {
+                return Err(DecodeError::new(
+                    DecodeErrorCode::InvalidInput,
+                    format!("补丁指令无效: 位置 {}, 指令 {}", pos, op),
+                ));
+            }
+        };
+        if output.len() + len > target_len {
+            return Err(DecodeError::new(DecodeErrorCode::LengthMismatch, "补丁输出超出目标长度"));
+        }
+
+        let (source, next) = if op == OP_COPY { (base, pos + 9) } else { (&ops[..], start + len) };
+        let chunk = start
+            .checked_add(len)
+            .and_then(|end| source.get(start..end))
+            .ok_or_else(|| DecodeError::new(DecodeErrorCode::InvalidInput, format!("补丁指令超出范围: 位置 {}", pos)))?;
+        output.extend_from_slice(chunk);
+        pos = next;
+    }
+
+    if output.len() != target_len {
+        return Err(DecodeError::new(
+            DecodeErrorCode::LengthMismatch,
+            format!("补丁应用后长度不匹配: 期望 {}, 实际 {}", target_len, output.len()),
+        ));
+    }
+    let crc32 = crc32fast::hash(&output);
+    if crc32 != target_crc32 {
+        return Err(DecodeError::new(
+            DecodeErrorCode::ChecksumMismatch,
+            format!("补丁应用后 CRC32 校验失败: 期望 {:08x}, 实际 {:08x}", target_crc32, crc32),
+        ));
+    }
+    Ok(output)
+}
+
+// 生成指令流: 用滚动哈希在基准块索引中查找匹配，匹配区间输出 COPY，其余输出 INSERT
+fn diff_ops(base: &[u8], updated: &[u8]) -> Vec<u8> {
+    let mut ops = Vec::new();
+
+    if base.len() < BLOCK_SIZE || updated.len() < BLOCK_SIZE {
+        push_insert(&mut ops, updated);
+        return ops;
+    }
+
+    // 基准数据按块建立索引 (只保留每个哈希第一次出现的位置)
+    let mut index: HashMap<u64, usize> = HashMap::new();
+    for offset in (0..=base.len() - BLOCK_SIZE).step_by(BLOCK_SIZE) {
+        index.entry(block_hash(&base[offset..offset + BLOCK_SIZE])).or_insert(offset);
+    }
+
+    // HASH_BASE^(BLOCK_SIZE - 1)，用于移出窗口首字节
+    let top = (1..BLOCK_SIZE).fold(1u64, |acc, _| acc.wrapping_mul(HASH_BASE));
+
+    let mut literal_start = 0;
+    let mut pos = 0;
+    let mut hash = block_hash(&updated[..BLOCK_SIZE]);
+
+    while pos + BLOCK_SIZE <= updated.len() {
+        let matched = index
+            .get(&hash)
+            .copied()
+            .filter(|&base_offset| base[base_offset..base_offset + BLOCK_SIZE] == updated[pos..pos + BLOCK_SIZE]);
+
+        if let Some(mut base_offset) = matched {
+            // 向前扩展匹配区间 (吃掉待输出字面量的末尾)
+            let mut start = pos;
+            while start > literal_start && base_offset > 0 && base[base_offset - 1] == updated[start - 1] {
+                start -= 1;
+                base_offset -= 1;
+            }
+
+            // 向后扩展匹配区间
+            let mut len = pos - start + BLOCK_SIZE;
+            while base_offset + len < base.len()
+                && start + len < updated.len()
+                && base[base_offset + len] == updated[start + len]
+            {
+                len += 1;
+            }
+
+            push_insert(&mut ops, &updated[literal_start..start]);
+            push_copy(&mut ops, base_offset, len);
+
+            pos = start + len;
+            literal_start = pos;
+            if pos + BLOCK_SIZE <= updated.len() {
+                hash = block_hash(&updated[pos..pos + BLOCK_SIZE]);
+            }
+            continue;
+        }
+
+        // 窗口右移一个字节
+        if pos + BLOCK_SIZE < updated.len() {
+            hash = hash
+                .wrapping_sub((updated[pos] as u64).wrapping_mul(top))
+                .wrapping_mul(HASH_BASE)
+                .wrapping_add(updated[pos + BLOCK_SIZE] as u64);
+        }
+        pos += 1;
+    }
+
+    push_insert(&mut ops, &updated[literal_start..]);
+    ops
+}
+
+fn block_hash(block: &[u8]) -> u64 {
+    block
+        .iter()
+        .fold(0u64, |acc, &byte| acc.wrapping_mul(HASH_BASE).wrapping_add(byte as u64))
+}
+
+fn push_copy(ops: &mut Vec<u8>, offset: usize, len: usize) {
+    ops.push(OP_COPY);
+    ops.extend_from_slice(&(offset as u32).to_le_bytes());
+    ops.extend_from_slice(&(len as u32).to_le_bytes());
+}
+
+fn push_insert(ops: &mut Vec<u8>, data: &[u8]) {
+    if data.is_empty() {
+        return;
+    }
+    ops.push(OP_INSERT);
+    ops.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    ops.extend_from_slice(data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 % 251) as u8 ^ (i >> 8) as u8).collect()
+    }
+
+    #[test]
+    fn patch_round_trip() {
+        let base = sample(20_000);
+        let mut updated = base.clone();
+        updated[5000..5010].copy_from_slice(b"0123456789");
+        updated.splice(12_000..12_000, b"inserted".iter().copied());
+        updated.truncate(18_000);
+
+        let patch = diff(&base, &updated).unwrap();
+        assert!(patch.len() < 1000, "{}", patch.len());
+        assert_eq!(target_len(&patch).unwrap(), updated.len());
+        assert_eq!(apply(&base, &patch).unwrap(), updated);
+
+        // 小于匹配块的输入整体作为插入数据
+        let patch = diff(b"abc", b"abcd").unwrap();
+        assert_eq!(apply(b"abc", &patch).unwrap(), b"abcd");
+    }
+
+    #[test]
+    fn mismatched_or_corrupt_patches_are_rejected() {
+        let base = sample(4096);
+        let updated = sample(5000);
+        let patch = diff(&base, &updated).unwrap();
+        let code = |base: &[u8], patch: &[u8]| apply(base, patch).err().map(|e| e.code);
+
+        assert_eq!(code(&base[1..], &patch), Some(DecodeErrorCode::ChecksumMismatch));
+        assert_eq!(code(&base, &patch[..20]), Some(DecodeErrorCode::TooShort));
+        assert_eq!(code(&base, &patch[..patch.len() - 1]), Some(DecodeErrorCode::LengthMismatch));
+
+        let mut magic = patch.clone();
+        magic[7] = b'X';
+        assert_eq!(code(&base, &magic), Some(DecodeErrorCode::BadMagic));
+
+        let mut version = patch.clone();
+        version[8] = 2;
+        assert_eq!(code(&base, &version), Some(DecodeErrorCode::UnsupportedVersion));
+
+        let mut target_crc32 = patch.clone();
+        target_crc32[24] ^= 1;
+        assert_eq!(code(&base, &target_crc32), Some(DecodeErrorCode::ChecksumMismatch));
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// 载荷类型标识 (对应扩展头部中的 content_type 字节)
+//
+// 解码器按载荷类型选择输出方式，不再从容器版本号推断。版本 1/2 没有该字节，
+// 载荷类型由版本号决定 (1 = JSON, 2 = GLB)，与字节取值一致。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PayloadKind {
+    Json = 1,
+    Glb = 2,
+    Cbor = 3,
+    // PNG / JPEG / WebP / KTX2 等图像数据，具体格式由载荷自身的魔数区分
+    Image = 4,
+    PointCloud = 5,
+    // MessagePack 编码的单个值，可按结构化对象输出
+    MsgPack = 6,
+    // 带长度前缀的 Protobuf 消息序列，按注册的描述符解码
+    Protobuf = 7,
+    // 应用自定义的二进制数据，解码器不做任何解释
+    Custom = 255,
+}
+
+impl PayloadKind {
+    pub const ALL: [PayloadKind; 8] = [
+        PayloadKind::Json,
+        PayloadKind::Glb,
+        PayloadKind::Cbor,
+        PayloadKind::Image,
+        PayloadKind::PointCloud,
+        PayloadKind::MsgPack,
+        PayloadKind::Protobuf,
+        PayloadKind::Custom,
+    ];
+
+    pub fn from_byte(byte: u8) -> Result<PayloadKind, DecodeError> {
+        PayloadKind::ALL.into_iter().find(|kind| *kind as u8 == byte).ok_or_else(|| {
+            DecodeError::new(DecodeErrorCode::UnsupportedContentType, format!("不支持的内容类型: {}", byte))
+        })
+    }
+
+    pub fn from_name(name: &str) -> Result<PayloadKind, String> {
+        PayloadKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == name)
+            .ok_or_else(|| format!("不支持的内容类型: {}", name))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PayloadKind::Json => "json",
+            PayloadKind::Glb => "glb",
+            PayloadKind::Cbor => "cbor",
+            PayloadKind::Image => "image",
+            PayloadKind::PointCloud => "pointcloud",
+            PayloadKind::MsgPack => "msgpack",
+            PayloadKind::Protobuf => "protobuf",
+            PayloadKind::Custom => "custom",
+        }
+    }
+
+    // 只有 JSON 载荷按 UTF-8 文本输出，其他类型按二进制输出
+    pub fn is_text(&self) -> bool {
+        *self == PayloadKind::Json
+    }
+}
@@ -0,0 +1,49 @@
+// 平台相关的时间和随机数
+//
+// 启用 js 特性时 (WASM 模块) 取自 JS 全局对象: Date.now() 和 crypto.getRandomValues；
+// 其他宿主使用操作系统的时钟和随机数源
+
+// 当前 Unix 时间 (毫秒)，用于来源信息的创建时间
+#[cfg(feature = "js")]
+pub fn now_ms() -> u64 {
+    js_sys::Date::now() as u64
+}
+
+#[cfg(not(feature = "js"))]
+pub fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+// 由 crypto.getRandomValues 生成随机字节 (nonce、盐)
+#[cfg(feature = "js")]
+pub fn random_bytes<const N: usize>() -> Result<[u8; N], String> {
+    use wasm_bindgen::JsCast;
+
+    let unavailable = |_| "当前环境不支持 crypto.getRandomValues".to_string();
+    let crypto = js_sys::Reflect::get(&js_sys::global(), &"crypto".into()).map_err(unavailable)?;
+    let get_random_values = js_sys::Reflect::get(&crypto, &"getRandomValues".into())
+        .map_err(unavailable)?
+        .dyn_into::<js_sys::Function>()
+        .map_err(unavailable)?;
+    let array = js_sys::Uint8Array::new_with_length(N as u32);
+    get_random_values.call1(&crypto, &array).map_err(unavailable)?;
+    let mut bytes = [0u8; N];
+    array.copy_to(&mut bytes);
+    Ok(bytes)
+}
+
+// 由操作系统随机数源生成随机字节
+#[cfg(all(not(feature = "js"), not(target_arch = "wasm32")))]
+pub fn random_bytes<const N: usize>() -> Result<[u8; N], String> {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("无法获取随机数: {}", e))?;
+    Ok(bytes)
+}
+
+// 不启用 js 特性的 WASM 构建没有随机数源，需要随机 nonce / 盐的编码改用确定性档案
+#[cfg(all(not(feature = "js"), target_arch = "wasm32"))]
+pub fn random_bytes<const N: usize>() -> Result<[u8; N], String> {
+    Err("当前环境没有随机数源 (WASM 构建需要启用 js 特性)".to_string())
+}
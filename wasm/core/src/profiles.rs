@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::codec::Codec;
+use crate::provenance;
+
+// 编码器配置档案
+//
+// 将压缩算法、级别、块大小和校验方式打包成具名档案，保证不同工具使用一致的编码参数。
+// 内置 "fast" / "balanced" / "max" / "reproducible" 四个档案，运行时可注册自定义档案 (同名时覆盖内置档案)。
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EncoderProfile {
+    pub name: String,
+    pub codec: Codec,
+    #[serde(default)]
+    pub level: u32,
+    #[serde(default)]
+    pub block_size: u32,
+    #[serde(default)]
+    pub checksum: ChecksumMode,
+    // 确定性编码: 相同输入总是得到逐字节相同的输出 (用于内容寻址存储)。
+    // 编码默认值 (block_size = 0) 换成显式参数，时间戳等随时间变化的元数据写为 0
+    #[serde(default)]
+    pub deterministic: bool,
+    // 写入头部来源信息的生成工具 / 流水线标识 (最长 16 字节)，设置后容器带有创建时间和该标识
+    #[serde(default)]
+    pub producer: Option<String>,
+}
+
+// 写入头部的校验字段
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChecksumMode {
+    None,
+    #[default]
+    Crc32,
+    Sha256,
+    // CRC32 + SHA-256
+    All,
+}
+
+impl ChecksumMode {
+    pub fn crc32(&self) -> bool {
+        matches!(self, ChecksumMode::Crc32 | ChecksumMode::All)
+    }
+
+    pub fn sha256(&self) -> bool {
+        matches!(self, ChecksumMode::Sha256 | ChecksumMode::All)
+    }
+}
+
+impl EncoderProfile {
+    fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("档案名称不能为空".to_string());
+        }
+        let max_level = match self.codec {
+            Codec::Zlib => 9,
+            Codec::Brotli => 11,
+            Codec::Zstd => 22,
+            Codec::Lz4 | Codec::Store => u32::MAX,
+        };
+        if self.level > max_level {
+            return Err(format!(
+                "档案 {}: {} 的压缩级别不能超过 {}",
+                self.name,
+                self.codec.name(),
+                max_level
+            ));
+        }
+        if let Some(producer) = &self.producer {
+            provenance::validate_producer(producer).map_err(|e| format!("档案 {}: {}", self.name, e))?;
+        }
+        Ok(())
+    }
+
+    // 确定性档案把依赖编码库默认值的参数固定下来，库默认值变化时输出不变
+    pub fn pinned(mut self) -> EncoderProfile {
+        if self.deterministic && self.block_size == 0 {
+            self.block_size = match self.codec {
+                Codec::Brotli => 1 << 22,
+                Codec::Lz4 => 64 * 1024,
+                Codec::Zlib | Codec::Zstd | Codec::Store => 0,
+            };
+        }
+        self
+    }
+}
+
+const DEFAULT_PROFILE: &str = "balanced";
+
+fn builtin_profiles() -> Vec<EncoderProfile> {
+    vec![
+        EncoderProfile {
+            name: "fast".to_string(),
+            codec: Codec::Lz4,
+            level: 0,
+            block_size: 64 * 1024,
+            checksum: ChecksumMode::Crc32,
+            deterministic: false,
+            producer: None,
+        },
+        EncoderProfile {
+            name: "balanced".to_string(),
+            codec: Codec::Zlib,
+            level: 6,
+            block_size: 0,
+            checksum: ChecksumMode::Crc32,
+            deterministic: false,
+            producer: None,
+        },
+        EncoderProfile {
+            name: "max".to_string(),
+            codec: Codec::Brotli,
+            level: 11,
+            block_size: 4 * 1024 * 1024,
+            checksum: ChecksumMode::All,
+            deterministic: false,
+            producer: None,
+        },
+        // 内容寻址存储使用: 确定性输出，SHA-256 可直接作为内容地址
+        EncoderProfile {
+            name: "reproducible".to_string(),
+            codec: Codec::Zlib,
+            level: 9,
+            block_size: 0,
+            checksum: ChecksumMode::All,
+            deterministic: true,
+            producer: None,
+        },
+    ]
+}
+
+struct ProfileRegistry {
+    custom: BTreeMap<String, EncoderProfile>,
+    // 为空时使用 DEFAULT_PROFILE
+    default_name: String,
+}
+
+static REGISTRY: Mutex<ProfileRegistry> = Mutex::new(ProfileRegistry {
+    custom: BTreeMap::new(),
+    default_name: String::new(),
+});
+
+fn with_registry<T>(f: impl FnOnce(&mut ProfileRegistry) -> T) -> T {
+    let mut registry = REGISTRY.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut registry)
+}
+
+impl ProfileRegistry {
+    fn find(&self, name: &str) -> Option<EncoderProfile> {
+        self.custom
+            .get(name)
+            .cloned()
+            .or_else(|| builtin_profiles().into_iter().find(|profile| profile.name == name))
+    }
+
+    fn default_name(&self) -> &str {
+        if self.default_name.is_empty() {
+            DEFAULT_PROFILE
+        } else {
+            &self.default_name
+        }
+    }
+}
+
+// 按名称查找档案，name 为空时返回默认档案
+pub fn resolve_profile(name: Option<&str>) -> Result<EncoderProfile, String> {
+    with_registry(|registry| {
+        let name = name.unwrap_or_else(|| registry.default_name()).to_string();
+        registry
+            .find(&name)
+            .map(EncoderProfile::pinned)
+            .ok_or_else(|| format!("未知的编码档案: {}", name))
+    })
+}
+
+// 导出/导入使用的 JSON 结构
+#[derive(Serialize, Deserialize)]
+struct ProfileExport {
+    #[serde(default)]
+    default: Option<String>,
+    profiles: Vec<EncoderProfile>,
+}
+
+// 所有可用档案名称 (内置 + 自定义)
+pub fn profile_names() -> Vec<String> {
+    with_registry(|registry| {
+        let mut names: Vec<String> = builtin_profiles().into_iter().map(|profile| profile.name).collect();
+        for name in registry.custom.keys() {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        names
+    })
+}
+
+// 按名称查找档案 (不做确定性参数固定)
+pub fn find_profile(name: &str) -> Option<EncoderProfile> {
+    with_registry(|registry| registry.find(name))
+}
+
+// 校验后注册或覆盖一个自定义档案
+pub fn set_profile(profile: EncoderProfile) -> Result<(), String> {
+    profile.validate()?;
+    with_registry(|registry| registry.custom.insert(profile.name.clone(), profile));
+    Ok(())
+}
+
+// 删除自定义档案，内置档案无法删除
+pub fn remove_profile(name: &str) -> bool {
+    with_registry(|registry| registry.custom.remove(name).is_some())
+}
+
+pub fn set_default_profile(name: &str) -> Result<(), String> {
+    with_registry(|registry| {
+        if registry.find(name).is_none() {
+            return Err(format!("未知的编码档案: {}", name));
+        }
+        registry.default_name = name.to_string();
+        Ok(())
+    })
+}
+
+// 自定义档案和默认档案名称的 JSON
+pub fn export_profiles() -> String {
+    let export = with_registry(|registry| ProfileExport {
+        default: Some(registry.default_name().to_string()),
+        profiles: registry.custom.values().cloned().collect(),
+    });
+    serde_json::to_string_pretty(&export).unwrap()
+}
+
+// 从 JSON 导入档案 (export_profiles 的输出或档案数组)，返回导入的档案数
+pub fn import_profiles(json: &str) -> Result<u32, String> {
+    let export: ProfileExport = serde_json::from_str(json)
+        .or_else(|_| {
+            serde_json::from_str::<Vec<EncoderProfile>>(json).map(|profiles| ProfileExport {
+                default: None,
+                profiles,
+            })
+        })
+        .map_err(|e| format!("档案 JSON 无效: {}", e))?;
+
+    for profile in &export.profiles {
+        profile.validate()?;
+    }
+
+    with_registry(|registry| {
+        let count = export.profiles.len() as u32;
+        for profile in export.profiles {
+            registry.custom.insert(profile.name.clone(), profile);
+        }
+        if let Some(default) = export.default {
+            if registry.find(&default).is_none() {
+                return Err(format!("未知的编码档案: {}", default));
+            }
+            registry.default_name = default;
+        }
+        Ok(count)
+    })
+}
@@ -0,0 +1,48 @@
+use crate::byte_reader::ByteReader;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::format;
+
+// 来源信息 (版本 3，FLAG_PROVENANCE)
+//
+// 记录容器的创建时间和生成工具，线上发现有问题的资源时可以追溯是哪条流水线产出的。
+// created_at 为 Unix 毫秒时间戳 (0 = 未知，确定性编码总是写 0)，producer 为最长 16 字节的
+// UTF-8 标识，不足部分补 0
+pub const PRODUCER_LEN: usize = 16;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Provenance {
+    pub created_at: u64,
+    pub producer: String,
+}
+
+impl Provenance {
+    // 由扩展字段读取 (调用方保证 FLAG_PROVENANCE 已置位)
+    pub fn parse(reader: &ByteReader, flags: u16) -> Result<Provenance, DecodeError> {
+        let bytes = reader.field_bytes(&format::v3_extension_field(flags, "producer")?)?;
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        let producer = std::str::from_utf8(&bytes[..end])
+            .map_err(|_| DecodeError::new(DecodeErrorCode::InvalidInput, "头部的生成工具标识不是有效的 UTF-8"))?;
+        Ok(Provenance {
+            created_at: reader.field_u64(&format::v3_extension_field(flags, "created_at")?)?,
+            producer: producer.to_string(),
+        })
+    }
+
+    pub fn write(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.created_at.to_le_bytes());
+        let mut producer = [0u8; PRODUCER_LEN];
+        producer[..self.producer.len()].copy_from_slice(self.producer.as_bytes());
+        output.extend_from_slice(&producer);
+    }
+}
+
+// 检查生成工具标识能否写入头部
+pub fn validate_producer(producer: &str) -> Result<(), String> {
+    if producer.is_empty() || producer.len() > PRODUCER_LEN {
+        return Err(format!("生成工具标识长度必须为 1 到 {} 字节: {:?}", PRODUCER_LEN, producer));
+    }
+    if producer.contains('\0') {
+        return Err(format!("生成工具标识不能包含 NUL 字符: {:?}", producer));
+    }
+    Ok(())
+}
@@ -0,0 +1,209 @@
+use crate::codec::{self, Codec};
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// 块索引 (版本 3，FLAG_BLOCK_INDEX)
+//
+// 载荷按 block_size 切块并独立压缩，头部扩展字段之后的偏移表记录每块压缩数据的结束位置，
+// 读取任意区间时只需解压覆盖该区间的块，适合数 GB 的点云等大载荷。
+#[derive(Clone, Debug)]
+pub struct BlockIndex {
+    pub block_size: u32,
+    // 各块压缩数据的结束偏移 (相对压缩数据起始)
+    pub ends: Vec<u32>,
+}
+
+// 需要解压的一个块
+pub struct BlockSpan {
+    pub index: usize,
+    // 压缩数据区间 (相对压缩数据起始)
+    pub compressed_start: usize,
+    pub compressed_end: usize,
+    // 块的未压缩长度
+    pub len: usize,
+    // 块内需要的数据区间
+    pub take_start: usize,
+    pub take_end: usize,
+}
+
+impl BlockIndex {
+    // 读取并校验偏移表: 块大小不为 0，偏移单调不减，最后一块结束于压缩数据末尾
+    pub fn parse(block_size: u32, table: &[u8], compressed_len: usize) -> Result<BlockIndex, DecodeError> {
+        let ends: Vec<u32> = table
+            .chunks_exact(4)
+            .map(|end| u32::from_le_bytes([end[0], end[1], end[2], end[3]]))
+            .collect();
+        let ordered = ends.windows(2).all(|pair| pair[0] <= pair[1]);
+        if block_size == 0 || ends.is_empty() || !ordered || ends[ends.len() - 1] as usize != compressed_len {
+            return Err(DecodeError::new(DecodeErrorCode::InvalidInput, "块索引无效"));
+        }
+        Ok(BlockIndex { block_size, ends })
+    }
+
+    pub fn write(&self, output: &mut Vec<u8>) {
+        for end in &self.ends {
+            output.extend_from_slice(&end.to_le_bytes());
+        }
+    }
+
+    // 覆盖未压缩区间 [start, end) 的块，original_len 用于计算最后一块的长度和截断区间。
+    // 未压缩偏移使用 u64，超过 4GB 的载荷也可以按区间读取
+    pub fn spans(&self, original_len: u64, start: u64, end: u64) -> Result<Vec<BlockSpan>, DecodeError> {
+        let block_size = self.block_size as u64;
+        let expected_blocks = original_len.div_ceil(block_size).max(1);
+        if self.ends.len() as u64 != expected_blocks {
+            return Err(DecodeError::new(
+                DecodeErrorCode::LengthMismatch,
+                format!("块索引与原始长度不一致: {} 块, 原始长度 {} 字节", self.ends.len(), original_len),
+            ));
+        }
+        // 超出载荷末尾的部分截断
+        let end = end.min(original_len);
+        if start >= end {
+            return Ok(Vec::new());
+        }
+
+        // 块数与块内偏移都不超过 u32，转换为 usize 不会截断
+        Ok((start / block_size..=(end - 1) / block_size)
+            .map(|index| {
+                let block_start = index * block_size;
+                let len = block_size.min(original_len - block_start);
+                let index = index as usize;
+                BlockSpan {
+                    index,
+                    compressed_start: if index == 0 { 0 } else { self.ends[index - 1] as usize },
+                    compressed_end: self.ends[index] as usize,
+                    len: len as usize,
+                    take_start: start.saturating_sub(block_start) as usize,
+                    take_end: (end - block_start).min(len) as usize,
+                }
+            })
+            .collect())
+    }
+}
+
+// 解压一个块并取出需要的部分，追加到 output
+pub fn decode_span(codec: Codec, compressed: &[u8], span: &BlockSpan, output: &mut Vec<u8>) -> Result<(), DecodeError> {
+    let block = codec::decompress_block(codec, compressed, span.index == 0, span.len)?;
+    output.extend_from_slice(&block[span.take_start..span.take_end]);
+    Ok(())
+}
+
+// JS 传入的区间 [start, start + len) 截断到载荷范围内 (负数和 NaN 按 0 处理)
+pub fn clamp_range(start: f64, len: f64, original_len: u64) -> (u64, u64) {
+    let start = (start as u64).min(original_len);
+    let end = start.saturating_add(len as u64).min(original_len);
+    (start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::{decode, locate_payload, parse_container_header};
+    use crate::encoder::encode_seekable_container;
+    use crate::format::{self, FLAG_DICTIONARY};
+    use crate::payload_kind::PayloadKind;
+    use crate::profiles::resolve_profile;
+
+    const BLOCK_SIZE: u32 = 1000;
+
+    fn payload() -> Vec<u8> {
+        (0..4500u32).map(|i| (i * 7 % 251) as u8).collect()
+    }
+
+    fn seekable(codec: Codec) -> Vec<u8> {
+        let mut profile = resolve_profile(Some("balanced")).unwrap();
+        profile.codec = codec;
+        encode_seekable_container(&payload(), PayloadKind::Custom, BLOCK_SIZE, &profile).unwrap()
+    }
+
+    fn read_range(data: &[u8], start: u64, end: u64) -> Result<Vec<u8>, DecodeError> {
+        let (header, compressed, original_len) = locate_payload(data)?;
+        let index = header.block_index.as_ref().unwrap();
+        let mut output = Vec::new();
+        for span in index.spans(original_len, start, end)? {
+            decode_span(header.codec, &compressed[span.compressed_start..span.compressed_end], &span, &mut output)?;
+        }
+        Ok(output)
+    }
+
+    fn extension_offset(data: &[u8], name: &str) -> usize {
+        let flags = format::FLAGS.read(data) as u16;
+        format::v3_extension_field(flags, name).unwrap().offset
+    }
+
+    #[test]
+    fn ranges_round_trip() {
+        let payload = payload();
+        for codec in [Codec::Zlib, Codec::Lz4, Codec::Store, Codec::Zstd] {
+            let data = seekable(codec);
+            assert_eq!(decode(&data).unwrap().2, payload, "{}", codec.name());
+            for (start, end) in [(0, 4500), (0, 1), (999, 1001), (1500, 3200), (4000, 4500), (4499, 4500)] {
+                let range = read_range(&data, start, end).unwrap();
+                assert_eq!(range, &payload[start as usize..end as usize], "{} [{}, {})", codec.name(), start, end);
+            }
+            assert!(read_range(&data, 10, 10).unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn spans_cover_only_needed_blocks() {
+        let (header, _, original_len) = locate_payload(&seekable(Codec::Zlib)).unwrap();
+        let index = header.block_index.unwrap();
+        assert_eq!(index.ends.len(), 5);
+        let spans = index.spans(original_len, 1500, 3200).unwrap();
+        let blocks: Vec<_> = spans.iter().map(|span| (span.index, span.take_start, span.take_end)).collect();
+        assert_eq!(blocks, [(1, 500, 1000), (2, 0, 1000), (3, 0, 200)]);
+        // 最后一块只有 500 字节
+        assert_eq!(index.spans(original_len, 4200, 9000).unwrap()[0].len, 500);
+    }
+
+    #[test]
+    fn malformed_tables_are_rejected() {
+        let invalid = Some(DecodeErrorCode::InvalidInput);
+        let table = |ends: &[u32]| ends.iter().flat_map(|end| end.to_le_bytes()).collect::<Vec<u8>>();
+        assert!(BlockIndex::parse(100, &table(&[10, 20, 30]), 30).is_ok());
+        assert_eq!(BlockIndex::parse(0, &table(&[10, 20, 30]), 30).err().map(|e| e.code), invalid);
+        assert_eq!(BlockIndex::parse(100, &table(&[10, 5, 30]), 30).err().map(|e| e.code), invalid);
+        assert_eq!(BlockIndex::parse(100, &table(&[10, 20, 29]), 30).err().map(|e| e.code), invalid);
+        assert_eq!(BlockIndex::parse(100, &[], 0).err().map(|e| e.code), invalid);
+
+        let index = BlockIndex::parse(100, &table(&[10, 20, 30]), 30).unwrap();
+        assert_eq!(index.spans(1000, 0, 10).err().map(|e| e.code), Some(DecodeErrorCode::LengthMismatch));
+    }
+
+    #[test]
+    fn truncated_and_oversized_tables_are_rejected() {
+        let data = seekable(Codec::Lz4);
+        let table_end = parse_container_header(&data).unwrap().payload_offset;
+        assert!(parse_container_header(&data[..table_end - 2]).is_err());
+
+        // 块数声明为 u32::MAX: 偏移表长度超出数据，不按声明分配
+        let mut oversized = data.clone();
+        let at = extension_offset(&data, "block_count");
+        oversized[at..at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(parse_container_header(&oversized).is_err());
+
+        // 偏移表改为递减
+        let mut unordered = data.clone();
+        unordered[table_end - 8..table_end - 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(parse_container_header(&unordered).err().map(|e| e.code), Some(DecodeErrorCode::InvalidInput));
+    }
+
+    #[test]
+    fn block_index_flag_conflicts_are_rejected() {
+        let mut data = seekable(Codec::Zlib);
+        let flags = format::FLAGS.read(&data) as u16 | FLAG_DICTIONARY;
+        data[format::FLAGS.offset..format::FLAGS.offset + 2].copy_from_slice(&flags.to_le_bytes());
+        assert_eq!(parse_container_header(&data).err().map(|e| e.code), Some(DecodeErrorCode::UnsupportedFlags));
+    }
+
+    #[test]
+    fn corrupted_block_is_reported() {
+        let mut data = seekable(Codec::Lz4);
+        let (header, _, _) = locate_payload(&data).unwrap();
+        let second_block = header.payload_offset + header.block_index.as_ref().unwrap().ends[0] as usize;
+        data[second_block..second_block + 16].fill(0xff);
+        assert!(read_range(&data, 0, 1000).is_ok());
+        assert!(read_range(&data, 1000, 2000).is_err());
+    }
+}
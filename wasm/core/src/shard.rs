@@ -0,0 +1,282 @@
+use crate::buffer_pool;
+use crate::byte_reader::ByteReader;
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// FastDog 分片格式 (CDN 把大文件切成多个分片并行下载)
+//
+// 魔数 "FASTDOGS"(8) | 版本(4) | 分片序号(4) | 分片总数(4) | 完整文件长度(4) | 分片偏移(4)
+// | 分片长度(4) | 分片 CRC32(4) | 分片数据
+//
+// 按偏移拼接全部分片得到原始的 FastDog 容器
+pub const SHARD_MAGIC: &[u8; 8] = b"FASTDOGS";
+pub const SHARD_VERSION: u32 = 1;
+const SHARD_HEADER_LEN: usize = 36;
+
+struct ShardHeader {
+    index: u32,
+    count: u32,
+    total_len: u32,
+    offset: u32,
+    len: u32,
+    crc32: u32,
+}
+
+impl ShardHeader {
+    fn parse(shard: &[u8]) -> Result<ShardHeader, DecodeError> {
+        if shard.len() < SHARD_HEADER_LEN {
+            return Err(DecodeError::new(DecodeErrorCode::TooShort, "数据太短，不是有效的 FastDog 分片"));
+        }
+        if &shard[0..8] != SHARD_MAGIC {
+            return Err(DecodeError::new(DecodeErrorCode::BadMagic, format!("无效的分片魔数: {:?}", &shard[0..8])));
+        }
+        let reader = ByteReader::new(shard, "分片头部");
+        let version = reader.u32_at(8)?;
+        if version != SHARD_VERSION {
+            return Err(DecodeError::new(
+                DecodeErrorCode::UnsupportedVersion,
+                format!("不支持的分片版本: {}", version),
+            ));
+        }
+
+        let header = ShardHeader {
+            index: reader.u32_at(12)?,
+            count: reader.u32_at(16)?,
+            total_len: reader.u32_at(20)?,
+            offset: reader.u32_at(24)?,
+            len: reader.u32_at(28)?,
+            crc32: reader.u32_at(32)?,
+        };
+        // 每个分片至少 1 字节 (空文件为 1 个空分片)，分片总数不会超过文件长度
+        if header.count == 0 || header.index >= header.count || header.count > header.total_len.max(1) {
+            return Err(DecodeError::new(
+                DecodeErrorCode::InvalidInput,
+                format!("分片序号无效: {} / {}", header.index, header.count),
+            ));
+        }
+        if header.offset as u64 + header.len as u64 > header.total_len as u64 {
+            return Err(DecodeError::new(DecodeErrorCode::InvalidInput, "分片区间超出完整文件长度"));
+        }
+        if shard.len() - SHARD_HEADER_LEN != header.len as usize {
+            return Err(DecodeError::new(
+                DecodeErrorCode::LengthMismatch,
+                format!("分片 {} 长度不匹配: 期望 {}, 实际 {}", header.index, header.len, shard.len() - SHARD_HEADER_LEN),
+            ));
+        }
+        Ok(header)
+    }
+}
+
+// 分片重组状态
+//
+// 分片可以按任意顺序到达，每个分片到达时校验 CRC32，全部到齐后按偏移拼接得到原始容器。
+// 重复到达的相同分片被忽略
+#[derive(Default)]
+pub struct ShardSet {
+    // 已收到的分片: 偏移和数据
+    shards: Vec<Option<(u32, Vec<u8>)>>,
+    total_len: u32,
+    received_bytes: usize,
+}
+
+impl ShardSet {
+    // 添加一个分片，返回是否已收齐全部分片。on_store 在保存新分片之前以保存后的累计字节数调用 (内存配额)，
+    // 返回错误时不保存该分片。分片损坏或与之前的分片不一致时返回错误，已收到的分片不受影响
+    pub fn add<F>(&mut self, shard: &[u8], on_store: F) -> Result<bool, DecodeError>
+    where
+        F: FnOnce(usize) -> Result<(), DecodeError>,
+    {
+        let header = ShardHeader::parse(shard)?;
+        let data = &shard[SHARD_HEADER_LEN..];
+        let crc32 = crc32fast::hash(data);
+        if crc32 != header.crc32 {
+            return Err(DecodeError::new(
+                DecodeErrorCode::ChecksumMismatch,
+                format!("分片 {} CRC32 校验失败: 期望 {:08x}, 实际 {:08x}", header.index, header.crc32, crc32),
+            ));
+        }
+
+        if self.shards.is_empty() {
+            self.shards = vec![None; header.count as usize];
+            self.total_len = header.total_len;
+        } else if header.count as usize != self.shards.len() || header.total_len != self.total_len {
+            return Err(DecodeError::new(
+                DecodeErrorCode::InvalidInput,
+                format!(
+                    "分片 {} 与之前的分片不属于同一文件: 分片总数 {} / {}, 文件长度 {} / {}",
+                    header.index,
+                    header.count,
+                    self.shards.len(),
+                    header.total_len,
+                    self.total_len
+                ),
+            ));
+        }
+
+        let index = header.index as usize;
+        if let Some((offset, existing)) = &self.shards[index] {
+            if existing != data || *offset != header.offset {
+                return Err(DecodeError::new(
+                    DecodeErrorCode::InvalidInput,
+                    format!("分片 {} 重复且内容不同", header.index),
+                ));
+            }
+            return Ok(self.is_complete());
+        }
+
+        on_store(self.received_bytes + data.len())?;
+        self.received_bytes += data.len();
+        self.shards[index] = Some((header.offset, data.to_vec()));
+        Ok(self.is_complete())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        !self.shards.is_empty() && self.shards.iter().all(Option::is_some)
+    }
+
+    // 尚未收到的分片序号 (收到第一个分片之前为空)
+    pub fn missing(&self) -> Vec<u32> {
+        (0..self.shards.len() as u32)
+            .filter(|&index| self.shards[index as usize].is_none())
+            .collect()
+    }
+
+    pub fn shard_count(&self) -> u32 {
+        self.shards.len() as u32
+    }
+
+    pub fn received_count(&self) -> u32 {
+        self.shards.iter().filter(|shard| shard.is_some()).count() as u32
+    }
+
+    // 已缓存的分片数据字节数
+    pub fn received_bytes(&self) -> usize {
+        self.received_bytes
+    }
+
+    // 完整文件长度 (收到第一个分片之前为 0)
+    pub fn total_len(&self) -> u32 {
+        self.total_len
+    }
+
+    // 按偏移拼接分片，要求分片恰好无重叠地覆盖完整文件
+    pub fn assemble(&self) -> Result<Vec<u8>, DecodeError> {
+        if !self.is_complete() {
+            return Err(DecodeError::new(
+                DecodeErrorCode::Truncated,
+                format!("分片未收齐: 已收到 {} / {}", self.received_count(), self.shards.len()),
+            ));
+        }
+
+        let mut shards: Vec<(u32, &[u8])> = self
+            .shards
+            .iter()
+            .flatten()
+            .map(|(offset, shard)| (*offset, &shard[..]))
+            .collect();
+        // 空分片排在同一偏移的非空分片之前
+        shards.sort_by_key(|(offset, shard)| (*offset, shard.len()));
+
+        let mut data = buffer_pool::try_allocate(self.total_len as usize)?;
+        for (offset, shard) in shards {
+            if offset as usize != data.len() {
+                return Err(DecodeError::new(
+                    DecodeErrorCode::InvalidInput,
+                    format!("分片区间不连续: 偏移 {}, 已拼接 {} 字节", offset, data.len()),
+                ));
+            }
+            data.extend_from_slice(shard);
+        }
+        if data.len() != self.total_len as usize {
+            return Err(DecodeError::new(
+                DecodeErrorCode::LengthMismatch,
+                format!("拼接后长度不匹配: 期望 {}, 实际 {}", self.total_len, data.len()),
+            ));
+        }
+        Ok(data)
+    }
+}
+
+// 把 FastDog 文件切成 shard_count 个分片 (与 CDN 的切分方式一致)
+pub fn split(data: &[u8], shard_count: u32) -> Result<Vec<Vec<u8>>, String> {
+    let total_len = u32::try_from(data.len()).map_err(|_| "文件超过 4GB，无法分片".to_string())?;
+    if shard_count == 0 || shard_count > total_len.max(1) {
+        return Err(format!("分片数必须在 1 到 {} 之间", total_len.max(1)));
+    }
+    let shard_len = data.len().div_ceil(shard_count as usize);
+
+    Ok((0..shard_count as usize)
+        .map(|index| {
+            let start = (index * shard_len).min(data.len());
+            let end = (start + shard_len).min(data.len());
+            let chunk = &data[start..end];
+
+            let mut shard = Vec::with_capacity(SHARD_HEADER_LEN + chunk.len());
+            shard.extend_from_slice(SHARD_MAGIC);
+            shard.extend_from_slice(&SHARD_VERSION.to_le_bytes());
+            shard.extend_from_slice(&(index as u32).to_le_bytes());
+            shard.extend_from_slice(&shard_count.to_le_bytes());
+            shard.extend_from_slice(&total_len.to_le_bytes());
+            shard.extend_from_slice(&(start as u32).to_le_bytes());
+            shard.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+            shard.extend_from_slice(&crc32fast::hash(chunk).to_le_bytes());
+            shard.extend_from_slice(chunk);
+            shard
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 253) as u8).collect()
+    }
+
+    #[test]
+    fn shards_reassemble_in_any_order() {
+        let data = sample(10_001);
+        let shards = split(&data, 4).unwrap();
+        assert_eq!(shards.len(), 4);
+
+        let mut set = ShardSet::default();
+        for shard in shards[1..].iter().rev() {
+            assert!(!set.add(shard, |_| Ok(())).unwrap());
+        }
+        assert_eq!(set.missing(), [0]);
+        // 重复的相同分片被忽略
+        assert!(!set.add(&shards[1], |_| Ok(())).unwrap());
+        assert!(set.add(&shards[0], |_| Ok(())).unwrap());
+        assert_eq!(set.received_bytes(), data.len());
+        assert_eq!(set.assemble().unwrap(), data);
+
+        let empty = split(&[], 1).unwrap();
+        let mut set = ShardSet::default();
+        assert!(set.add(&empty[0], |_| Ok(())).unwrap());
+        assert!(set.assemble().unwrap().is_empty());
+    }
+
+    #[test]
+    fn bad_shards_leave_the_set_unchanged() {
+        let data = sample(1000);
+        let shards = split(&data, 2).unwrap();
+        let mut set = ShardSet::default();
+        let code = |result: Result<bool, DecodeError>| result.err().map(|e| e.code);
+
+        let mut corrupt = shards[0].clone();
+        corrupt[SHARD_HEADER_LEN] ^= 1;
+        assert_eq!(code(set.add(&corrupt, |_| Ok(()))), Some(DecodeErrorCode::ChecksumMismatch));
+        assert_eq!(code(set.add(&shards[0][..20], |_| Ok(()))), Some(DecodeErrorCode::TooShort));
+        let refused = set.add(&shards[0], |_| Err(DecodeError::new(DecodeErrorCode::QuotaExceeded, "配额不足")));
+        assert_eq!(code(refused), Some(DecodeErrorCode::QuotaExceeded));
+        assert_eq!(set.received_count(), 0);
+
+        set.add(&shards[0], |_| Ok(())).unwrap();
+        assert_eq!(code(set.assemble().map(|_| true)), Some(DecodeErrorCode::Truncated));
+        let other = split(&sample(2000), 2).unwrap();
+        assert_eq!(code(set.add(&other[1], |_| Ok(()))), Some(DecodeErrorCode::InvalidInput));
+
+        assert!(split(&data, 0).is_err());
+        assert!(split(&data, 1001).is_err());
+    }
+}
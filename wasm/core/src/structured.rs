@@ -0,0 +1,144 @@
+use serde::ser::{Error, SerializeMap, SerializeSeq};
+use serde::{Serialize, Serializer};
+
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// MessagePack / CBOR 等二进制结构化载荷解码后的值
+//
+// 按 JSON 兼容的序列化器直接转为 JS 值，不经过 JSON 字符串: 映射输出为普通对象，
+// 字节串输出为 Uint8Array，超出 2^53 的整数按 number 输出 (与 JSON.parse 一样会损失精度)
+#[derive(Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    Array(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+    // MessagePack 扩展类型 / CBOR 标签: 输出为 { type, data }
+    Ext(i64, Box<Value>),
+}
+
+// 嵌套层数上限，防止恶意载荷耗尽调用栈
+pub const MAX_DEPTH: usize = 512;
+
+// 解码器共用的大端字节读取游标
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    format: &'static str,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8], format: &'static str) -> Reader<'a> {
+        Reader { data, pos: 0, format }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub fn error(&self, message: impl std::fmt::Display) -> DecodeError {
+        DecodeError::new(
+            DecodeErrorCode::InvalidInput,
+            format!("{} 解析失败 (偏移 {}): {}", self.format, self.pos, message),
+        )
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.remaining() < len {
+            return Err(self.error(format!("数据不完整，需要 {} 字节，剩余 {} 字节", len, self.remaining())));
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub fn peek(&self) -> Result<u8, DecodeError> {
+        self.data.get(self.pos).copied().ok_or_else(|| self.error("数据不完整"))
+    }
+
+    pub fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    // 1 / 2 / 4 / 8 字节的大端无符号整数
+    pub fn uint(&mut self, size: usize) -> Result<u64, DecodeError> {
+        Ok(self.take(size)?.iter().fold(0, |value, &byte| (value << 8) | byte as u64))
+    }
+
+    // 长度字段转为 usize，并检查不超过剩余数据 (每个元素至少占 min_item 字节)
+    pub fn length(&mut self, len: u64, min_item: usize) -> Result<usize, DecodeError> {
+        usize::try_from(len)
+            .ok()
+            .filter(|&len| len.saturating_mul(min_item) <= self.remaining())
+            .ok_or_else(|| self.error(format!("长度 {} 超出剩余数据", len)))
+    }
+
+    pub fn string(&mut self, len: usize) -> Result<String, DecodeError> {
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|e| self.utf8_error(e))
+    }
+
+    pub fn utf8_error(&self, error: std::string::FromUtf8Error) -> DecodeError {
+        DecodeError::new(
+            DecodeErrorCode::Utf8Error,
+            format!("{} 字符串不是有效的 UTF-8 (偏移 {} 之前): {}", self.format, self.pos, error),
+        )
+    }
+}
+
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+// 映射的键转为对象属性名: 字符串原样使用，数字等标量按 JS 的属性名规则转为字符串
+fn key_string(key: &Value) -> Option<String> {
+    match key {
+        Value::Str(key) => Some(key.clone()),
+        Value::Int(value) => Some(value.to_string()),
+        Value::UInt(value) => Some(value.to_string()),
+        Value::Float(value) => Some(value.to_string()),
+        Value::Bool(value) => Some(value.to_string()),
+        Value::Null => Some("null".to_string()),
+        _ => None,
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_none(),
+            Value::Bool(value) => serializer.serialize_bool(*value),
+            Value::Int(value) if value.unsigned_abs() <= MAX_SAFE_INTEGER => serializer.serialize_i64(*value),
+            Value::Int(value) => serializer.serialize_f64(*value as f64),
+            Value::UInt(value) if *value <= MAX_SAFE_INTEGER => serializer.serialize_u64(*value),
+            Value::UInt(value) => serializer.serialize_f64(*value as f64),
+            Value::Float(value) => serializer.serialize_f64(*value),
+            Value::Str(value) => serializer.serialize_str(value),
+            Value::Bytes(value) => serializer.serialize_bytes(value),
+            Value::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            Value::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    let key = key_string(key).ok_or_else(|| S::Error::custom("映射的键必须是字符串或数字等标量"))?;
+                    map.serialize_entry(&key, value)?;
+                }
+                map.end()
+            }
+            Value::Ext(kind, data) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("type", kind)?;
+                map.serialize_entry("data", data)?;
+                map.end()
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::buffer_pool;
+use crate::codec::Codec;
+use crate::container::{self, ContainerHeader};
+use crate::encoder::{encode_legacy_container, encode_transcoded_container};
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::payload_kind::PayloadKind;
+use crate::profiles::{resolve_profile, EncoderProfile};
+
+// 容器转码
+//
+// 在 WASM 内解码后按目标版本和编码重新编码，迁移工具把旧的版本 1 zlib 存档升级为版本 3 时
+// 载荷不必回到 JS。版本 3 保留原容器的元数据、来源信息和块索引的块大小，校验字段按档案重新计算;
+// 引用压缩字典的容器解压后不再使用字典。版本 1/2 只能以 zlib 存放 JSON / GLB 载荷，
+// 原容器带有元数据时报错而不是丢弃。加密容器返回 KeyRequired
+
+// 切换编码时使用的压缩级别 (档案的级别只对档案自身的编码有意义)
+fn default_level(codec: Codec) -> u32 {
+    match codec {
+        Codec::Zlib => 6,
+        Codec::Brotli => 9,
+        Codec::Zstd => 3,
+        Codec::Lz4 | Codec::Store => 0,
+    }
+}
+
+pub fn parse_codec(name: &str) -> Result<Codec, String> {
+    Codec::ALL
+        .into_iter()
+        .find(|codec| codec.name() == name)
+        .ok_or_else(|| {
+            let names: Vec<&str> = Codec::ALL.iter().map(Codec::name).collect();
+            format!("不支持的压缩编码: {} (可选 {})", name, names.join(" / "))
+        })
+}
+
+// 目标编码覆盖档案的编码，编码不同时级别和块大小换成该编码的默认值
+pub fn target_profile(profile: Option<&str>, codec: Option<Codec>) -> Result<EncoderProfile, String> {
+    let mut profile = resolve_profile(profile)?;
+    if let Some(codec) = codec.filter(|&codec| codec != profile.codec) {
+        profile.codec = codec;
+        profile.level = default_level(codec);
+        profile.block_size = 0;
+        profile = profile.pinned();
+    }
+    Ok(profile)
+}
+
+// 解码后转码为 target_version (1 / 2 / 3) 和 target_codec ("zlib" / "brotli" / "lz4" / "store" / "zstd")，
+// target_codec 省略时版本 3 使用档案的编码，版本 1/2 固定为 zlib; profile 省略时使用默认档案
+pub fn transcode(
+    data: &[u8],
+    target_version: u32,
+    target_codec: Option<&str>,
+    profile: Option<&str>,
+) -> Result<Vec<u8>, DecodeError> {
+    let codec = target_codec.map(parse_codec).transpose().map_err(invalid)?;
+    let (header, _, payload) = container::decode(data)?;
+    let output = transcode_payload(&header, &payload, target_version, codec, profile);
+    buffer_pool::recycle(payload);
+    output
+}
+
+fn invalid(message: String) -> DecodeError {
+    DecodeError::new(DecodeErrorCode::InvalidInput, message)
+}
+
+// 把已解码的载荷按原容器的头部重新编码
+pub fn transcode_payload(
+    header: &ContainerHeader,
+    payload: &[u8],
+    target_version: u32,
+    codec: Option<Codec>,
+    profile: Option<&str>,
+) -> Result<Vec<u8>, DecodeError> {
+    let output = match target_version {
+        1 | 2 => {
+            if codec.is_some_and(|codec| codec != Codec::Zlib) {
+                return Err(invalid("版本 1/2 只支持 zlib 编码".to_string()));
+            }
+            if !header.metadata.is_empty() {
+                return Err(invalid("版本 1/2 不支持元数据，转码会丢失原容器的元数据".to_string()));
+            }
+            // 载荷类型决定版本号: JSON 为 1，GLB 为 2
+            let legacy_version = match header.kind {
+                PayloadKind::Json => 1,
+                PayloadKind::Glb => 2,
+                _ => 0,
+            };
+            if legacy_version != target_version {
+                return Err(invalid(format!(
+                    "版本 {} 不能存放 {} 载荷 (版本 1 为 JSON，版本 2 为 GLB)",
+                    target_version,
+                    header.kind.name()
+                )));
+            }
+            let profile = target_profile(profile, Some(Codec::Zlib)).map_err(invalid)?;
+            encode_legacy_container(payload, header.kind, profile.level)
+        }
+        3 => {
+            let profile = target_profile(profile, codec).map_err(invalid)?;
+            let block_size = header.block_index.as_ref().map(|index| index.block_size);
+            encode_transcoded_container(
+                payload,
+                header.kind,
+                &header.metadata,
+                header.provenance.as_ref(),
+                block_size,
+                &profile,
+            )
+        }
+        other => return Err(invalid(format!("不支持的目标版本: {}", other))),
+    };
+    output.map_err(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder::encode_metadata_container;
+    use crate::metadata::Metadata;
+
+    fn scene() -> Vec<u8> {
+        let nodes: Vec<String> = (0..200).map(|i| format!(r#"{{"name":"node{}","translation":[{},0,0]}}"#, i, i)).collect();
+        format!(r#"{{"nodes":[{}]}}"#, nodes.join(",")).into_bytes()
+    }
+
+    #[test]
+    fn every_codec_is_a_target() {
+        let payload = scene();
+        let legacy = encode_legacy_container(&payload, PayloadKind::Json, 6).unwrap();
+        for codec in Codec::ALL {
+            let output = transcode(&legacy, 3, Some(codec.name()), None).unwrap();
+            let (header, _, decoded) = container::decode(&output).unwrap();
+            assert_eq!((header.version, header.codec, header.kind), (3, codec, PayloadKind::Json), "{}", codec.name());
+            assert_eq!(decoded, payload, "{}", codec.name());
+        }
+        let error = transcode(&legacy, 3, Some("lzma"), None).unwrap_err();
+        assert!(error.message.contains("zstd"), "{}", error.message);
+        assert_eq!(transcode(&legacy, 1, Some("zstd"), None).unwrap_err().code, DecodeErrorCode::InvalidInput);
+    }
+
+    // zstd 容器转回版本 1 时元数据阻止转码，转为其他编码时保留
+    #[test]
+    fn zstd_round_trip_keeps_metadata() {
+        let payload = scene();
+        let metadata = Metadata::from([("author".to_string(), "fastdog".to_string())]);
+        let profile = target_profile(None, Some(Codec::Zstd)).unwrap();
+        let original = encode_metadata_container(&payload, PayloadKind::Json, &metadata, &profile).unwrap();
+
+        let zlib = transcode(&original, 3, Some("zlib"), None).unwrap();
+        let (header, _, decoded) = container::decode(&zlib).unwrap();
+        assert_eq!((header.codec, &header.metadata, decoded), (Codec::Zlib, &metadata, payload.clone()));
+        let zstd = transcode(&zlib, 3, Some("zstd"), Some("max")).unwrap();
+        let (header, _, decoded) = container::decode(&zstd).unwrap();
+        assert_eq!((header.codec, &header.metadata, decoded), (Codec::Zstd, &metadata, payload));
+        assert_eq!(transcode(&zstd, 1, None, None).unwrap_err().code, DecodeErrorCode::InvalidInput);
+    }
+}
@@ -0,0 +1,330 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::codec::Codec;
+use crate::container::{decompress_payload, decompress_payload_prefix, locate_payload, verify_payload, ContainerHeader};
+use crate::error::DecodeError;
+use crate::payload_kind::PayloadKind;
+use crate::{cbor, msgpack};
+
+// 严格校验时最多解压的字节数，超过该大小的载荷只探测开头部分
+const PROBE_LIMIT: usize = 64 * 1024;
+
+// GLB 块类型
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+
+// 严格校验结果，reasons 列出所有未通过的检查项
+#[derive(Serialize, Default)]
+pub struct ValidationReport {
+    pub valid: bool,
+    pub version: Option<u32>,
+    pub codec: Option<String>,
+    pub content_type: Option<String>,
+    pub compressed_size: Option<u64>,
+    pub original_size: Option<u64>,
+    // 实际解压探测的字节数
+    pub probed_bytes: u32,
+    // 载荷完整解压并通过长度/CRC32 校验 (仅小载荷)
+    pub fully_verified: bool,
+    pub reasons: Vec<String>,
+}
+
+// 严格格式校验: 检查头部字段、长度一致性、压缩比是否可信以及载荷开头的内容，
+// 最多只解压 PROBE_LIMIT 字节，不做完整解码
+pub fn validate_strict(data: &[u8]) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    let (header, compressed, original_len) = match locate_payload(data) {
+        Ok(located) => located,
+        Err(e) => {
+            report.reasons.push(e.message);
+            return report;
+        }
+    };
+    report.version = Some(header.version);
+    report.codec = Some(header.codec.name().to_string());
+    report.content_type = Some(header.kind.name().to_string());
+    report.compressed_size = Some(header.compressed_len as u64);
+    report.original_size = Some(original_len);
+
+    if data.len() > header.total_len() {
+        report.reasons.push(format!("容器末尾有 {} 字节多余数据", data.len() - header.total_len()));
+    }
+    check_ratio(&header, original_len, &mut report.reasons);
+
+    if report.reasons.is_empty() {
+        if original_len <= PROBE_LIMIT as u64 {
+            // 小载荷直接完整解码，同时校验长度和 CRC32
+            match decode(&data[..header.total_len()], |_| Ok(())) {
+                Ok(decompressed) => {
+                    report.probed_bytes = decompressed.len() as u32;
+                    report.fully_verified = true;
+                    check_content(header.kind, &decompressed, original_len, &mut report.reasons);
+                }
+                Err(e) => report.reasons.push(e.message),
+            }
+        } else {
+            match decompress_payload_prefix(&header, compressed, original_len, PROBE_LIMIT) {
+                Ok(prefix) => {
+                    report.probed_bytes = prefix.len() as u32;
+                    if prefix.len() < PROBE_LIMIT {
+                        report.reasons.push(format!(
+                            "解压后数据长度不匹配: 期望 {}, 实际 {}",
+                            original_len,
+                            prefix.len()
+                        ));
+                    }
+                    check_content(header.kind, &prefix, original_len, &mut report.reasons);
+                }
+                Err(e) => report.reasons.push(e.message),
+            }
+        }
+    }
+
+    report.valid = report.reasons.is_empty();
+    report
+}
+
+// 深度校验: 完整解压并校验长度、CRC32 和 SHA-256，JSON 载荷完整解析，GLB 载荷检查头部和块表。
+// on_slice 在每个解压分片边界以目前的输出长度调用 (内存配额)，返回错误时该错误计入 reasons
+pub fn validate_deep<F>(data: &[u8], on_slice: F) -> ValidationReport
+where
+    F: FnMut(usize) -> Result<(), DecodeError>,
+{
+    let mut report = ValidationReport::default();
+
+    let (header, _, original_len) = match locate_payload(data) {
+        Ok(located) => located,
+        Err(e) => {
+            report.reasons.push(e.message);
+            return report;
+        }
+    };
+    report.version = Some(header.version);
+    report.codec = Some(header.codec.name().to_string());
+    report.content_type = Some(header.kind.name().to_string());
+    report.compressed_size = Some(header.compressed_len as u64);
+    report.original_size = Some(original_len);
+
+    if data.len() > header.total_len() {
+        report.reasons.push(format!("容器末尾有 {} 字节多余数据", data.len() - header.total_len()));
+    }
+
+    match decode(&data[..header.total_len()], on_slice) {
+        Ok(decompressed) => {
+            report.probed_bytes = decompressed.len() as u32;
+            report.fully_verified = true;
+            if let Some(expected) = header.sha256 {
+                let actual: [u8; 32] = Sha256::digest(&decompressed).into();
+                if actual != expected {
+                    report.reasons.push("SHA-256 校验失败".to_string());
+                }
+            }
+            match header.kind {
+                PayloadKind::Json => check_json(&decompressed, &mut report.reasons),
+                PayloadKind::Glb => check_glb(&decompressed, &mut report.reasons),
+                PayloadKind::MsgPack => {
+                    if let Err(e) = msgpack::decode(&decompressed) {
+                        report.reasons.push(e.message);
+                    }
+                }
+                PayloadKind::Cbor => {
+                    if let Err(e) = cbor::decode(&decompressed) {
+                        report.reasons.push(e.message);
+                    }
+                }
+                // 其他类型的载荷不做内容检查
+                _ => {}
+            }
+        }
+        Err(e) => report.reasons.push(e.message),
+    }
+
+    report.valid = report.reasons.is_empty();
+    report
+}
+
+// 完整解压并校验长度和 CRC32
+fn decode<F>(data: &[u8], on_slice: F) -> Result<Vec<u8>, DecodeError>
+where
+    F: FnMut(usize) -> Result<(), DecodeError>,
+{
+    let (header, compressed, original_len) = locate_payload(data)?;
+    let decompressed = decompress_payload(&header, compressed, original_len, on_slice)?;
+    verify_payload(&header, original_len, &decompressed)?;
+    Ok(decompressed)
+}
+
+// 完整解析 JSON 载荷 (不构建值，只检查语法)
+fn check_json(payload: &[u8], reasons: &mut Vec<String>) {
+    if let Err(e) = serde_json::from_slice::<serde::de::IgnoredAny>(payload) {
+        reasons.push(format!("JSON 解析失败: {}", e));
+    }
+}
+
+// 检查 GLB 头部和块表: 首块为 JSON 且可解析，可选的第二块为 BIN，
+// 各块按 4 字节对齐且恰好铺满头部声明的长度
+fn check_glb(payload: &[u8], reasons: &mut Vec<String>) {
+    let before = reasons.len();
+    check_content(PayloadKind::Glb, payload, payload.len() as u64, reasons);
+    if reasons.len() > before {
+        return;
+    }
+
+    let mut offset = 12;
+    let mut index = 0;
+    while offset < payload.len() {
+        if payload.len() - offset < 8 {
+            reasons.push(format!("GLB 块 {} 的块头不完整 (偏移 {})", index, offset));
+            return;
+        }
+        let chunk_len = u32::from_le_bytes([payload[offset], payload[offset + 1], payload[offset + 2], payload[offset + 3]]) as usize;
+        let chunk_type = u32::from_le_bytes([payload[offset + 4], payload[offset + 5], payload[offset + 6], payload[offset + 7]]);
+        let start = offset + 8;
+        if chunk_len > payload.len() - start {
+            reasons.push(format!("GLB 块 {} 长度 {} 超出载荷范围 (偏移 {})", index, chunk_len, offset));
+            return;
+        }
+        if !chunk_len.is_multiple_of(4) {
+            reasons.push(format!("GLB 块 {} 长度 {} 未按 4 字节对齐", index, chunk_len));
+        }
+
+        match (index, chunk_type) {
+            (0, CHUNK_JSON) => check_json(&payload[start..start + chunk_len], reasons),
+            (0, _) => reasons.push(format!("GLB 首块类型应为 JSON, 实际为 {:#010x}", chunk_type)),
+            (1, CHUNK_BIN) => {}
+            (_, CHUNK_JSON) | (_, CHUNK_BIN) => {
+                reasons.push(format!("GLB 块 {} 的类型 {:#010x} 位置不正确", index, chunk_type));
+            }
+            // 未知类型的扩展块按规范忽略
+            _ => {}
+        }
+
+        offset = start + chunk_len;
+        index += 1;
+    }
+    if index == 0 {
+        reasons.push("GLB 载荷缺少 JSON 块".to_string());
+    }
+}
+
+// 检查声明的原始长度与压缩数据长度是否可能一致
+fn check_ratio(header: &ContainerHeader, original_len: u64, reasons: &mut Vec<String>) {
+    let compressed_len = header.compressed_len as u64;
+
+    if header.codec == Codec::Store {
+        if compressed_len != original_len {
+            reasons.push(format!(
+                "存储模式下压缩数据长度 ({}) 与原始长度 ({}) 不一致",
+                compressed_len, original_len
+            ));
+        }
+        return;
+    }
+
+    // 各编码理论上的最大压缩比 (见 Codec::max_ratio)，brotli 没有实用的上限
+    let max_ratio = header.codec.max_ratio().map(|ratio| ratio as u64);
+
+    if compressed_len == 0 {
+        reasons.push("压缩数据为空".to_string());
+        return;
+    }
+    if let Some(max_ratio) = max_ratio {
+        // 加上固定开销的余量，避免极小载荷误报
+        if original_len > compressed_len * max_ratio + 1024 {
+            reasons.push(format!(
+                "声明的原始长度 {} 超出 {} 编码可能的压缩比 (压缩数据 {} 字节)",
+                original_len,
+                header.codec.name(),
+                compressed_len
+            ));
+        }
+    }
+}
+
+// 检查载荷开头是否符合内容类型
+fn check_content(kind: PayloadKind, prefix: &[u8], original_len: u64, reasons: &mut Vec<String>) {
+    match kind {
+        PayloadKind::Json if original_len > 0 => {
+            let first = prefix.iter().find(|b| !b.is_ascii_whitespace());
+            if !matches!(first, Some(b'{') | Some(b'[')) {
+                reasons.push("JSON 载荷不是以对象或数组开头".to_string());
+            }
+            // 截断在多字节字符中间不算错误
+            if let Err(e) = std::str::from_utf8(prefix) {
+                if e.error_len().is_some() {
+                    reasons.push(format!("JSON 载荷包含无效的 UTF-8 (偏移 {})", e.valid_up_to()));
+                }
+            }
+        }
+        PayloadKind::Glb => {
+            if prefix.len() < 12 || &prefix[0..4] != b"glTF" {
+                reasons.push("GLB 载荷缺少 glTF 魔数".to_string());
+                return;
+            }
+            let glb_version = u32::from_le_bytes([prefix[4], prefix[5], prefix[6], prefix[7]]);
+            let glb_len = u32::from_le_bytes([prefix[8], prefix[9], prefix[10], prefix[11]]);
+            if glb_version != 2 {
+                reasons.push(format!("不支持的 GLB 版本: {}", glb_version));
+            }
+            if glb_len as u64 != original_len {
+                reasons.push(format!("GLB 头部声明长度 {} 与载荷长度 {} 不一致", glb_len, original_len));
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder::encode_container;
+    use crate::error::DecodeErrorCode;
+    use crate::transcode::target_profile;
+
+    fn json_container(payload: &[u8]) -> Vec<u8> {
+        let profile = target_profile(None, Some(Codec::Zlib)).unwrap();
+        encode_container(payload, PayloadKind::Json, &profile).unwrap()
+    }
+
+    #[test]
+    fn valid_container_passes_both_checks() {
+        let container = json_container(br#"{"nodes":[1,2,3]}"#);
+        let strict = validate_strict(&container);
+        assert!(strict.valid, "{:?}", strict.reasons);
+        assert!(strict.fully_verified);
+        let deep = validate_deep(&container, |_| Ok(()));
+        assert!(deep.valid, "{:?}", deep.reasons);
+        assert_eq!(deep.probed_bytes, 17);
+    }
+
+    #[test]
+    fn problems_are_listed_as_reasons() {
+        let mut trailing = json_container(br#"{"nodes":[1,2,3]}"#);
+        trailing.extend_from_slice(b"xx");
+        let report = validate_strict(&trailing);
+        assert!(!report.valid);
+        assert_eq!(report.reasons, ["容器末尾有 2 字节多余数据"]);
+
+        // 开头合法但不完整的 JSON 只有深度校验能发现
+        let truncated_json = json_container(br#"{"nodes":[1,2"#);
+        assert!(validate_strict(&truncated_json).valid);
+        let report = validate_deep(&truncated_json, |_| Ok(()));
+        assert!(!report.valid);
+        assert!(report.reasons[0].starts_with("JSON 解析失败"));
+
+        let report = validate_strict(b"FASTDOG");
+        assert!(!report.valid);
+        assert_eq!(report.version, None);
+    }
+
+    #[test]
+    fn deep_check_reports_aborted_decodes() {
+        let container = json_container(br#"{"nodes":[1,2,3]}"#);
+        let report = validate_deep(&container, |_| Err(DecodeError::new(DecodeErrorCode::QuotaExceeded, "配额不足")));
+        assert!(!report.valid);
+        assert!(!report.fully_verified);
+        assert_eq!(report.reasons, ["配额不足"]);
+    }
+}
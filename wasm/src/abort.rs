@@ -0,0 +1,39 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::AbortSignal;
+
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// 信号已触发时返回取消错误。异步解码在每个分片 / 数据块之间检查，
+// 返回错误后解码状态随之释放，缓冲区不会等到垃圾回收才归还
+pub(crate) fn check(signal: Option<&AbortSignal>) -> Result<(), DecodeError> {
+    match signal {
+        Some(signal) if signal.aborted() => Err(DecodeError::new(DecodeErrorCode::Aborted, "解码已取消")),
+        _ => Ok(()),
+    }
+}
+
+// 信号触发时执行回调 (例如取消正在等待的读取)，离开作用域时移除监听
+pub(crate) struct OnAbort {
+    signal: AbortSignal,
+    listener: Closure<dyn FnMut()>,
+}
+
+impl OnAbort {
+    pub fn new(signal: Option<&AbortSignal>, callback: impl FnMut() + 'static) -> Option<OnAbort> {
+        let signal = signal?.clone();
+        let listener = Closure::<dyn FnMut()>::new(callback);
+        signal
+            .add_event_listener_with_callback("abort", listener.as_ref().unchecked_ref())
+            .ok()?;
+        Some(OnAbort { signal, listener })
+    }
+}
+
+impl Drop for OnAbort {
+    fn drop(&mut self) {
+        let _ = self
+            .signal
+            .remove_event_listener_with_callback("abort", self.listener.as_ref().unchecked_ref());
+    }
+}
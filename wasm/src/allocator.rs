@@ -0,0 +1,147 @@
+use serde::Serialize;
+use std::alloc::{GlobalAlloc, Layout};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use wasm_bindgen::prelude::*;
+
+use crate::guard;
+
+// 全局分配器与分配统计
+//
+// 默认使用标准库的系统分配器 (wasm32 上即 dlmalloc)。开启 dlmalloc 特性时在所有目标上都使用
+// dlmalloc crate。wee_alloc 已停止维护，频繁分配释放时速度慢且容易产生碎片，只在开启 wee_alloc
+// 特性时作为体积优先的选项保留。
+// 外层计数记录分配 / 释放次数和当前、峰值字节数，供长时间运行的会话诊断内存碎片
+#[cfg(all(feature = "wee_alloc", feature = "dlmalloc"))]
+compile_error!("wee_alloc 和 dlmalloc 特性只能开启一个");
+
+#[cfg(not(any(feature = "wee_alloc", feature = "dlmalloc")))]
+type Inner = std::alloc::System;
+#[cfg(not(any(feature = "wee_alloc", feature = "dlmalloc")))]
+const fn inner() -> Inner {
+    std::alloc::System
+}
+#[cfg(not(any(feature = "wee_alloc", feature = "dlmalloc")))]
+const ALLOCATOR_NAME: &str = if cfg!(target_arch = "wasm32") { "dlmalloc" } else { "system" };
+
+#[cfg(all(feature = "dlmalloc", not(feature = "wee_alloc")))]
+type Inner = dlmalloc::GlobalDlmalloc;
+#[cfg(all(feature = "dlmalloc", not(feature = "wee_alloc")))]
+const fn inner() -> Inner {
+    dlmalloc::GlobalDlmalloc
+}
+#[cfg(all(feature = "dlmalloc", not(feature = "wee_alloc")))]
+const ALLOCATOR_NAME: &str = "dlmalloc";
+
+#[cfg(feature = "wee_alloc")]
+type Inner = wee_alloc::WeeAlloc<'static>;
+#[cfg(feature = "wee_alloc")]
+const fn inner() -> Inner {
+    wee_alloc::WeeAlloc::INIT
+}
+#[cfg(feature = "wee_alloc")]
+const ALLOCATOR_NAME: &str = "wee_alloc";
+
+struct CountingAllocator<A> {
+    inner: A,
+    allocations: AtomicU64,
+    deallocations: AtomicU64,
+    reallocations: AtomicU64,
+    current_bytes: AtomicUsize,
+    peak_bytes: AtomicUsize,
+}
+
+#[global_allocator]
+static ALLOC: CountingAllocator<Inner> = CountingAllocator {
+    inner: inner(),
+    allocations: AtomicU64::new(0),
+    deallocations: AtomicU64::new(0),
+    reallocations: AtomicU64::new(0),
+    current_bytes: AtomicUsize::new(0),
+    peak_bytes: AtomicUsize::new(0),
+};
+
+impl<A> CountingAllocator<A> {
+    fn grow(&self, bytes: usize) {
+        let current = self.current_bytes.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        self.peak_bytes.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn shrink(&self, bytes: usize) {
+        self.current_bytes.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+// 安全性: 只转发给内层分配器并更新计数，不改变分配语义
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc(layout);
+        if !ptr.is_null() {
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+        self.deallocations.fetch_add(1, Ordering::Relaxed);
+        self.shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = self.inner.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            self.reallocations.fetch_add(1, Ordering::Relaxed);
+            if new_size > layout.size() {
+                self.grow(new_size - layout.size());
+            } else {
+                self.shrink(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+#[derive(Serialize)]
+struct AllocatorStats {
+    allocator: &'static str,
+    allocations: f64,
+    deallocations: f64,
+    reallocations: f64,
+    // 尚未释放的字节数 (按请求大小计，不含分配器自身的开销)
+    current_bytes: f64,
+    // 模块加载 (或上次 reset_allocator_peak) 以来 current_bytes 的最高值
+    peak_bytes: f64,
+}
+
+// 分配器统计。current_bytes 远低于 get_memory_stats 的 wasm_memory_bytes 说明内存碎片严重
+// (WASM 内存只增不减，峰值之后释放的内存只能被后续分配复用)
+#[wasm_bindgen(unchecked_return_type = "AllocatorStats")]
+pub fn get_allocator_stats() -> JsValue {
+    let stats = AllocatorStats {
+        allocator: ALLOCATOR_NAME,
+        allocations: ALLOC.allocations.load(Ordering::Relaxed) as f64,
+        deallocations: ALLOC.deallocations.load(Ordering::Relaxed) as f64,
+        reallocations: ALLOC.reallocations.load(Ordering::Relaxed) as f64,
+        current_bytes: ALLOC.current_bytes.load(Ordering::Relaxed) as f64,
+        peak_bytes: ALLOC.peak_bytes.load(Ordering::Relaxed) as f64,
+    };
+    guard::to_js(&stats)
+}
+
+// 把峰值重置为当前用量，便于测量某一阶段 (如加载一个场景) 的峰值
+#[wasm_bindgen]
+pub fn reset_allocator_peak() {
+    ALLOC
+        .peak_bytes
+        .store(ALLOC.current_bytes.load(Ordering::Relaxed), Ordering::Relaxed);
+}
@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use wasm_bindgen::prelude::*;
+
+use crate::buffer_pool;
+use crate::error::DecodeError;
+use crate::plain::PlainObject;
+use crate::quota::{self, Reservation};
+
+// 零拷贝解码结果的缓冲区池
+//
+// 每个解码结果以句柄 ID 登记，缓冲区采用引用计数，所有句柄释放后立即回收。
+// 缓冲区存放在堆上，登记期间地址保持不变，JS 可以安全地在 WASM 内存上建立视图。
+struct Slot {
+    data: AlignedBytes,
+    refs: u32,
+    // 缓冲区占用的内存配额，回收时归还
+    _reservation: Reservation,
+}
+
+// 缓冲区起始地址的对齐要求，保证 JS 可直接在其上建立 Float32Array / Float64Array 视图
+const ALIGNMENT: usize = 8;
+
+// 起始地址按 ALIGNMENT 对齐的字节缓冲区
+//
+// 分配器返回的地址已对齐时直接接管 Vec 的内存 (通常如此)，否则复制到 u64 数组中。
+// 接管的 Vec 保留原容量 (来自缓冲区复用池时容量通常大于长度)，回收时放回复用池
+enum AlignedBytes {
+    Bytes(Vec<u8>),
+    Words(Box<[u64]>, usize),
+}
+
+impl AlignedBytes {
+    fn from_vec(data: Vec<u8>) -> AlignedBytes {
+        if (data.as_ptr() as usize).is_multiple_of(ALIGNMENT) {
+            return AlignedBytes::Bytes(data);
+        }
+
+        let len = data.len();
+        let mut words = vec![0u64; len.div_ceil(8)].into_boxed_slice();
+        // 安全性: words 至少有 len 字节，且与 data 不重叠
+        unsafe {
+            std::ptr::copy_nonoverlapping(data.as_ptr(), words.as_mut_ptr() as *mut u8, len);
+        }
+        buffer_pool::recycle(data);
+        AlignedBytes::Words(words, len)
+    }
+
+    fn as_ptr(&self) -> *const u8 {
+        match self {
+            AlignedBytes::Bytes(bytes) => bytes.as_ptr(),
+            AlignedBytes::Words(words, _) => words.as_ptr() as *const u8,
+        }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        match self {
+            AlignedBytes::Bytes(bytes) => bytes,
+            // 安全性: words 至少有 len 字节，借用期间不会被其他引用访问
+            AlignedBytes::Words(words, len) => unsafe {
+                std::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, *len)
+            },
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            AlignedBytes::Bytes(bytes) => bytes.len(),
+            AlignedBytes::Words(_, len) => *len,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // 可放回缓冲区复用池的 Vec
+    fn into_vec(self) -> Option<Vec<u8>> {
+        match self {
+            AlignedBytes::Bytes(bytes) => Some(bytes),
+            AlignedBytes::Words(..) => None,
+        }
+    }
+}
+
+struct Arena {
+    slots: BTreeMap<u32, Slot>,
+    // 数据指针 -> 句柄，供只持有指针的旧接口释放缓冲区
+    by_ptr: BTreeMap<u32, u32>,
+    next_handle: u32,
+}
+
+static ARENA: Mutex<Arena> = Mutex::new(Arena {
+    slots: BTreeMap::new(),
+    by_ptr: BTreeMap::new(),
+    next_handle: 1,
+});
+
+fn with_arena<T>(f: impl FnOnce(&mut Arena) -> T) -> T {
+    let mut arena = ARENA.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut arena)
+}
+
+// 登记缓冲区，返回 (句柄, 数据指针, 长度)，初始引用计数为 1。超出内存配额时返回错误
+pub(crate) fn register(data: Vec<u8>) -> Result<(u32, u32, u32), DecodeError> {
+    let reservation = quota::reserve(data.len())?;
+    let data = AlignedBytes::from_vec(data);
+    let ptr = data.as_ptr() as u32;
+    let len = data.len() as u32;
+
+    with_arena(|arena| {
+        // 句柄单调递增 (跳过 0)，避免已释放的句柄被新缓冲区复用
+        let mut handle = arena.next_handle;
+        while handle == 0 || arena.slots.contains_key(&handle) {
+            handle = handle.wrapping_add(1);
+        }
+        arena.next_handle = handle.wrapping_add(1);
+        arena.slots.insert(
+            handle,
+            Slot {
+                data,
+                refs: 1,
+                _reservation: reservation,
+            },
+        );
+        // 空缓冲区的指针是悬空占位值，不登记到指针索引
+        if len > 0 {
+            arena.by_ptr.insert(ptr, handle);
+        }
+        Ok((handle, ptr, len))
+    })
+}
+
+// 查找句柄对应缓冲区的起始地址和长度
+pub(crate) fn lookup(handle: u32) -> Option<(*const u8, usize)> {
+    with_arena(|arena| arena.slots.get(&handle).map(|slot| (slot.data.as_ptr(), slot.data.len())))
+}
+
+// 在登记的缓冲区内 [ptr, ptr + len) 区间上执行 f，区间不完整落在某个缓冲区内时返回 None。
+// f 执行期间持有缓冲区池的锁，不能再调用本模块的函数
+pub(crate) fn with_region_mut<T>(ptr: u32, len: u32, f: impl FnOnce(&mut [u8]) -> T) -> Option<T> {
+    with_arena(|arena| {
+        let (&start, &handle) = arena.by_ptr.range(..=ptr).next_back()?;
+        let slot = arena.slots.get_mut(&handle)?;
+        let offset = (ptr - start) as usize;
+        let end = offset.checked_add(len as usize)?;
+        slot.data.as_mut_slice().get_mut(offset..end).map(f)
+    })
+}
+
+// 分配一块零填充的缓冲区 (计入内存配额)，供 decode_into_ptr 等接口作为输出区域，
+// 用 release_decoded_buffer(handle) 释放
+#[wasm_bindgen(unchecked_return_type = "DecodeBufferRegion")]
+pub fn allocate_decode_buffer(len: u32) -> Result<JsValue, JsValue> {
+    let (handle, data_ptr, data_len) = register(buffer_pool::try_zeroed(len as usize)?)?;
+    Ok(PlainObject::new()
+        .set("handle", handle)
+        .set("data_ptr", data_ptr)
+        .set("data_len", data_len)
+        .build())
+}
+
+// 增加缓冲区引用计数，句柄无效时返回 false
+#[wasm_bindgen]
+pub fn retain_decoded_buffer(handle: u32) -> bool {
+    with_arena(|arena| match arena.slots.get_mut(&handle) {
+        Some(slot) => {
+            slot.refs += 1;
+            true
+        }
+        None => false,
+    })
+}
+
+// 释放一次引用，计数归零时回收缓冲区。句柄无效时返回 false
+#[wasm_bindgen]
+pub fn release_decoded_buffer(handle: u32) -> bool {
+    with_arena(|arena| arena.release(handle))
+}
+
+// 按 decode_fastdog_binary_zero_copy 返回的 data_ptr/data_len 释放缓冲区 (释放一次引用)。
+// 指针未登记或长度不符时返回 false
+#[wasm_bindgen]
+pub fn free_decoded_buffer(ptr: u32, len: u32) -> bool {
+    with_arena(|arena| {
+        let Some(&handle) = arena.by_ptr.get(&ptr) else {
+            return false;
+        };
+        if arena.slots.get(&handle).map(|slot| slot.data.len() as u32) != Some(len) {
+            return false;
+        }
+        arena.release(handle)
+    })
+}
+
+impl Arena {
+    fn release(&mut self, handle: u32) -> bool {
+        let Some(slot) = self.slots.get_mut(&handle) else {
+            return false;
+        };
+        slot.refs -= 1;
+        if slot.refs == 0 {
+            if let Some(slot) = self.slots.remove(&handle) {
+                if !slot.data.is_empty() {
+                    self.by_ptr.remove(&(slot.data.as_ptr() as u32));
+                }
+                if let Some(bytes) = slot.data.into_vec() {
+                    buffer_pool::recycle(bytes);
+                }
+            }
+        }
+        true
+    }
+}
+
+// 当前登记的缓冲区数量
+#[wasm_bindgen]
+pub fn decoded_buffer_count() -> u32 {
+    with_arena(|arena| arena.slots.len() as u32)
+}
+
+// 当前登记的缓冲区总字节数
+#[wasm_bindgen]
+pub fn decoded_buffer_bytes() -> u32 {
+    with_arena(|arena| arena.slots.values().map(|slot| slot.data.len() as u32).sum())
+}
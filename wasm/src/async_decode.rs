@@ -0,0 +1,92 @@
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::AbortSignal;
+
+use crate::error::DecodeError;
+use crate::{abort, clock, guard, quota};
+use crate::{
+    into_decode_value, locate_payload, open_slice_decoder, payload_len, verify_payload, DecodeResult, DecodeStats,
+};
+
+// 连续解压的时间预算 (毫秒)，超过后让出一次事件循环，保证页面在解码期间仍能响应输入和渲染
+const SLICE_BUDGET_MS: f64 = 8.0;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &js_sys::Function, timeout: i32) -> JsValue;
+}
+
+// 通过 setTimeout(0) 等待下一个宏任务 (窗口和 Worker 中均可用)
+pub(crate) async fn yield_to_event_loop() {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        set_timeout(&resolve, 0);
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+// 异步解码函数，返回 Promise，结果格式与 decode_fastdog_binary 相同
+//
+// 按分片解压，每连续解压约 SLICE_BUDGET_MS 毫秒让出一次事件循环，调用方可以直接 await，
+// 不会因为一次几百毫秒的同步解码卡住主线程。
+// signal 触发后在下一个分片边界中止，返回 Aborted 错误码并立即释放解码缓冲区。
+// max_decode_ms 与 DecoderOptions.max_decode_ms 含义相同，按全局时钟 (见 set_clock) 从调用开始计时，
+// 包括让出事件循环的时间，在分片边界检查，超时返回 Timeout 错误码
+#[wasm_bindgen(unchecked_return_type = "DecodeResult")]
+pub async fn decode_fastdog_binary_async(data: Vec<u8>, signal: Option<AbortSignal>, max_decode_ms: Option<f64>) -> JsValue {
+    let start_time = clock::now();
+    let compressed_size = data.len() as u64;
+    let max_decode_ms = max_decode_ms.filter(|ms| *ms > 0.0);
+    
+    match decode_async_internal(data, signal, start_time, max_decode_ms).await {
+        Ok(value) => value,
+        Err(error) => {
+            let error_result = DecodeResult {
+                success: false,
+                data: None,
+                error_code: Some(error.code),
+                error: Some(error.message),
+                stats: DecodeStats::failed(compressed_size, clock::now() - start_time),
+            };
+            guard::to_js(&error_result)
+        }
+    }
+}
+
+async fn decode_async_internal(
+    data: Vec<u8>,
+    signal: Option<AbortSignal>,
+    start_time: f64,
+    max_decode_ms: Option<f64>,
+) -> Result<JsValue, DecodeError> {
+    abort::check(signal.as_ref())?;
+    let (header, _, original_len) = locate_payload(&data)?;
+    quota::begin_decode();
+    let mut reservation = quota::reserve(payload_len(original_len)?)?;
+    
+    #[cfg(feature = "chaos")]
+    crate::chaos::maybe_fail_inflate()?;
+    
+    let mut decoder = open_slice_decoder(&header, Rc::new(data), original_len)?;
+    let mut slice_start = clock::now();
+    while !decoder.step()? {
+        reservation.grow_to(decoder.output_len())?;
+        let now = clock::now();
+        clock::check_deadline(start_time, now, max_decode_ms)?;
+        if now - slice_start >= SLICE_BUDGET_MS {
+            yield_to_event_loop().await;
+            slice_start = clock::now();
+        }
+        abort::check(signal.as_ref())?;
+    }
+    
+    #[allow(unused_mut)]
+    let mut decompressed = decoder.into_output();
+    
+    #[cfg(feature = "chaos")]
+    crate::chaos::maybe_corrupt(&mut decompressed);
+    
+    verify_payload(&header, original_len, &decompressed)?;
+    into_decode_value((header, original_len, decompressed), start_time)
+}
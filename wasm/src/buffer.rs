@@ -0,0 +1,75 @@
+use wasm_bindgen::prelude::*;
+
+use crate::guard;
+use crate::plain::PlainObject;
+use crate::{arena, clock, decode_container, DecodeStats};
+
+// 零拷贝解码结果句柄
+//
+// 取代 data_ptr/data_len 裸指针接口: 缓冲区由 WASM 侧持有，JS 通过 view() 获得视图，
+// 调用 free() (wasm-bindgen 自动生成) 后立即释放缓冲区，不依赖垃圾回收时机。
+#[wasm_bindgen]
+pub struct DecodedBuffer {
+    handle: u32,
+    stats: DecodeStats,
+}
+
+#[wasm_bindgen]
+impl DecodedBuffer {
+    // 返回 WASM 内存上的 Uint8Array 视图 (不复制)。
+    // WASM 内存增长后旧视图会失效，需要重新调用 view()
+    #[wasm_bindgen]
+    pub fn view(&self) -> Result<js_sys::Uint8Array, JsValue> {
+        let (ptr, len) = arena::lookup(self.handle).ok_or_else(|| JsValue::from_str("缓冲区已释放"))?;
+        // 安全性: 缓冲区在句柄释放前不会移动或释放，视图仅在下次内存增长前有效
+        Ok(unsafe { js_sys::Uint8Array::view(std::slice::from_raw_parts(ptr, len)) })
+    }
+
+    #[wasm_bindgen]
+    pub fn len(&self) -> u32 {
+        arena::lookup(self.handle).map_or(0, |(_, len)| len as u32)
+    }
+
+    #[wasm_bindgen]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn handle(&self) -> u32 {
+        self.handle
+    }
+
+    #[wasm_bindgen(getter, unchecked_return_type = "DecodeStats")]
+    pub fn stats(&self) -> JsValue {
+        guard::to_js(&self.stats)
+    }
+
+    // 复制数据到独立的 Uint8Array，返回可结构化克隆的普通对象 (可以 postMessage 到其他 Worker)
+    #[wasm_bindgen(js_name = toPlainObject, unchecked_return_type = "DecodedBufferSnapshot")]
+    pub fn to_plain_object(&self) -> JsValue {
+        let object = PlainObject::new().set("handle", self.handle).set("stats", self.stats());
+        match arena::lookup(self.handle) {
+            // 安全性: 缓冲区在句柄释放前不会移动或释放，此处立即复制
+            Some((ptr, len)) => object.set_bytes("data", unsafe { std::slice::from_raw_parts(ptr, len) }),
+            None => object.set("data", JsValue::NULL),
+        }
+        .build()
+    }
+}
+
+impl Drop for DecodedBuffer {
+    fn drop(&mut self) {
+        arena::release_decoded_buffer(self.handle);
+    }
+}
+
+// 零拷贝解码，返回 DecodedBuffer 句柄对象，解码失败时抛出异常
+#[wasm_bindgen]
+pub fn decode_fastdog_buffer(data: &[u8]) -> Result<DecodedBuffer, JsValue> {
+    let start_time = clock::now();
+    let (header, original_len, decompressed) = decode_container(data)?;
+    let stats = DecodeStats::from_header(&header, original_len, clock::now() - start_time);
+    let (handle, _, _) = arena::register(decompressed)?;
+    Ok(DecodedBuffer { handle, stats })
+}
@@ -0,0 +1,16 @@
+use wasm_bindgen::prelude::*;
+
+pub(crate) use fastdog_core::buffer_pool::*;
+
+// 设置池中空闲缓冲区的总容量上限和单个缓冲区的容量上限 (字节)，传入 undefined/null 恢复默认值
+// (16MB / 4MB)。收紧上限时立即释放超出的缓冲区
+#[wasm_bindgen]
+pub fn set_pool_limits(max_pooled_bytes: Option<u32>, max_buffer_bytes: Option<u32>) {
+    set_limits(max_pooled_bytes.map(|bytes| bytes as usize), max_buffer_bytes.map(|bytes| bytes as usize));
+}
+
+// 释放池中所有空闲缓冲区 (如切换场景后归还内存)
+#[wasm_bindgen]
+pub fn clear_pool() {
+    clear();
+}
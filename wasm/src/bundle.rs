@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::codec::Codec;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::guard;
+use crate::profiles::resolve_profile;
+use crate::{payload_len, quota};
+
+pub use fastdog_core::bundle::BundleEntry;
+pub(crate) use fastdog_core::bundle::BundleWriter;
+use fastdog_core::bundle::{Bundle, EntryChange, EntryData};
+
+// encode_bundle 的输入条目
+#[derive(Deserialize)]
+struct BundleInput {
+    name: String,
+    data: serde_bytes::ByteBuf,
+    #[serde(default)]
+    content_type: Option<String>,
+}
+
+// 解码后的条目: 清单信息和解压后的数据
+#[derive(Serialize)]
+struct DecodedEntry<'a> {
+    name: &'a str,
+    content_type: &'a str,
+    codec: Codec,
+    compressed_size: u64,
+    original_size: u64,
+    crc32: u32,
+    #[serde(with = "serde_bytes")]
+    data: Vec<u8>,
+}
+
+// 把多个载荷打包成一个文件 (如场景的几何体、纹理和配置 JSON)
+//
+// entries: [{ name, data: Uint8Array, content_type? }]，content_type 省略时为 "binary"。
+// 每个条目按 profile 指定的编码档案独立压缩，名称不能重复
+#[wasm_bindgen]
+pub fn encode_bundle(
+    #[wasm_bindgen(unchecked_param_type = "BundleInput[]")] entries: JsValue,
+    profile: Option<String>,
+) -> Result<Vec<u8>, JsValue> {
+    let entries: Vec<BundleInput> = serde_wasm_bindgen::from_value(entries)
+        .map_err(|e| JsValue::from_str(&format!("打包条目格式无效: {}", e)))?;
+    let profile = resolve_profile(profile.as_deref()).map_err(|e| JsValue::from_str(&e))?;
+
+    let entries: Vec<EntryData> = entries
+        .iter()
+        .map(|entry| EntryData {
+            name: &entry.name,
+            data: &entry.data,
+            content_type: entry.content_type.as_deref(),
+        })
+        .collect();
+    Ok(fastdog_core::bundle::encode(&entries, &profile)?)
+}
+
+// 解码整个打包文件，按清单顺序返回所有条目及解压后的数据，任一条目损坏时抛出异常
+#[wasm_bindgen(unchecked_return_type = "DecodedBundleEntry[]")]
+pub fn decode_bundle(data: &[u8]) -> Result<JsValue, JsValue> {
+    let bundle = Bundle::parse(data)?;
+    let mut decoded = Vec::with_capacity(bundle.entries.len());
+    for entry in &bundle.entries {
+        decoded.push(DecodedEntry {
+            name: &entry.name,
+            content_type: &entry.content_type,
+            codec: entry.codec,
+            compressed_size: entry.compressed_size,
+            original_size: entry.original_size,
+            crc32: entry.crc32,
+            data: decode_reserved(&bundle, entry)?,
+        });
+    }
+    Ok(guard::try_to_js(&decoded)?)
+}
+
+// 列出打包文件的条目 (名称、内容类型、编码、偏移、压缩/原始大小和 CRC32)。
+// 只解析清单，不读取条目数据 (只下载了文件开头的清单部分也可以列出)，适合文件选择界面和进度估算
+#[wasm_bindgen(unchecked_return_type = "BundleEntry[]")]
+pub fn list_entries(data: &[u8]) -> Result<JsValue, JsValue> {
+    let bundle = Bundle::parse(data)?;
+    Ok(guard::try_to_js(&bundle.entries)?)
+}
+
+// 只解压打包文件中的一个具名条目 (如只取 "lod0.glb")，其他条目的数据不会被读取或解压。
+// 条目不存在或数据损坏时抛出异常
+#[wasm_bindgen]
+pub fn decode_entry(data: &[u8], name: &str) -> Result<Vec<u8>, JsValue> {
+    let bundle = Bundle::parse(data)?;
+    let entry = bundle
+        .entries
+        .iter()
+        .find(|entry| entry.name == name)
+        .ok_or_else(|| DecodeError::new(DecodeErrorCode::InvalidInput, format!("条目不存在: {}", name)))?;
+    decode_reserved(&bundle, entry)
+}
+
+// 与容器解码相同: 按清单声明的大小登记内存配额，输出超出时继续按实际大小登记
+fn decode_reserved(bundle: &Bundle, entry: &BundleEntry) -> Result<Vec<u8>, JsValue> {
+    let mut reservation = quota::reserve(payload_len(entry.original_size)?)?;
+    Ok(bundle.decode_entry(entry, |len| reservation.grow_to(len))?)
+}
+
+// update_archive 的修改条目: data 为 null 表示删除该条目
+#[derive(Deserialize)]
+struct ChangedEntry {
+    name: String,
+    #[serde(default)]
+    data: Option<serde_bytes::ByteBuf>,
+    #[serde(default)]
+    content_type: Option<String>,
+}
+
+// 增量更新打包文件
+//
+// changed_entries: [{ name, data: Uint8Array | null, content_type? }]
+// 未修改的条目直接复用原有压缩数据，只重新压缩修改过的条目；新名称的条目追加到末尾。
+#[wasm_bindgen]
+pub fn update_archive(
+    original: &[u8],
+    #[wasm_bindgen(unchecked_param_type = "ArchiveChange[]")] changed_entries: JsValue,
+    profile: Option<String>,
+) -> Result<Vec<u8>, JsValue> {
+    let changes: Vec<ChangedEntry> = serde_wasm_bindgen::from_value(changed_entries)
+        .map_err(|e| JsValue::from_str(&format!("修改条目格式无效: {}", e)))?;
+    let profile = resolve_profile(profile.as_deref()).map_err(|e| JsValue::from_str(&e))?;
+
+    let changes: Vec<EntryChange> = changes
+        .iter()
+        .map(|change| EntryChange {
+            name: &change.name,
+            data: change.data.as_deref().map(Vec::as_slice),
+            content_type: change.content_type.as_deref(),
+        })
+        .collect();
+    Ok(fastdog_core::bundle::update(original, &changes, &profile)?)
+}
@@ -0,0 +1,71 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::codec::Codec;
+use crate::format::{FLAG_NAMES, SUPPORTED_FLAGS};
+use crate::payload_kind::PayloadKind;
+use crate::{guard, meshopt, password, quota, structured};
+
+// 能力检测
+//
+// 应用据此与服务端协商请求的容器版本、编码和载荷类型 (如构建不含 brotli 时改要 zlib 资源)。
+// threads / simd 反映的是本次构建是否启用，threads 还需要宿主以跨源隔离方式启动线程池才能实际使用
+
+// 2^53 - 1: 超出后 JS 数字不能精确表示长度
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+#[derive(Serialize)]
+struct Capabilities {
+    library_version: &'static str,
+    container_versions: &'static [u32],
+    codecs: Vec<&'static str>,
+    payload_kinds: Vec<&'static str>,
+    // 解码器识别的版本 3 标志位名称
+    flags: Vec<&'static str>,
+    // GLB 访问器接口在模块内解码的网格压缩扩展。不含 KHR_draco_mesh_compression (没有 Draco 解码器)
+    mesh_codecs: &'static [&'static str],
+    threads: bool,
+    simd: bool,
+    // 错误注入模式，生产构建应为 false
+    chaos: bool,
+    limits: Limits,
+}
+
+#[derive(Serialize)]
+struct Limits {
+    // 单个载荷解压后的最大字节数 (受平台寻址范围限制，wasm32 上为 4 GiB - 1)
+    max_payload_size: f64,
+    // 当前内存配额 (字节)，未设置时为 null
+    memory_quota: Option<u32>,
+    // MessagePack / CBOR / Protobuf 载荷的最大嵌套层数
+    max_nesting_depth: usize,
+    // 口令保护容器接受的最大 PBKDF2 迭代次数
+    max_kdf_iterations: u32,
+}
+
+// 返回本构建支持的容器版本、编码、载荷类型、构建特性和解码限制
+#[wasm_bindgen(unchecked_return_type = "Capabilities")]
+pub fn get_capabilities() -> JsValue {
+    let capabilities = Capabilities {
+        library_version: env!("CARGO_PKG_VERSION"),
+        container_versions: &[1, 2, 3],
+        codecs: Codec::ALL.iter().map(|codec| codec.name()).collect(),
+        payload_kinds: PayloadKind::ALL.iter().map(|kind| kind.name()).collect(),
+        flags: FLAG_NAMES
+            .iter()
+            .filter(|(flag, _)| SUPPORTED_FLAGS & flag != 0)
+            .map(|&(_, name)| name)
+            .collect(),
+        mesh_codecs: &[meshopt::EXT_MESHOPT_COMPRESSION],
+        threads: cfg!(feature = "threads"),
+        simd: cfg!(target_feature = "simd128"),
+        chaos: cfg!(feature = "chaos"),
+        limits: Limits {
+            max_payload_size: (usize::MAX as u64).min(MAX_SAFE_INTEGER) as f64,
+            memory_quota: quota::get_memory_quota(),
+            max_nesting_depth: structured::MAX_DEPTH,
+            max_kdf_iterations: password::MAX_ITERATIONS,
+        },
+    };
+    guard::to_js(&capabilities)
+}
@@ -0,0 +1,96 @@
+// 错误注入 (混沌测试) 模式
+//
+// 仅在启用 `chaos` 功能的调试/QA 构建中编译。开启后按给定概率模拟解压失败
+// 或破坏解压缓冲区，用于演练应用层的错误处理与重试逻辑，无需手工构造损坏的样本文件。
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::logging;
+
+struct ChaosState {
+    corrupt_rate: f64,
+    inflate_failure_rate: f64,
+    rng: u64,
+}
+
+thread_local! {
+    static STATE: RefCell<ChaosState> = const { RefCell::new(ChaosState {
+        corrupt_rate: 0.0,
+        inflate_failure_rate: 0.0,
+        rng: 0,
+    }) };
+}
+
+impl ChaosState {
+    // xorshift64*: 固定种子即可复现同一组注入结果
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.rng;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.rng = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn roll(&mut self, rate: f64) -> bool {
+        if rate <= 0.0 {
+            return false;
+        }
+        let sample = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        sample < rate
+    }
+}
+
+// 开启混沌模式: corrupt_rate 为破坏解压缓冲区的概率, inflate_failure_rate 为模拟解压失败的概率
+#[wasm_bindgen]
+pub fn set_chaos_mode(corrupt_rate: f64, inflate_failure_rate: f64, seed: u32) {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.corrupt_rate = corrupt_rate.clamp(0.0, 1.0);
+        state.inflate_failure_rate = inflate_failure_rate.clamp(0.0, 1.0);
+        // 种子不能为 0, 否则 xorshift 会一直输出 0
+        state.rng = (seed as u64) << 32 | 0x9E37_79B9;
+    });
+    logging::warn(&format!(
+        "🐒 混沌模式已开启: 缓冲区破坏概率 {}, 解压失败概率 {}",
+        corrupt_rate, inflate_failure_rate
+    ));
+}
+
+// 关闭混沌模式
+#[wasm_bindgen]
+pub fn disable_chaos_mode() {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.corrupt_rate = 0.0;
+        state.inflate_failure_rate = 0.0;
+    });
+}
+
+// 按概率模拟解压失败
+pub(crate) fn maybe_fail_inflate() -> Result<(), DecodeError> {
+    let fail = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let rate = state.inflate_failure_rate;
+        state.roll(rate)
+    });
+    if fail {
+        return Err(DecodeError::new(DecodeErrorCode::InflateError, "解压缩失败: 混沌模式注入的模拟错误"));
+    }
+    Ok(())
+}
+
+// 按概率翻转缓冲区中的一个随机字节
+pub(crate) fn maybe_corrupt(buffer: &mut [u8]) {
+    if buffer.is_empty() {
+        return;
+    }
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let rate = state.corrupt_rate;
+        if state.roll(rate) {
+            let index = (state.next_u64() % buffer.len() as u64) as usize;
+            buffer[index] ^= 0xFF;
+        }
+    });
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+use crate::clock;
+use crate::codec::SliceDecoder;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::guard;
+use crate::plain::PlainObject;
+use crate::quota::{self, Reservation};
+use crate::{
+    into_decode_result, locate_payload, open_slice_decoder, payload_len, verify_payload, ContainerHeader, DecodeStats,
+};
+
+// 协作式分块解码器
+//
+// 每次调用 step() 只解压一个固定大小的分块并返回进度，调用方在分块之间自行让出事件循环
+// (requestAnimationFrame / setTimeout / scheduler.yield)，低端设备上也不会造成主线程卡顿。
+#[wasm_bindgen]
+pub struct ChunkedDecoder {
+    header: ContainerHeader,
+    original_len: u64,
+    decoder: Option<SliceDecoder>,
+    // 解压输出占用的内存配额
+    reservation: Reservation,
+    steps: u32,
+    start_time: f64,
+    max_decode_ms: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ChunkedStepResult {
+    pub success: bool,
+    pub data: Option<String>,
+    pub error: Option<String>,
+    pub error_code: Option<DecodeErrorCode>,
+    pub progress: f32,
+    pub is_complete: bool,
+    pub steps: u32,
+    pub decoded_bytes: u64,
+    pub expected_bytes: u64,
+    pub stats: Option<DecodeStats>,
+}
+
+#[wasm_bindgen]
+impl ChunkedDecoder {
+    // chunk_size: 每次 step() 解压的最大字节数，省略时为 64KB。
+    // max_decode_ms: 与 DecoderOptions.max_decode_ms 含义相同，按全局时钟 (见 set_clock) 从创建解码器开始计时，
+    // 包括两次 step() 之间的时间，每次 step() 解压后检查，超时返回 Timeout 错误码并结束解码
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: Vec<u8>, chunk_size: Option<u32>, max_decode_ms: Option<f64>) -> Result<ChunkedDecoder, JsValue> {
+        let start_time = clock::now();
+        let (header, _, original_len) = locate_payload(&data)?;
+        quota::begin_decode();
+        let reservation = quota::reserve(payload_len(original_len)?)?;
+        
+        #[cfg(feature = "chaos")]
+        crate::chaos::maybe_fail_inflate()?;
+        
+        let mut decoder = open_slice_decoder(&header, Rc::new(data), original_len)?;
+        decoder.set_slice_size(chunk_size.unwrap_or(0));
+        
+        Ok(ChunkedDecoder {
+            header,
+            original_len,
+            decoder: Some(decoder),
+            reservation,
+            steps: 0,
+            start_time,
+            max_decode_ms: max_decode_ms.filter(|ms| *ms > 0.0),
+        })
+    }
+    
+    // 解压下一个分块。完成时结果中带有解码数据和统计信息，出错或完成后解码器不再可用
+    #[wasm_bindgen(unchecked_return_type = "ChunkedStepResult")]
+    pub fn step(&mut self) -> JsValue {
+        let result = match self.decoder.as_mut() {
+            None => {
+                let error = DecodeError::new(DecodeErrorCode::InvalidState, "解码已结束");
+                self.step_result(false, None, Some(error), None)
+            }
+            Some(decoder) => {
+                self.steps += 1;
+                let stepped = decoder
+                    .step()
+                    .and_then(|done| self.reservation.grow_to(decoder.output_len()).map(|_| done))
+                    .and_then(|done| clock::check_deadline(self.start_time, clock::now(), self.max_decode_ms).map(|_| done));
+                match stepped {
+                    Ok(false) => self.step_result(true, None, None, None),
+                    Ok(true) => self.finish(),
+                    Err(error) => {
+                        self.decoder = None;
+                        self.step_result(false, None, Some(error), None)
+                    }
+                }
+            }
+        };
+        guard::to_js(&result)
+    }
+    
+    #[wasm_bindgen]
+    pub fn get_progress(&self) -> f32 {
+        match &self.decoder {
+            Some(decoder) if self.original_len > 0 => {
+                (decoder.output_len() as f32 / self.original_len as f32).min(1.0)
+            }
+            Some(_) => 0.0,
+            None => 1.0,
+        }
+    }
+    
+    #[wasm_bindgen]
+    pub fn is_complete(&self) -> bool {
+        self.decoder.is_none()
+    }
+    
+    // 当前进度的普通对象快照 (可结构化克隆)
+    #[wasm_bindgen(js_name = toPlainObject, unchecked_return_type = "ChunkedDecoderSnapshot")]
+    pub fn to_plain_object(&self) -> JsValue {
+        PlainObject::new()
+            .set("progress", self.get_progress())
+            .set("is_complete", self.is_complete())
+            .set("steps", self.steps)
+            .set_opt("decoded_bytes", self.decoder.as_ref().map(|decoder| decoder.output_len() as f64))
+            .set("expected_bytes", self.original_len as f64)
+            .set("version", self.header.version)
+            .set("codec", self.header.codec.name())
+            .build()
+    }
+}
+
+impl ChunkedDecoder {
+    fn finish(&mut self) -> ChunkedStepResult {
+        // decoder 在调用前已确认存在
+        #[allow(unused_mut)]
+        let mut decompressed = self.decoder.take().unwrap().into_output();
+        
+        #[cfg(feature = "chaos")]
+        crate::chaos::maybe_corrupt(&mut decompressed);
+        
+        self.reservation = Reservation::empty();
+        let result = verify_payload(&self.header, self.original_len, &decompressed).and_then(|_| {
+            into_decode_result((self.header.clone(), self.original_len, decompressed), self.start_time)
+        });
+        match result {
+            Ok(decode_result) => self.step_result(true, decode_result.data, None, Some(decode_result.stats)),
+            Err(error) => self.step_result(false, None, Some(error), None),
+        }
+    }
+    
+    fn step_result(
+        &self,
+        success: bool,
+        data: Option<String>,
+        error: Option<DecodeError>,
+        stats: Option<DecodeStats>,
+    ) -> ChunkedStepResult {
+        let decoded_bytes = match (&self.decoder, &stats) {
+            (Some(decoder), _) => decoder.output_len() as u64,
+            (None, Some(stats)) => stats.original_size,
+            (None, None) => 0,
+        };
+        ChunkedStepResult {
+            success,
+            data,
+            error_code: error.as_ref().map(|error| error.code),
+            error: error.map(|error| error.message),
+            progress: if stats.is_some() { 1.0 } else { self.get_progress() },
+            is_complete: stats.is_some(),
+            steps: self.steps,
+            decoded_bytes,
+            expected_bytes: self.original_len,
+            stats,
+        }
+    }
+}
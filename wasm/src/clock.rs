@@ -0,0 +1,58 @@
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::error::{DecodeError, DecodeErrorCode};
+
+// 计时时钟
+//
+// 默认使用 performance.now() (亚毫秒精度、单调递增，不受系统时间调整影响)，
+// 运行环境没有 performance 时退回 Date.now()。测试中可以用 set_clock 注入模拟时钟，
+// 使 decode_time_ms 等统计结果可重现。
+
+thread_local! {
+    // 第一次计时时查找 globalThis.performance.now，窗口、Worker 和 Node 中都可用
+    static PERFORMANCE_NOW: Option<(JsValue, js_sys::Function)> = {
+        let performance = js_sys::Reflect::get(&js_sys::global(), &"performance".into()).ok()?;
+        let now = js_sys::Reflect::get(&performance, &"now".into()).ok()?;
+        now.dyn_into::<js_sys::Function>().ok().map(|now| (performance, now))
+    };
+    static MOCK_CLOCK: RefCell<Option<js_sys::Function>> = const { RefCell::new(None) };
+}
+
+// 当前时间 (毫秒)，只用于计算时间差
+pub(crate) fn now() -> f64 {
+    if let Some(time) = MOCK_CLOCK.with(|clock| call_clock(clock.borrow().as_ref())) {
+        return time;
+    }
+    PERFORMANCE_NOW
+        .with(|performance| {
+            let (this, now) = performance.as_ref()?;
+            now.call0(this).ok()?.as_f64()
+        })
+        .unwrap_or_else(js_sys::Date::now)
+}
+
+// max_decode_ms 检查: 从 start_time 起已超过 limit 毫秒时返回 Timeout。
+// 各解码接口在解压分片边界调用 (同步、异步和分块解码)
+pub(crate) fn check_deadline(start_time: f64, now: f64, limit: Option<f64>) -> Result<(), DecodeError> {
+    match limit {
+        Some(limit) if now - start_time > limit => Err(DecodeError::new(
+            DecodeErrorCode::Timeout,
+            format!("解码超时: 超过 {} ms 限制", limit),
+        )),
+        _ => Ok(()),
+    }
+}
+
+// 调用 JS 时钟函数，抛出异常或没有返回数字时返回 None
+pub(crate) fn call_clock(clock: Option<&js_sys::Function>) -> Option<f64> {
+    clock?.call0(&JsValue::NULL).ok()?.as_f64()
+}
+
+// 替换全局计时时钟: 传入返回毫秒数的函数 (如测试中的模拟时钟)，传入 undefined/null 恢复 performance.now()。
+// 对所有解码接口的耗时统计、基准测试和 max_decode_ms 检查生效；DecoderOptions.set_clock 优先于此设置
+#[wasm_bindgen]
+pub fn set_clock(clock: Option<js_sys::Function>) {
+    MOCK_CLOCK.with(|current| *current.borrow_mut() = clock);
+}
@@ -0,0 +1,61 @@
+use wasm_bindgen::prelude::*;
+
+use crate::codec;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::{arena, dictionary, locate_payload, payload_len, quota, verify_payload};
+
+// 解压到调用方提供的缓冲区
+//
+// 自行管理内存区域的引擎 (如按帧复用的资源缓冲区) 可以把解压结果直接写入已有的缓冲区，
+// 省去每次解码分配 Vec 再复制出去的开销。缓冲区至少要有头部声明的原始长度，
+// 只写入开头的原始长度字节，返回写入的字节数; 失败时缓冲区内容未定义。
+// 输出内存由调用方持有，不计入内存配额
+
+// 解压到 JS 传入的 Uint8Array (wasm-bindgen 在返回时把结果复制回原数组)
+#[wasm_bindgen]
+pub fn decode_into(data: &[u8], out: &mut [u8]) -> Result<u32, JsValue> {
+    Ok(decode_container_into(data, out)? as u32)
+}
+
+// 解压到 WASM 内存中的 [out_ptr, out_ptr + out_len) 区域，不经过任何复制。
+// 区域必须完整落在 allocate_decode_buffer 分配 (或解码接口登记) 的某个缓冲区内
+#[wasm_bindgen]
+pub fn decode_into_ptr(data: &[u8], out_ptr: u32, out_len: u32) -> Result<u32, JsValue> {
+    let written = arena::with_region_mut(out_ptr, out_len, |out| decode_container_into(data, out)).ok_or_else(|| {
+        DecodeError::new(
+            DecodeErrorCode::InvalidInput,
+            format!("输出区域 0x{:x} (+{} 字节) 不在已登记的缓冲区内", out_ptr, out_len),
+        )
+    })??;
+    Ok(written as u32)
+}
+
+fn decode_container_into(data: &[u8], out: &mut [u8]) -> Result<usize, DecodeError> {
+    let (header, compressed, original_len) = locate_payload(data)?;
+    let len = payload_len(original_len)?;
+    if out.len() < len {
+        return Err(DecodeError::new(
+            DecodeErrorCode::OutputLimitExceeded,
+            format!("输出缓冲区过小: 需要 {} 字节，只有 {} 字节", len, out.len()),
+        ));
+    }
+    let out = &mut out[..len];
+    quota::begin_decode();
+
+    #[cfg(feature = "chaos")]
+    crate::chaos::maybe_fail_inflate()?;
+
+    let written = match header.dict_id {
+        Some(id) => {
+            let dictionary = dictionary::lookup(id)?;
+            codec::decompress_to_slice_with_dictionary(header.codec, compressed, out, &dictionary)?
+        }
+        None => codec::decompress_to_slice(header.codec, compressed, out)?,
+    };
+
+    #[cfg(feature = "chaos")]
+    crate::chaos::maybe_corrupt(&mut out[..written]);
+
+    verify_payload(&header, original_len, &out[..written])?;
+    Ok(written)
+}
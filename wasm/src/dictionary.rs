@@ -0,0 +1,16 @@
+use wasm_bindgen::prelude::*;
+
+pub(crate) use fastdog_core::dictionary::*;
+
+// 注册压缩字典，返回字典 ID (编码时传给 encode_fastdog_with_dictionary)。
+// 可以同时注册多个字典，解码时按容器头部的字典 ID 选择
+#[wasm_bindgen]
+pub fn set_dictionary(dictionary: Vec<u8>) -> Result<u32, JsValue> {
+    register(dictionary).map_err(|e| JsValue::from_str(&e))
+}
+
+// 注销压缩字典，返回该字典是否已注册
+#[wasm_bindgen]
+pub fn remove_dictionary(id: u32) -> bool {
+    remove(id)
+}
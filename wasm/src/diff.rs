@@ -0,0 +1,295 @@
+use serde::Serialize;
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+use crate::inspect::{inspect_container, Inspection};
+use crate::metadata::Metadata;
+use crate::payload_kind::PayloadKind;
+use crate::{decode_container, guard};
+
+// JSON 差异结果，路径使用 JSON Pointer (RFC 6901)
+#[derive(Serialize, Default)]
+pub struct JsonDiffResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub added: Vec<JsonDiffEntry>,
+    pub removed: Vec<JsonDiffEntry>,
+    pub changed: Vec<JsonChange>,
+}
+
+#[derive(Serialize)]
+pub struct JsonDiffEntry {
+    pub path: String,
+    pub value: Value,
+}
+
+#[derive(Serialize)]
+pub struct JsonChange {
+    pub path: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+// 比较两个 JSON 载荷 (版本 1) 文件，返回结构化差异 (新增/删除/修改的路径)
+#[wasm_bindgen(unchecked_return_type = "JsonDiffResult")]
+pub fn diff_json(a: &[u8], b: &[u8]) -> JsValue {
+    let result = match (decode_json_value(a), decode_json_value(b)) {
+        (Ok(left), Ok(right)) => {
+            let mut result = JsonDiffResult {
+                success: true,
+                ..Default::default()
+            };
+            diff_values("", &left, &right, &mut result);
+            result
+        }
+        (Err(e), _) => JsonDiffResult {
+            error: Some(format!("文件 A: {}", e)),
+            ..Default::default()
+        },
+        (_, Err(e)) => JsonDiffResult {
+            error: Some(format!("文件 B: {}", e)),
+            ..Default::default()
+        },
+    };
+
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    guard::to_js_with(&result, &serializer)
+}
+
+// 容器差异结果: 头部字段、元数据和解压后载荷的差异
+#[derive(Serialize, Default)]
+pub struct ContainerDiffResult {
+    pub success: bool,
+    pub error: Option<String>,
+    // 两个容器逐字节相同
+    pub identical: bool,
+    // 解压后的载荷相同 (头部、编码或压缩参数可能不同)
+    pub payload_identical: bool,
+    pub header: Vec<FieldChange>,
+    pub metadata: Vec<FieldChange>,
+    pub payload: Option<PayloadDiff>,
+}
+
+// 一个头部字段或元数据键的差异，from 为 A 中的值，to 为 B 中的值 (不存在时为 null)
+#[derive(Serialize)]
+pub struct FieldChange {
+    pub name: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PayloadDiff {
+    // "json": 两个载荷都是 JSON，按 JSON Pointer 路径比较; "binary": 按字节区间比较
+    pub mode: &'static str,
+    pub size_a: f64,
+    pub size_b: f64,
+    pub added: Vec<JsonDiffEntry>,
+    pub removed: Vec<JsonDiffEntry>,
+    pub changed: Vec<JsonChange>,
+    // 内容不同的字节区间 (偏移相对载荷起始)，长度不同时最后一段为较长载荷多出的部分
+    pub ranges: Vec<ByteRange>,
+    pub differing_bytes: f64,
+    // 区间数超过 MAX_BYTE_RANGES 时只列出前面的区间
+    pub ranges_truncated: bool,
+}
+
+#[derive(Serialize)]
+pub struct ByteRange {
+    pub offset: f64,
+    pub length: f64,
+}
+
+const MAX_BYTE_RANGES: usize = 1000;
+
+// 比较两个容器 (任意版本)，返回头部字段、元数据和解压后载荷的差异，
+// 用于排查 "同一个场景，不同的字节" 这类流水线问题。JSON 载荷按路径比较，其他载荷按字节区间比较
+#[wasm_bindgen(unchecked_return_type = "ContainerDiffResult")]
+pub fn diff_containers(a: &[u8], b: &[u8]) -> JsValue {
+    let result = match (decode_container(a), decode_container(b)) {
+        (Ok(left), Ok(right)) => {
+            let header = diff_header_fields(a, b);
+            let metadata = diff_metadata(&left.0.metadata, &right.0.metadata);
+            let payload = diff_payloads((left.0.kind, &left.2), (right.0.kind, &right.2));
+            ContainerDiffResult {
+                success: true,
+                identical: a == b,
+                payload_identical: left.2 == right.2,
+                header,
+                metadata,
+                payload: Some(payload),
+                ..Default::default()
+            }
+        }
+        (Err(e), _) => ContainerDiffResult {
+            error: Some(format!("文件 A: {}", e)),
+            ..Default::default()
+        },
+        (_, Err(e)) => ContainerDiffResult {
+            error: Some(format!("文件 B: {}", e)),
+            ..Default::default()
+        },
+    };
+
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    guard::to_js_with(&result, &serializer)
+}
+
+// 按 inspect 列出的头部字段 (含原始长度) 逐个比较
+fn diff_header_fields(a: &[u8], b: &[u8]) -> Vec<FieldChange> {
+    let (left, right) = (inspect_container(a), inspect_container(b));
+    let fields = |inspection: &Inspection| -> Metadata {
+        let mut fields: Metadata = inspection
+            .header_fields()
+            .map(|(name, value)| (name.to_string(), value.unwrap_or_default().to_string()))
+            .collect();
+        if let Some(original_size) = inspection.original_size() {
+            fields.insert("original_len".to_string(), original_size.to_string());
+        }
+        fields
+    };
+    diff_metadata(&fields(&left), &fields(&right))
+}
+
+fn diff_metadata(left: &Metadata, right: &Metadata) -> Vec<FieldChange> {
+    let mut names: Vec<&String> = left.keys().chain(right.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter(|name| left.get(*name) != right.get(*name))
+        .map(|name| FieldChange {
+            name: name.clone(),
+            from: left.get(name).cloned(),
+            to: right.get(name).cloned(),
+        })
+        .collect()
+}
+
+fn diff_payloads(left: (PayloadKind, &[u8]), right: (PayloadKind, &[u8])) -> PayloadDiff {
+    let mut diff = PayloadDiff {
+        mode: "binary",
+        size_a: left.1.len() as f64,
+        size_b: right.1.len() as f64,
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+        ranges: Vec::new(),
+        differing_bytes: 0.0,
+        ranges_truncated: false,
+    };
+    // 两边都是可解析的 JSON 时按路径比较，否则回退到字节比较
+    if left.0 == PayloadKind::Json && right.0 == PayloadKind::Json {
+        if let (Ok(left), Ok(right)) = (serde_json::from_slice(left.1), serde_json::from_slice(right.1)) {
+            let mut result = JsonDiffResult::default();
+            diff_values("", &left, &right, &mut result);
+            diff.mode = "json";
+            diff.added = result.added;
+            diff.removed = result.removed;
+            diff.changed = result.changed;
+            return diff;
+        }
+    }
+    diff_bytes(left.1, right.1, &mut diff);
+    diff
+}
+
+// 合并连续的不同字节为区间
+fn diff_bytes(left: &[u8], right: &[u8], diff: &mut PayloadDiff) {
+    let common = left.len().min(right.len());
+    let mut differing = left.len().max(right.len()) - common;
+    let push = |diff: &mut PayloadDiff, offset: usize, length: usize| {
+        if diff.ranges.len() == MAX_BYTE_RANGES {
+            diff.ranges_truncated = true;
+        } else {
+            diff.ranges.push(ByteRange {
+                offset: offset as f64,
+                length: length as f64,
+            });
+        }
+    };
+    let mut start = None;
+    for (offset, (l, r)) in left.iter().zip(right).enumerate() {
+        match (l != r, start) {
+            (true, None) => start = Some(offset),
+            (false, Some(begin)) => {
+                push(diff, begin, offset - begin);
+                start = None;
+            }
+            _ => {}
+        }
+        differing += (l != r) as usize;
+    }
+    // 较长载荷多出的部分与末尾的不同字节合并为一段
+    let tail_start = start.unwrap_or(common);
+    if tail_start < left.len().max(right.len()) {
+        push(diff, tail_start, left.len().max(right.len()) - tail_start);
+    }
+    diff.differing_bytes = differing as f64;
+}
+
+fn decode_json_value(data: &[u8]) -> Result<Value, String> {
+    let (header, _, decompressed) = decode_container(data)?;
+    if header.kind != PayloadKind::Json {
+        return Err(format!("载荷不是 JSON 格式 (内容类型 {})", header.kind.name()));
+    }
+    serde_json::from_slice(&decompressed).map_err(|e| format!("JSON 解析失败: {}", e))
+}
+
+fn diff_values(path: &str, left: &Value, right: &Value, result: &mut JsonDiffResult) {
+    match (left, right) {
+        (Value::Object(left_map), Value::Object(right_map)) => {
+            for (key, left_value) in left_map {
+                let child = format!("{}/{}", path, escape_pointer(key));
+                match right_map.get(key) {
+                    Some(right_value) => diff_values(&child, left_value, right_value, result),
+                    None => result.removed.push(JsonDiffEntry {
+                        path: child,
+                        value: left_value.clone(),
+                    }),
+                }
+            }
+            for (key, right_value) in right_map {
+                if !left_map.contains_key(key) {
+                    result.added.push(JsonDiffEntry {
+                        path: format!("{}/{}", path, escape_pointer(key)),
+                        value: right_value.clone(),
+                    });
+                }
+            }
+        }
+        (Value::Array(left_items), Value::Array(right_items)) => {
+            // 数组按下标逐项比较
+            for (index, left_value) in left_items.iter().enumerate() {
+                let child = format!("{}/{}", path, index);
+                match right_items.get(index) {
+                    Some(right_value) => diff_values(&child, left_value, right_value, result),
+                    None => result.removed.push(JsonDiffEntry {
+                        path: child,
+                        value: left_value.clone(),
+                    }),
+                }
+            }
+            for (index, right_value) in right_items.iter().enumerate().skip(left_items.len()) {
+                result.added.push(JsonDiffEntry {
+                    path: format!("{}/{}", path, index),
+                    value: right_value.clone(),
+                });
+            }
+        }
+        _ => {
+            if left != right {
+                result.changed.push(JsonChange {
+                    path: path.to_string(),
+                    from: left.clone(),
+                    to: right.clone(),
+                });
+            }
+        }
+    }
+}
+
+// JSON Pointer 转义: "~" -> "~0", "/" -> "~1"
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
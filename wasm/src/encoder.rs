@@ -0,0 +1,93 @@
+use wasm_bindgen::prelude::*;
+
+use crate::codec::Codec;
+use crate::payload_kind::PayloadKind;
+use crate::profiles::resolve_profile;
+use crate::transcode::target_profile;
+use crate::{encryption, metadata};
+
+pub(crate) use fastdog_core::encoder::*;
+
+// 编码函数: content_type 为载荷类型名称 ("json"、"glb"、"cbor"、"image"、"pointcloud"、"msgpack"、"protobuf" 或 "custom")，profile 省略时使用默认档案
+#[wasm_bindgen]
+pub fn encode_fastdog(payload: &[u8], content_type: &str, profile: Option<String>) -> Result<Vec<u8>, JsValue> {
+    let kind = PayloadKind::from_name(content_type).map_err(|e| JsValue::from_str(&e))?;
+    let profile = resolve_profile(profile.as_deref()).map_err(|e| JsValue::from_str(&e))?;
+    encode_container(payload, kind, &profile).map_err(|e| JsValue::from_str(&e))
+}
+
+// 编码为可随机访问的容器: 载荷按 block_size 字节切块独立压缩并写入块偏移表，
+// decode_range 只解压覆盖请求区间的块。块越小随机访问越快，压缩率越低；brotli 档案不支持
+#[wasm_bindgen]
+pub fn encode_fastdog_seekable(
+    payload: &[u8],
+    content_type: &str,
+    block_size: u32,
+    profile: Option<String>,
+) -> Result<Vec<u8>, JsValue> {
+    let kind = PayloadKind::from_name(content_type).map_err(|e| JsValue::from_str(&e))?;
+    let profile = resolve_profile(profile.as_deref()).map_err(|e| JsValue::from_str(&e))?;
+    encode_seekable_container(payload, kind, block_size, &profile).map_err(|e| JsValue::from_str(&e))
+}
+
+// 使用 set_dictionary 注册的字典编码，适合大量结构相似的小载荷 (如 JSON 瓦片)。
+// profile 省略时使用默认档案改为 zstd 编码，解码端需要注册同一份字典
+#[wasm_bindgen]
+pub fn encode_fastdog_with_dictionary(
+    payload: &[u8],
+    content_type: &str,
+    dict_id: u32,
+    profile: Option<String>,
+) -> Result<Vec<u8>, JsValue> {
+    let kind = PayloadKind::from_name(content_type).map_err(|e| JsValue::from_str(&e))?;
+    let profile = match profile.as_deref() {
+        Some(name) => resolve_profile(Some(name)),
+        None => target_profile(None, Some(Codec::Zstd)),
+    }
+    .map_err(|e| JsValue::from_str(&e))?;
+    encode_dictionary_container(payload, kind, dict_id, &profile).map_err(|e| JsValue::from_str(&e))
+}
+
+// 编码并附加键值元数据 (场景名称、作者、坐标系、应用版本等)，值必须是字符串。
+// 元数据写在头部，get_metadata 无需解压载荷即可读取
+#[wasm_bindgen]
+pub fn encode_fastdog_with_metadata(
+    payload: &[u8],
+    content_type: &str,
+    #[wasm_bindgen(unchecked_param_type = "Record<string, string>")] metadata: JsValue,
+    profile: Option<String>,
+) -> Result<Vec<u8>, JsValue> {
+    let kind = PayloadKind::from_name(content_type).map_err(|e| JsValue::from_str(&e))?;
+    let metadata = metadata::from_js(metadata).map_err(|e| JsValue::from_str(&e))?;
+    let profile = resolve_profile(profile.as_deref()).map_err(|e| JsValue::from_str(&e))?;
+    encode_metadata_container(payload, kind, &metadata, &profile).map_err(|e| JsValue::from_str(&e))
+}
+
+// 编码并用 32 字节密钥做 AES-256-GCM 加密 (先压缩后加密)，解码端使用 decode_encrypted 和同一密钥。
+// 头部和元数据不加密但受认证保护。确定性档案的 nonce 由密钥和内容派生，其余档案使用随机 nonce
+#[wasm_bindgen]
+pub fn encode_fastdog_encrypted(
+    payload: &[u8],
+    content_type: &str,
+    key: &[u8],
+    profile: Option<String>,
+) -> Result<Vec<u8>, JsValue> {
+    let kind = PayloadKind::from_name(content_type).map_err(|e| JsValue::from_str(&e))?;
+    let key = encryption::parse_key(key).map_err(|e| JsValue::from_str(&e.message))?;
+    let profile = resolve_profile(profile.as_deref()).map_err(|e| JsValue::from_str(&e))?;
+    encode_encrypted_container(payload, kind, &key, &profile).map_err(|e| JsValue::from_str(&e))
+}
+
+// 编码并用口令保护: 按 PBKDF2-HMAC-SHA256 由口令派生密钥后做 AES-256-GCM 加密，
+// 解码端使用 decode_with_password 和同一口令。口令按 UTF-8 字节派生，不做 Unicode 规范化
+#[wasm_bindgen]
+pub fn encode_fastdog_with_password(
+    payload: &[u8],
+    content_type: &str,
+    password: &str,
+    profile: Option<String>,
+) -> Result<Vec<u8>, JsValue> {
+    let kind = PayloadKind::from_name(content_type).map_err(|e| JsValue::from_str(&e))?;
+    let profile = resolve_profile(profile.as_deref()).map_err(|e| JsValue::from_str(&e))?;
+    encode_password_container(payload, kind, password, &profile).map_err(|e| JsValue::from_str(&e))
+}
@@ -0,0 +1,120 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::error::DecodeError;
+use crate::{
+    clock, guard, inflate_container, into_decode_value, locate_raw_payload, ContainerHeader, DecodeResult, DecodeStats,
+};
+
+pub(crate) use fastdog_core::encryption::*;
+
+// 通过 Web Crypto (crypto.subtle) 解密，浏览器通常使用硬件加速的 AES 指令。
+// 非安全上下文 (http 页面) 等没有 crypto.subtle 的环境返回 None，由调用方改用内置实现
+async fn subtle_decrypt_payload(
+    header: &ContainerHeader,
+    data: &[u8],
+    ciphertext: &[u8],
+    key: &[u8; 32],
+) -> Result<Option<Vec<u8>>, DecodeError> {
+    let Some(subtle) = subtle_crypto() else {
+        return Ok(None);
+    };
+    let (encryption, aad) = authenticated_header(header, data)?;
+
+    let usages = js_sys::Array::of1(&"decrypt".into());
+    let key_data = js_sys::Uint8Array::from(&key[..]);
+    let key = subtle
+        .import_key_with_str("raw", &key_data, "AES-GCM", false, &usages)
+        .map_err(|_| decryption_failed())?;
+    let key: web_sys::CryptoKey = JsFuture::from(key).await.map_err(|_| decryption_failed())?.unchecked_into();
+
+    let params = web_sys::AesGcmParams::new("AES-GCM", &js_sys::Uint8Array::from(&encryption.nonce[..]));
+    params.set_additional_data(&js_sys::Uint8Array::from(&aad[..]));
+    params.set_tag_length(128);
+    // Web Crypto 要求认证标签附在密文之后
+    let mut sealed = Vec::with_capacity(ciphertext.len() + 16);
+    sealed.extend_from_slice(ciphertext);
+    sealed.extend_from_slice(&encryption.tag);
+    let decrypted = subtle
+        .decrypt_with_object_and_u8_array(&params, &key, &sealed)
+        .map_err(|_| decryption_failed())?;
+    // 认证失败时 Promise 以 OperationError 拒绝
+    let decrypted = JsFuture::from(decrypted).await.map_err(|_| decryption_failed())?;
+    Ok(Some(js_sys::Uint8Array::new(&decrypted).to_vec()))
+}
+
+// 全局对象 (窗口或 Worker) 上的 crypto.subtle
+fn subtle_crypto() -> Option<web_sys::SubtleCrypto> {
+    let crypto = js_sys::Reflect::get(&js_sys::global(), &"crypto".into()).ok()?;
+    if crypto.is_undefined() || crypto.is_null() {
+        return None;
+    }
+    let subtle = js_sys::Reflect::get(&crypto, &"subtle".into()).ok()?;
+    if subtle.is_undefined() || subtle.is_null() {
+        return None;
+    }
+    Some(subtle.unchecked_into())
+}
+
+// 解密并解压完整容器
+pub(crate) fn decrypt_container(data: &[u8], key: &[u8]) -> Result<(ContainerHeader, u64, Vec<u8>), DecodeError> {
+    let key = parse_key(key)?;
+    let (header, ciphertext, original_len) = locate_raw_payload(data)?;
+    let compressed = decrypt_payload(&header, data, ciphertext, &key)?;
+    inflate_container(header, &compressed, original_len, |_| Ok(()))
+}
+
+// 异步版本: 先用 Web Crypto 解密，没有 crypto.subtle 时回退到内置实现
+async fn decrypt_container_async(data: &[u8], key: &[u8]) -> Result<(ContainerHeader, u64, Vec<u8>), DecodeError> {
+    let key = parse_key(key)?;
+    let (header, ciphertext, original_len) = locate_raw_payload(data)?;
+    let compressed = match subtle_decrypt_payload(&header, data, ciphertext, &key).await? {
+        Some(compressed) => compressed,
+        None => decrypt_payload(&header, data, ciphertext, &key)?,
+    };
+    inflate_container(header, &compressed, original_len, |_| Ok(()))
+}
+
+// 解码加密容器: 用 32 字节密钥做 AES-256-GCM 解密后再解压，结果与 decode_fastdog_binary 相同。
+// 密钥错误或数据被篡改时返回 DecryptionFailed 错误码
+#[wasm_bindgen(unchecked_return_type = "DecodeResult")]
+pub fn decode_encrypted(data: &[u8], key: &[u8]) -> JsValue {
+    let start_time = clock::now();
+
+    match decrypt_container(data, key).and_then(|decoded| into_decode_value(decoded, start_time)) {
+        Ok(value) => value,
+        Err(error) => {
+            let error_result = DecodeResult {
+                success: false,
+                data: None,
+                error_code: Some(error.code),
+                error: Some(error.message),
+                stats: DecodeStats::failed(data.len() as u64, clock::now() - start_time),
+            };
+            guard::to_js(&error_result)
+        }
+    }
+}
+
+// 异步解码加密容器，返回 Promise，结果格式与 decode_encrypted 相同。
+// AES-GCM 交给 Web Crypto (crypto.subtle) 完成，大载荷的解密明显快于内置实现；
+// 页面不是安全上下文等没有 crypto.subtle 的环境自动回退到内置实现
+#[wasm_bindgen(unchecked_return_type = "DecodeResult")]
+pub async fn decode_encrypted_async(data: Vec<u8>, key: Vec<u8>) -> JsValue {
+    let start_time = clock::now();
+
+    match decrypt_container_async(&data, &key).await.and_then(|decoded| into_decode_value(decoded, start_time)) {
+        Ok(value) => value,
+        Err(error) => {
+            let error_result = DecodeResult {
+                success: false,
+                data: None,
+                error_code: Some(error.code),
+                error: Some(error.message),
+                stats: DecodeStats::failed(data.len() as u64, clock::now() - start_time),
+            };
+            guard::to_js(&error_result)
+        }
+    }
+}
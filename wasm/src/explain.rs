@@ -0,0 +1,238 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::*;
+
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::guard;
+use crate::integrity::to_hex;
+use crate::payload_kind::PayloadKind;
+use crate::{clock, decompress_payload, format, locate_payload, payload_len, quota, ContainerHeader};
+
+// 解码过程中的一步决策或检查
+#[derive(Serialize)]
+pub struct TraceStep {
+    pub stage: &'static str,
+    pub ok: bool,
+    pub message: String,
+    // 距解码开始的时间 (毫秒)
+    pub elapsed_ms: f64,
+}
+
+// 解码追踪报告: steps 供程序分析，text 为逐行的可读版本
+#[derive(Serialize, Default)]
+pub struct DecodeTrace {
+    pub success: bool,
+    pub error: Option<String>,
+    pub error_code: Option<DecodeErrorCode>,
+    pub version: Option<u32>,
+    pub codec: Option<String>,
+    pub content_type: Option<String>,
+    pub compressed_size: Option<u64>,
+    pub original_size: Option<u64>,
+    pub total_time_ms: f64,
+    pub steps: Vec<TraceStep>,
+    pub text: String,
+}
+
+struct Tracer {
+    start_time: f64,
+    trace: DecodeTrace,
+}
+
+impl Tracer {
+    fn step(&mut self, stage: &'static str, message: String) {
+        self.push(stage, true, message);
+    }
+
+    fn push(&mut self, stage: &'static str, ok: bool, message: String) {
+        let elapsed_ms = clock::now() - self.start_time;
+        self.trace.steps.push(TraceStep {
+            stage,
+            ok,
+            message,
+            elapsed_ms,
+        });
+    }
+
+    // 记录失败的步骤并结束追踪
+    fn fail(mut self, stage: &'static str, error: DecodeError) -> DecodeTrace {
+        self.push(stage, false, error.message.clone());
+        self.trace.error_code = Some(error.code);
+        self.trace.error = Some(error.message);
+        self.finish()
+    }
+
+    fn finish(mut self) -> DecodeTrace {
+        self.trace.total_time_ms = clock::now() - self.start_time;
+        self.trace.text = self
+            .trace
+            .steps
+            .iter()
+            .map(|step| {
+                let mark = if step.ok { "✅" } else { "❌" };
+                format!("[{:>8.2}ms] {} {}: {}", step.elapsed_ms, mark, step.stage, step.message)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.trace
+    }
+}
+
+// 解码单个文件并记录每一步决策 (版本分支、编码、缓冲区大小、各项校验和耗时)，
+// 用于排查客户文件的解码问题。解码结果本身不返回，只返回追踪报告
+#[wasm_bindgen(unchecked_return_type = "DecodeTrace")]
+pub fn explain_decode(data: &[u8]) -> JsValue {
+    let tracer = Tracer {
+        start_time: clock::now(),
+        trace: DecodeTrace::default(),
+    };
+    guard::to_js(&explain(tracer, data))
+}
+
+fn explain(mut tracer: Tracer, data: &[u8]) -> DecodeTrace {
+    tracer.step("input", format!("输入 {} 字节", data.len()));
+
+    // 1. 头部与载荷定位
+    let (header, compressed, original_len) = match locate_payload(data) {
+        Ok(located) => located,
+        Err(e) => return tracer.fail("header", e),
+    };
+    tracer.trace.version = Some(header.version);
+    tracer.trace.codec = Some(header.codec.name().to_string());
+    tracer.trace.content_type = Some(header.kind.name().to_string());
+    tracer.trace.compressed_size = Some(header.compressed_len as u64);
+    tracer.trace.original_size = Some(original_len);
+    tracer.step("header", describe_header(&header, data));
+    tracer.step(
+        "payload",
+        format!(
+            "压缩数据位于 [{}, {})，原始长度字段声明 {} 字节，容器共 {} 字节",
+            header.payload_offset,
+            header.payload_offset + header.compressed_len,
+            original_len,
+            header.total_len()
+        ),
+    );
+    if data.len() > header.total_len() {
+        tracer.step("payload", format!("容器末尾有 {} 字节多余数据，已忽略", data.len() - header.total_len()));
+    }
+
+    // 2. 内存配额
+    let mut reservation = match payload_len(original_len).and_then(quota::reserve) {
+        Ok(reservation) => reservation,
+        Err(e) => return tracer.fail("quota", e),
+    };
+    tracer.step("quota", format!("预留 {} 字节输出缓冲区", original_len));
+
+    // 3. 解压
+    let inflate_start = clock::now();
+    let mut slices = 0u32;
+    let decompressed = decompress_payload(&header, compressed, original_len, |len| {
+        slices += 1;
+        reservation.grow_to(len)
+    });
+    let decompressed = match decompressed {
+        Ok(decompressed) => decompressed,
+        Err(e) => return tracer.fail("inflate", e),
+    };
+    tracer.step(
+        "inflate",
+        format!(
+            "{} 解压 {} -> {} 字节，{} 个分片，耗时 {:.2}ms",
+            header.codec.name(),
+            compressed.len(),
+            decompressed.len(),
+            slices,
+            clock::now() - inflate_start
+        ),
+    );
+
+    // 4. 校验
+    if decompressed.len() as u64 != original_len {
+        let error = DecodeError::new(
+            DecodeErrorCode::LengthMismatch,
+            format!("解压后数据长度不匹配: 期望 {}, 实际 {}", original_len, decompressed.len()),
+        );
+        return tracer.fail("length", error);
+    }
+    tracer.step("length", format!("长度一致: {} 字节", original_len));
+
+    match header.crc32 {
+        Some(expected) => {
+            let actual = crc32fast::hash(&decompressed);
+            if actual != expected {
+                let error = DecodeError::new(
+                    DecodeErrorCode::ChecksumMismatch,
+                    format!("CRC32 校验失败: 期望 {:08x}, 实际 {:08x}", expected, actual),
+                );
+                return tracer.fail("crc32", error);
+            }
+            tracer.step("crc32", format!("CRC32 一致: {:08x}", actual));
+        }
+        None => tracer.step("crc32", "头部不含 CRC32，跳过".to_string()),
+    }
+
+    match header.sha256 {
+        Some(expected) => {
+            let actual: [u8; 32] = Sha256::digest(&decompressed).into();
+            if actual != expected {
+                let error = DecodeError::new(
+                    DecodeErrorCode::ChecksumMismatch,
+                    format!("SHA-256 校验失败: 期望 {}, 实际 {}", to_hex(&expected), to_hex(&actual)),
+                );
+                return tracer.fail("sha256", error);
+            }
+            tracer.step("sha256", format!("SHA-256 一致: {}", to_hex(&actual)));
+        }
+        None => tracer.step("sha256", "头部不含 SHA-256 摘要，跳过".to_string()),
+    }
+
+    // 5. 内容转换 (与 decode_fastdog_binary 相同)
+    match header.kind {
+        PayloadKind::Json => match std::str::from_utf8(&decompressed) {
+            Ok(_) => tracer.step("content", format!("JSON 载荷，UTF-8 有效，输出 {} 字节字符串", decompressed.len())),
+            Err(e) => {
+                let error = DecodeError::new(DecodeErrorCode::Utf8Error, format!("UTF-8 解码失败: {}", e));
+                return tracer.fail("content", error);
+            }
+        },
+        kind => tracer.step(
+            "content",
+            format!(
+                "{} 载荷，base64 编码后输出 {} 字节字符串",
+                kind.name(),
+                decompressed.len().div_ceil(3) * 4
+            ),
+        ),
+    }
+
+    tracer.trace.success = true;
+    tracer.finish()
+}
+
+// 描述头部解析时走的版本分支
+fn describe_header(header: &ContainerHeader, data: &[u8]) -> String {
+    let content_type = header.kind.name();
+    if header.version < 3 {
+        return format!(
+            "版本 {} (旧格式): 编码固定为 zlib，内容类型 {} 由版本号决定，头部 {} 字节",
+            header.version, content_type, header.payload_offset
+        );
+    }
+
+    let flags = format::FLAGS.read(data) as u16;
+    let extensions: Vec<&str> = format::v3_extension_fields(flags).map(|field| field.name).collect();
+    let extensions = if extensions.is_empty() {
+        "无".to_string()
+    } else {
+        extensions.join(", ")
+    };
+    format!(
+        "版本 3 (扩展头部): 编码 {}，内容类型 {}，标志 {:#06x}，扩展字段: {}，头部 {} 字节",
+        header.codec.name(),
+        content_type,
+        flags,
+        extensions,
+        header.payload_offset
+    )
+}
@@ -0,0 +1,10 @@
+use wasm_bindgen::prelude::*;
+
+pub(crate) use fastdog_core::format::*;
+
+// 输出指定容器版本的机器可读格式说明 (字段名、偏移、大小、类型和语义)
+#[wasm_bindgen(unchecked_return_type = "FormatDescription")]
+pub fn describe_format(version: u32) -> Result<JsValue, JsValue> {
+    let description = describe(version).map_err(|e| JsValue::from_str(&e))?;
+    serde_wasm_bindgen::to_value(&description).map_err(|e| JsValue::from_str(&e.to_string()))
+}
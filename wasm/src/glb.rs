@@ -0,0 +1,181 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::byte_reader::ByteReader;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::guard;
+use crate::payload_kind::PayloadKind;
+use crate::plain::PlainObject;
+use crate::{clock, decode_container, lod, DecodeStats};
+
+// GLB (glTF 2.0 二进制容器) 解析
+//
+// 布局: 头部 魔数 "glTF" | 版本 u32 (2) | 总长度 u32，之后为若干块: 长度 u32 | 类型 u32 | 数据。
+// 首块必须是 JSON，可选的第二块为 BIN，其余未知类型的块按规范忽略
+pub(crate) const GLB_MAGIC: &[u8; 4] = b"glTF";
+pub(crate) const GLB_HEADER_LEN: usize = 12;
+pub(crate) const CHUNK_JSON: u32 = 0x4E4F534A;
+pub(crate) const CHUNK_BIN: u32 = 0x004E4942;
+
+// 拆分后的 GLB: JSON 块 (含末尾的空格填充) 和 BIN 块
+pub(crate) struct Glb<'a> {
+    pub json: &'a [u8],
+    pub bin: Option<&'a [u8]>,
+}
+
+fn invalid(message: impl Into<String>) -> DecodeError {
+    DecodeError::new(DecodeErrorCode::InvalidInput, message)
+}
+
+// 检查 GLB 头部并拆分 JSON / BIN 块
+pub(crate) fn split(payload: &[u8]) -> Result<Glb<'_>, DecodeError> {
+    if payload.len() < GLB_HEADER_LEN || &payload[..4] != GLB_MAGIC {
+        return Err(invalid("载荷缺少 glTF 魔数"));
+    }
+    let reader = ByteReader::new(payload, "GLB 载荷");
+    let version = reader.u32_at(4)?;
+    if version != 2 {
+        return Err(invalid(format!("不支持的 GLB 版本: {}", version)));
+    }
+    let declared_len = reader.u32_at(8)? as usize;
+    if declared_len > payload.len() {
+        return Err(invalid(format!("GLB 头部声明长度 {} 超出载荷长度 {}", declared_len, payload.len())));
+    }
+
+    let mut json = None;
+    let mut bin = None;
+    let mut offset = GLB_HEADER_LEN;
+    let mut index = 0;
+    while offset < declared_len {
+        if declared_len - offset < 8 {
+            return Err(invalid(format!("GLB 块 {} 的块头不完整 (偏移 {})", index, offset)));
+        }
+        let chunk_len = reader.u32_at(offset)? as usize;
+        let chunk_type = reader.u32_at(offset + 4)?;
+        let start = offset + 8;
+        if chunk_len > declared_len - start {
+            return Err(invalid(format!("GLB 块 {} 长度 {} 超出载荷范围 (偏移 {})", index, chunk_len, offset)));
+        }
+        let chunk = &payload[start..start + chunk_len];
+        match (index, chunk_type) {
+            (0, CHUNK_JSON) => json = Some(chunk),
+            (0, _) => return Err(invalid(format!("GLB 首块类型应为 JSON, 实际为 {:#010x}", chunk_type))),
+            (1, CHUNK_BIN) => bin = Some(chunk),
+            (_, CHUNK_JSON) | (_, CHUNK_BIN) => {
+                return Err(invalid(format!("GLB 块 {} 的类型 {:#010x} 位置不正确", index, chunk_type)));
+            }
+            _ => {}
+        }
+        offset = start + chunk_len;
+        index += 1;
+    }
+
+    let json = json.ok_or_else(|| invalid("GLB 载荷缺少 JSON 块"))?;
+    Ok(Glb { json, bin })
+}
+
+// 渐进式 LOD 载荷 (encode_fastdog_lod) 还原为最精细一级的 GLB，其他载荷原样返回
+pub(crate) fn resolve_lod(payload: Vec<u8>) -> Result<Vec<u8>, DecodeError> {
+    if lod::is_lod(&payload) {
+        lod::finest_level(&payload)
+    } else {
+        Ok(payload)
+    }
+}
+
+// 解码 GLB 载荷并解析 JSON 块
+pub(crate) fn decode_glb_container(data: &[u8]) -> Result<(serde_json::Value, Vec<u8>, DecodeStats), DecodeError> {
+    let start_time = clock::now();
+    let (header, original_len, decompressed) = decode_container(data)?;
+    if header.kind != PayloadKind::Glb {
+        return Err(DecodeError::new(
+            DecodeErrorCode::UnsupportedContentType,
+            format!("载荷不是 GLB 格式 (内容类型: {})", header.kind.name()),
+        ));
+    }
+    let decompressed = resolve_lod(decompressed)?;
+    let glb = split(&decompressed)?;
+    let json = serde_json::from_slice(glb.json).map_err(|e| invalid(format!("glTF JSON 解析失败: {}", e)))?;
+    let bin = glb.bin.unwrap_or_default().to_vec();
+    Ok((json, bin, DecodeStats::from_header(&header, original_len, clock::now() - start_time)))
+}
+
+#[derive(Serialize)]
+struct GlbDecodeResult {
+    success: bool,
+    json: Option<serde_json::Value>,
+    #[serde(with = "serde_bytes")]
+    bin: Option<Vec<u8>>,
+    error: Option<String>,
+    error_code: Option<DecodeErrorCode>,
+    stats: DecodeStats,
+}
+
+// 解码 GLB 载荷并拆分为 glTF JSON (已解析的对象) 和 BIN 块 (Uint8Array)，
+// 加载器无需再在 JS 中解析 GLB 头部和 JSON。没有 BIN 块时 bin 为空数组
+#[wasm_bindgen(unchecked_return_type = "GlbDecodeResult")]
+pub fn decode_fastdog_glb_parsed(data: &[u8]) -> JsValue {
+    let start_time = clock::now();
+
+    let result = match decode_glb_container(data) {
+        Ok((json, bin, stats)) => GlbDecodeResult {
+            success: true,
+            json: Some(json),
+            bin: Some(bin),
+            error: None,
+            error_code: None,
+            stats,
+        },
+        Err(error) => GlbDecodeResult {
+            success: false,
+            json: None,
+            bin: None,
+            error: Some(error.message),
+            error_code: Some(error.code),
+            stats: DecodeStats::failed(data.len() as u64, clock::now() - start_time),
+        },
+    };
+    // JSON 对象输出为普通对象而不是 Map
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    guard::to_js_with(&result, &serializer)
+}
+
+// GLTFLoader 通过 LoadingManager 的 URL 映射读取 BIN 块时使用的资源名
+const THREE_BIN_URI: &str = "fastdog:bin";
+
+// 解码 GLB 载荷并输出 THREE.GLTFLoader.parse 可直接使用的形式:
+// { success, json: glTF JSON 字符串, buffer: BIN 块 (ArrayBuffer), resources: { 资源名: ArrayBuffer }, stats }。
+// JSON 中缓冲区 0 的 uri 改写为资源名，由 static/js/fastdog-three.js 的 parseFastDogGLTF
+// 把资源名映射为 Blob URL 后交给 GLTFLoader 解析
+#[wasm_bindgen(unchecked_return_type = "ThreeGltfResult")]
+pub fn decode_fastdog_three(data: &[u8]) -> JsValue {
+    let start_time = clock::now();
+
+    match decode_glb_container(data) {
+        Ok((mut json, bin, stats)) => {
+            let buffer = js_sys::Uint8Array::from(&bin[..]).buffer();
+            let mut resources = PlainObject::new();
+            // 只有引用 GLB BIN 块的缓冲区 (没有 uri) 需要改写，外部缓冲区仍按原 uri 加载
+            if json.pointer("/buffers/0").is_some_and(|buffer| buffer.get("uri").is_none()) {
+                json["buffers"][0]["uri"] = THREE_BIN_URI.into();
+                resources = resources.set(THREE_BIN_URI, buffer.clone());
+            }
+            PlainObject::new()
+                .set("success", true)
+                .set("json", json.to_string())
+                .set("buffer", buffer)
+                .set("resources", resources.build())
+                .set("stats", guard::to_js(&stats))
+                .build()
+        }
+        Err(error) => PlainObject::new()
+            .set("success", false)
+            .set("error", error.message)
+            .set("error_code", guard::to_js(&error.code))
+            .set(
+                "stats",
+                guard::to_js(&DecodeStats::failed(data.len() as u64, clock::now() - start_time)),
+            )
+            .build(),
+    }
+}
@@ -0,0 +1,716 @@
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::guard;
+use crate::payload_kind::PayloadKind;
+use crate::plain::PlainObject;
+use crate::ktx2;
+use crate::meshopt::{self, MeshoptCompression, EXT_MESHOPT_COMPRESSION};
+use crate::{arena, clock, decode_container, glb, DecodeStats};
+
+// glTF 访问器 (accessor) 数据提取
+//
+// 解码后的 GLB 整体登记到零拷贝缓冲区池，访问器按 bufferView 的偏移直接映射为
+// WASM 内存上的类型化数组 (Float32Array / Uint16Array 等)，可直接上传 GPU。
+// 交错存储 (byteStride 大于元素大小) 的访问器无法直接映射，复制为紧凑排列的独立数组
+
+pub(crate) const COMPONENT_I8: u32 = 5120;
+pub(crate) const COMPONENT_U8: u32 = 5121;
+pub(crate) const COMPONENT_I16: u32 = 5122;
+pub(crate) const COMPONENT_U16: u32 = 5123;
+pub(crate) const COMPONENT_U32: u32 = 5125;
+pub(crate) const COMPONENT_F32: u32 = 5126;
+
+// glTF JSON 中访问器提取需要的部分，其余字段忽略
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Gltf {
+    #[serde(default)]
+    pub accessors: Vec<Accessor>,
+    #[serde(default)]
+    pub buffer_views: Vec<BufferView>,
+    #[serde(default)]
+    pub buffers: Vec<Buffer>,
+    #[serde(default)]
+    pub meshes: Vec<Mesh>,
+    #[serde(default)]
+    pub images: Vec<Image>,
+    // 加载器必须支持才能正确读取的扩展
+    #[serde(default)]
+    pub extensions_required: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Accessor {
+    pub buffer_view: Option<usize>,
+    #[serde(default)]
+    pub byte_offset: usize,
+    pub component_type: u32,
+    pub count: usize,
+    #[serde(rename = "type")]
+    pub kind: String,
+    // 整数分量是否归一化到 [0, 1] / [-1, 1]
+    #[serde(default)]
+    pub normalized: bool,
+    pub sparse: Option<serde::de::IgnoredAny>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BufferView {
+    pub buffer: usize,
+    #[serde(default)]
+    pub byte_offset: usize,
+    pub byte_length: usize,
+    pub byte_stride: Option<usize>,
+    #[serde(default)]
+    pub extensions: BufferViewExtensions,
+}
+
+#[derive(Deserialize, Default)]
+pub(crate) struct BufferViewExtensions {
+    #[serde(rename = "EXT_meshopt_compression")]
+    pub meshopt: Option<MeshoptCompression>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Buffer {
+    pub uri: Option<String>,
+}
+
+// 图像 (GLB 内嵌的纹理通过 bufferView 引用，格式按数据开头的标识判断，不依赖 mimeType)
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Image {
+    pub buffer_view: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Mesh {
+    pub primitives: Vec<Primitive>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Primitive {
+    pub attributes: std::collections::BTreeMap<String, usize>,
+    pub indices: Option<usize>,
+    // 图元类型，缺省为 4 (TRIANGLES)
+    pub mode: Option<u32>,
+    #[serde(default)]
+    pub extensions: std::collections::BTreeMap<String, serde::de::IgnoredAny>,
+}
+
+// Draco 压缩的图元: 顶点数据在扩展引用的 bufferView 中，accessors 只有元素数没有数据
+pub(crate) const KHR_DRACO_MESH_COMPRESSION: &str = "KHR_draco_mesh_compression";
+
+// 访问器在 BIN 块中的位置
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct AccessorLayout {
+    pub component_type: u32,
+    pub component_size: usize,
+    // 每个元素的分量数 (VEC3 为 3)
+    pub components: usize,
+    pub count: usize,
+    // 第一个元素相对 BIN 块起始的偏移，没有 bufferView 时为 None (数据全为 0)
+    pub offset: Option<usize>,
+    // 相邻元素的间隔，紧凑排列时等于元素大小
+    pub stride: usize,
+    pub normalized: bool,
+}
+
+impl AccessorLayout {
+    pub fn element_size(&self) -> usize {
+        self.component_size * self.components
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.element_size() * self.count
+    }
+
+    // 数据是否紧凑排列，可以直接映射为类型化数组
+    pub fn is_packed(&self) -> bool {
+        self.stride == self.element_size() || self.count <= 1
+    }
+}
+
+fn invalid(message: impl Into<String>) -> DecodeError {
+    DecodeError::new(DecodeErrorCode::InvalidInput, message)
+}
+
+fn component_size(component_type: u32) -> Result<usize, DecodeError> {
+    match component_type {
+        COMPONENT_I8 | COMPONENT_U8 => Ok(1),
+        COMPONENT_I16 | COMPONENT_U16 => Ok(2),
+        COMPONENT_U32 | COMPONENT_F32 => Ok(4),
+        _ => Err(invalid(format!("不支持的访问器分量类型: {}", component_type))),
+    }
+}
+
+fn component_count(kind: &str) -> Result<usize, DecodeError> {
+    match kind {
+        "SCALAR" => Ok(1),
+        "VEC2" => Ok(2),
+        "VEC3" => Ok(3),
+        "VEC4" | "MAT2" => Ok(4),
+        "MAT3" => Ok(9),
+        "MAT4" => Ok(16),
+        _ => Err(invalid(format!("不支持的访问器类型: {}", kind))),
+    }
+}
+
+impl Gltf {
+    pub fn parse(json: &serde_json::Value) -> Result<Gltf, DecodeError> {
+        Gltf::deserialize(json).map_err(|e| invalid(format!("glTF JSON 结构无效: {}", e)))
+    }
+
+    // 访问器是否属于 Draco 压缩的图元 (数据需要 Draco 解码器才能还原)
+    pub fn is_draco_accessor(&self, index: usize) -> bool {
+        self.meshes
+            .iter()
+            .flat_map(|mesh| &mesh.primitives)
+            .filter(|primitive| primitive.extensions.contains_key(KHR_DRACO_MESH_COMPRESSION))
+            .any(|primitive| primitive.indices == Some(index) || primitive.attributes.values().any(|&i| i == index))
+    }
+
+    // 计算访问器在 BIN 块中的位置并检查范围
+    pub fn layout(&self, index: usize, bin_len: usize) -> Result<AccessorLayout, DecodeError> {
+        let accessor = self
+            .accessors
+            .get(index)
+            .ok_or_else(|| invalid(format!("访问器 {} 不存在 (共 {} 个)", index, self.accessors.len())))?;
+        // 本模块不包含 Draco 解码器，不能把只有元素数的访问器当作全 0 数据返回
+        if self.is_draco_accessor(index) {
+            return Err(DecodeError::new(
+                DecodeErrorCode::UnsupportedContentType,
+                format!(
+                    "访问器 {} 属于 Draco 压缩的图元 (KHR_draco_mesh_compression)，本模块不包含 Draco 解码器，请使用 DRACOLoader 等解码",
+                    index
+                ),
+            ));
+        }
+        if accessor.sparse.is_some() {
+            return Err(invalid(format!("访问器 {} 使用稀疏存储，暂不支持", index)));
+        }
+        let component_size = component_size(accessor.component_type)?;
+        let components = component_count(&accessor.kind)?;
+        // 1/2 字节分量的矩阵每列按 4 字节对齐填充，不是紧凑的分量序列
+        if accessor.kind.starts_with("MAT") && component_size < 4 {
+            return Err(invalid(format!("访问器 {} 是带列填充的 {} 矩阵，暂不支持", index, accessor.kind)));
+        }
+        let element_size = component_size * components;
+
+        let Some(view_index) = accessor.buffer_view else {
+            // 没有 bufferView 时数据全为 0，需要按元素数分配
+            if accessor.count.checked_mul(element_size).is_none_or(|len| len > u32::MAX as usize) {
+                return Err(invalid(format!("访问器 {} 的元素数过大", index)));
+            }
+            return Ok(AccessorLayout {
+                component_type: accessor.component_type,
+                component_size,
+                components,
+                count: accessor.count,
+                offset: None,
+                stride: element_size,
+                normalized: accessor.normalized,
+            });
+        };
+        let view = self
+            .buffer_views
+            .get(view_index)
+            .ok_or_else(|| invalid(format!("访问器 {} 引用的 bufferView {} 不存在", index, view_index)))?;
+        if view.buffer != 0 || self.buffers.first().is_some_and(|buffer| buffer.uri.is_some()) {
+            return Err(invalid(format!("访问器 {} 引用外部缓冲区，只支持 GLB 的 BIN 块", index)));
+        }
+        let stride = view.byte_stride.unwrap_or(element_size);
+        if stride < element_size {
+            return Err(invalid(format!("访问器 {} 的 byteStride {} 小于元素大小 {}", index, stride, element_size)));
+        }
+        let offset = view
+            .byte_offset
+            .checked_add(accessor.byte_offset)
+            .filter(|offset| offset.is_multiple_of(component_size))
+            .ok_or_else(|| invalid(format!("访问器 {} 的偏移未按分量大小对齐", index)))?;
+        // 最后一个元素的结束位置必须在 bufferView 和 BIN 块之内
+        let span = match accessor.count {
+            0 => 0,
+            count => (count - 1)
+                .checked_mul(stride)
+                .and_then(|len| len.checked_add(element_size))
+                .ok_or_else(|| invalid(format!("访问器 {} 的元素数过大", index)))?,
+        };
+        let view_end = view.byte_offset.checked_add(view.byte_length);
+        let end = offset.checked_add(span);
+        match (end, view_end) {
+            (Some(end), Some(view_end)) if end <= view_end && view_end <= bin_len => {}
+            _ => return Err(invalid(format!("访问器 {} 超出 bufferView 或 BIN 块范围", index))),
+        }
+
+        Ok(AccessorLayout {
+            component_type: accessor.component_type,
+            component_size,
+            components,
+            count: accessor.count,
+            offset: Some(offset),
+            stride,
+            normalized: accessor.normalized,
+        })
+    }
+
+    // 内嵌图像在 BIN 块中的范围
+    pub fn image_range(&self, index: usize, bin_len: usize) -> Result<std::ops::Range<usize>, DecodeError> {
+        let image = self
+            .images
+            .get(index)
+            .ok_or_else(|| invalid(format!("图像 {} 不存在 (共 {} 个)", index, self.images.len())))?;
+        let view = image
+            .buffer_view
+            .and_then(|view_index| self.buffer_views.get(view_index))
+            .ok_or_else(|| invalid(format!("图像 {} 不是通过 bufferView 内嵌的图像", index)))?;
+        if view.buffer != 0 || self.buffers.first().is_some_and(|buffer| buffer.uri.is_some()) {
+            return Err(invalid(format!("图像 {} 引用外部缓冲区，只支持 GLB 的 BIN 块", index)));
+        }
+        view.byte_offset
+            .checked_add(view.byte_length)
+            .filter(|&end| end <= bin_len)
+            .map(|end| view.byte_offset..end)
+            .ok_or_else(|| invalid(format!("图像 {} 超出 BIN 块范围", index)))
+    }
+}
+
+// 解码 EXT_meshopt_compression 压缩的 bufferView: 解码后的数据按 4 字节对齐追加在缓冲区末尾，
+// bufferView 改为指向解码结果 (偏移相对 BIN 块起始)。返回解码过的 bufferView 数
+fn decode_meshopt_views(gltf: &mut Gltf, buffer: &mut Vec<u8>, bin_offset: usize, bin_len: usize) -> Result<usize, DecodeError> {
+    let external = gltf.buffers.first().is_some_and(|buffer| buffer.uri.is_some());
+    let mut decoded_views = 0;
+    for (index, view) in gltf.buffer_views.iter_mut().enumerate() {
+        let Some(ext) = view.extensions.meshopt.take() else {
+            continue;
+        };
+        if ext.buffer != 0 || external {
+            return Err(invalid(format!("bufferView {} 的 meshopt 数据在外部缓冲区，只支持 GLB 的 BIN 块", index)));
+        }
+        let source = ext
+            .byte_offset
+            .checked_add(ext.byte_length)
+            .filter(|&end| end <= bin_len)
+            .map(|end| &buffer[bin_offset + ext.byte_offset..bin_offset + end])
+            .ok_or_else(|| invalid(format!("bufferView {} 的 meshopt 数据超出 BIN 块范围", index)))?;
+        let decoded = meshopt::decode_buffer_view(&ext, source)
+            .map_err(|error| DecodeError::new(error.code, format!("bufferView {}: {}", index, error.message)))?;
+
+        let padding = (4 - (buffer.len() - bin_offset) % 4) % 4;
+        buffer.resize(buffer.len() + padding, 0);
+        view.buffer = 0;
+        view.byte_offset = buffer.len() - bin_offset;
+        view.byte_length = decoded.len();
+        buffer.extend_from_slice(&decoded);
+        decoded_views += 1;
+    }
+    Ok(decoded_views)
+}
+
+// WebGPU 缓冲区偏移对齐: 各数据段按 256 字节 (minUniformBufferOffsetAlignment 等限制的默认值) 对齐，
+// 整块数据一次 writeBuffer 写入同一个 GPUBuffer 后可以直接按段偏移绑定
+pub(crate) const GPU_SLICE_ALIGNMENT: usize = 256;
+
+// GPU 数据段在整块数据中的位置
+pub(crate) struct GpuSlice {
+    pub offset: usize,
+    pub size: usize,
+    pub stride: usize,
+    pub count: usize,
+    // WebGPU 顶点格式 (如 float32x3) 或索引格式 (uint16 / uint32)
+    pub format: Option<String>,
+}
+
+// 元素补齐到 4 字节后对应的 WebGPU 顶点格式。补齐后超过 4 个分量 (矩阵) 时没有对应格式
+fn vertex_format(layout: &AccessorLayout, padded_size: usize) -> Option<String> {
+    let base = match (layout.component_type, layout.normalized) {
+        (COMPONENT_F32, _) => "float32",
+        (COMPONENT_U32, _) => "uint32",
+        (COMPONENT_U8, true) => "unorm8",
+        (COMPONENT_U8, false) => "uint8",
+        (COMPONENT_I8, true) => "snorm8",
+        (COMPONENT_I8, false) => "sint8",
+        (COMPONENT_U16, true) => "unorm16",
+        (COMPONENT_U16, false) => "uint16",
+        (COMPONENT_I16, true) => "snorm16",
+        _ => "sint16",
+    };
+    match padded_size / layout.component_size {
+        1 => Some(base.to_string()),
+        components @ 2..=4 => Some(format!("{}x{}", base, components)),
+        _ => None,
+    }
+}
+
+// 追加一个按 GPU_SLICE_ALIGNMENT 对齐的数据段，每个元素补齐到 padded_size 字节 (4 的倍数)
+fn push_gpu_slice(output: &mut Vec<u8>, packed: &[u8], element_size: usize, padded_size: usize) -> (usize, usize) {
+    output.resize(output.len().next_multiple_of(GPU_SLICE_ALIGNMENT), 0);
+    let offset = output.len();
+    if padded_size == element_size {
+        output.extend_from_slice(packed);
+    } else {
+        for element in packed.chunks_exact(element_size) {
+            output.extend_from_slice(element);
+            output.resize(output.len() + padded_size - element_size, 0);
+        }
+    }
+    // writeBuffer 要求写入长度为 4 的倍数
+    output.resize(output.len().next_multiple_of(4), 0);
+    (offset, output.len() - offset)
+}
+
+// 把图元的顶点属性和索引排列为对齐的数据段。1 / 2 字节的分量补齐到每个元素 4 字节，
+// WebGPU 不支持的 8 位索引扩展为 16 位
+pub(crate) fn gpu_slices(
+    bin: &[u8],
+    attributes: &[(&str, AccessorLayout)],
+    indices: Option<AccessorLayout>,
+) -> (Vec<u8>, Vec<GpuSlice>, Option<GpuSlice>) {
+    let mut output = Vec::new();
+    let mut slices = Vec::with_capacity(attributes.len());
+    for (_, layout) in attributes {
+        let element_size = layout.element_size();
+        let padded_size = element_size.next_multiple_of(4);
+        let (offset, size) = push_gpu_slice(&mut output, &pack(bin, layout), element_size, padded_size);
+        slices.push(GpuSlice {
+            offset,
+            size,
+            stride: padded_size,
+            count: layout.count,
+            format: vertex_format(layout, padded_size),
+        });
+    }
+    let indices = indices.map(|layout| {
+        let mut packed = pack(bin, &layout);
+        if layout.component_size == 1 {
+            packed = packed.iter().flat_map(|&index| (index as u16).to_le_bytes()).collect();
+        }
+        let index_size = layout.component_size.max(2);
+        let (offset, size) = push_gpu_slice(&mut output, &packed, index_size, index_size);
+        GpuSlice {
+            offset,
+            size,
+            stride: index_size,
+            count: layout.count,
+            format: Some(if index_size == 2 { "uint16" } else { "uint32" }.to_string()),
+        }
+    });
+    output.resize(output.len().next_multiple_of(4), 0);
+    (output, slices, indices)
+}
+
+// 把交错存储的访问器复制为紧凑排列 (没有 bufferView 的访问器为全 0)
+pub(crate) fn pack(bin: &[u8], layout: &AccessorLayout) -> Vec<u8> {
+    let element_size = layout.element_size();
+    let mut packed = vec![0u8; layout.byte_len()];
+    if let Some(offset) = layout.offset {
+        for (index, element) in packed.chunks_exact_mut(element_size).enumerate() {
+            let start = offset + index * layout.stride;
+            element.copy_from_slice(&bin[start..start + element_size]);
+        }
+    }
+    packed
+}
+
+// 在 ArrayBuffer 上按分量类型建立类型化数组
+fn typed_array(component_type: u32, buffer: &JsValue, byte_offset: u32, len: u32) -> JsValue {
+    match component_type {
+        COMPONENT_I8 => js_sys::Int8Array::new_with_byte_offset_and_length(buffer, byte_offset, len).into(),
+        COMPONENT_U8 => js_sys::Uint8Array::new_with_byte_offset_and_length(buffer, byte_offset, len).into(),
+        COMPONENT_I16 => js_sys::Int16Array::new_with_byte_offset_and_length(buffer, byte_offset, len).into(),
+        COMPONENT_U16 => js_sys::Uint16Array::new_with_byte_offset_and_length(buffer, byte_offset, len).into(),
+        COMPONENT_U32 => js_sys::Uint32Array::new_with_byte_offset_and_length(buffer, byte_offset, len).into(),
+        _ => js_sys::Float32Array::new_with_byte_offset_and_length(buffer, byte_offset, len).into(),
+    }
+}
+
+// 访问器数据: 紧凑排列的直接引用 BIN 块，其余为复制后的数据
+enum AccessorData {
+    Mapped(AccessorLayout, usize),
+    Packed(AccessorLayout, Vec<u8>),
+}
+
+// 解码后的 GLB 及其访问器
+//
+// 返回的类型化数组是 WASM 内存上的视图 (交错存储的访问器除外)，在下次 WASM 内存增长前有效，
+// 应在取得后立即上传 GPU 或复制，不要长期保存。调用 free() 后缓冲区立即释放
+#[wasm_bindgen]
+pub struct GlbAccessors {
+    handle: u32,
+    // BIN 块在缓冲区中的偏移和长度
+    bin_offset: usize,
+    bin_len: usize,
+    json: serde_json::Value,
+    gltf: Gltf,
+    stats: DecodeStats,
+}
+
+#[wasm_bindgen]
+impl GlbAccessors {
+    // 解析后的 glTF JSON
+    #[wasm_bindgen(getter)]
+    pub fn json(&self) -> JsValue {
+        guard::json_to_js(&self.json)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn accessor_count(&self) -> u32 {
+        self.gltf.accessors.len() as u32
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn mesh_count(&self) -> u32 {
+        self.gltf.meshes.len() as u32
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn image_count(&self) -> u32 {
+        self.gltf.images.len() as u32
+    }
+
+    #[wasm_bindgen(getter, unchecked_return_type = "DecodeStats")]
+    pub fn stats(&self) -> JsValue {
+        guard::to_js(&self.stats)
+    }
+
+    // glTF 的 extensionsRequired 中本模块没有处理的部分 (如 KHR_draco_mesh_compression)，
+    // 用于判断是否需要额外的解码器。已解码的网格压缩扩展 (见 stats.mesh_codecs) 不包含在内
+    #[wasm_bindgen(getter)]
+    pub fn extensions_required(&self) -> Vec<String> {
+        self.gltf
+            .extensions_required
+            .iter()
+            .filter(|extension| !self.stats.mesh_codecs.contains(extension))
+            .cloned()
+            .collect()
+    }
+
+    // 第 index 个访问器的数据 (Float32Array / Uint16Array 等，按 componentType 决定)
+    #[wasm_bindgen(unchecked_return_type = "GltfTypedArray")]
+    pub fn accessor(&self, index: u32) -> Result<JsValue, JsValue> {
+        let data = self.accessor_data(index as usize)?;
+        Ok(self.to_typed_array(data)?)
+    }
+
+    // 网格图元的全部顶点属性和索引:
+    // { attributes: { POSITION: Float32Array, NORMAL: ..., TEXCOORD_0: ... }, indices?: Uint16Array | Uint32Array, mode }
+    #[wasm_bindgen(unchecked_return_type = "GltfPrimitiveArrays")]
+    pub fn primitive(&self, mesh: u32, primitive: u32) -> Result<JsValue, JsValue> {
+        let primitive = self
+            .gltf
+            .meshes
+            .get(mesh as usize)
+            .and_then(|mesh_data| mesh_data.primitives.get(primitive as usize))
+            .ok_or_else(|| invalid(format!("网格 {} 的图元 {} 不存在", mesh, primitive)))?;
+
+        // 先完成全部复制 (可能使 WASM 内存增长)，再建立视图，避免先建立的视图失效
+        let mut attributes = Vec::with_capacity(primitive.attributes.len());
+        for (name, &index) in &primitive.attributes {
+            attributes.push((name, self.accessor_data(index)?));
+        }
+        let indices = primitive.indices.map(|index| self.accessor_data(index)).transpose()?;
+
+        let mut object = PlainObject::new();
+        for (name, data) in attributes {
+            object = object.set(name, self.to_typed_array(data)?);
+        }
+        let indices = indices.map(|data| self.to_typed_array(data)).transpose()?;
+        Ok(PlainObject::new()
+            .set("attributes", object.build())
+            .set_opt("indices", indices)
+            .set("mode", primitive.mode.unwrap_or(4))
+            .build())
+    }
+
+    // 内嵌 KTX2 纹理的信息: { width, height, levels, vk_format, supercompression, basis?, format?, srgb }。
+    // basis 为 "etc1s" / "uastc" 时需要 Basis 转码器，format 为可直接输出的目标格式
+    #[wasm_bindgen(unchecked_return_type = "Ktx2TextureInfo")]
+    pub fn texture_info(&self, image: u32) -> Result<JsValue, JsValue> {
+        let texture = ktx2::parse(self.image(image as usize)?)?;
+        let native = texture.native_format();
+        Ok(PlainObject::new()
+            .set("width", texture.width)
+            .set("height", texture.height)
+            .set("levels", texture.level_count() as u32)
+            .set("vk_format", texture.vk_format)
+            .set("supercompression", texture.supercompression_name())
+            .set_opt("basis", texture.basis())
+            .set_opt("format", native.map(|(name, _)| name))
+            .set("srgb", native.is_some_and(|(_, srgb)| srgb))
+            .build())
+    }
+
+    // 按目标格式 ("rgba8" / "bc7" / "astc") 输出内嵌 KTX2 纹理的全部 mip 级别:
+    // { width, height, format, srgb, levels: Uint8Array[] }，级别 0 为最大尺寸。
+    // 目前只支持纹理本身即为目标格式的情况 (zstd / zlib 超压缩会先解压)，Basis Universal 纹理返回 UnsupportedContentType
+    #[wasm_bindgen(unchecked_return_type = "Ktx2Texture")]
+    pub fn texture(&self, image: u32, target: &str) -> Result<JsValue, JsValue> {
+        let texture = ktx2::parse(self.image(image as usize)?)?;
+        if texture.layer_count > 1 || texture.face_count > 1 {
+            return Err(DecodeError::new(
+                DecodeErrorCode::UnsupportedContentType,
+                format!("图像 {} 是纹理数组或立方体贴图，暂不支持", image),
+            )
+            .into());
+        }
+        let levels = js_sys::Array::new();
+        for level in texture.transcode(target)? {
+            levels.push(&js_sys::Uint8Array::from(&level[..]));
+        }
+        Ok(PlainObject::new()
+            .set("width", texture.width)
+            .set("height", texture.height)
+            .set("format", target)
+            .set("srgb", texture.native_format().is_some_and(|(_, srgb)| srgb))
+            .set("levels", levels)
+            .build())
+    }
+
+    // 图元的 WebGPU 数据段: 与 primitive() 相同的数据按 256 字节对齐排列在一块登记到缓冲区池的内存中，
+    // 可以用 queue.writeBuffer(buffer, 0, memory.buffer, data_ptr, data_len) 直接从 WASM 内存上传。
+    // 返回 { handle, data_ptr, data_len, attributes: { 名称: { offset, size, stride, count, format } },
+    // indices?, mode }，上传后调用 release_decoded_buffer(handle) 释放
+    #[wasm_bindgen(unchecked_return_type = "GpuPrimitiveLayout")]
+    pub fn gpu_primitive(&self, mesh: u32, primitive: u32) -> Result<JsValue, JsValue> {
+        let primitive = self
+            .gltf
+            .meshes
+            .get(mesh as usize)
+            .and_then(|mesh_data| mesh_data.primitives.get(primitive as usize))
+            .ok_or_else(|| invalid(format!("网格 {} 的图元 {} 不存在", mesh, primitive)))?;
+        let bin = self.bin()?;
+        let mut attributes = Vec::with_capacity(primitive.attributes.len());
+        for (name, &index) in &primitive.attributes {
+            attributes.push((name.as_str(), self.gltf.layout(index, bin.len())?));
+        }
+        let indices = primitive.indices.map(|index| self.gltf.layout(index, bin.len())).transpose()?;
+        let (data, slices, indices) = gpu_slices(bin, &attributes, indices);
+
+        let slice_object = |slice: GpuSlice| {
+            PlainObject::new()
+                .set("offset", slice.offset as u32)
+                .set("size", slice.size as u32)
+                .set("stride", slice.stride as u32)
+                .set("count", slice.count as u32)
+                .set_opt("format", slice.format)
+                .build()
+        };
+        let mut object = PlainObject::new();
+        for ((name, _), slice) in attributes.iter().zip(slices) {
+            object = object.set(name, slice_object(slice));
+        }
+        let (handle, data_ptr, data_len) = arena::register(data)?;
+        Ok(PlainObject::new()
+            .set("handle", handle)
+            .set("data_ptr", data_ptr)
+            .set("data_len", data_len)
+            .set("attributes", object.build())
+            .set_opt("indices", indices.map(slice_object))
+            .set("mode", primitive.mode.unwrap_or(4))
+            .build())
+    }
+}
+
+impl GlbAccessors {
+    fn image(&self, index: usize) -> Result<&[u8], DecodeError> {
+        let bin = self.bin()?;
+        let range = self.gltf.image_range(index, bin.len())?;
+        let data = &bin[range];
+        if !ktx2::is_ktx2(data) {
+            return Err(DecodeError::new(
+                DecodeErrorCode::UnsupportedContentType,
+                format!("图像 {} 不是 KTX2 纹理 (PNG / JPEG 等请用浏览器解码)", index),
+            ));
+        }
+        Ok(data)
+    }
+
+    fn bin(&self) -> Result<&[u8], DecodeError> {
+        let (ptr, _) = arena::lookup(self.handle)
+            .ok_or_else(|| DecodeError::new(DecodeErrorCode::InvalidState, "缓冲区已释放"))?;
+        // 安全性: 缓冲区在句柄释放前不会移动或释放，BIN 块范围在解析时已检查
+        Ok(unsafe { std::slice::from_raw_parts(ptr.add(self.bin_offset), self.bin_len) })
+    }
+
+    fn accessor_data(&self, index: usize) -> Result<AccessorData, DecodeError> {
+        let bin = self.bin()?;
+        let layout = self.gltf.layout(index, bin.len())?;
+        // JSON 块长度未按 4 字节对齐的 GLB 中 BIN 块地址可能不满足类型化数组的对齐要求
+        let address = layout.offset.map(|offset| bin.as_ptr() as usize + offset);
+        match address {
+            Some(address) if layout.is_packed() && address.is_multiple_of(layout.component_size) => {
+                Ok(AccessorData::Mapped(layout, address))
+            }
+            _ => Ok(AccessorData::Packed(layout, pack(bin, &layout))),
+        }
+    }
+
+    fn to_typed_array(&self, data: AccessorData) -> Result<JsValue, DecodeError> {
+        match data {
+            AccessorData::Mapped(layout, address) => {
+                let memory = wasm_bindgen::memory().unchecked_into::<js_sys::WebAssembly::Memory>();
+                let len = (layout.count * layout.components) as u32;
+                Ok(typed_array(layout.component_type, &memory.buffer(), address as u32, len))
+            }
+            AccessorData::Packed(layout, bytes) => {
+                let buffer = js_sys::Uint8Array::from(&bytes[..]).buffer();
+                let len = (layout.count * layout.components) as u32;
+                Ok(typed_array(layout.component_type, &buffer, 0, len))
+            }
+        }
+    }
+}
+
+impl Drop for GlbAccessors {
+    fn drop(&mut self) {
+        arena::release_decoded_buffer(self.handle);
+    }
+}
+
+// 解码 GLB 载荷并建立访问器索引，非 GLB 载荷或 glTF 结构无效时抛出异常。
+// EXT_meshopt_compression 压缩的 bufferView (gltfpack 输出) 在这里一并解码
+#[wasm_bindgen]
+pub fn decode_glb_accessors(data: &[u8]) -> Result<GlbAccessors, JsValue> {
+    let start_time = clock::now();
+    let (header, original_len, decompressed) = decode_container(data)?;
+    if header.kind != PayloadKind::Glb {
+        return Err(DecodeError::new(
+            DecodeErrorCode::UnsupportedContentType,
+            format!("载荷不是 GLB 格式 (内容类型: {})", header.kind.name()),
+        )
+        .into());
+    }
+    let mut decompressed = glb::resolve_lod(decompressed)?;
+    let parts = glb::split(&decompressed)?;
+    let json: serde_json::Value =
+        serde_json::from_slice(parts.json).map_err(|e| invalid(format!("glTF JSON 解析失败: {}", e)))?;
+    let mut gltf = Gltf::parse(&json)?;
+    let (bin_offset, mut bin_len) = match parts.bin {
+        Some(bin) => (bin.as_ptr() as usize - decompressed.as_ptr() as usize, bin.len()),
+        None => (0, 0),
+    };
+    let mut mesh_codecs = Vec::new();
+    if decode_meshopt_views(&mut gltf, &mut decompressed, bin_offset, bin_len)? > 0 {
+        bin_len = decompressed.len() - bin_offset;
+        mesh_codecs.push(EXT_MESHOPT_COMPRESSION.to_string());
+    }
+    let stats = DecodeStats {
+        mesh_codecs,
+        ..DecodeStats::from_header(&header, original_len, clock::now() - start_time)
+    };
+
+    let (handle, _, _) = arena::register(decompressed)?;
+    Ok(GlbAccessors {
+        handle,
+        bin_offset,
+        bin_len,
+        json,
+        gltf,
+        stats,
+    })
+}
@@ -0,0 +1,91 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, Once};
+use wasm_bindgen::prelude::*;
+
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::plain::PlainObject;
+
+// panic 防护
+//
+// release 构建为 panic = "abort"，panic 无法被捕获或转换为错误结果: panic hook 执行后 WASM 执行
+// unreachable 指令，JS 调用方收到 WebAssembly.RuntimeError，持有的锁和借用停留在中途状态，之后实例不再可靠。
+// 因此这里只能消除 panic 来源，而不是在导出接口外层拦截:
+// - 结果转为 JS 值时不 unwrap，转换失败 (如 JSON 中超出 2^53 的整数、非字符串的映射键) 时返回
+//   { success: false, error_code: "Internal" } 或抛出带 code 的 Error
+// - 头部字段按表读取时数据不足不会越界 (format::Field)，长度来自不可信数据的缓冲区用可失败的分配
+//   (buffer_pool::try_allocate)，失败时返回 QuotaExceeded
+// 剩余的 panic (模块缺陷) 由 panic hook 记录实例已中毒，JS 侧捕获 RuntimeError 后可用 is_poisoned()
+// 检查并重新加载模块。其他分配失败直接中止而不经过 panic hook，is_poisoned() 不会返回 true
+static POISONED: AtomicBool = AtomicBool::new(false);
+static PANIC_MESSAGE: Mutex<Option<String>> = Mutex::new(None);
+
+// 在已有的 panic hook (console_error_panic_hook) 之前记录中毒状态
+pub(crate) fn install_panic_hook() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            POISONED.store(true, Ordering::SeqCst);
+            if let Ok(mut message) = PANIC_MESSAGE.lock() {
+                *message = Some(info.to_string());
+            }
+            previous(info);
+        }));
+    });
+}
+
+// 实例是否发生过 panic。返回 true 时应丢弃当前实例并重新初始化模块
+#[wasm_bindgen]
+pub fn is_poisoned() -> bool {
+    POISONED.load(Ordering::SeqCst)
+}
+
+// 最近一次 panic 的信息 (含源码位置)，没有发生过 panic 时返回 undefined
+#[wasm_bindgen]
+pub fn last_panic_message() -> Option<String> {
+    PANIC_MESSAGE.lock().ok().and_then(|message| message.clone())
+}
+
+fn serialization_error(error: serde_wasm_bindgen::Error) -> DecodeError {
+    DecodeError::new(DecodeErrorCode::Internal, format!("结果无法转换为 JS 值: {}", error))
+}
+
+// 转为 JS 值，失败时返回错误
+pub(crate) fn try_to_js<T: Serialize + ?Sized>(value: &T) -> Result<JsValue, DecodeError> {
+    serde_wasm_bindgen::to_value(value).map_err(serialization_error)
+}
+
+// 按指定的序列化器 (如 json_compatible) 转为 JS 值，失败时返回错误
+pub(crate) fn try_to_js_with<T: Serialize + ?Sized>(
+    value: &T,
+    serializer: &serde_wasm_bindgen::Serializer,
+) -> Result<JsValue, DecodeError> {
+    value.serialize(serializer).map_err(serialization_error)
+}
+
+// 转为 JS 值，失败时返回失败结果对象 (用于返回结果对象而不抛出异常的接口)
+pub(crate) fn to_js<T: Serialize + ?Sized>(value: &T) -> JsValue {
+    try_to_js(value).unwrap_or_else(|error| failure(&error))
+}
+
+// 与 to_js 相同，使用指定的序列化器
+pub(crate) fn to_js_with<T: Serialize + ?Sized>(value: &T, serializer: &serde_wasm_bindgen::Serializer) -> JsValue {
+    try_to_js_with(value, serializer).unwrap_or_else(|error| failure(&error))
+}
+
+// { success: false, error, error_code } 形式的失败结果
+pub(crate) fn failure(error: &DecodeError) -> JsValue {
+    PlainObject::new()
+        .set("success", false)
+        .set("error", error.message.as_str())
+        .set("error_code", error.code.as_str())
+        .build()
+}
+
+// serde_json 值转为 JS 值。含有超出 2^53 的整数时按 JSON.parse 的语义转换 (损失精度)，而不是失败
+pub(crate) fn json_to_js(value: &serde_json::Value) -> JsValue {
+    try_to_js_with(value, &serde_wasm_bindgen::Serializer::json_compatible())
+        .or_else(|_| js_sys::JSON::parse(&value.to_string()))
+        .unwrap_or(JsValue::NULL)
+}
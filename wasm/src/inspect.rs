@@ -0,0 +1,22 @@
+use wasm_bindgen::prelude::*;
+
+use crate::guard;
+
+pub(crate) use fastdog_core::inspect::*;
+
+// 检查容器结构，返回各段的位置和内容。不解压载荷，因此不校验 CRC32 / SHA-256 (完整校验用 validate_deep)
+#[wasm_bindgen(unchecked_return_type = "Inspection")]
+pub fn inspect(data: &[u8]) -> JsValue {
+    // 元数据输出为普通对象而不是 Map
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+    guard::to_js_with(&inspect_container(data), &serializer)
+}
+
+// 带注释的十六进制转储，供技术支持在浏览器控制台中直接排查损坏的文件:
+// console.log(debug_dump(bytes))。按 inspect 的结果逐段输出，头部和尾部逐字段标注名称和值，
+// 魔数、版本和长度字段以 * 标记; 块偏移表、元数据区、压缩数据和多余字节每段最多显示 max_bytes 字节
+// (默认 64)。头部无法解析时列出能读到的字段，其余按原始字节显示
+#[wasm_bindgen]
+pub fn debug_dump(data: &[u8], max_bytes: Option<u32>) -> String {
+    fastdog_core::inspect::debug_dump(data, max_bytes)
+}
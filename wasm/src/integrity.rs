@@ -0,0 +1,67 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::*;
+
+use crate::decode_container;
+use crate::error::DecodeErrorCode;
+use crate::guard;
+
+pub(crate) use fastdog_core::integrity::*;
+
+// 完整性校验报告
+#[derive(Serialize, Default)]
+pub struct IntegrityReport {
+    pub valid: bool,
+    pub has_digest: bool,
+    pub checksum_verified: bool,
+    pub digest: Option<String>,
+    pub error: Option<String>,
+    pub error_code: Option<DecodeErrorCode>,
+}
+
+// 校验容器中的 SHA-256 摘要 (防篡改)
+//
+// 只解压并计算摘要，不做字符串转换。没有摘要段的文件 valid 为 false。
+#[wasm_bindgen(unchecked_return_type = "IntegrityReport")]
+pub fn verify_integrity(data: &[u8]) -> JsValue {
+    let report = match decode_container(data) {
+        Ok((header, _, decompressed)) => {
+            let actual: [u8; 32] = Sha256::digest(&decompressed).into();
+            match header.sha256 {
+                Some(expected) if expected == actual => IntegrityReport {
+                    valid: true,
+                    has_digest: true,
+                    checksum_verified: header.crc32.is_some(),
+                    digest: Some(to_hex(&actual)),
+                    error: None,
+                    error_code: None,
+                },
+                Some(expected) => IntegrityReport {
+                    has_digest: true,
+                    checksum_verified: header.crc32.is_some(),
+                    digest: Some(to_hex(&actual)),
+                    error: Some(format!(
+                        "SHA-256 校验失败: 期望 {}, 实际 {}",
+                        to_hex(&expected),
+                        to_hex(&actual)
+                    )),
+                    error_code: Some(DecodeErrorCode::ChecksumMismatch),
+                    ..Default::default()
+                },
+                None => IntegrityReport {
+                    checksum_verified: header.crc32.is_some(),
+                    digest: Some(to_hex(&actual)),
+                    error: Some("文件不包含 SHA-256 摘要".to_string()),
+                    ..Default::default()
+                },
+            }
+        }
+        Err(e) => IntegrityReport {
+            error_code: Some(e.code),
+            error: Some(e.message),
+            ..Default::default()
+        },
+    };
+
+    guard::to_js(&report)
+}
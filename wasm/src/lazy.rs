@@ -0,0 +1,108 @@
+use wasm_bindgen::prelude::*;
+
+use crate::plain::PlainObject;
+use crate::quota::{self, Reservation};
+use crate::decode_container;
+use crate::payload_kind::PayloadKind;
+
+// 惰性载荷访问器
+//
+// 解压后的数据保留在 WASM 内存中，JS 侧只按需取出小片段或执行查找，
+// 适合只需扫描或采样超大载荷的工具，避免整体复制到 JS。
+#[wasm_bindgen]
+pub struct LazyPayload {
+    data: Vec<u8>,
+    version: u32,
+    kind: PayloadKind,
+    // 载荷占用的内存配额
+    _reservation: Reservation,
+}
+
+#[wasm_bindgen]
+impl LazyPayload {
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: &[u8]) -> Result<LazyPayload, JsValue> {
+        let (header, _, decompressed) = decode_container(data)?;
+        let reservation = quota::reserve(decompressed.len())?;
+        Ok(LazyPayload {
+            data: decompressed,
+            version: header.version,
+            kind: header.kind,
+            _reservation: reservation,
+        })
+    }
+
+    // 解压后的载荷长度
+    #[wasm_bindgen]
+    pub fn len(&self) -> u32 {
+        self.data.len() as u32
+    }
+
+    #[wasm_bindgen]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn content_type(&self) -> String {
+        self.kind.name().to_string()
+    }
+
+    // 复制 [offset, offset + len) 区间的数据，超出末尾的部分会被截断
+    #[wasm_bindgen]
+    pub fn slice(&self, offset: u32, len: u32) -> Vec<u8> {
+        let start = (offset as usize).min(self.data.len());
+        let end = start.saturating_add(len as usize).min(self.data.len());
+        self.data[start..end].to_vec()
+    }
+
+    // 可结构化克隆的普通对象，data 为整个载荷的 Uint8Array 副本
+    #[wasm_bindgen(js_name = toPlainObject, unchecked_return_type = "LazyPayloadSnapshot")]
+    pub fn to_plain_object(&self) -> JsValue {
+        PlainObject::new()
+            .set("version", self.version)
+            .set("content_type", self.content_type())
+            .set("length", self.len())
+            .set_bytes("data", &self.data)
+            .build()
+    }
+
+    // 从 from 开始查找字节序列，返回首次出现的偏移量，找不到时返回 undefined
+    #[wasm_bindgen]
+    pub fn find(&self, needle: &[u8], from: Option<u32>) -> Option<u32> {
+        let from = (from.unwrap_or(0) as usize).min(self.data.len());
+        find_bytes(&self.data[from..], needle).map(|pos| (from + pos) as u32)
+    }
+}
+
+pub(crate) fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    if needle.len() > haystack.len() {
+        return None;
+    }
+
+    // 先按首字节定位候选位置，再比较剩余字节
+    let first = needle[0];
+    let last_start = haystack.len() - needle.len();
+    let mut pos = 0;
+    while pos <= last_start {
+        match haystack[pos..=last_start].iter().position(|&b| b == first) {
+            Some(offset) => {
+                let candidate = pos + offset;
+                if &haystack[candidate..candidate + needle.len()] == needle {
+                    return Some(candidate);
+                }
+                pos = candidate + 1;
+            }
+            None => return None,
+        }
+    }
+    None
+}
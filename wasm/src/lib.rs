@@ -1,9 +1,132 @@
 use wasm_bindgen::prelude::*;
-use web_sys::console;
-use flate2::read::ZlibDecoder;
-use std::io::Read;
 use serde::{Deserialize, Serialize};
-use serde_wasm_bindgen;
+
+mod abort;
+mod allocator;
+mod arena;
+mod async_decode;
+mod buffer;
+mod buffer_pool;
+mod bundle;
+mod capabilities;
+mod chunked;
+mod clock;
+mod decode_into;
+mod dictionary;
+mod diff;
+mod encoder;
+mod encryption;
+mod explain;
+mod format;
+mod glb;
+mod guard;
+mod gltf;
+mod inspect;
+mod integrity;
+mod lazy;
+mod locale;
+mod lod;
+mod logging;
+mod metadata;
+mod multi;
+mod options;
+#[cfg(feature = "threads")]
+mod parallel;
+mod password;
+mod patch;
+mod plain;
+mod pool;
+mod profiles;
+mod protobuf;
+mod quota;
+mod range_fetch;
+mod recompress;
+mod resume;
+mod sample;
+mod seek;
+mod shard;
+mod small;
+mod stream_encoder;
+mod text;
+mod transcode;
+mod transform;
+mod types;
+mod validate;
+mod web_stream;
+#[cfg(feature = "chaos")]
+mod chaos;
+
+// 格式实现位于 fastdog-core，这里在 crate 根引入，各模块仍以 crate::codec、crate::parse_header_fields 等路径使用
+use fastdog_core::{byte_reader, cbor, codec, error, json_stream, ktx2, meshopt, msgpack, payload_kind, structured};
+use fastdog_core::container::{
+    bound_stream_output, decompress_payload, decompress_payload_prefix, header_available, locate_payload,
+    locate_raw_payload, open_slice_decoder, open_stream_inflater, parse_container_header, parse_header_fields, payload_len,
+    required_header_len, split_footer_stream, verify_payload, ContainerHeader,
+};
+use fastdog_core::format::{FLAG_FOOTER, FLAG_PROVENANCE};
+use fastdog_core::integrity::verify_sha256;
+use byte_reader::ByteReader;
+use codec::Codec;
+use error::DecodeError;
+use options::OutputFormat;
+use payload_kind::PayloadKind;
+pub use allocator::{get_allocator_stats, reset_allocator_peak};
+pub use arena::{
+    allocate_decode_buffer, decoded_buffer_bytes, decoded_buffer_count, free_decoded_buffer, release_decoded_buffer,
+    retain_decoded_buffer,
+};
+pub use async_decode::decode_fastdog_binary_async;
+pub use buffer::{decode_fastdog_buffer, DecodedBuffer};
+pub use buffer_pool::{clear_pool, set_pool_limits};
+pub use bundle::{decode_bundle, decode_entry, encode_bundle, list_entries, update_archive, BundleEntry};
+pub use capabilities::get_capabilities;
+pub use chunked::{ChunkedDecoder, ChunkedStepResult};
+pub use clock::set_clock;
+pub use decode_into::{decode_into, decode_into_ptr};
+pub use dictionary::{remove_dictionary, set_dictionary};
+pub use diff::{diff_containers, diff_json};
+pub use encryption::{decode_encrypted, decode_encrypted_async};
+pub use encoder::{
+    encode_fastdog, encode_fastdog_encrypted, encode_fastdog_seekable, encode_fastdog_with_dictionary,
+    encode_fastdog_with_metadata, encode_fastdog_with_password,
+};
+pub use error::DecodeErrorCode;
+pub use explain::{explain_decode, DecodeTrace};
+pub use format::describe_format;
+pub use glb::{decode_fastdog_glb_parsed, decode_fastdog_three};
+pub use guard::{is_poisoned, last_panic_message};
+pub use gltf::{decode_glb_accessors, GlbAccessors};
+pub use inspect::{debug_dump, inspect};
+pub use integrity::verify_integrity;
+pub use lazy::LazyPayload;
+pub use locale::{get_locale, set_locale};
+pub use lod::encode_fastdog_lod;
+pub use logging::{get_log_level, set_log_level, set_logger};
+pub use metadata::get_metadata;
+pub use multi::decode_fastdog_multi;
+pub use options::DecoderOptions;
+#[cfg(feature = "threads")]
+pub use parallel::decode_fastdog_binary_parallel;
+pub use password::decode_with_password;
+pub use patch::{apply_patch, create_patch};
+pub use pool::DecoderPool;
+pub use protobuf::{clear_protobuf_descriptors, decode_fastdog_protobuf, register_protobuf_descriptors};
+pub use profiles::{
+    export_encoder_profiles, get_encoder_profile, import_encoder_profiles, list_encoder_profiles,
+    remove_encoder_profile, set_default_encoder_profile, set_encoder_profile,
+};
+pub use fastdog_core::profiles::{ChecksumMode, EncoderProfile};
+pub use quota::{get_memory_quota, get_memory_stats, memory_quota_used, set_memory_quota};
+pub use range_fetch::RangeFetcher;
+pub use recompress::recompress_report;
+pub use sample::make_sample;
+pub use seek::decode_range;
+pub use shard::{split_shards, ShardAssembler};
+pub use small::{decode_fastdog_small, decode_fastdog_small_batch};
+pub use stream_encoder::StreamEncoder;
+pub use transcode::transcode;
+pub use validate::{validate_deep, validate_strict, ValidationReport};
+pub use web_stream::{create_decode_transform, decode_from_blob, decode_from_response};
 
 // 当 `console_error_panic_hook` 功能启用时，我们可以调用
 // `set_panic_hook` 函数至少一次在初始化期间，然后我们将获得
@@ -16,56 +139,104 @@ fn set_panic_hook() {
     console_error_panic_hook::set_once();
 }
 
-// 使用 `wee_alloc` 作为全局分配器。
-#[cfg(feature = "wee_alloc")]
-#[global_allocator]
-static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
-
 // 定义解码结果结构
 #[derive(Serialize, Deserialize)]
 pub struct DecodeResult {
     pub success: bool,
     pub data: Option<String>,
     pub error: Option<String>,
+    pub error_code: Option<DecodeErrorCode>,
     pub stats: DecodeStats,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct DecodeStats {
-    pub original_size: u32,
-    pub compressed_size: u32,
+    // 64 位长度，在 JS 中为 number (2^53 以内精确)
+    pub original_size: u64,
+    pub compressed_size: u64,
     pub decode_time_ms: f64,
     pub compression_ratio: f32,
     pub format_version: u32,
+    pub codec: String,
+    pub checksum_verified: bool,
+    // 解码时应用的网格压缩扩展 (如 EXT_meshopt_compression)，仅 GLB 访问器接口填写
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mesh_codecs: Vec<String>,
 }
 
-// 日志宏
-macro_rules! log {
-    ( $( $t:tt )* ) => {
-        console::log_1(&format!( $( $t )* ).into());
+impl DecodeStats {
+    fn from_header(header: &ContainerHeader, original_len: u64, decode_time_ms: f64) -> DecodeStats {
+        DecodeStats {
+            original_size: original_len,
+            compressed_size: header.compressed_len as u64,
+            decode_time_ms,
+            compression_ratio: header.compression_ratio(original_len),
+            format_version: header.version,
+            codec: header.codec.name().to_string(),
+            checksum_verified: header.crc32.is_some(),
+            mesh_codecs: Vec::new(),
+        }
+    }
+    
+    // 解码失败时返回的统计信息
+    fn failed(compressed_size: u64, decode_time_ms: f64) -> DecodeStats {
+        DecodeStats {
+            original_size: 0,
+            compressed_size,
+            decode_time_ms,
+            compression_ratio: 0.0,
+            format_version: 0,
+            codec: String::new(),
+            checksum_verified: false,
+            mesh_codecs: Vec::new(),
+        }
     }
 }
 
-// 简单的base64编码实现
-fn base64_encode(data: &[u8]) -> String {
-    const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-    let mut result = String::new();
-    
-    for chunk in data.chunks(3) {
-        let mut buf = [0u8; 3];
-        for (i, &byte) in chunk.iter().enumerate() {
-            buf[i] = byte;
+// 日志宏: log!(Info, "...")，级别未开启时不格式化消息
+macro_rules! log {
+    ( $level:ident, $( $t:tt )* ) => {
+        if logging::enabled(logging::LogLevel::$level) {
+            logging::write(logging::LogLevel::$level, &format!( $( $t )* ));
         }
-        
-        let b = ((buf[0] as u32) << 16) | ((buf[1] as u32) << 8) | (buf[2] as u32);
-        
-        result.push(CHARS[((b >> 18) & 63) as usize] as char);
-        result.push(CHARS[((b >> 12) & 63) as usize] as char);
-        result.push(if chunk.len() > 1 { CHARS[((b >> 6) & 63) as usize] as char } else { '=' });
-        result.push(if chunk.len() > 2 { CHARS[(b & 63) as usize] as char } else { '=' });
     }
+}
+
+// 生成 {"type":"<kind>","data":"<base64>"} JSON 字符串 (如 {"type":"glb",...})
+// base64 crate 按块批量编码，比逐字节循环快得多
+fn binary_base64_json(kind: PayloadKind, data: &[u8]) -> String {
+    use base64::Engine;
+    const SUFFIX: &str = "\"}";
+    let prefix = format!("{{\"type\":\"{}\",\"data\":\"", kind.name());
+    
+    let mut json = String::with_capacity(prefix.len() + data.len().div_ceil(3) * 4 + SUFFIX.len());
+    json.push_str(&prefix);
+    base64::engine::general_purpose::STANDARD.encode_string(data, &mut json);
+    json.push_str(SUFFIX);
+    json
+}
+
+// base64 解码，忽略空白字符，兼容标准和 URL 安全字母表，填充可有可无
+fn base64_decode_bytes(input: &str) -> Result<Vec<u8>, DecodeError> {
+    use base64::alphabet;
+    use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+    use base64::Engine;
+    
+    const CONFIG: GeneralPurposeConfig = GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+    const STANDARD: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, CONFIG);
+    const URL_SAFE: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, CONFIG);
     
-    result
+    let compact: String = input.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    let engine = if compact.contains(['-', '_']) { URL_SAFE } else { STANDARD };
+    engine
+        .decode(compact.as_bytes())
+        .map_err(|e| DecodeError::new(DecodeErrorCode::InvalidInput, format!("base64 解码失败: {}", e)))
+}
+
+// base64 解码导出，便于接收 base64 数据的管线全程在 WASM 内处理
+#[wasm_bindgen]
+pub fn base64_decode(input: &str) -> Result<Vec<u8>, JsValue> {
+    base64_decode_bytes(input).map_err(JsValue::from)
 }
 
 // 初始化函数
@@ -73,31 +244,188 @@ fn base64_encode(data: &[u8]) -> String {
 pub fn init() {
     #[cfg(feature = "console_error_panic_hook")]
     set_panic_hook();
+    guard::install_panic_hook();
     
-    log!("🚀 FastDog WASM Decoder initialized");
+    log!(Info, "🚀 FastDog WASM Decoder initialized");
 }
 
 // 主要的解码函数
-#[wasm_bindgen]
+#[wasm_bindgen(unchecked_return_type = "DecodeResult")]
 pub fn decode_fastdog_binary(data: &[u8]) -> JsValue {
-    let start_time = js_sys::Date::now();
+    let start_time = clock::now();
     
-    match decode_binary_internal(data, start_time) {
-        Ok(result) => serde_wasm_bindgen::to_value(&result).unwrap(),
+    match decode_container(data).and_then(|decoded| into_decode_value(decoded, start_time)) {
+        Ok(value) => value,
+        Err(error) => {
+            let error_result = DecodeResult {
+                success: false,
+                data: None,
+                error_code: Some(error.code),
+                error: Some(error.message),
+                stats: DecodeStats::failed(data.len() as u64, clock::now() - start_time),
+            };
+            guard::to_js(&error_result)
+        }
+    }
+}
+
+// 带选项的解码函数
+#[wasm_bindgen(unchecked_return_type = "DecodeResult")]
+pub fn decode_fastdog_binary_with_options(data: &[u8], options: &DecoderOptions) -> JsValue {
+    use base64::Engine;
+    
+    let start_time = options.now();
+    let max_decode_ms = options.max_decode_ms();
+    
+    let result = locate_payload(data)
+        .and_then(|(header, _, original_len)| {
+            if options.strict() {
+                check_strict(data, &header)?;
+            }
+            
+            // 头部声明的原始长度先检查一次输出限制，声明值不可信，解压过程中再按实际输出检查
+            options.check_output(header.compressed_len, payload_len(original_len)?)?;
+            if let Some(shared) = options.shared_output() {
+                shared.check(payload_len(original_len)?)?;
+            }
+            
+            // 在每个解压分片边界检查输出限制和是否超时，避免异常输入耗尽内存或长时间卡住设备
+            decode_container_sliced(data, |decompressed_len| {
+                options.check_output(header.compressed_len, decompressed_len)?;
+                clock::check_deadline(start_time, options.now(), max_decode_ms)
+            })
+        })
+        .and_then(|(header, original_len, decompressed)| {
+            if options.strict() {
+                verify_sha256(&header, &decompressed)?;
+            }
+            
+            // 在返回前执行解码转换钩子
+            let content_type = header.kind.name();
+            let transformed = transform::apply_transforms(options.transforms(), decompressed, content_type)
+                .map_err(|e| DecodeError::new(DecodeErrorCode::TransformError, e))?;
+            
+            // 写入 SharedArrayBuffer 时不再生成其他形式的输出
+            let (data, output) = if let Some(shared) = options.shared_output() {
+                let region = shared.write(&transformed)?;
+                buffer_pool::recycle(transformed);
+                (None, Some(ExtraOutput::Shared(region)))
+            } else {
+                match options.format() {
+                    // 大文本载荷由 TextDecoder 直接转为 JS 字符串
+                    OutputFormat::Auto | OutputFormat::Text
+                        if (header.kind.is_text() || options.format() == OutputFormat::Text)
+                            && transformed.len() >= text::TEXT_DECODER_THRESHOLD =>
+                    {
+                        let text = text::utf8_to_js(&transformed)?;
+                        buffer_pool::recycle(transformed);
+                        (None, Some(ExtraOutput::Data(text)))
+                    }
+                    OutputFormat::Auto => (Some(payload_string(&header, transformed)?), None),
+                    OutputFormat::Text => (Some(utf8_string(transformed)?), None),
+                    OutputFormat::Base64 => (Some(base64::engine::general_purpose::STANDARD.encode(&transformed)), None),
+                    OutputFormat::Bytes => (None, Some(ExtraOutput::Bytes(transformed))),
+                    OutputFormat::Object => (None, Some(ExtraOutput::Value(payload_value(&header, &transformed)?))),
+                    OutputFormat::ArrayBuffer => (None, Some(ExtraOutput::Transferable(transformed))),
+                }
+            };
+            let result = DecodeResult {
+                success: true,
+                data,
+                error: None,
+                error_code: None,
+                stats: DecodeStats::from_header(&header, original_len, options.now() - start_time),
+            };
+            Ok((result, output))
+        });
+    
+    match result {
+        Ok((result, output)) => {
+            // 按次开启的日志不受全局日志级别限制
+            if options.log() {
+                let message = format!(
+                    "✅ 解码完成: {} → {} bytes, {:.2} ms",
+                    result.stats.compressed_size, result.stats.original_size, result.stats.decode_time_ms
+                );
+                logging::write(logging::LogLevel::Info, &message);
+            }
+            let value = guard::to_js(&result);
+            // 对新建的结果对象设置属性不会失败
+            match output {
+                Some(ExtraOutput::Bytes(bytes)) => {
+                    let _ = js_sys::Reflect::set(&value, &"bytes".into(), &js_sys::Uint8Array::from(&bytes[..]));
+                    buffer_pool::recycle(bytes);
+                }
+                Some(ExtraOutput::Transferable(bytes)) => {
+                    // 新建的 Uint8Array 自带独立的 ArrayBuffer，不与 WASM 内存 (包括多线程构建的共享内存) 关联
+                    let array = js_sys::Uint8Array::new_with_length(bytes.len() as u32);
+                    array.copy_from(&bytes);
+                    let _ = js_sys::Reflect::set(&value, &"buffer".into(), &array.buffer());
+                    buffer_pool::recycle(bytes);
+                }
+                Some(ExtraOutput::Value(parsed)) => {
+                    let _ = js_sys::Reflect::set(&value, &"value".into(), &parsed);
+                }
+                Some(ExtraOutput::Data(text)) => {
+                    let _ = js_sys::Reflect::set(&value, &"data".into(), &text);
+                }
+                Some(ExtraOutput::Shared(region)) => {
+                    let _ = js_sys::Reflect::set(&value, &"shared_output".into(), &region);
+                }
+                None => {}
+            }
+            value
+        }
+        Err(error) => {
+            if options.log() {
+                let message = format!("❌ 解码失败 ({}): {}", error.code.as_str(), error.message);
+                logging::write(logging::LogLevel::Error, &message);
+            }
+            let error_result = DecodeResult {
+                success: false,
+                data: None,
+                error_code: Some(error.code),
+                error: Some(error.message),
+                stats: DecodeStats::failed(data.len() as u64, options.now() - start_time),
+            };
+            guard::to_js(&error_result)
+        }
+    }
+}
+
+// 严格模式的头部检查: 容器之后不能有多余字节，头部必须带校验字段
+fn check_strict(data: &[u8], header: &ContainerHeader) -> Result<(), DecodeError> {
+    if data.len() != header.total_len() {
+        return Err(DecodeError::new(
+            DecodeErrorCode::LengthMismatch,
+            format!("容器之后有多余数据: 容器 {} 字节, 输入 {} 字节", header.total_len(), data.len()),
+        ));
+    }
+    if header.crc32.is_none() && header.sha256.is_none() {
+        return Err(DecodeError::new(
+            DecodeErrorCode::UnsupportedFlags,
+            "严格模式要求头部带 CRC32 或 SHA-256 校验字段",
+        ));
+    }
+    Ok(())
+}
+
+// 解码 base64 编码的 FastDog 数据，结果格式与 decode_fastdog_binary 相同
+#[wasm_bindgen(unchecked_return_type = "DecodeResult")]
+pub fn decode_fastdog_binary_b64(input: &str) -> JsValue {
+    let start_time = clock::now();
+    
+    match base64_decode_bytes(input) {
+        Ok(data) => decode_fastdog_binary(&data),
         Err(error) => {
             let error_result = DecodeResult {
                 success: false,
                 data: None,
-                error: Some(error),
-                stats: DecodeStats {
-                    original_size: 0,
-                    compressed_size: data.len() as u32,
-                    decode_time_ms: js_sys::Date::now() - start_time,
-                    compression_ratio: 0.0,
-                    format_version: 0,
-                },
+                error_code: Some(error.code),
+                error: Some(error.message),
+                stats: DecodeStats::failed(input.len() as u64, clock::now() - start_time),
             };
-            serde_wasm_bindgen::to_value(&error_result).unwrap()
+            guard::to_js(&error_result)
         }
     }
 }
@@ -106,34 +434,33 @@ pub fn decode_fastdog_binary(data: &[u8]) -> JsValue {
 #[derive(Serialize, Deserialize)]
 pub struct BinaryDecodeResult {
     pub success: bool,
+    // 缓冲区句柄，使用完毕后调用 release_decoded_buffer(handle) 或 free_decoded_buffer(data_ptr, data_len) 释放
+    pub handle: u32,
     pub data_ptr: u32,
     pub data_len: u32,
     pub error: Option<String>,
+    pub error_code: Option<DecodeErrorCode>,
     pub stats: DecodeStats,
 }
 
-// 零拷贝二进制解码函数
-#[wasm_bindgen]
+// 零拷贝二进制解码函数 (裸指针接口，新代码请使用 decode_fastdog_buffer 返回的 DecodedBuffer)
+#[wasm_bindgen(unchecked_return_type = "BinaryDecodeResult")]
 pub fn decode_fastdog_binary_zero_copy(data: &[u8]) -> JsValue {
-    let start_time = js_sys::Date::now();
+    let start_time = clock::now();
     
     match decode_binary_internal_zero_copy(data, start_time) {
-        Ok(result) => serde_wasm_bindgen::to_value(&result).unwrap(),
+        Ok(result) => guard::to_js(&result),
         Err(error) => {
             let error_result = BinaryDecodeResult {
                 success: false,
+                handle: 0,
                 data_ptr: 0,
                 data_len: 0,
-                error: Some(error),
-                stats: DecodeStats {
-                    original_size: 0,
-                    compressed_size: data.len() as u32,
-                    decode_time_ms: js_sys::Date::now() - start_time,
-                    compression_ratio: 0.0,
-                    format_version: 0,
-                },
+                error_code: Some(error.code),
+                error: Some(error.message),
+                stats: DecodeStats::failed(data.len() as u64, clock::now() - start_time),
             };
-            serde_wasm_bindgen::to_value(&error_result).unwrap()
+            guard::to_js(&error_result)
         }
     }
 }
@@ -141,258 +468,217 @@ pub fn decode_fastdog_binary_zero_copy(data: &[u8]) -> JsValue {
 // 直接返回二进制数据的解码函数
 #[wasm_bindgen]
 pub fn decode_fastdog_to_binary(data: &[u8]) -> Vec<u8> {
-    match decode_binary_raw(data) {
-        Ok(binary_data) => binary_data,
-        Err(_) => Vec::new(), // 错误时返回空向量
+    // 错误时返回空向量
+    decode_binary_raw(data).unwrap_or_default()
+}
+
+// GLB 载荷 (内容类型 2) 直接以 Uint8Array 返回，避免 base64 编码和 JSON 字符串化，
+// three.js 等加载器可以直接使用。非 GLB 载荷或解码失败时抛出异常
+#[wasm_bindgen]
+pub fn decode_fastdog_glb(data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let (header, _, decompressed) = decode_container(data)?;
+    if header.kind != PayloadKind::Glb {
+        return Err(DecodeError::new(
+            DecodeErrorCode::UnsupportedContentType,
+            format!("载荷不是 GLB 格式 (内容类型: {})", header.kind.name()),
+        )
+        .into());
     }
+    Ok(decompressed)
 }
 
 // 获取解码统计信息的单独函数
-#[wasm_bindgen]
+#[wasm_bindgen(unchecked_return_type = "DecodeStats")]
 pub fn get_decode_stats(data: &[u8]) -> JsValue {
-    let start_time = js_sys::Date::now();
+    let start_time = clock::now();
     
     match decode_binary_internal(data, start_time) {
-        Ok(result) => serde_wasm_bindgen::to_value(&result.stats).unwrap(),
+        Ok(result) => guard::to_js(&result.stats),
         Err(_) => {
-            let error_stats = DecodeStats {
-                original_size: 0,
-                compressed_size: data.len() as u32,
-                decode_time_ms: js_sys::Date::now() - start_time,
-                compression_ratio: 0.0,
-                format_version: 0,
-            };
-            serde_wasm_bindgen::to_value(&error_stats).unwrap()
+            let error_stats = DecodeStats::failed(data.len() as u64, clock::now() - start_time);
+            guard::to_js(&error_stats)
         }
     }
 }
 
 // 内部解码实现
-fn decode_binary_internal(data: &[u8], start_time: f64) -> Result<DecodeResult, String> {
-    if data.len() < 20 {
-        return Err("数据太短，不是有效的 FastDog 格式".to_string());
-    }
-    
-    let mut cursor = 0;
-    
-    // 1. 验证魔数 (8字节)
-    let magic = &data[cursor..cursor + 8];
-    if magic != b"FASTDOG1" {
-        return Err(format!("无效的魔数: {:?}", magic));
-    }
-    cursor += 8;
-    
-    // 2. 读取版本号 (4字节)
-    let version = u32::from_le_bytes([
-        data[cursor], data[cursor + 1], data[cursor + 2], data[cursor + 3]
-    ]);
-    cursor += 4;
-    
-    if version != 1 && version != 2 {
-        return Err(format!("不支持的版本: {}", version));
-    }
-    
-    // 3. 读取压缩数据长度 (4字节)
-    let compressed_len = u32::from_le_bytes([
-        data[cursor], data[cursor + 1], data[cursor + 2], data[cursor + 3]
-    ]) as usize;
-    cursor += 4;
-    
-    // 4. 读取压缩数据
-    if cursor + compressed_len > data.len() {
-        return Err("压缩数据长度超出范围".to_string());
+fn decode_binary_internal(data: &[u8], start_time: f64) -> Result<DecodeResult, DecodeError> {
+    into_decode_result(decode_container(data)?, start_time)
+}
+
+// 将解压结果按内容类型转换为 DecodeResult
+fn into_decode_result(
+    (header, original_len, decompressed): (ContainerHeader, u64, Vec<u8>),
+    start_time: f64,
+) -> Result<DecodeResult, DecodeError> {
+    let decode_time = clock::now() - start_time;
+
+    let data_result = payload_string(&header, decompressed)?;
+
+    Ok(DecodeResult {
+        success: true,
+        data: Some(data_result),
+        error: None,
+        error_code: None,
+        stats: DecodeStats::from_header(&header, original_len, decode_time),
+    })
+}
+
+// 与 into_decode_result 相同，直接输出 JS 对象: 大 JSON 载荷由 TextDecoder 转为字符串，不经过 Rust String
+pub(crate) fn into_decode_value(
+    (header, original_len, decompressed): (ContainerHeader, u64, Vec<u8>),
+    start_time: f64,
+) -> Result<JsValue, DecodeError> {
+    if !header.kind.is_text() || decompressed.len() < text::TEXT_DECODER_THRESHOLD {
+        let result = into_decode_result((header, original_len, decompressed), start_time)?;
+        let value = guard::try_to_js(&result)?;
+        // 结果字符串 (JSON 载荷由解压缓冲区原地转换而来) 转为 JS 字符串后放回复用池
+        if let Some(data) = result.data {
+            buffer_pool::recycle(data.into_bytes());
+        }
+        return Ok(value);
     }
-    
-    let compressed_data = &data[cursor..cursor + compressed_len];
-    cursor += compressed_len;
-    
-    // 5. 读取原始数据长度 (4字节) - 用于验证
-    if cursor + 4 > data.len() {
-        return Err("缺少原始数据长度字段".to_string());
+    let decode_time = clock::now() - start_time;
+    let data = text::utf8_to_js(&decompressed)?;
+    buffer_pool::recycle(decompressed);
+    let result = DecodeResult {
+        success: true,
+        data: None,
+        error: None,
+        error_code: None,
+        stats: DecodeStats::from_header(&header, original_len, decode_time),
+    };
+    let value = guard::try_to_js(&result)?;
+    // 对新建的结果对象设置属性不会失败
+    let _ = js_sys::Reflect::set(&value, &"data".into(), &data);
+    Ok(value)
+}
+
+// 根据载荷类型把载荷转换为结果中的字符串
+fn payload_string(header: &ContainerHeader, decompressed: Vec<u8>) -> Result<String, DecodeError> {
+    if header.kind.is_text() {
+        // JSON 载荷: 转换为 UTF-8 字符串
+        utf8_string(decompressed)
+    } else {
+        // 二进制载荷 (GLB 等): 使用 base64 编码，直接写入预分配的字符串以避免再复制一次
+        let json = binary_base64_json(header.kind, &decompressed);
+        buffer_pool::recycle(decompressed);
+        Ok(json)
     }
-    
-    let original_len = u32::from_le_bytes([
-        data[cursor], data[cursor + 1], data[cursor + 2], data[cursor + 3]
-    ]);
-    
-    // 6. 解压缩数据
-    let mut decoder = ZlibDecoder::new(compressed_data);
-    let mut decompressed = Vec::new();
-    
-    match decoder.read_to_end(&mut decompressed) {
-        Ok(_) => {
-            // 验证解压后的数据长度
-            if decompressed.len() != original_len as usize {
-                return Err(format!(
-                    "解压后数据长度不匹配: 期望 {}, 实际 {}",
-                    original_len,
-                    decompressed.len()
-                ));
-            }
-            
-            let decode_time = js_sys::Date::now() - start_time;
-            
-            // 根据版本处理数据
-            let data_result = if version == 1 {
-                // 版本1: JSON格式，转换为UTF-8字符串
-                match String::from_utf8(decompressed) {
-                    Ok(json_str) => json_str,
-                    Err(e) => return Err(format!("UTF-8 解码失败: {}", e)),
-                }
-            } else if version == 2 {
-                // 版本2: GLB二进制格式，使用简单的base64编码
-                let base64_str = base64_encode(&decompressed);
-                format!("{{\"type\":\"glb\",\"data\":\"{}\"}}", base64_str)
-            } else {
-                return Err(format!("不支持的版本: {}", version));
-            };
-            
-            Ok(DecodeResult {
-                success: true,
-                data: Some(data_result),
-                error: None,
-                stats: DecodeStats {
-                    original_size: original_len,
-                    compressed_size: compressed_len as u32,
-                    decode_time_ms: decode_time,
-                    compression_ratio: compressed_len as f32 / original_len as f32,
-                    format_version: version,
-                },
-            })
+}
+
+// 结果对象上 data 之外的输出字段
+enum ExtraOutput {
+    Bytes(Vec<u8>),
+    // 以独立 ArrayBuffer 输出的载荷
+    Transferable(Vec<u8>),
+    Value(JsValue),
+    // 已转换为 JS 字符串的 data
+    Data(JsValue),
+    // 写入 SharedArrayBuffer 的区域
+    Shared(JsValue),
+}
+
+// 结构化载荷直接从字节解析为 JS 值，不经过中间字符串: JSON 由 serde_json 解析，MessagePack / CBOR 由内置解码器解析
+fn payload_value(header: &ContainerHeader, decompressed: &[u8]) -> Result<JsValue, DecodeError> {
+    // 对象输出为普通对象而不是 Map
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    let value = match header.kind {
+        PayloadKind::Json => serde_json::from_slice::<serde_json::Value>(decompressed)
+            .map_err(|e| DecodeError::new(DecodeErrorCode::InvalidInput, format!("JSON 解析失败: {}", e)))?
+            .serialize(&serializer),
+        PayloadKind::MsgPack => msgpack::decode(decompressed)?.serialize(&serializer),
+        PayloadKind::Cbor => cbor::decode(decompressed)?.serialize(&serializer),
+        kind => {
+            return Err(DecodeError::new(
+                DecodeErrorCode::UnsupportedContentType,
+                format!("输出形式 object 只适用于 JSON / MessagePack / CBOR 载荷，当前内容类型为 {}", kind.name()),
+            ));
         }
-        Err(e) => Err(format!("解压缩失败: {}", e)),
-    }
+    };
+    value.map_err(|e| DecodeError::new(DecodeErrorCode::InvalidInput, format!("载荷无法转换为 JS 值: {}", e)))
+}
+
+fn utf8_string(bytes: Vec<u8>) -> Result<String, DecodeError> {
+    String::from_utf8(bytes)
+        .map_err(|e| DecodeError::new(DecodeErrorCode::Utf8Error, format!("UTF-8 解码失败: {}", e)))
 }
 
 // 零拷贝解码内部实现
-fn decode_binary_internal_zero_copy(data: &[u8], start_time: f64) -> Result<BinaryDecodeResult, String> {
-    let decompressed = decode_binary_raw(data)?;
-    let decode_time = js_sys::Date::now() - start_time;
-    
-    // 将数据存储在静态内存中，返回指针
-    let data_ptr = decompressed.as_ptr() as u32;
-    let data_len = decompressed.len() as u32;
-    
-    // 防止数据被释放，使用Box::leak
-    let leaked_data = Box::leak(decompressed.into_boxed_slice());
-    
-    // 获取格式信息
-    let (original_len, compressed_len, version) = get_format_metadata(data)?;
-    
+fn decode_binary_internal_zero_copy(data: &[u8], start_time: f64) -> Result<BinaryDecodeResult, DecodeError> {
+    let (header, original_len, decompressed) = decode_container(data)?;
+    let decode_time = clock::now() - start_time;
+
+    // 将数据登记到缓冲区池中，句柄释放前地址保持不变，返回句柄和指针
+    let (handle, data_ptr, data_len) = arena::register(decompressed)?;
+
     Ok(BinaryDecodeResult {
         success: true,
+        handle,
         data_ptr,
         data_len,
         error: None,
-        stats: DecodeStats {
-            original_size: original_len,
-            compressed_size: compressed_len,
-            decode_time_ms: decode_time,
-            compression_ratio: compressed_len as f32 / original_len as f32,
-            format_version: version,
-        },
+        error_code: None,
+        stats: DecodeStats::from_header(&header, original_len, decode_time),
     })
 }
 
 // 原始二进制解码函数
-fn decode_binary_raw(data: &[u8]) -> Result<Vec<u8>, String> {
-    if data.len() < 20 {
-        return Err("数据太短，不是有效的 FastDog 格式".to_string());
-    }
-    
-    let mut cursor = 0;
-    
-    // 1. 验证魔数 (8字节)
-    let magic = &data[cursor..cursor + 8];
-    if magic != b"FASTDOG1" {
-        return Err(format!("无效的魔数: {:?}", magic));
-    }
-    cursor += 8;
-    
-    // 2. 读取版本号 (4字节)
-    let version = u32::from_le_bytes([
-        data[cursor], data[cursor + 1], data[cursor + 2], data[cursor + 3]
-    ]);
-    cursor += 4;
-    
-    if version != 1 && version != 2 {
-        return Err(format!("不支持的版本: {}", version));
-    }
-    
-    // 3. 读取压缩数据长度 (4字节)
-    let compressed_len = u32::from_le_bytes([
-        data[cursor], data[cursor + 1], data[cursor + 2], data[cursor + 3]
-    ]) as usize;
-    cursor += 4;
-    
-    // 4. 读取压缩数据
-    if cursor + compressed_len > data.len() {
-        return Err("压缩数据长度超出范围".to_string());
-    }
-    
-    let compressed_data = &data[cursor..cursor + compressed_len];
-    cursor += compressed_len;
-    
-    // 5. 读取原始数据长度 (4字节) - 用于验证
-    if cursor + 4 > data.len() {
-        return Err("缺少原始数据长度字段".to_string());
-    }
-    
-    let original_len = u32::from_le_bytes([
-        data[cursor], data[cursor + 1], data[cursor + 2], data[cursor + 3]
-    ]);
-    
-    // 6. 解压缩数据
-    let mut decoder = ZlibDecoder::new(compressed_data);
-    let mut decompressed = Vec::with_capacity(original_len as usize);
-    
-    match decoder.read_to_end(&mut decompressed) {
-        Ok(_) => {
-            // 验证解压后的数据长度
-            if decompressed.len() != original_len as usize {
-                return Err(format!(
-                    "解压后数据长度不匹配: 期望 {}, 实际 {}",
-                    original_len,
-                    decompressed.len()
-                ));
-            }
-            
-            Ok(decompressed)
-        }
-        Err(e) => Err(format!("解压缩失败: {}", e)),
-    }
+fn decode_binary_raw(data: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let (_, _, decompressed) = decode_container(data)?;
+    Ok(decompressed)
 }
 
-// 获取格式元数据
-fn get_format_metadata(data: &[u8]) -> Result<(u32, u32, u32), String> {
-    if data.len() < 20 {
-        return Err("数据太短".to_string());
-    }
-    
-    let mut cursor = 8; // 跳过魔数
-    
-    // 读取版本号
-    let version = u32::from_le_bytes([
-        data[cursor], data[cursor + 1], data[cursor + 2], data[cursor + 3]
-    ]);
-    cursor += 4;
-    
-    // 读取压缩数据长度
-    let compressed_len = u32::from_le_bytes([
-        data[cursor], data[cursor + 1], data[cursor + 2], data[cursor + 3]
-    ]);
-    cursor += 4;
+// 解析并解压完整容器，返回头部、原始长度和解压后的数据
+fn decode_container(data: &[u8]) -> Result<(ContainerHeader, u64, Vec<u8>), DecodeError> {
+    decode_container_sliced(data, |_| Ok(()))
+}
+
+// 分片解压版本，on_slice 在每个解压分片边界调用，返回错误即中止解码
+fn decode_container_sliced<F>(data: &[u8], on_slice: F) -> Result<(ContainerHeader, u64, Vec<u8>), DecodeError>
+where
+    F: FnMut(usize) -> Result<(), DecodeError>,
+{
+    let (header, compressed_data, original_len) = locate_payload(data)?;
+    inflate_container(header, compressed_data, original_len, on_slice)
+}
+
+// 解压已定位 (加密容器已解密) 的压缩数据并校验结果
+fn inflate_container<F>(
+    header: ContainerHeader,
+    compressed_data: &[u8],
+    original_len: u64,
+    on_slice: F,
+) -> Result<(ContainerHeader, u64, Vec<u8>), DecodeError>
+where
+    F: FnMut(usize) -> Result<(), DecodeError>,
+{
+    quota::begin_decode();
     
-    cursor += compressed_len as usize; // 跳过压缩数据
+    // 按头部声明的大小预先登记内存配额，解压过程中输出超出声明大小时继续按实际大小登记
+    let mut reservation = quota::reserve(payload_len(original_len)?)?;
+    let mut on_slice = on_slice;
+    let on_slice = |decompressed_len: usize| {
+        reservation.grow_to(decompressed_len)?;
+        on_slice(decompressed_len)
+    };
+
+    // 解压缩数据
+    #[cfg(feature = "chaos")]
+    chaos::maybe_fail_inflate()?;
     
-    // 读取原始数据长度
-    let original_len = u32::from_le_bytes([
-        data[cursor], data[cursor + 1], data[cursor + 2], data[cursor + 3]
-    ]);
+    #[allow(unused_mut)]
+    let mut decompressed = decompress_payload(&header, compressed_data, original_len, on_slice)?;
     
-    Ok((original_len, compressed_len, version))
+    #[cfg(feature = "chaos")]
+    chaos::maybe_corrupt(&mut decompressed);
+
+    verify_payload(&header, original_len, &decompressed)?;
+
+    Ok((header, original_len, decompressed))
 }
 
+
 // 验证二进制格式的函数
 #[wasm_bindgen]
 pub fn validate_fastdog_format(data: &[u8]) -> bool {
@@ -408,117 +694,222 @@ pub fn validate_fastdog_format(data: &[u8]) -> bool {
     
     // 检查版本
     let version = u32::from_le_bytes([data[8], data[9], data[10], data[11]]);
-    version == 1 || version == 2
+    version == 1 || version == 2 || version == 3
 }
 
 // 获取格式信息的函数
-#[wasm_bindgen]
+#[wasm_bindgen(unchecked_return_type = "FormatInfo")]
 pub fn get_format_info(data: &[u8]) -> JsValue {
     #[derive(Serialize)]
-    struct FormatInfo {
+    struct FormatInfo<'a> {
         valid: bool,
-        magic: String,
+        magic: &'a str,
         version: u32,
-        compressed_size: u32,
-        original_size: u32,
-        total_size: u32,
-    }
-    
-    if data.len() < 20 {
-        let info = FormatInfo {
-            valid: false,
-            magic: "N/A".to_string(),
-            version: 0,
-            compressed_size: 0,
-            original_size: 0,
-            total_size: data.len() as u32,
-        };
-        return serde_wasm_bindgen::to_value(&info).unwrap();
+        codec: &'a str,
+        compressed_size: f64,
+        original_size: f64,
+        total_size: f64,
+        // 来源信息 (头部带有 FLAG_PROVENANCE 时存在)
+        created_at: Option<f64>,
+        producer: Option<&'a str>,
     }
-    
-    let magic = String::from_utf8_lossy(&data[0..8]).to_string();
-    let version = u32::from_le_bytes([data[8], data[9], data[10], data[11]]);
-    let compressed_size = u32::from_le_bytes([data[12], data[13], data[14], data[15]]);
-    let original_size = if data.len() >= 20 + compressed_size as usize {
-        u32::from_le_bytes([
-            data[16 + compressed_size as usize],
-            data[17 + compressed_size as usize],
-            data[18 + compressed_size as usize],
-            data[19 + compressed_size as usize],
-        ])
-    } else {
-        0
-    };
-    
+
+    // 由 inspect 的结果得到精简的格式信息，各版本的有效性判断与解码一致
+    let inspection = inspect::inspect_container(data);
     let info = FormatInfo {
-        valid: magic == "FASTDOG1" && version == 1,
-        magic,
-        version,
-        compressed_size,
-        original_size,
-        total_size: data.len() as u32,
+        valid: inspection.valid,
+        magic: inspection.magic().unwrap_or("N/A"),
+        version: inspection.version().unwrap_or(0),
+        codec: inspection.codec.unwrap_or(""),
+        compressed_size: inspection.compressed_size().unwrap_or(0.0),
+        original_size: inspection.original_size().unwrap_or(0.0),
+        total_size: inspection.total_size,
+        created_at: inspection.created_at(),
+        producer: inspection.producer(),
     };
     
-    serde_wasm_bindgen::to_value(&info).unwrap()
+    guard::to_js(&info)
 }
 
 // 性能基准测试函数
-#[wasm_bindgen]
-pub fn benchmark_decode(data: &[u8], iterations: u32) -> JsValue {
+//
+// warmup 次预热解码不计入统计 (省略时为 iterations 的 10%，最多 10 次)，用于排除首次调用时的
+// 内存增长和 JIT 影响。百分位按最近秩法计算，吞吐量按成功解码的原始大小计算 (MB = 1024 * 1024 字节)
+#[wasm_bindgen(unchecked_return_type = "BenchmarkResult")]
+pub fn benchmark_decode(data: &[u8], iterations: u32, warmup: Option<u32>) -> JsValue {
     #[derive(Serialize)]
     struct BenchmarkResult {
         iterations: u32,
+        warmup_iterations: u32,
         total_time_ms: f64,
         avg_time_ms: f64,
         min_time_ms: f64,
         max_time_ms: f64,
+        p50_time_ms: f64,
+        p95_time_ms: f64,
+        p99_time_ms: f64,
+        std_dev_ms: f64,
+        original_size: u64,
+        throughput_mb_s: f64,
         success_rate: f32,
     }
     
-    let mut times = Vec::new();
+    let warmup = warmup.unwrap_or((iterations / 10).min(10));
+    for _ in 0..warmup {
+        let _ = decode_binary_internal(data, clock::now());
+    }
+    
+    let mut times = Vec::with_capacity(iterations as usize);
     let mut successes = 0;
+    let mut original_size = 0;
     
     for _ in 0..iterations {
-        let start = js_sys::Date::now();
-        match decode_binary_internal(data, start) {
-            Ok(_) => {
-                successes += 1;
-                times.push(js_sys::Date::now() - start);
-            }
-            Err(_) => {
-                times.push(js_sys::Date::now() - start);
-            }
+        let start = clock::now();
+        let result = decode_binary_internal(data, start);
+        times.push(clock::now() - start);
+        if let Ok(result) = result {
+            successes += 1;
+            original_size = result.stats.original_size;
         }
     }
     
+    times.sort_by(|a, b| a.total_cmp(b));
+    let count = times.len().max(1) as f64;
     let total_time: f64 = times.iter().sum();
-    let avg_time = total_time / iterations as f64;
-    let min_time = times.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-    let max_time = times.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+    let avg_time = total_time / count;
+    let variance = times.iter().map(|time| (time - avg_time).powi(2)).sum::<f64>() / count;
+    // 计时精度不足时耗时可能为 0，此时不报告吞吐量
+    let throughput = if successes > 0 && avg_time > 0.0 {
+        original_size as f64 / (1024.0 * 1024.0) / (avg_time / 1000.0)
+    } else {
+        0.0
+    };
     
     let result = BenchmarkResult {
         iterations,
+        warmup_iterations: warmup,
         total_time_ms: total_time,
         avg_time_ms: avg_time,
-        min_time_ms: min_time,
-        max_time_ms: max_time,
-        success_rate: successes as f32 / iterations as f32,
+        min_time_ms: times.first().copied().unwrap_or(0.0),
+        max_time_ms: times.last().copied().unwrap_or(0.0),
+        p50_time_ms: percentile(&times, 50.0),
+        p95_time_ms: percentile(&times, 95.0),
+        p99_time_ms: percentile(&times, 99.0),
+        std_dev_ms: variance.sqrt(),
+        original_size,
+        throughput_mb_s: throughput,
+        success_rate: if iterations > 0 { successes as f32 / iterations as f32 } else { 0.0 },
     };
     
-    serde_wasm_bindgen::to_value(&result).unwrap()
+    guard::to_js(&result)
+}
+
+// 已排序样本的百分位 (最近秩法)，没有样本时为 0
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
 }
 
 // 流式解码器结构
+//
+// 数据块到达后立即送入增量解压器，不缓存完整的压缩数据，峰值内存约为解压后大小。
+// tee 模式保留的原始字节与解码状态分开保存。
 #[wasm_bindgen]
 pub struct StreamDecoder {
-    buffer: Vec<u8>,
-    header_parsed: bool,
-    expected_size: Option<u32>,
-    compressed_size: Option<u32>,
-    original_size: Option<u32>,
+    // 头部解析完成前缓存的数据
+    header_buffer: Vec<u8>,
+    header: Option<ContainerHeader>,
+    expected_size: Option<u64>,
+    compressed_size: Option<u64>,
+    original_size: Option<u64>,
     version: Option<u32>,
+    codec: Option<Codec>,
+    kind: Option<PayloadKind>,
+    // 增量解压器，头部解析后创建
+    inflater: Option<codec::StreamInflater>,
+    compressed_received: usize,
+    // 压缩数据之后的原始长度字段
+    trailer: Vec<u8>,
+    // 各数据块累计的处理耗时 (毫秒)
+    decode_time_ms: f64,
+    completed: bool,
+    failed: bool,
     chunks_processed: u32,
-    total_received: u32,
+    total_received: u64,
+    header_callback: Option<js_sys::Function>,
+    callbacks: StreamCallbacks,
+    tee: bool,
+    // tee 模式下保留的原始字节
+    original: Vec<u8>,
+    // 渐进式 LOD: 每解压出一级调用一次
+    lod_callback: Option<js_sys::Function>,
+    lod: Option<lod::LodStream>,
+    // 流式 JSON: 顶层数组的每个元素到达时调用一次
+    json_element_callback: Option<js_sys::Function>,
+    json_stream: Option<json_stream::JsonArrayStream>,
+    // 逐级 / 逐元素输出时，解压输出逐块取出保存在这里
+    taken_output: Vec<u8>,
+    // 缓存数据占用的内存配额
+    reservation: quota::Reservation,
+}
+
+// 推送式通知回调，参数与 add_chunk 返回值相同
+#[derive(Default)]
+struct StreamCallbacks {
+    on_progress: Option<js_sys::Function>,
+    on_complete: Option<js_sys::Function>,
+    on_error: Option<js_sys::Function>,
+}
+
+// 压缩数据写入解压器前限制输出: 累计长度不超过声明的原始长度，新增输出不超过剩余的内存配额
+fn limit_stream_output(
+    header: &ContainerHeader,
+    trailer: &[u8],
+    inflater: &mut codec::StreamInflater,
+) -> Result<(), DecodeError> {
+    bound_stream_output(header, trailer, inflater)?;
+    inflater.set_output_budget(quota::available());
+    Ok(())
+}
+
+// 以普通对象调用 LOD 回调
+fn notify_lod_level(callback: &Option<js_sys::Function>, level: lod::LodLevel) {
+    let Some(callback) = callback else {
+        return;
+    };
+    let event = plain::PlainObject::new()
+        .set("level", level.level)
+        .set("level_count", level.level_count)
+        .set_bytes("glb", level.glb)
+        .set_opt("patch", level.patch.map(js_sys::Uint8Array::from))
+        .build();
+    if let Err(e) = callback.call1(&JsValue::NULL, &event) {
+        log!(Error, "⚠️ 流式解码: LOD 回调执行失败: {:?}", e);
+    }
+}
+
+// 以普通对象调用流式 JSON 回调
+fn notify_json_element(callback: &Option<js_sys::Function>, index: u32, element: serde_json::Value) {
+    let Some(callback) = callback else {
+        return;
+    };
+    let element = guard::json_to_js(&element);
+    if let Err(e) = callback.call2(&JsValue::NULL, &element, &JsValue::from(index)) {
+        log!(Error, "⚠️ 流式解码: JSON 元素回调执行失败: {:?}", e);
+    }
+}
+
+// 头部解析完成时传给回调的信息
+#[derive(Serialize, Deserialize)]
+pub struct StreamHeaderInfo {
+    pub version: u32,
+    pub content_type: String,
+    pub codec: String,
+    pub compressed_size: u64,
+    pub expected_size: u64,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -526,182 +917,455 @@ pub struct StreamDecodeResult {
     pub success: bool,
     pub data: Option<String>,
     pub error: Option<String>,
+    pub error_code: Option<DecodeErrorCode>,
     pub progress: f32,
     pub is_complete: bool,
     pub chunks_processed: u32,
-    pub total_received: u32,
+    pub total_received: u64,
     pub stats: Option<DecodeStats>,
 }
 
+impl Default for StreamDecoder {
+    fn default() -> Self {
+        Self::new(None, None, None, None)
+    }
+}
+
 #[wasm_bindgen]
 impl StreamDecoder {
+    // 回调均为可选: on_header 收到 StreamHeaderInfo，on_progress / on_complete / on_error
+    // 收到与 add_chunk 返回值相同的 StreamDecodeResult，调用方无需轮询每次的返回值
     #[wasm_bindgen(constructor)]
-    pub fn new() -> StreamDecoder {
+    pub fn new(
+        #[wasm_bindgen(unchecked_param_type = "StreamHeaderCallback | null")] on_header: Option<js_sys::Function>,
+        #[wasm_bindgen(unchecked_param_type = "StreamResultCallback | null")] on_progress: Option<js_sys::Function>,
+        #[wasm_bindgen(unchecked_param_type = "StreamResultCallback | null")] on_complete: Option<js_sys::Function>,
+        #[wasm_bindgen(unchecked_param_type = "StreamResultCallback | null")] on_error: Option<js_sys::Function>,
+    ) -> StreamDecoder {
         StreamDecoder {
-            buffer: Vec::new(),
-            header_parsed: false,
+            header_buffer: Vec::new(),
+            header: None,
             expected_size: None,
             compressed_size: None,
             original_size: None,
             version: None,
+            codec: None,
+            kind: None,
+            inflater: None,
+            compressed_received: 0,
+            trailer: Vec::new(),
+            decode_time_ms: 0.0,
+            completed: false,
+            failed: false,
             chunks_processed: 0,
             total_received: 0,
+            header_callback: on_header,
+            callbacks: StreamCallbacks {
+                on_progress,
+                on_complete,
+                on_error,
+            },
+            tee: false,
+            original: Vec::new(),
+            lod_callback: None,
+            lod: None,
+            json_element_callback: None,
+            json_stream: None,
+            taken_output: Vec::new(),
+            reservation: quota::Reservation::for_stream(),
         }
     }
 
+    // 注册头部回调: 头部一旦解析成功即以 StreamHeaderInfo 调用，
+    // 便于在载荷到达前把数据流路由给合适的消费者 (JSON 解析器 / GLTF 加载器)
     #[wasm_bindgen]
+    pub fn set_header_callback(&mut self, callback: js_sys::Function) {
+        self.header_callback = Some(callback);
+    }
+
+    #[wasm_bindgen(unchecked_return_type = "StreamDecodeResult")]
     pub fn add_chunk(&mut self, chunk: &[u8]) -> JsValue {
-        let start_time = js_sys::Date::now();
+        let start_time = clock::now();
         
-        // 添加数据块到缓冲区
-        self.buffer.extend_from_slice(chunk);
-        self.total_received += chunk.len() as u32;
-        self.chunks_processed += 1;
+        if self.completed || self.failed {
+            let state = if self.completed { "已完成" } else { "已失败" };
+            let error = DecodeError::new(DecodeErrorCode::InvalidState, format!("流式解码{}，请先调用 reset()", state));
+            return self.emit(self.error_result(error));
+        }
         
-        // 尝试解析头部信息
-        if !self.header_parsed && self.buffer.len() >= 20 {
-            match self.parse_header() {
-                Ok(_) => {
-                    log!("📋 流式解码: 头部解析成功, 预期大小: {} bytes", self.expected_size.unwrap_or(0));
-                }
-                Err(e) => {
-                    let result = StreamDecodeResult {
-                        success: false,
-                        data: None,
-                        error: Some(format!("头部解析失败: {}", e)),
-                        progress: 0.0,
-                        is_complete: false,
-                        chunks_processed: self.chunks_processed,
-                        total_received: self.total_received,
-                        stats: None,
-                    };
-                    return serde_wasm_bindgen::to_value(&result).unwrap();
-                }
-            }
+        self.total_received += chunk.len() as u64;
+        self.chunks_processed += 1;
+        if self.tee {
+            self.original.extend_from_slice(chunk);
         }
         
-        // 计算进度
-        let progress = if let Some(expected) = self.expected_size {
-            (self.buffer.len() as f32 / expected as f32).min(1.0)
-        } else {
-            0.0
-        };
+        let processed = self.process_chunk(chunk, start_time);
+        self.decode_time_ms += clock::now() - start_time;
         
-        // 检查是否可以尝试解码
-        let can_decode = self.header_parsed && 
-            self.expected_size.map_or(false, |size| self.buffer.len() >= size as usize);
+        // 按实际缓存的数据量更新内存配额，超出时中止解码
+        let processed = processed.and_then(|decoded| {
+            self.reservation.resize(self.held_bytes())?;
+            Ok(decoded)
+        });
         
-        if can_decode {
-            // 尝试完整解码
-            match self.try_decode(start_time) {
-                Ok(decode_result) => {
-                    let result = StreamDecodeResult {
-                        success: true,
-                        data: decode_result.data,
-                        error: None,
-                        progress: 1.0,
-                        is_complete: true,
-                        chunks_processed: self.chunks_processed,
-                        total_received: self.total_received,
-                        stats: Some(decode_result.stats),
-                    };
-                    return serde_wasm_bindgen::to_value(&result).unwrap();
-                }
-                Err(e) => {
-                    let result = StreamDecodeResult {
-                        success: false,
-                        data: None,
-                        error: Some(e),
-                        progress,
-                        is_complete: false,
-                        chunks_processed: self.chunks_processed,
-                        total_received: self.total_received,
-                        stats: None,
-                    };
-                    return serde_wasm_bindgen::to_value(&result).unwrap();
-                }
+        let result = match processed {
+            Ok(Some(decode_result)) => StreamDecodeResult {
+                success: true,
+                data: decode_result.data,
+                error: None,
+                error_code: None,
+                progress: 1.0,
+                is_complete: true,
+                chunks_processed: self.chunks_processed,
+                total_received: self.total_received,
+                stats: Some(decode_result.stats),
+            },
+            // 返回进度信息
+            Ok(None) => StreamDecodeResult {
+                success: true,
+                data: None,
+                error: None,
+                error_code: None,
+                progress: self.get_progress(),
+                is_complete: false,
+                chunks_processed: self.chunks_processed,
+                total_received: self.total_received,
+                stats: None,
+            },
+            Err(e) => {
+                // 释放解码状态，tee 副本保留
+                self.failed = true;
+                self.inflater = None;
+                self.header_buffer = Vec::new();
+                let _ = self.reservation.resize(self.held_bytes());
+                self.error_result(e)
             }
-        }
-        
-        // 返回进度信息
-        let result = StreamDecodeResult {
-            success: true,
-            data: None,
-            error: None,
-            progress,
-            is_complete: false,
-            chunks_processed: self.chunks_processed,
-            total_received: self.total_received,
-            stats: None,
         };
         
-        serde_wasm_bindgen::to_value(&result).unwrap()
+        self.emit(result)
     }
     
     #[wasm_bindgen]
     pub fn reset(&mut self) {
-        self.buffer.clear();
-        self.header_parsed = false;
-        self.expected_size = None;
-        self.compressed_size = None;
-        self.original_size = None;
-        self.version = None;
-        self.chunks_processed = 0;
-        self.total_received = 0;
+        *self = StreamDecoder {
+            header_callback: self.header_callback.take(),
+            callbacks: std::mem::take(&mut self.callbacks),
+            tee: self.tee,
+            lod_callback: self.lod_callback.take(),
+            json_element_callback: self.json_element_callback.take(),
+            ..StreamDecoder::default()
+        };
     }
     
     #[wasm_bindgen]
     pub fn get_progress(&self) -> f32 {
-        if let Some(expected) = self.expected_size {
-            (self.buffer.len() as f32 / expected as f32).min(1.0)
-        } else {
-            0.0
+        match (&self.header, self.expected_size) {
+            (Some(header), Some(expected)) if expected > 0 => {
+                let received = header.payload_offset + self.compressed_received + self.trailer.len();
+                (received as f32 / expected as f32).min(1.0)
+            }
+            _ => 0.0,
         }
     }
     
+    // WASM 侧为解码缓存的字节数 (头部、解压输出等，不含 tee 副本)
     #[wasm_bindgen]
     pub fn get_buffer_size(&self) -> u32 {
-        self.buffer.len() as u32
+        (self.held_bytes() - self.original.len()) as u32
     }
     
     #[wasm_bindgen]
-    pub fn get_expected_size(&self) -> Option<u32> {
-        self.expected_size
+    pub fn get_expected_size(&self) -> Option<f64> {
+        self.expected_size.map(|size| size as f64)
+    }
+    
+    // 开启 tee 模式: 解码的同时保留未经改动的原始字节，下载完成后可直接写入 Cache/IndexedDB，
+    // 无需在 JS 中再缓存一份下载数据。必须在接收数据前开启
+    #[wasm_bindgen]
+    pub fn set_tee(&mut self, enabled: bool) -> Result<(), JsValue> {
+        if enabled && !self.tee && self.total_received > 0 {
+            return Err(JsValue::from_str("tee 模式需在接收数据前开启"));
+        }
+        self.tee = enabled;
+        if !enabled {
+            self.original = Vec::new();
+        }
+        Ok(())
+    }
+    
+    // 注册渐进式 LOD 回调 (encode_fastdog_lod 生成的 GLB 容器): 每解压出一级即以
+    // { level, level_count, glb: Uint8Array, patch?: Uint8Array } 调用，glb 为该级完整的 GLB，
+    // patch 为由上一级得到本级的补丁。必须在接收数据前注册，非 LOD 载荷的 GLB 解码失败
+    #[wasm_bindgen]
+    pub fn set_lod_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "LodLevelCallback")] callback: js_sys::Function,
+    ) -> Result<(), JsValue> {
+        if self.total_received > 0 {
+            return Err(JsValue::from_str("LOD 回调需在接收数据前注册"));
+        }
+        self.lod_callback = Some(callback);
+        Ok(())
+    }
+
+    // 注册流式 JSON 回调: 载荷为 JSON 时按顶层数组解析，每个元素解压完成即以 (element, index) 调用，
+    // 应用可以在整个文档解压完成前开始处理记录。完成时的结果仍带有完整的 data。
+    // 必须在接收数据前注册，顶层不是数组的 JSON 载荷解码失败
+    #[wasm_bindgen]
+    pub fn set_json_element_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "JsonElementCallback")] callback: js_sys::Function,
+    ) -> Result<(), JsValue> {
+        if self.total_received > 0 {
+            return Err(JsValue::from_str("JSON 元素回调需在接收数据前注册"));
+        }
+        self.json_element_callback = Some(callback);
+        Ok(())
+    }
+
+    // 返回目前已接收的原始字节 (tee 模式)
+    #[wasm_bindgen]
+    pub fn original_bytes(&self) -> Result<Vec<u8>, JsValue> {
+        if !self.tee {
+            return Err(JsValue::from_str("未开启 tee 模式，请先调用 set_tee(true)"));
+        }
+        Ok(self.original.clone())
+    }
+    
+    // 当前解码状态的普通对象快照 (可结构化克隆)，tee 模式下包含已接收的原始字节
+    #[wasm_bindgen(js_name = toPlainObject, unchecked_return_type = "StreamDecoderSnapshot")]
+    pub fn to_plain_object(&self) -> JsValue {
+        let object = plain::PlainObject::new()
+            .set("progress", self.get_progress())
+            .set("buffer_size", self.get_buffer_size())
+            .set_opt("expected_size", self.get_expected_size())
+            .set_opt("compressed_size", self.compressed_size.map(|size| size as f64))
+            .set_opt("original_size", self.original_size.map(|size| size as f64))
+            .set_opt("version", self.version)
+            .set_opt("codec", self.codec.map(|codec| codec.name()))
+            .set_opt("content_type", self.kind.map(|kind| kind.name()))
+            .set("chunks_processed", self.chunks_processed)
+            .set("total_received", self.total_received as f64)
+            .set("tee", self.tee);
+        if self.tee {
+            object.set_bytes("original_bytes", &self.original).build()
+        } else {
+            object.build()
+        }
     }
 }
 
 impl StreamDecoder {
-    fn parse_header(&mut self) -> Result<(), String> {
-        if self.buffer.len() < 20 {
-            return Err("数据不足以解析头部".to_string());
+    // 处理一个数据块，解码完成时返回结果
+    fn process_chunk(&mut self, chunk: &[u8], start_time: f64) -> Result<Option<DecodeResult>, DecodeError> {
+        if self.header.is_some() {
+            self.feed_payload(chunk)?;
+        } else {
+            self.header_buffer.extend_from_slice(chunk);
+            if !header_available(&self.header_buffer) {
+                return Ok(None);
+            }
+            
+            let payload_offset = self.parse_header().map_err(|e| e.context("头部解析失败"))?;
+            log!(Debug, "📋 流式解码: 头部解析成功, 预期大小: {} bytes", self.expected_size.unwrap_or(0));
+            self.notify_header();
+            
+            // 头部之后的数据送入解压器，头部缓存随即释放
+            let buffered = std::mem::take(&mut self.header_buffer);
+            self.feed_payload(ByteReader::new(&buffered, "容器头部").rest_from(payload_offset)?)?;
         }
         
-        // 检查魔数
-        let magic = &self.buffer[0..8];
-        if magic != b"FASTDOG1" {
-            return Err("无效的文件格式".to_string());
+        let trailer_len = self.header.as_ref().map_or(format::ORIGINAL_LEN.size, ContainerHeader::trailer_len);
+        if self.compressed_received < self.compressed_size.unwrap_or(0) as usize || self.trailer.len() < trailer_len {
+            return Ok(None);
         }
+        self.finish(start_time).map(Some)
+    }
+    
+    fn parse_header(&mut self) -> Result<usize, DecodeError> {
+        // 检查魔数并解析版本、编码和压缩大小
+        let header = parse_header_fields(&self.header_buffer)?;
+        quota::begin_decode();
+        self.version = Some(header.version);
+        self.codec = Some(header.codec);
+        self.kind = Some(header.kind);
         
-        // 解析版本
-        self.version = Some(u32::from_le_bytes([
-            self.buffer[8], self.buffer[9], self.buffer[10], self.buffer[11]
-        ]));
+        // 计算预期总大小 (头部 + 压缩数据 + 原始大小字段)，尾部帧格式在找到尾部后才知道
+        if !header.footer {
+            self.compressed_size = Some(header.compressed_len as u64);
+            self.expected_size = Some(header.total_len() as u64);
+        }
         
-        // 解析压缩大小
-        self.compressed_size = Some(u32::from_le_bytes([
-            self.buffer[12], self.buffer[13], self.buffer[14], self.buffer[15]
-        ]));
+        #[cfg(feature = "chaos")]
+        chaos::maybe_fail_inflate()?;
         
-        // 计算预期总大小 (头部 + 压缩数据 + 原始大小字段)
-        if let Some(compressed_size) = self.compressed_size {
-            self.expected_size = Some(20 + compressed_size);
+        self.inflater = Some(open_stream_inflater(&header)?);
+        if self.lod_callback.is_some() && header.kind == PayloadKind::Glb {
+            self.lod = Some(lod::LodStream::default());
+        }
+        if self.json_element_callback.is_some() && header.kind == PayloadKind::Json {
+            self.json_stream = Some(json_stream::JsonArrayStream::default());
+        }
+        let payload_offset = header.payload_offset;
+        self.header = Some(header);
+        Ok(payload_offset)
+    }
+    
+    // 压缩数据送入解压器，之后的 4 或 8 字节作为原始长度字段 (尾部帧格式为尾部)，多余的数据忽略
+    fn feed_payload(&mut self, bytes: &[u8]) -> Result<(), DecodeError> {
+        if let Some(header) = self.header.as_mut().filter(|header| header.footer) {
+            let compressed = split_footer_stream(header, &mut self.trailer, self.compressed_received, bytes);
+            if let Some(inflater) = self.inflater.as_mut() {
+                limit_stream_output(header, &self.trailer, inflater)?;
+                inflater.write(&compressed)?;
+            }
+            self.compressed_received += compressed.len();
+            if self.trailer.len() == format::FOOTER_LEN {
+                self.compressed_size = Some(header.compressed_len as u64);
+                self.expected_size = Some(header.total_len() as u64);
+            }
+            return self.advance_progressive();
         }
         
-        self.header_parsed = true;
+        // 原始长度字段先于压缩数据写入解压器，同一数据块中到达时按声明的长度限制输出
+        let remaining = (self.compressed_size.unwrap_or(0) as usize).saturating_sub(self.compressed_received);
+        let take = remaining.min(bytes.len());
+        let rest = &bytes[take..];
+        let trailer_len = self.header.as_ref().map_or(format::ORIGINAL_LEN.size, ContainerHeader::trailer_len);
+        let need = trailer_len.saturating_sub(self.trailer.len()).min(rest.len());
+        self.trailer.extend_from_slice(&rest[..need]);
+        
+        if take > 0 {
+            if let (Some(header), Some(inflater)) = (self.header.as_ref(), self.inflater.as_mut()) {
+                limit_stream_output(header, &self.trailer, inflater)?;
+                inflater.write(&bytes[..take])?;
+            }
+            self.compressed_received += take;
+        }
+        self.advance_progressive()
+    }
+
+    // 逐级 / 逐元素输出时是否需要提前取出解压输出
+    fn is_progressive(&self) -> bool {
+        self.lod.is_some() || self.json_stream.is_some()
+    }
+
+    // 取出目前的解压输出，把新到达的 LOD 级别或 JSON 元素交给回调
+    fn advance_progressive(&mut self) -> Result<(), DecodeError> {
+        if !self.is_progressive() {
+            return Ok(());
+        }
+        let Some(inflater) = self.inflater.as_mut() else {
+            return Ok(());
+        };
+        self.taken_output.extend_from_slice(&inflater.take_output());
+        if let Some(lod) = self.lod.as_mut() {
+            lod.advance(&self.taken_output, |level| notify_lod_level(&self.lod_callback, level))?;
+        }
+        if let Some(stream) = self.json_stream.as_mut() {
+            stream.advance(&self.taken_output, |index, element| {
+                notify_json_element(&self.json_element_callback, index, element)
+            })?;
+        }
         Ok(())
     }
     
-    fn try_decode(&self, start_time: f64) -> Result<DecodeResult, String> {
-        decode_binary_internal(&self.buffer, start_time)
+    fn finish(&mut self, start_time: f64) -> Result<DecodeResult, DecodeError> {
+        let (Some(header), Some(mut inflater)) = (self.header.clone(), self.inflater.take()) else {
+            return Err(DecodeError::new(DecodeErrorCode::InvalidState, "流式解码状态无效"));
+        };
+        let original_len = header.read_original_len(&self.trailer)?;
+        self.original_size = Some(original_len);
+        inflater.set_output_budget(quota::available());
+        
+        #[allow(unused_mut)]
+        let mut decompressed = inflater.finish(payload_len(original_len)?)?;
+        if self.is_progressive() {
+            // 之前取出的输出在前
+            let mut output = std::mem::take(&mut self.taken_output);
+            output.extend_from_slice(&decompressed);
+            decompressed = output;
+        }
+        
+        #[cfg(feature = "chaos")]
+        chaos::maybe_corrupt(&mut decompressed);
+        
+        verify_payload(&header, original_len, &decompressed)?;
+        if let Some(mut lod) = self.lod.take() {
+            lod.advance(&decompressed, |level| notify_lod_level(&self.lod_callback, level))?;
+            lod.check_complete(decompressed.len())?;
+        }
+        if let Some(mut stream) = self.json_stream.take() {
+            stream.advance(&decompressed, |index, element| {
+                notify_json_element(&self.json_element_callback, index, element)
+            })?;
+            stream.check_complete()?;
+        }
+        self.completed = true;
+        
+        // 解码耗时按各数据块的处理时间累计
+        let elapsed_before = self.decode_time_ms;
+        into_decode_result((header, original_len, decompressed), start_time - elapsed_before)
+    }
+    
+    // 当前缓存的总字节数 (计入内存配额)
+    fn held_bytes(&self) -> usize {
+        self.header_buffer.len()
+            + self.inflater.as_ref().map_or(0, |inflater| inflater.buffered_len())
+            + self.trailer.len()
+            + self.original.len()
+            + self.taken_output.len()
+            + self.lod.as_ref().map_or(0, lod::LodStream::held_bytes)
     }
-}
\ No newline at end of file
+    
+    fn error_result(&self, error: DecodeError) -> StreamDecodeResult {
+        StreamDecodeResult {
+            success: false,
+            data: None,
+            error_code: Some(error.code),
+            error: Some(error.message),
+            progress: self.get_progress(),
+            is_complete: false,
+            chunks_processed: self.chunks_processed,
+            total_received: self.total_received,
+            stats: None,
+        }
+    }
+    
+    // 序列化结果并按状态调用对应的回调
+    fn emit(&self, result: StreamDecodeResult) -> JsValue {
+        let callback = if !result.success {
+            &self.callbacks.on_error
+        } else if result.is_complete {
+            &self.callbacks.on_complete
+        } else {
+            &self.callbacks.on_progress
+        };
+        
+        let value = guard::to_js(&result);
+        if let Some(callback) = callback {
+            if let Err(e) = callback.call1(&JsValue::NULL, &value) {
+                log!(Error, "⚠️ 流式解码: 回调执行失败: {:?}", e);
+            }
+        }
+        value
+    }
+    
+    fn notify_header(&self) {
+        let Some(callback) = &self.header_callback else {
+            return;
+        };
+        
+        let info = StreamHeaderInfo {
+            version: self.version.unwrap_or(0),
+            content_type: self.kind.map_or("unknown", |kind| kind.name()).to_string(),
+            codec: self.codec.map(|codec| codec.name()).unwrap_or_default().to_string(),
+            compressed_size: self.compressed_size.unwrap_or(0),
+            expected_size: self.expected_size.unwrap_or(0),
+        };
+        
+        if let Err(e) = callback.call1(&JsValue::NULL, &guard::to_js(&info)) {
+            log!(Error, "⚠️ 流式解码: 头部回调执行失败: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use wasm_bindgen::prelude::*;
+
+pub(crate) use fastdog_core::locale::*;
+
+// 设置错误信息语言: "zh" (默认) 或 "en"，之后产生的解码错误使用该语言。
+// 中文信息带有具体的长度、偏移等细节；英文信息按错误码给出固定说明，细节以 error_code 为准
+#[wasm_bindgen]
+pub fn set_locale(locale: &str) -> Result<(), JsValue> {
+    let locale = Locale::from_name(locale)
+        .ok_or_else(|| JsValue::from_str(&format!("不支持的语言: {} (可选 zh / en)", locale)))?;
+    set(locale);
+    Ok(())
+}
+
+#[wasm_bindgen]
+pub fn get_locale() -> String {
+    current().name().to_string()
+}
@@ -0,0 +1,149 @@
+use wasm_bindgen::prelude::*;
+
+use crate::byte_reader::ByteReader;
+use crate::encoder::encode_container;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::payload_kind::PayloadKind;
+use crate::profiles::resolve_profile;
+use crate::patch;
+
+// 渐进式 LOD 载荷 (内容类型 GLB)
+//
+// 布局: 魔数 "FDOGLOD1" | 级别数 u32 | 各级别依次为 长度 u32 | 数据。
+// 级别 0 是完整的低精度 GLB，之后每一级是把上一级 GLB 变为本级 GLB 的二进制补丁 (FASTDOGP 格式)，
+// 越靠后越精细。整个 LOD 载荷作为一个 GLB 容器的载荷压缩，流式解码时每解压出一级即可渲染，
+// 不必等待最精细的模型下载完成。普通 GLB 解码接口直接得到最精细一级
+pub(crate) const LOD_MAGIC: &[u8; 8] = b"FDOGLOD1";
+const LOD_HEADER_LEN: usize = 12;
+
+pub(crate) fn is_lod(payload: &[u8]) -> bool {
+    payload.starts_with(LOD_MAGIC)
+}
+
+fn invalid(message: impl Into<String>) -> DecodeError {
+    DecodeError::new(DecodeErrorCode::InvalidInput, message)
+}
+
+// 由各级 GLB (从粗到精) 生成 LOD 载荷
+pub(crate) fn build(levels: &[&[u8]]) -> Result<Vec<u8>, String> {
+    let Some((&base, _)) = levels.split_first() else {
+        return Err("LOD 至少需要一个级别".to_string());
+    };
+    let mut payload = Vec::with_capacity(LOD_HEADER_LEN + base.len());
+    payload.extend_from_slice(LOD_MAGIC);
+    payload.extend_from_slice(&(levels.len() as u32).to_le_bytes());
+    let mut push_segment = |segment: &[u8]| {
+        payload.extend_from_slice(&(segment.len() as u32).to_le_bytes());
+        payload.extend_from_slice(segment);
+    };
+    push_segment(base);
+    for pair in levels.windows(2) {
+        push_segment(&patch::diff(pair[0], pair[1])?);
+    }
+    Ok(payload)
+}
+
+// 还原最精细一级的 GLB
+pub(crate) fn finest_level(payload: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let mut stream = LodStream::default();
+    let mut finest = Vec::new();
+    stream.advance(payload, |level| finest = level.glb.to_vec())?;
+    stream.check_complete(payload.len())?;
+    Ok(finest)
+}
+
+// 已还原的一个级别
+pub(crate) struct LodLevel<'a> {
+    pub level: u32,
+    pub level_count: u32,
+    pub glb: &'a [u8],
+    // 由上一级得到本级的补丁，级别 0 为 None
+    pub patch: Option<&'a [u8]>,
+}
+
+// 按到达顺序解析 LOD 载荷，解压输出由调用方保存，这里只记录解析位置和当前级别的 GLB
+#[derive(Default)]
+pub(crate) struct LodStream {
+    cursor: usize,
+    level_count: Option<u32>,
+    levels_done: u32,
+    current: Vec<u8>,
+}
+
+impl LodStream {
+    // payload 为目前已解压的全部数据，每完整到达一级调用一次 on_level
+    pub fn advance(&mut self, payload: &[u8], mut on_level: impl FnMut(LodLevel)) -> Result<(), DecodeError> {
+        let level_count = match self.level_count {
+            Some(count) => count,
+            None if payload.len() < LOD_HEADER_LEN => return Ok(()),
+            None => {
+                if !is_lod(payload) {
+                    return Err(invalid("载荷不是 LOD 格式 (魔数不匹配)"));
+                }
+                let count = ByteReader::new(payload, "LOD 头部").u32_at(8)?;
+                if count == 0 {
+                    return Err(invalid("LOD 载荷没有任何级别"));
+                }
+                self.cursor = LOD_HEADER_LEN;
+                self.level_count = Some(count);
+                count
+            }
+        };
+
+        while self.levels_done < level_count && payload.len() - self.cursor >= 4 {
+            let len = ByteReader::new(payload, "LOD 级别").u32_at(self.cursor)? as usize;
+            let start = self.cursor + 4;
+            if payload.len() - start < len {
+                break;
+            }
+            let segment = &payload[start..start + len];
+            let patch = if self.levels_done == 0 {
+                self.current = segment.to_vec();
+                None
+            } else {
+                self.current = patch::apply(&self.current, segment)
+                    .map_err(|e| e.context(&format!("LOD 级别 {} 的补丁无法应用", self.levels_done)))?;
+                Some(segment)
+            };
+            on_level(LodLevel {
+                level: self.levels_done,
+                level_count,
+                glb: &self.current,
+                patch,
+            });
+            self.levels_done += 1;
+            self.cursor = start + len;
+        }
+        Ok(())
+    }
+
+    // 载荷结束时检查全部级别都已到达且没有多余数据
+    pub fn check_complete(&self, payload_len: usize) -> Result<(), DecodeError> {
+        match self.level_count {
+            Some(count) if self.levels_done == count && self.cursor == payload_len => Ok(()),
+            Some(count) if self.levels_done == count => Err(invalid("LOD 载荷末尾有多余数据")),
+            Some(count) => Err(invalid(format!("LOD 载荷不完整: 共 {} 级, 只有 {} 级", count, self.levels_done))),
+            None => Err(invalid("LOD 载荷头部不完整")),
+        }
+    }
+
+    pub fn held_bytes(&self) -> usize {
+        self.current.len()
+    }
+}
+
+// 把从粗到精的多个 GLB 编码为渐进式 LOD 容器 (内容类型 GLB)。
+// StreamDecoder 通过 set_lod_callback 在每一级到达时得到可渲染的 GLB，普通 GLB 解码接口得到最精细一级。
+// 流式逐级输出需要 zlib / brotli / zstd / store 编码 (lz4 帧在全部到达后才能解压)
+#[wasm_bindgen]
+pub fn encode_fastdog_lod(
+    #[wasm_bindgen(unchecked_param_type = "Uint8Array[]")] levels: JsValue,
+    profile: Option<String>,
+) -> Result<Vec<u8>, JsValue> {
+    let levels: Vec<serde_bytes::ByteBuf> = serde_wasm_bindgen::from_value(levels)
+        .map_err(|e| JsValue::from_str(&format!("LOD 级别格式无效: {}", e)))?;
+    let profile = resolve_profile(profile.as_deref()).map_err(|e| JsValue::from_str(&e))?;
+    let levels: Vec<&[u8]> = levels.iter().map(|level| level.as_slice()).collect();
+    let payload = build(&levels).map_err(|e| JsValue::from_str(&e))?;
+    encode_container(&payload, PayloadKind::Glb, &profile).map_err(|e| JsValue::from_str(&e))
+}
@@ -0,0 +1,108 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU8, Ordering};
+use wasm_bindgen::prelude::*;
+use web_sys::console;
+
+// 日志级别，级别越高输出越多
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum LogLevel {
+    Off = 0,
+    Error = 1,
+    Info = 2,
+    Debug = 3,
+}
+
+impl LogLevel {
+    fn from_name(name: &str) -> Option<LogLevel> {
+        match name.to_ascii_lowercase().as_str() {
+            "off" => Some(LogLevel::Off),
+            "error" => Some(LogLevel::Error),
+            "info" => Some(LogLevel::Info),
+            "debug" => Some(LogLevel::Debug),
+            _ => None,
+        }
+    }
+
+    fn from_u8(value: u8) -> LogLevel {
+        match value {
+            1 => LogLevel::Error,
+            2 => LogLevel::Info,
+            3 => LogLevel::Debug,
+            _ => LogLevel::Off,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            LogLevel::Off => "off",
+            LogLevel::Error => "error",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        }
+    }
+}
+
+// 默认关闭，生产环境不产生任何控制台输出
+static LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Off as u8);
+
+thread_local! {
+    // 宿主注册的日志回调，未注册时写入控制台
+    static LOGGER: RefCell<Option<js_sys::Function>> = const { RefCell::new(None) };
+}
+
+// log! 宏在格式化消息之前调用，级别未开启时不做任何格式化
+pub(crate) fn enabled(level: LogLevel) -> bool {
+    level != LogLevel::Off && level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+pub(crate) fn write(level: LogLevel, message: &str) {
+    if !call_logger(level.name(), message) {
+        match level {
+            LogLevel::Error => console::error_1(&message.into()),
+            _ => console::log_1(&message.into()),
+        }
+    }
+}
+
+// 不受日志级别限制的警告 (混沌模式开启提示)
+#[cfg(feature = "chaos")]
+pub(crate) fn warn(message: &str) {
+    if !call_logger("warn", message) {
+        console::warn_1(&message.into());
+    }
+}
+
+// 交给宿主的日志回调，没有注册回调时返回 false。回调抛出的异常被忽略，避免日志失败影响解码。
+// 先取出回调再调用，回调中可以再次调用 set_logger
+fn call_logger(level: &str, message: &str) -> bool {
+    match LOGGER.with(|logger| logger.borrow().clone()) {
+        Some(callback) => {
+            let _ = callback.call2(&JsValue::NULL, &JsValue::from_str(level), &JsValue::from_str(message));
+            true
+        }
+        None => false,
+    }
+}
+
+// 设置日志级别: "off" (默认) / "error" / "info" / "debug"
+#[wasm_bindgen]
+pub fn set_log_level(level: &str) -> Result<(), JsValue> {
+    let level = LogLevel::from_name(level).ok_or_else(|| {
+        JsValue::from_str(&format!("不支持的日志级别: {} (可选 off / error / info / debug)", level))
+    })?;
+    LEVEL.store(level as u8, Ordering::Relaxed);
+    Ok(())
+}
+
+#[wasm_bindgen]
+pub fn get_log_level() -> String {
+    LogLevel::from_u8(LEVEL.load(Ordering::Relaxed)).name().to_string()
+}
+
+// 注册日志回调 callback(level: "error" | "warn" | "info" | "debug", message: string)，
+// 之后的日志和警告交给宿主的日志/遥测系统而不写入控制台；传入 undefined/null 恢复控制台输出。
+// 输出哪些日志仍由 set_log_level 决定
+#[wasm_bindgen]
+pub fn set_logger(#[wasm_bindgen(unchecked_param_type = "LogCallback | null")] callback: Option<js_sys::Function>) {
+    LOGGER.with(|logger| *logger.borrow_mut() = callback);
+}
@@ -0,0 +1,19 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{guard, parse_header_fields};
+
+pub(crate) use fastdog_core::metadata::*;
+
+// 读取容器头部的元数据 (只需要头部，不解压载荷)，没有元数据时返回空对象
+#[wasm_bindgen(unchecked_return_type = "Record<string, string>")]
+pub fn get_metadata(data: &[u8]) -> Result<JsValue, JsValue> {
+    let header = parse_header_fields(data)?;
+    // 输出普通对象而不是 Map
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    Ok(guard::try_to_js_with(&header.metadata, &serializer)?)
+}
+
+// 由 JS 对象得到元数据，值必须是字符串
+pub(crate) fn from_js(metadata: JsValue) -> Result<Metadata, String> {
+    serde_wasm_bindgen::from_value(metadata).map_err(|e| format!("元数据格式无效: {}", e))
+}
@@ -0,0 +1,48 @@
+use wasm_bindgen::prelude::*;
+
+use crate::clock;
+use crate::error::DecodeError;
+use crate::{decode_binary_internal, guard, parse_container_header, DecodeResult, DecodeStats};
+
+// 解码首尾相接的多条 FASTDOG1 记录 (服务端把多个容器拼接在同一个响应中)
+//
+// 按每条记录头部的长度字段依次定位，返回与记录一一对应的 DecodeResult 数组，各自带有统计信息。
+// 载荷损坏的记录以失败结果占位，之后的记录照常解码；头部无法解析或长度超出数据末尾时
+// 无法定位下一条记录，以一个失败结果结束数组。
+#[wasm_bindgen(unchecked_return_type = "DecodeResult[]")]
+pub fn decode_fastdog_multi(data: &[u8]) -> JsValue {
+    let mut results = Vec::new();
+    let mut offset = 0;
+
+    while offset < data.len() {
+        let start_time = clock::now();
+        let record = &data[offset..];
+        let record_len = match parse_container_header(record) {
+            Ok(header) if header.total_len() <= record.len() => header.total_len(),
+            _ => {
+                // 交给完整解码流程生成带错误码的失败结果 (截断、魔数错误等)
+                let result = decode_binary_internal(record, start_time)
+                    .unwrap_or_else(|error| failed_result(error, record.len(), start_time));
+                results.push(result);
+                break;
+            }
+        };
+
+        let result = decode_binary_internal(&record[..record_len], start_time)
+            .unwrap_or_else(|error| failed_result(error, record_len, start_time));
+        results.push(result);
+        offset += record_len;
+    }
+
+    guard::to_js(&results)
+}
+
+fn failed_result(error: DecodeError, record_len: usize, start_time: f64) -> DecodeResult {
+    DecodeResult {
+        success: false,
+        data: None,
+        error_code: Some(error.code),
+        error: Some(error.message),
+        stats: DecodeStats::failed(record_len as u64, clock::now() - start_time),
+    }
+}
@@ -0,0 +1,293 @@
+use wasm_bindgen::prelude::*;
+
+use crate::clock;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::plain::PlainObject;
+use crate::transform::Transform;
+
+// 解码结果中载荷的输出形式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    // 按内容类型: JSON 为字符串，GLB 为 {"type":"glb","data":"<base64>"} 字符串 (与 decode_fastdog_binary 相同)
+    #[default]
+    Auto,
+    // 载荷按 UTF-8 解码为字符串，不论内容类型
+    Text,
+    // 载荷的 base64 字符串
+    Base64,
+    // 载荷放在结果的 bytes 字段 (Uint8Array)，data 为空
+    Bytes,
+    // JSON / MessagePack / CBOR 载荷在 WASM 内解析，结果的 value 字段为解析后的值，data 为空，JS 侧不再需要 JSON.parse
+    Object,
+    // 载荷放在结果的 buffer 字段: 独立的 ArrayBuffer (不是 WASM 内存上的视图)，data 为空，
+    // 可以放进 postMessage 的 transfer 列表零拷贝转移出 Worker
+    ArrayBuffer,
+}
+
+impl OutputFormat {
+    fn from_name(name: &str) -> Option<OutputFormat> {
+        match name {
+            "auto" => Some(OutputFormat::Auto),
+            "text" => Some(OutputFormat::Text),
+            "base64" => Some(OutputFormat::Base64),
+            "bytes" => Some(OutputFormat::Bytes),
+            "object" => Some(OutputFormat::Object),
+            "arraybuffer" => Some(OutputFormat::ArrayBuffer),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::Auto => "auto",
+            OutputFormat::Text => "text",
+            OutputFormat::Base64 => "base64",
+            OutputFormat::Bytes => "bytes",
+            OutputFormat::Object => "object",
+            OutputFormat::ArrayBuffer => "arraybuffer",
+        }
+    }
+}
+
+// 解码选项，按次传给 `decode_fastdog_binary_with_options`
+#[wasm_bindgen]
+#[derive(Clone, Default)]
+pub struct DecoderOptions {
+    max_decode_ms: Option<f64>,
+    max_output_bytes: Option<u32>,
+    max_ratio: Option<f64>,
+    output_format: OutputFormat,
+    strict: bool,
+    log: bool,
+    clock: Option<js_sys::Function>,
+    transforms: Vec<Transform>,
+    shared_output: Option<SharedOutput>,
+}
+
+// 载荷写入的 SharedArrayBuffer 区域
+#[derive(Clone)]
+pub(crate) struct SharedOutput {
+    buffer: js_sys::SharedArrayBuffer,
+    byte_offset: u32,
+}
+
+impl SharedOutput {
+    // 检查区域能否容纳 len 字节
+    pub(crate) fn check(&self, len: usize) -> Result<(), DecodeError> {
+        let available = (self.buffer.byte_length() as usize).saturating_sub(self.byte_offset as usize);
+        if len > available {
+            return Err(DecodeError::new(
+                DecodeErrorCode::OutputLimitExceeded,
+                format!(
+                    "SharedArrayBuffer 空间不足: 偏移 {} 之后需要 {} 字节，只有 {} 字节",
+                    self.byte_offset, len, available
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    // 写入载荷，返回写入区域 { byte_offset, byte_length }
+    pub(crate) fn write(&self, bytes: &[u8]) -> Result<JsValue, DecodeError> {
+        self.check(bytes.len())?;
+        js_sys::Uint8Array::new_with_byte_offset_and_length(&self.buffer, self.byte_offset, bytes.len() as u32)
+            .copy_from(bytes);
+        Ok(PlainObject::new()
+            .set("byte_offset", self.byte_offset)
+            .set("byte_length", bytes.len() as u32)
+            .build())
+    }
+}
+
+#[wasm_bindgen]
+impl DecoderOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> DecoderOptions {
+        DecoderOptions::default()
+    }
+
+    // 单次解码允许的最长耗时 (毫秒)。在解压分片边界检查，超时则中止并返回 Timeout 错误码。
+    // 异步和分块解码 (decode_fastdog_binary_async / ChunkedDecoder) 以参数形式接受同样的限制
+    #[wasm_bindgen(getter)]
+    pub fn max_decode_ms(&self) -> Option<f64> {
+        self.max_decode_ms
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_max_decode_ms(&mut self, value: Option<f64>) {
+        self.max_decode_ms = value.filter(|ms| *ms > 0.0);
+    }
+
+    // 解压输出的最大字节数。先按头部声明的原始长度检查，解压过程中再按实际输出检查，
+    // 防止声明很小、实际膨胀到数 GB 的解压炸弹，超出时返回 OutputLimitExceeded 错误码
+    #[wasm_bindgen(getter)]
+    pub fn max_output_bytes(&self) -> Option<u32> {
+        self.max_output_bytes
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_max_output_bytes(&mut self, value: Option<u32>) {
+        self.max_output_bytes = value;
+    }
+
+    // 允许的最大压缩比 (输出字节数 / 压缩数据字节数)，检查方式与 max_output_bytes 相同
+    #[wasm_bindgen(getter)]
+    pub fn max_ratio(&self) -> Option<f64> {
+        self.max_ratio
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_max_ratio(&mut self, value: Option<f64>) {
+        self.max_ratio = value.filter(|ratio| *ratio > 0.0);
+    }
+
+    // 载荷输出形式: "auto" (默认) / "text" / "base64" / "bytes" / "object" / "arraybuffer"
+    #[wasm_bindgen(getter)]
+    pub fn output_format(&self) -> String {
+        self.output_format.name().to_string()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_output_format(&mut self, value: &str) -> Result<(), JsValue> {
+        self.output_format = OutputFormat::from_name(value).ok_or_else(|| {
+            JsValue::from_str(&format!("不支持的输出形式: {} (可选 auto / text / base64 / bytes / object / arraybuffer)", value))
+        })?;
+        Ok(())
+    }
+
+    // 严格模式: 拒绝容器之后的多余字节，要求头部带 CRC32 或 SHA-256 校验字段，并校验 SHA-256 摘要。
+    // 默认为宽松模式，与 decode_fastdog_binary 的行为一致
+    #[wasm_bindgen(getter)]
+    pub fn strict(&self) -> bool {
+        self.strict
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_strict(&mut self, value: bool) {
+        self.strict = value;
+    }
+
+    // 在控制台输出本次解码的结果摘要
+    #[wasm_bindgen(getter)]
+    pub fn log(&self) -> bool {
+        self.log
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_log(&mut self, value: bool) {
+        self.log = value;
+    }
+
+    // 计时来源: 返回毫秒数的函数，用于本次解码的耗时统计和 max_decode_ms 检查。
+    // 传入 undefined/null 恢复全局时钟 (见 set_clock)
+    #[wasm_bindgen]
+    pub fn set_clock(&mut self, clock: Option<js_sys::Function>) {
+        self.clock = clock;
+    }
+
+    // 把载荷写入调用方提供的 SharedArrayBuffer (需要 COOP/COEP 跨源隔离)，从 byte_offset (默认 0) 开始。
+    // 设置后忽略 output_format: 结果的 data 为空，shared_output 字段为写入的区域 { byte_offset, byte_length }，
+    // 物理、渲染等多个 Worker 可以直接读取同一块内存，不再需要结构化克隆复制。
+    // 空间不足时返回 OutputLimitExceeded，传入 undefined/null 取消
+    #[wasm_bindgen]
+    pub fn set_shared_output(&mut self, buffer: Option<js_sys::SharedArrayBuffer>, byte_offset: Option<u32>) {
+        self.shared_output = buffer.map(|buffer| SharedOutput {
+            buffer,
+            byte_offset: byte_offset.unwrap_or(0),
+        });
+    }
+
+    // 添加内置的查找替换转换 (按字节匹配，如替换 JSON 中资源的基础 URL)
+    #[wasm_bindgen]
+    pub fn add_replace_transform(&mut self, from: &str, to: &str) {
+        self.transforms.push(Transform::Replace {
+            from: from.as_bytes().to_vec(),
+            to: to.as_bytes().to_vec(),
+        });
+    }
+
+    // 添加 JS 转换回调: callback(bytes: Uint8Array, content_type: string)，
+    // 返回 Uint8Array 或字符串替换载荷，返回 undefined 表示保持不变
+    #[wasm_bindgen]
+    pub fn add_transform(&mut self, callback: js_sys::Function) {
+        self.transforms.push(Transform::Callback(callback));
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_transforms(&mut self) {
+        self.transforms.clear();
+    }
+
+    // 可结构化克隆的普通对象。JS 回调转换无法克隆，只记录数量
+    #[wasm_bindgen(js_name = toPlainObject, unchecked_return_type = "DecoderOptionsSnapshot")]
+    pub fn to_plain_object(&self) -> JsValue {
+        let replacements = js_sys::Array::new();
+        let mut callbacks = 0u32;
+        for transform in &self.transforms {
+            match transform {
+                Transform::Replace { from, to } => {
+                    replacements.push(
+                        &PlainObject::new()
+                            .set("from", String::from_utf8_lossy(from).into_owned())
+                            .set("to", String::from_utf8_lossy(to).into_owned())
+                            .build(),
+                    );
+                }
+                Transform::Callback(_) => callbacks += 1,
+            }
+        }
+        PlainObject::new()
+            .set_opt("max_decode_ms", self.max_decode_ms)
+            .set_opt("max_output_bytes", self.max_output_bytes)
+            .set_opt("max_ratio", self.max_ratio)
+            .set("output_format", self.output_format.name())
+            .set("strict", self.strict)
+            .set("log", self.log)
+            .set("custom_clock", self.clock.is_some())
+            .set_opt("shared_output_offset", self.shared_output.as_ref().map(|shared| shared.byte_offset))
+            .set("replace_transforms", replacements)
+            .set("callback_transforms", callbacks)
+            .build()
+    }
+}
+
+impl DecoderOptions {
+    pub(crate) fn transforms(&self) -> &[Transform] {
+        &self.transforms
+    }
+
+    pub(crate) fn shared_output(&self) -> Option<&SharedOutput> {
+        self.shared_output.as_ref()
+    }
+
+    pub(crate) fn format(&self) -> OutputFormat {
+        self.output_format
+    }
+
+    // 当前时间 (毫秒)。计时函数抛出异常或没有返回数字时退回全局时钟
+    pub(crate) fn now(&self) -> f64 {
+        clock::call_clock(self.clock.as_ref()).unwrap_or_else(clock::now)
+    }
+
+    // 检查解压输出是否超出 max_output_bytes / max_ratio 限制
+    pub(crate) fn check_output(&self, compressed_len: usize, output_len: usize) -> Result<(), DecodeError> {
+        if let Some(limit) = self.max_output_bytes {
+            if output_len > limit as usize {
+                return Err(DecodeError::new(
+                    DecodeErrorCode::OutputLimitExceeded,
+                    format!("解压输出超出限制: {} 字节 > {} 字节", output_len, limit),
+                ));
+            }
+        }
+        if let Some(limit) = self.max_ratio {
+            let ratio = output_len as f64 / compressed_len.max(1) as f64;
+            if ratio > limit {
+                return Err(DecodeError::new(
+                    DecodeErrorCode::OutputLimitExceeded,
+                    format!("压缩比超出限制: {:.1} > {}", ratio, limit),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::codec::{self, Codec};
+use crate::error::DecodeError;
+use crate::{
+    clock, decompress_payload, guard, into_decode_result, locate_payload, payload_len, quota, verify_payload, DecodeResult,
+    DecodeStats,
+};
+
+// 多线程解压 (`threads` 功能)
+//
+// 把压缩数据中相互独立的帧/块分派到 rayon 线程池并行解压。目前 lz4 编码 (帧格式，默认独立块)
+// 可以并行；zlib / brotli 是单一数据流，仍按顺序解压。
+//
+// 在浏览器中需要以 atomics + bulk-memory 构建 (SharedArrayBuffer 支持的内存)，并由宿主在调用前
+// 启动线程池 (如 wasm-bindgen-rayon 的 initThreadPool)；未启动线程池时 rayon 在当前线程中执行。
+#[wasm_bindgen(unchecked_return_type = "DecodeResult")]
+pub fn decode_fastdog_binary_parallel(data: &[u8]) -> JsValue {
+    let start_time = clock::now();
+
+    match decode_parallel_internal(data, start_time) {
+        Ok(result) => guard::to_js(&result),
+        Err(error) => {
+            let error_result = DecodeResult {
+                success: false,
+                data: None,
+                error_code: Some(error.code),
+                error: Some(error.message),
+                stats: DecodeStats::failed(data.len() as u64, clock::now() - start_time),
+            };
+            guard::to_js(&error_result)
+        }
+    }
+}
+
+fn decode_parallel_internal(data: &[u8], start_time: f64) -> Result<DecodeResult, DecodeError> {
+    let (header, compressed, original_len) = locate_payload(data)?;
+    quota::begin_decode();
+    let _reservation = quota::reserve(payload_len(original_len)?)?;
+
+    #[cfg(feature = "chaos")]
+    crate::chaos::maybe_fail_inflate()?;
+
+    let blocks = match (header.codec, header.dict_id) {
+        (Codec::Lz4, None) => split_lz4_frames(compressed),
+        _ => None,
+    };
+    #[allow(unused_mut)]
+    let mut decompressed = match blocks {
+        Some(blocks) => decompress_lz4_blocks(&blocks)?,
+        None => decompress_payload(&header, compressed, original_len, |_| Ok(()))?,
+    };
+
+    #[cfg(feature = "chaos")]
+    crate::chaos::maybe_corrupt(&mut decompressed);
+
+    verify_payload(&header, original_len, &decompressed)?;
+    into_decode_result((header, original_len, decompressed), start_time)
+}
+
+// LZ4 帧格式中的一个数据块
+struct Lz4Block<'a> {
+    data: &'a [u8],
+    // 块未压缩 (原样存储)
+    stored: bool,
+    max_size: usize,
+}
+
+const LZ4_FRAME_MAGIC: u32 = 0x184D_2204;
+
+// 拆分 LZ4 帧 (可连续多帧) 为独立块。块之间有依赖 (linked 模式)、带字典或格式无法识别时返回 None，
+// 由调用方退回顺序解压。块校验和与内容校验和在此跳过，载荷完整性由容器的 CRC32 保证
+fn split_lz4_frames(mut input: &[u8]) -> Option<Vec<Lz4Block<'_>>> {
+    let read_u32 = |bytes: &[u8], offset: usize| -> Option<u32> {
+        bytes.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+
+    let mut blocks = Vec::new();
+    while !input.is_empty() {
+        if read_u32(input, 0)? != LZ4_FRAME_MAGIC {
+            return None;
+        }
+        let flg = *input.get(4)?;
+        let bd = *input.get(5)?;
+        let independent = flg & 0x20 != 0;
+        let block_checksum = flg & 0x10 != 0;
+        let content_size = flg & 0x08 != 0;
+        let content_checksum = flg & 0x04 != 0;
+        let dict_id = flg & 0x01 != 0;
+        if flg >> 6 != 0b01 || !independent || dict_id {
+            return None;
+        }
+        let max_size = match (bd >> 4) & 0x07 {
+            4 => 64 * 1024,
+            5 => 256 * 1024,
+            6 => 1024 * 1024,
+            7 => 4 * 1024 * 1024,
+            _ => return None,
+        };
+
+        // 魔数(4) + FLG(1) + BD(1) + [内容大小(8)] + 头部校验(1)
+        let mut cursor = 6 + if content_size { 8 } else { 0 } + 1;
+        loop {
+            let block_size = read_u32(input, cursor)?;
+            cursor += 4;
+            if block_size == 0 {
+                break;
+            }
+            let stored = block_size & 0x8000_0000 != 0;
+            let len = (block_size & 0x7FFF_FFFF) as usize;
+            blocks.push(Lz4Block {
+                data: input.get(cursor..cursor + len)?,
+                stored,
+                max_size,
+            });
+            cursor += len + if block_checksum { 4 } else { 0 };
+        }
+        if content_checksum {
+            cursor += 4;
+        }
+        input = input.get(cursor..)?;
+    }
+    Some(blocks)
+}
+
+fn decompress_lz4_blocks(blocks: &[Lz4Block<'_>]) -> Result<Vec<u8>, DecodeError> {
+    let parts: Vec<Vec<u8>> = blocks
+        .par_iter()
+        .map(|block| {
+            if block.stored {
+                Ok(block.data.to_vec())
+            } else {
+                lz4_flex::block::decompress(block.data, block.max_size)
+                    .map_err(|e| codec::inflate_error(Codec::Lz4, e))
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+    // 按各块实际解压出的长度分配，头部声明的原始长度由调用方之后校验
+    let mut output = Vec::with_capacity(parts.iter().map(Vec::len).sum());
+    for part in parts {
+        output.extend_from_slice(&part);
+    }
+    Ok(output)
+}
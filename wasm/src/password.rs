@@ -0,0 +1,40 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::{clock, encryption, guard, into_decode_value, parse_header_fields, DecodeResult, DecodeStats};
+
+pub(crate) use fastdog_core::password::*;
+
+// 由口令派生密钥并解密、解压容器
+fn decode_password_container(data: &[u8], password: &str) -> Result<(crate::ContainerHeader, u64, Vec<u8>), DecodeError> {
+    let header = parse_header_fields(data)?;
+    let Some(kdf) = &header.kdf else {
+        return Err(DecodeError::new(
+            DecodeErrorCode::InvalidInput,
+            "容器没有密码派生参数 (未加密的容器使用普通解码接口，密钥加密的容器使用 decode_encrypted)",
+        ));
+    };
+    encryption::decrypt_container(data, &kdf.derive_key(password))
+}
+
+// 解码口令保护的容器: 按头部参数派生密钥后解密，结果与 decode_fastdog_binary 相同。
+// 口令错误或数据被篡改时返回 DecryptionFailed。派生按设计耗时 (默认参数约数百毫秒)，
+// 需要保持页面响应时应在 Worker 中调用
+#[wasm_bindgen(unchecked_return_type = "DecodeResult")]
+pub fn decode_with_password(data: &[u8], password: &str) -> JsValue {
+    let start_time = clock::now();
+
+    match decode_password_container(data, password).and_then(|decoded| into_decode_value(decoded, start_time)) {
+        Ok(value) => value,
+        Err(error) => {
+            let error_result = DecodeResult {
+                success: false,
+                data: None,
+                error_code: Some(error.code),
+                error: Some(error.message),
+                stats: DecodeStats::failed(data.len() as u64, clock::now() - start_time),
+            };
+            guard::to_js(&error_result)
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::DecodeError;
+use crate::quota;
+
+pub(crate) use fastdog_core::patch::diff;
+
+// 生成从 base 到 updated 的二进制补丁，编辑器只需上传增量而非完整文件
+#[wasm_bindgen]
+pub fn create_patch(base: &[u8], updated: &[u8]) -> Result<Vec<u8>, JsValue> {
+    diff(base, updated).map_err(|e| JsValue::from_str(&e))
+}
+
+// 把 create_patch 生成的补丁应用到 base，返回更新后的数据。
+// 客户端用缓存的旧文件加补丁得到新文件，无需重新下载完整文件。
+// base 与生成补丁时的基准不一致时返回 ChecksumMismatch 错误码 (此时应重新下载完整文件)
+#[wasm_bindgen]
+pub fn apply_patch(base: &[u8], patch: &[u8]) -> Result<Vec<u8>, JsValue> {
+    Ok(apply(base, patch)?)
+}
+
+// 按补丁声明的目标长度登记内存配额后应用补丁
+pub(crate) fn apply(base: &[u8], patch: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let _reservation = quota::reserve(fastdog_core::patch::target_len(patch)?)?;
+    fastdog_core::patch::apply(base, patch)
+}
@@ -0,0 +1,40 @@
+use wasm_bindgen::prelude::*;
+
+// 构造可被结构化克隆的普通 JS 对象 (用于各个类的 toPlainObject)
+//
+// 只包含数字、字符串、布尔值、普通对象和类型化数组，可以直接 postMessage 到其他 Worker，
+// 不会携带 WASM 类实例的指针。
+pub(crate) struct PlainObject {
+    object: js_sys::Object,
+}
+
+impl PlainObject {
+    pub fn new() -> PlainObject {
+        PlainObject {
+            object: js_sys::Object::new(),
+        }
+    }
+
+    pub fn set(self, key: &str, value: impl Into<JsValue>) -> PlainObject {
+        // 对新建的普通对象设置属性不会失败
+        let _ = js_sys::Reflect::set(&self.object, &JsValue::from_str(key), &value.into());
+        self
+    }
+
+    // None 写为 undefined
+    pub fn set_opt<T: Into<JsValue>>(self, key: &str, value: Option<T>) -> PlainObject {
+        match value {
+            Some(value) => self.set(key, value),
+            None => self.set(key, JsValue::UNDEFINED),
+        }
+    }
+
+    // 复制为独立的 Uint8Array (不引用 WASM 内存)
+    pub fn set_bytes(self, key: &str, bytes: &[u8]) -> PlainObject {
+        self.set(key, js_sys::Uint8Array::from(bytes))
+    }
+
+    pub fn build(self) -> JsValue {
+        self.object.into()
+    }
+}
@@ -0,0 +1,207 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{ErrorEvent, MessageEvent, Worker, WorkerOptions, WorkerType};
+
+use crate::plain::PlainObject;
+
+// Web Worker 解码池
+//
+// 启动 N 个运行 worker 脚本 (static/js/fastdog-worker.js) 的 Worker，把解码任务分派给
+// 当前任务最少的 Worker，输入的 ArrayBuffer 以 transfer 方式传递，结果通过 Promise 返回。
+// 应用无需各自封装 Worker 通信。
+//
+// 消息协议: 请求 { id, data: ArrayBuffer }，响应 { id, result } 或 { id, error }，
+// result 与 decode_fastdog_binary 的返回值相同。
+struct PendingJob {
+    resolve: js_sys::Function,
+    reject: js_sys::Function,
+    worker: usize,
+}
+
+#[derive(Default)]
+struct PoolState {
+    pending: BTreeMap<u32, PendingJob>,
+    // 每个 Worker 上未完成的任务数
+    load: Vec<u32>,
+    next_id: u32,
+}
+
+impl PoolState {
+    fn settle(&mut self, id: u32) -> Option<PendingJob> {
+        let job = self.pending.remove(&id)?;
+        self.load[job.worker] -= 1;
+        Some(job)
+    }
+
+    // Worker 出错时拒绝分派给它的所有任务
+    fn fail_worker(&mut self, worker: usize, message: &str) {
+        let ids: Vec<u32> = self
+            .pending
+            .iter()
+            .filter(|(_, job)| job.worker == worker)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in ids {
+            if let Some(job) = self.settle(id) {
+                let _ = job.reject.call1(&JsValue::NULL, &JsValue::from_str(message));
+            }
+        }
+    }
+}
+
+type MessageHandler = Closure<dyn FnMut(MessageEvent)>;
+type ErrorHandler = Closure<dyn FnMut(ErrorEvent)>;
+
+#[wasm_bindgen]
+pub struct DecoderPool {
+    workers: Vec<Worker>,
+    state: Rc<RefCell<PoolState>>,
+    // 事件回调需要在 Worker 存活期间保持有效
+    _handlers: Vec<(MessageHandler, ErrorHandler)>,
+}
+
+#[wasm_bindgen]
+impl DecoderPool {
+    // worker_url: worker 脚本地址 (以 module 类型加载)；size 省略时使用 navigator.hardwareConcurrency
+    #[wasm_bindgen(constructor)]
+    pub fn new(worker_url: &str, size: Option<u32>) -> Result<DecoderPool, JsValue> {
+        let size = size.unwrap_or_else(default_pool_size).max(1);
+        let state = Rc::new(RefCell::new(PoolState {
+            load: vec![0; size as usize],
+            ..Default::default()
+        }));
+
+        let options = WorkerOptions::new();
+        options.set_type(WorkerType::Module);
+
+        let mut workers = Vec::with_capacity(size as usize);
+        let mut handlers = Vec::with_capacity(size as usize);
+        for index in 0..size as usize {
+            let worker = Worker::new_with_options(worker_url, &options)?;
+
+            let on_message = {
+                let state = state.clone();
+                Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                    handle_message(&state, event.data());
+                })
+            };
+            let on_error = {
+                let state = state.clone();
+                Closure::<dyn FnMut(ErrorEvent)>::new(move |event: ErrorEvent| {
+                    let message = format!("解码 Worker 出错: {}", event.message());
+                    state.borrow_mut().fail_worker(index, &message);
+                })
+            };
+            worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+            worker.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+            workers.push(worker);
+            handlers.push((on_message, on_error));
+        }
+
+        Ok(DecoderPool {
+            workers,
+            state,
+            _handlers: handlers,
+        })
+    }
+
+    // 提交解码任务，返回 Promise<DecodeResult>。
+    // 数据恰好占满整个 ArrayBuffer 时直接转移所有权 (调用方的 buffer 随后被分离)，否则先复制
+    #[wasm_bindgen(unchecked_return_type = "Promise<DecodeResult>")]
+    pub fn decode(&self, data: js_sys::Uint8Array) -> js_sys::Promise {
+        let buffer = data.buffer();
+        let buffer = if data.byte_offset() == 0 && data.byte_length() == buffer.byte_length() {
+            buffer
+        } else {
+            data.slice(0, data.length()).buffer()
+        };
+
+        let state = self.state.clone();
+        let workers = self.workers.clone();
+        js_sys::Promise::new(&mut |resolve, reject| {
+            if workers.is_empty() {
+                let _ = reject.call1(&JsValue::NULL, &JsValue::from_str("解码池已关闭"));
+                return;
+            }
+
+            let mut state = state.borrow_mut();
+            let worker = (0..workers.len()).min_by_key(|&index| state.load[index]).unwrap_or(0);
+            let id = state.next_id;
+            state.next_id = state.next_id.wrapping_add(1);
+
+            let message = js_sys::Object::new();
+            let _ = js_sys::Reflect::set(&message, &"id".into(), &id.into());
+            let _ = js_sys::Reflect::set(&message, &"data".into(), &buffer);
+            let transfer = js_sys::Array::of1(&buffer);
+
+            if let Err(error) = workers[worker].post_message_with_transfer(&message, &transfer) {
+                let _ = reject.call1(&JsValue::NULL, &error);
+                return;
+            }
+            state.load[worker] += 1;
+            state.pending.insert(id, PendingJob { resolve, reject, worker });
+        })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> u32 {
+        self.workers.len() as u32
+    }
+
+    // 未完成的任务数
+    #[wasm_bindgen(getter)]
+    pub fn pending(&self) -> u32 {
+        self.state.borrow().pending.len() as u32
+    }
+
+    #[wasm_bindgen(js_name = toPlainObject, unchecked_return_type = "DecoderPoolSnapshot")]
+    pub fn to_plain_object(&self) -> JsValue {
+        PlainObject::new().set("size", self.size()).set("pending", self.pending()).build()
+    }
+
+    // 终止所有 Worker，未完成的任务以错误结束
+    #[wasm_bindgen]
+    pub fn terminate(&mut self) {
+        let mut state = self.state.borrow_mut();
+        for (index, worker) in self.workers.drain(..).enumerate() {
+            worker.terminate();
+            state.fail_worker(index, "解码池已关闭");
+        }
+    }
+}
+
+impl Drop for DecoderPool {
+    fn drop(&mut self) {
+        self.terminate();
+    }
+}
+
+fn handle_message(state: &Rc<RefCell<PoolState>>, data: JsValue) {
+    let id = js_sys::Reflect::get(&data, &"id".into())
+        .ok()
+        .and_then(|id| id.as_f64())
+        .map(|id| id as u32);
+    let Some(job) = id.and_then(|id| state.borrow_mut().settle(id)) else {
+        return;
+    };
+
+    let error = js_sys::Reflect::get(&data, &"error".into()).unwrap_or(JsValue::UNDEFINED);
+    if error.is_undefined() {
+        let result = js_sys::Reflect::get(&data, &"result".into()).unwrap_or(JsValue::UNDEFINED);
+        let _ = job.resolve.call1(&JsValue::NULL, &result);
+    } else {
+        let _ = job.reject.call1(&JsValue::NULL, &error);
+    }
+}
+
+fn default_pool_size() -> u32 {
+    js_sys::Reflect::get(&js_sys::global(), &"navigator".into())
+        .and_then(|navigator| js_sys::Reflect::get(&navigator, &"hardwareConcurrency".into()))
+        .ok()
+        .and_then(|concurrency| concurrency.as_f64())
+        .map_or(4, |concurrency| concurrency as u32)
+}
@@ -0,0 +1,52 @@
+use wasm_bindgen::prelude::*;
+
+use crate::guard;
+
+pub(crate) use fastdog_core::profiles::*;
+
+// 列出所有可用档案名称 (内置 + 自定义)
+#[wasm_bindgen(unchecked_return_type = "string[]")]
+pub fn list_encoder_profiles() -> JsValue {
+    guard::to_js(&profile_names())
+}
+
+// 获取档案，不存在时返回 null
+#[wasm_bindgen(unchecked_return_type = "EncoderProfile | null")]
+pub fn get_encoder_profile(name: &str) -> JsValue {
+    match find_profile(name) {
+        Some(profile) => guard::to_js(&profile),
+        None => JsValue::NULL,
+    }
+}
+
+// 注册或覆盖一个自定义档案
+#[wasm_bindgen]
+pub fn set_encoder_profile(#[wasm_bindgen(unchecked_param_type = "EncoderProfile")] profile: JsValue) -> Result<(), JsValue> {
+    let profile: EncoderProfile = serde_wasm_bindgen::from_value(profile)
+        .map_err(|e| JsValue::from_str(&format!("档案格式无效: {}", e)))?;
+    set_profile(profile).map_err(|e| JsValue::from_str(&e))
+}
+
+// 删除自定义档案，内置档案无法删除
+#[wasm_bindgen]
+pub fn remove_encoder_profile(name: &str) -> bool {
+    remove_profile(name)
+}
+
+// 设置默认档案
+#[wasm_bindgen]
+pub fn set_default_encoder_profile(name: &str) -> Result<(), JsValue> {
+    set_default_profile(name).map_err(|e| JsValue::from_str(&e))
+}
+
+// 将自定义档案和默认档案名称导出为 JSON，便于在管线工具之间共享
+#[wasm_bindgen]
+pub fn export_encoder_profiles() -> String {
+    export_profiles()
+}
+
+// 从 JSON 导入档案 (export_encoder_profiles 的输出或档案数组)，返回导入的档案数
+#[wasm_bindgen]
+pub fn import_encoder_profiles(json: &str) -> Result<u32, JsValue> {
+    import_profiles(json).map_err(|e| JsValue::from_str(&e))
+}
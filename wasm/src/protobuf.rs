@@ -0,0 +1,511 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use base64::Engine;
+use wasm_bindgen::prelude::*;
+
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::guard;
+use crate::plain::PlainObject;
+use crate::structured::{Reader, Value, MAX_DEPTH};
+use crate::payload_kind::PayloadKind;
+use crate::{clock, decode_container, DecodeStats};
+
+// Protobuf 载荷 (内容类型 protobuf)
+//
+// 载荷为若干条带长度前缀的消息: varint 长度 | 消息，依次排列。消息类型由解码参数或容器元数据
+// `protobuf.message_type` 给出，按注册的描述符集 (protoc --descriptor_set_out 的 FileDescriptorSet)
+// 以 proto3 JSON 映射输出为对象: 字段名用 json_name，64 位整数为字符串，bytes 为 base64，
+// 枚举为名称，map 字段为对象。未知字段忽略，没有消息类型时每条消息输出原始字节
+pub(crate) const MESSAGE_TYPE_KEY: &str = "protobuf.message_type";
+
+thread_local! {
+    static REGISTRY: RefCell<Registry> = RefCell::new(Registry::default());
+}
+
+#[derive(Default)]
+struct Registry {
+    messages: HashMap<String, Rc<MessageType>>,
+    enums: HashMap<String, Rc<EnumType>>,
+}
+
+struct MessageType {
+    fields: HashMap<u32, FieldDescriptor>,
+    map_entry: bool,
+}
+
+struct FieldDescriptor {
+    json_name: String,
+    repeated: bool,
+    kind: u64,
+    // 消息 / 枚举字段引用的类型全名 (不含开头的点)
+    type_name: String,
+}
+
+struct EnumType {
+    values: HashMap<i32, String>,
+}
+
+// FieldDescriptorProto.Type
+const TYPE_DOUBLE: u64 = 1;
+const TYPE_FLOAT: u64 = 2;
+const TYPE_INT64: u64 = 3;
+const TYPE_UINT64: u64 = 4;
+const TYPE_INT32: u64 = 5;
+const TYPE_FIXED64: u64 = 6;
+const TYPE_FIXED32: u64 = 7;
+const TYPE_BOOL: u64 = 8;
+const TYPE_STRING: u64 = 9;
+const TYPE_GROUP: u64 = 10;
+const TYPE_MESSAGE: u64 = 11;
+const TYPE_BYTES: u64 = 12;
+const TYPE_UINT32: u64 = 13;
+const TYPE_ENUM: u64 = 14;
+const TYPE_SFIXED32: u64 = 15;
+const TYPE_SFIXED64: u64 = 16;
+const TYPE_SINT32: u64 = 17;
+const TYPE_SINT64: u64 = 18;
+
+const LABEL_REPEATED: u64 = 3;
+
+// 线上格式的一个字段值
+enum Wire<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+fn read_varint(reader: &mut Reader) -> Result<u64, DecodeError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = reader.u8()?;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(reader.error("varint 超过 10 字节"))
+}
+
+fn read_fixed(reader: &mut Reader, size: usize) -> Result<u64, DecodeError> {
+    Ok(reader.take(size)?.iter().rev().fold(0, |value, &byte| (value << 8) | byte as u64))
+}
+
+// 按线上格式读出一条消息的全部字段 (字段号, 值)
+fn read_fields<'a>(bytes: &'a [u8], format: &'static str) -> Result<Vec<(u32, Wire<'a>)>, DecodeError> {
+    let mut reader = Reader::new(bytes, format);
+    let mut fields = Vec::new();
+    while reader.remaining() > 0 {
+        let key = read_varint(&mut reader)?;
+        let number = u32::try_from(key >> 3)
+            .ok()
+            .filter(|&number| number > 0)
+            .ok_or_else(|| reader.error(format!("字段号无效: {}", key >> 3)))?;
+        let value = match key & 0x7 {
+            0 => Wire::Varint(read_varint(&mut reader)?),
+            1 => Wire::Fixed64(read_fixed(&mut reader, 8)?),
+            2 => {
+                let len = read_varint(&mut reader)?;
+                let len = reader.length(len, 1)?;
+                Wire::Bytes(reader.take(len)?)
+            }
+            5 => Wire::Fixed32(read_fixed(&mut reader, 4)? as u32),
+            wire_type => return Err(reader.error(format!("不支持的线上类型 {} (字段 {})", wire_type, number))),
+        };
+        fields.push((number, value));
+    }
+    Ok(fields)
+}
+
+fn string_field(value: &Wire) -> String {
+    match value {
+        Wire::Bytes(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+        _ => String::new(),
+    }
+}
+
+fn varint_field(value: &Wire) -> u64 {
+    match value {
+        Wire::Varint(value) => *value,
+        _ => 0,
+    }
+}
+
+fn qualified(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", scope, name)
+    }
+}
+
+// 未设置 json_name 时按 protoc 的规则由字段名得到: 去掉下划线，其后的字母大写
+fn default_json_name(name: &str) -> String {
+    let mut json_name = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            json_name.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            json_name.push(c);
+        }
+    }
+    json_name
+}
+
+const DESCRIPTOR_FORMAT: &str = "Protobuf 描述符集";
+
+impl Registry {
+    // FileDescriptorSet: file = 1
+    fn add_descriptor_set(&mut self, bytes: &[u8]) -> Result<Vec<String>, DecodeError> {
+        let mut added = Vec::new();
+        for (number, file) in read_fields(bytes, DESCRIPTOR_FORMAT)? {
+            if let (1, Wire::Bytes(file)) = (number, file) {
+                self.add_file(file, &mut added)?;
+            }
+        }
+        Ok(added)
+    }
+
+    // FileDescriptorProto: package = 2, message_type = 4, enum_type = 5
+    fn add_file(&mut self, bytes: &[u8], added: &mut Vec<String>) -> Result<(), DecodeError> {
+        let fields = read_fields(bytes, DESCRIPTOR_FORMAT)?;
+        let package = fields.iter().find(|(number, _)| *number == 2).map(|(_, value)| string_field(value)).unwrap_or_default();
+        for (number, value) in &fields {
+            match (number, value) {
+                (4, Wire::Bytes(message)) => self.add_message(&package, message, added)?,
+                (5, Wire::Bytes(enumeration)) => self.add_enum(&package, enumeration)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    // DescriptorProto: name = 1, field = 2, nested_type = 3, enum_type = 4, options = 7 (map_entry = 7)
+    fn add_message(&mut self, scope: &str, bytes: &[u8], added: &mut Vec<String>) -> Result<(), DecodeError> {
+        let fields = read_fields(bytes, DESCRIPTOR_FORMAT)?;
+        let name = fields.iter().find(|(number, _)| *number == 1).map(|(_, value)| string_field(value)).unwrap_or_default();
+        let full_name = qualified(scope, &name);
+        let mut message = MessageType {
+            fields: HashMap::new(),
+            map_entry: false,
+        };
+        for (number, value) in &fields {
+            match (number, value) {
+                (2, Wire::Bytes(field)) => {
+                    let field = parse_field(field)?;
+                    message.fields.insert(field.0, field.1);
+                }
+                (3, Wire::Bytes(nested)) => self.add_message(&full_name, nested, added)?,
+                (4, Wire::Bytes(enumeration)) => self.add_enum(&full_name, enumeration)?,
+                (7, Wire::Bytes(options)) => {
+                    message.map_entry = read_fields(options, DESCRIPTOR_FORMAT)?
+                        .iter()
+                        .any(|(number, value)| *number == 7 && varint_field(value) != 0);
+                }
+                _ => {}
+            }
+        }
+        self.messages.insert(full_name.clone(), Rc::new(message));
+        added.push(full_name);
+        Ok(())
+    }
+
+    // EnumDescriptorProto: name = 1, value = 2 (EnumValueDescriptorProto: name = 1, number = 2)
+    fn add_enum(&mut self, scope: &str, bytes: &[u8]) -> Result<(), DecodeError> {
+        let fields = read_fields(bytes, DESCRIPTOR_FORMAT)?;
+        let mut name = String::new();
+        let mut values = HashMap::new();
+        for (number, value) in &fields {
+            match (number, value) {
+                (1, value) => name = string_field(value),
+                (2, Wire::Bytes(entry)) => {
+                    let entry = read_fields(entry, DESCRIPTOR_FORMAT)?;
+                    let value_name = entry.iter().find(|(number, _)| *number == 1).map(|(_, value)| string_field(value));
+                    let value_number = entry.iter().find(|(number, _)| *number == 2).map(|(_, value)| varint_field(value) as i32);
+                    if let (Some(value_name), Some(value_number)) = (value_name, value_number) {
+                        values.entry(value_number).or_insert(value_name);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.enums.insert(qualified(scope, &name), Rc::new(EnumType { values }));
+        Ok(())
+    }
+}
+
+// FieldDescriptorProto: name = 1, number = 3, label = 4, type = 5, type_name = 6, json_name = 10
+fn parse_field(bytes: &[u8]) -> Result<(u32, FieldDescriptor), DecodeError> {
+    let mut name = String::new();
+    let mut json_name = None;
+    let mut field_number = 0;
+    let mut field = FieldDescriptor {
+        json_name: String::new(),
+        repeated: false,
+        kind: 0,
+        type_name: String::new(),
+    };
+    for (number, value) in read_fields(bytes, DESCRIPTOR_FORMAT)? {
+        match number {
+            1 => name = string_field(&value),
+            3 => field_number = varint_field(&value) as u32,
+            4 => field.repeated = varint_field(&value) == LABEL_REPEATED,
+            5 => field.kind = varint_field(&value),
+            6 => field.type_name = string_field(&value).trim_start_matches('.').to_string(),
+            10 => json_name = Some(string_field(&value)),
+            _ => {}
+        }
+    }
+    field.json_name = json_name.unwrap_or_else(|| default_json_name(&name));
+    Ok((field_number, field))
+}
+
+// 按消息类型把线上格式转为对象
+struct Renderer<'a> {
+    registry: &'a Registry,
+}
+
+impl Renderer<'_> {
+    fn message_type(&self, name: &str) -> Result<Rc<MessageType>, DecodeError> {
+        self.registry.messages.get(name).cloned().ok_or_else(|| {
+            DecodeError::new(
+                DecodeErrorCode::InvalidInput,
+                format!("Protobuf 消息类型 {} 未注册，请先调用 register_protobuf_descriptors", name),
+            )
+        })
+    }
+
+    fn render(&self, type_name: &str, bytes: &[u8], depth: usize) -> Result<Value, DecodeError> {
+        if depth > MAX_DEPTH {
+            return Err(invalid(format!("Protobuf 消息嵌套超过 {} 层", MAX_DEPTH)));
+        }
+        let message = self.message_type(type_name)?;
+        // 按首次出现的顺序输出字段，重复出现的非 repeated 字段以最后一次为准
+        let mut entries: Vec<(Value, Value)> = Vec::new();
+        let mut positions: HashMap<u32, usize> = HashMap::new();
+        for (number, wire) in read_fields(bytes, "Protobuf")? {
+            let Some(field) = message.fields.get(&number) else {
+                continue;
+            };
+            let is_map = self.is_map(field);
+            let position = *positions.entry(number).or_insert_with(|| {
+                let initial = if is_map {
+                    Value::Map(Vec::new())
+                } else if field.repeated {
+                    Value::Array(Vec::new())
+                } else {
+                    Value::Null
+                };
+                entries.push((Value::Str(field.json_name.clone()), initial));
+                entries.len() - 1
+            });
+            match (&mut entries[position].1, wire) {
+                (Value::Map(map), Wire::Bytes(entry)) if is_map => {
+                    let (key, value) = self.map_entry(field, entry, depth)?;
+                    map.retain(|(existing, _)| *existing != key);
+                    map.push((key, value));
+                }
+                (Value::Array(items), wire) if field.repeated => self.field_values(field, wire, depth, items)?,
+                (slot, wire) => {
+                    let mut values = Vec::new();
+                    self.field_values(field, wire, depth, &mut values)?;
+                    if let Some(value) = values.pop() {
+                        *slot = value;
+                    }
+                }
+            }
+        }
+        Ok(Value::Map(entries))
+    }
+
+    fn is_map(&self, field: &FieldDescriptor) -> bool {
+        field.repeated
+            && field.kind == TYPE_MESSAGE
+            && self.registry.messages.get(&field.type_name).is_some_and(|message| message.map_entry)
+    }
+
+    // map 条目: key = 1, value = 2，缺省的键为空字符串，缺省的值为 null
+    fn map_entry(&self, field: &FieldDescriptor, bytes: &[u8], depth: usize) -> Result<(Value, Value), DecodeError> {
+        let entry = self.message_type(&field.type_name)?;
+        let (mut key, mut value) = (Value::Str(String::new()), Value::Null);
+        for (number, wire) in read_fields(bytes, "Protobuf")? {
+            let Some(entry_field) = entry.fields.get(&number) else {
+                continue;
+            };
+            let mut rendered = Vec::new();
+            self.field_values(entry_field, wire, depth + 1, &mut rendered)?;
+            match (number, rendered.pop()) {
+                (1, Some(rendered)) => key = rendered,
+                (2, Some(rendered)) => value = rendered,
+                _ => {}
+            }
+        }
+        Ok((key, value))
+    }
+
+    // 一个线上字段对应的值，packed 编码的 repeated 数值字段可能包含多个值
+    fn field_values(
+        &self,
+        field: &FieldDescriptor,
+        wire: Wire,
+        depth: usize,
+        values: &mut Vec<Value>,
+    ) -> Result<(), DecodeError> {
+        match (field.kind, wire) {
+            (TYPE_MESSAGE, Wire::Bytes(bytes)) => values.push(self.render(&field.type_name, bytes, depth + 1)?),
+            (TYPE_STRING, Wire::Bytes(bytes)) => values.push(Value::Str(
+                String::from_utf8(bytes.to_vec())
+                    .map_err(|e| DecodeError::new(DecodeErrorCode::Utf8Error, format!("Protobuf 字符串不是有效的 UTF-8: {}", e)))?,
+            )),
+            (TYPE_BYTES, Wire::Bytes(bytes)) => {
+                values.push(Value::Str(base64::engine::general_purpose::STANDARD.encode(bytes)))
+            }
+            (TYPE_GROUP, _) => return Err(invalid("不支持 proto2 group 字段")),
+            // packed 编码: 连续排列的数值
+            (_, Wire::Bytes(bytes)) => {
+                let mut reader = Reader::new(bytes, "Protobuf");
+                while reader.remaining() > 0 {
+                    let wire = match field.kind {
+                        TYPE_DOUBLE | TYPE_FIXED64 | TYPE_SFIXED64 => Wire::Fixed64(read_fixed(&mut reader, 8)?),
+                        TYPE_FLOAT | TYPE_FIXED32 | TYPE_SFIXED32 => Wire::Fixed32(read_fixed(&mut reader, 4)? as u32),
+                        _ => Wire::Varint(read_varint(&mut reader)?),
+                    };
+                    values.push(self.scalar(field, wire)?);
+                }
+            }
+            (_, wire) => values.push(self.scalar(field, wire)?),
+        }
+        Ok(())
+    }
+
+    fn scalar(&self, field: &FieldDescriptor, wire: Wire) -> Result<Value, DecodeError> {
+        let value = match (field.kind, wire) {
+            (TYPE_DOUBLE, Wire::Fixed64(bits)) => Value::Float(f64::from_bits(bits)),
+            (TYPE_FLOAT, Wire::Fixed32(bits)) => Value::Float(f32::from_bits(bits) as f64),
+            (TYPE_INT64, Wire::Varint(value)) => Value::Str((value as i64).to_string()),
+            (TYPE_UINT64, Wire::Varint(value)) | (TYPE_FIXED64, Wire::Fixed64(value)) => Value::Str(value.to_string()),
+            (TYPE_SFIXED64, Wire::Fixed64(value)) => Value::Str((value as i64).to_string()),
+            (TYPE_SINT64, Wire::Varint(value)) => Value::Str(zigzag(value).to_string()),
+            (TYPE_INT32, Wire::Varint(value)) => Value::Int(value as i32 as i64),
+            (TYPE_UINT32, Wire::Varint(value)) => Value::UInt(value as u32 as u64),
+            (TYPE_FIXED32, Wire::Fixed32(value)) => Value::UInt(value as u64),
+            (TYPE_SFIXED32, Wire::Fixed32(value)) => Value::Int(value as i32 as i64),
+            (TYPE_SINT32, Wire::Varint(value)) => Value::Int(zigzag(value as u32 as u64)),
+            (TYPE_BOOL, Wire::Varint(value)) => Value::Bool(value != 0),
+            (TYPE_ENUM, Wire::Varint(value)) => {
+                let number = value as i32;
+                match self.registry.enums.get(&field.type_name).and_then(|enumeration| enumeration.values.get(&number)) {
+                    Some(name) => Value::Str(name.clone()),
+                    None => Value::Int(number as i64),
+                }
+            }
+            _ => return Err(invalid(format!("字段 {} 的线上类型与描述符不一致", field.json_name))),
+        };
+        Ok(value)
+    }
+}
+
+fn zigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn invalid(message: impl Into<String>) -> DecodeError {
+    DecodeError::new(DecodeErrorCode::InvalidInput, message)
+}
+
+// 拆分带长度前缀的消息序列
+fn split_messages(payload: &[u8]) -> Result<Vec<&[u8]>, DecodeError> {
+    let mut reader = Reader::new(payload, "Protobuf 消息序列");
+    let mut messages = Vec::new();
+    while reader.remaining() > 0 {
+        let len = read_varint(&mut reader)?;
+        let len = reader.length(len, 1)?;
+        messages.push(reader.take(len)?);
+    }
+    Ok(messages)
+}
+
+// 解码全部消息: 有消息类型时输出对象，否则输出各条消息的原始字节
+fn render_messages(payload: &[u8], message_type: Option<&str>) -> Result<Value, DecodeError> {
+    let messages = split_messages(payload)?;
+    let Some(message_type) = message_type else {
+        return Ok(Value::Array(messages.into_iter().map(|message| Value::Bytes(message.to_vec())).collect()));
+    };
+    REGISTRY.with(|registry| {
+        let registry = registry.borrow();
+        let renderer = Renderer { registry: &registry };
+        renderer.message_type(message_type)?;
+        let rendered = messages
+            .into_iter()
+            .enumerate()
+            .map(|(index, message)| {
+                renderer
+                    .render(message_type, message, 0)
+                    .map_err(|e| e.context(&format!("第 {} 条消息", index)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Value::Array(rendered))
+    })
+}
+
+// 注册 Protobuf 描述符集 (protoc --include_imports --descriptor_set_out 生成的 FileDescriptorSet)，
+// 返回其中的消息类型全名。可以多次注册，同名类型以后注册的为准
+#[wasm_bindgen(unchecked_return_type = "string[]")]
+pub fn register_protobuf_descriptors(descriptor_set: &[u8]) -> Result<JsValue, JsValue> {
+    let added = REGISTRY
+        .with(|registry| registry.borrow_mut().add_descriptor_set(descriptor_set))
+        .map_err(|e| JsValue::from_str(&e.message))?;
+    Ok(guard::try_to_js(&added)?)
+}
+
+// 清除已注册的全部描述符
+#[wasm_bindgen]
+pub fn clear_protobuf_descriptors() {
+    REGISTRY.with(|registry| *registry.borrow_mut() = Registry::default());
+}
+
+// 解码 Protobuf 载荷。message_type 省略时使用容器元数据 protobuf.message_type，
+// 两者都没有时 messages 为各条消息的原始字节 (Uint8Array)
+#[wasm_bindgen(unchecked_return_type = "ProtobufDecodeResult")]
+pub fn decode_fastdog_protobuf(data: &[u8], message_type: Option<String>) -> JsValue {
+    let start_time = clock::now();
+
+    let result = decode_container(data).and_then(|(header, original_len, decompressed)| {
+        if header.kind != PayloadKind::Protobuf {
+            return Err(DecodeError::new(
+                DecodeErrorCode::UnsupportedContentType,
+                format!("载荷不是 Protobuf (内容类型为 {})", header.kind.name()),
+            ));
+        }
+        let message_type = message_type.or_else(|| header.metadata.get(MESSAGE_TYPE_KEY).cloned());
+        // 对象输出为普通对象而不是 Map
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        let messages = guard::try_to_js_with(&render_messages(&decompressed, message_type.as_deref())?, &serializer)?;
+        let stats = DecodeStats::from_header(&header, original_len, clock::now() - start_time);
+        Ok((message_type, messages, stats))
+    });
+
+    match result {
+        Ok((message_type, messages, stats)) => PlainObject::new()
+            .set("success", true)
+            .set_opt("message_type", message_type)
+            .set("messages", messages)
+            .set("stats", guard::to_js(&stats))
+            .build(),
+        Err(error) => PlainObject::new()
+            .set("success", false)
+            .set("error", error.message)
+            .set("error_code", guard::to_js(&error.code))
+            .set(
+                "stats",
+                guard::to_js(&DecodeStats::failed(data.len() as u64, clock::now() - start_time)),
+            )
+            .build(),
+    }
+}
@@ -0,0 +1,200 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use wasm_bindgen::prelude::*;
+
+use crate::arena;
+use crate::buffer_pool;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::guard;
+
+// 模块级内存配额
+//
+// 解码输出、零拷贝缓冲区池、流式解码缓冲区等由 WASM 侧持有的大块内存在分配前
+// 先向配额登记，超出配额时以 QuotaExceeded 错误失败，而不是让 WASM 内存一直增长直到页面崩溃。
+// 未设置配额时只统计用量。
+struct QuotaState {
+    limit: Option<u64>,
+    used: u64,
+    // 流式解码器缓存的字节数和存活的流式解码器数量
+    stream_bytes: u64,
+    stream_decoders: u32,
+    // 最近一次开始解码时的用量和之后的最高用量
+    decode_base: u64,
+    decode_peak: u64,
+}
+
+static QUOTA: Mutex<QuotaState> = Mutex::new(QuotaState {
+    limit: None,
+    used: 0,
+    stream_bytes: 0,
+    stream_decoders: 0,
+    decode_base: 0,
+    decode_peak: 0,
+});
+
+fn with_quota<T>(f: impl FnOnce(&mut QuotaState) -> T) -> T {
+    let mut quota = QUOTA.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut quota)
+}
+
+// 已登记的内存，释放时自动归还配额
+pub(crate) struct Reservation {
+    bytes: u64,
+    // 流式解码器的缓存，单独计入 get_memory_stats 的 stream_buffer_bytes
+    stream: bool,
+}
+
+impl Reservation {
+    pub fn empty() -> Reservation {
+        Reservation { bytes: 0, stream: false }
+    }
+
+    // 流式解码器持有的登记，存活期间计入活动流式解码器数量
+    pub fn for_stream() -> Reservation {
+        with_quota(|quota| quota.stream_decoders += 1);
+        Reservation { bytes: 0, stream: true }
+    }
+
+    // 调整登记的大小，增长超出配额时返回错误且保持原大小
+    pub fn resize(&mut self, bytes: usize) -> Result<(), DecodeError> {
+        let bytes = bytes as u64;
+        if bytes > self.bytes {
+            acquire(bytes - self.bytes)?;
+        } else {
+            with_quota(|quota| quota.used -= self.bytes - bytes);
+        }
+        if self.stream {
+            with_quota(|quota| quota.stream_bytes = quota.stream_bytes + bytes - self.bytes);
+        }
+        self.bytes = bytes;
+        Ok(())
+    }
+
+    // 只增不减的 resize，用于跟踪持续增长的缓冲区
+    pub fn grow_to(&mut self, bytes: usize) -> Result<(), DecodeError> {
+        if bytes as u64 > self.bytes {
+            self.resize(bytes)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        with_quota(|quota| {
+            quota.used -= self.bytes;
+            if self.stream {
+                quota.stream_bytes -= self.bytes;
+                quota.stream_decoders -= 1;
+            }
+        });
+    }
+}
+
+fn acquire(bytes: u64) -> Result<(), DecodeError> {
+    with_quota(|quota| {
+        let requested = quota.used.saturating_add(bytes);
+        match quota.limit {
+            Some(limit) if requested > limit => Err(DecodeError::new(
+                DecodeErrorCode::QuotaExceeded,
+                format!("内存配额不足: 需要 {} bytes, 已使用 {} / {} bytes", bytes, quota.used, limit),
+            )),
+            _ => {
+                quota.used = requested;
+                quota.decode_peak = quota.decode_peak.max(requested);
+                Ok(())
+            }
+        }
+    })
+}
+
+// 标记一次解码开始，之后的最高用量作为 get_memory_stats 的 last_decode_peak_bytes
+pub(crate) fn begin_decode() {
+    with_quota(|quota| {
+        quota.decode_base = quota.used;
+        quota.decode_peak = quota.used;
+    });
+}
+
+// 登记一块即将分配的内存
+pub(crate) fn reserve(bytes: usize) -> Result<Reservation, DecodeError> {
+    acquire(bytes as u64)?;
+    Ok(Reservation {
+        bytes: bytes as u64,
+        stream: false,
+    })
+}
+
+// 配额中尚未登记的字节数，未设置配额时为 None
+pub(crate) fn available() -> Option<usize> {
+    with_quota(|quota| {
+        quota
+            .limit
+            .map(|limit| usize::try_from(limit.saturating_sub(quota.used)).unwrap_or(usize::MAX))
+    })
+}
+
+// 设置内存配额 (字节)，传入 undefined/null 取消配额。
+// 已超出新配额的现有内存不会被回收，但之后的分配都会失败直到用量回落
+#[wasm_bindgen]
+pub fn set_memory_quota(bytes: Option<u32>) {
+    with_quota(|quota| quota.limit = bytes.map(u64::from));
+}
+
+#[wasm_bindgen]
+pub fn get_memory_quota() -> Option<u32> {
+    with_quota(|quota| quota.limit.map(|limit| limit as u32))
+}
+
+// 当前计入配额的内存字节数
+#[wasm_bindgen]
+pub fn memory_quota_used() -> u32 {
+    with_quota(|quota| quota.used.min(u32::MAX as u64) as u32)
+}
+
+#[derive(Serialize)]
+struct MemoryStats {
+    // WASM 线性内存 (64KB 每页)
+    wasm_memory_pages: u32,
+    wasm_memory_bytes: f64,
+    quota_used_bytes: f64,
+    quota_limit_bytes: Option<f64>,
+    // 零拷贝缓冲区池中尚未释放的缓冲区
+    decoded_buffer_count: u32,
+    decoded_buffer_bytes: u32,
+    // 缓冲区复用池中的空闲缓冲区 (不计入配额)
+    pooled_buffer_count: u32,
+    pooled_buffer_bytes: f64,
+    active_stream_decoders: u32,
+    stream_buffer_bytes: f64,
+    // 最近一次开始的解码期间登记内存的峰值增量 (多个解码交错进行时包含其他解码的分配)
+    last_decode_peak_bytes: f64,
+}
+
+// 内存使用统计，供移动端等内存紧张的环境监控内存压力
+#[wasm_bindgen(unchecked_return_type = "MemoryStats")]
+pub fn get_memory_stats() -> JsValue {
+    #[cfg(target_arch = "wasm32")]
+    let pages = core::arch::wasm32::memory_size(0) as u32;
+    #[cfg(not(target_arch = "wasm32"))]
+    let pages = 0;
+
+    // 先读取缓冲区池，避免同时持有两把锁
+    let decoded_buffer_count = arena::decoded_buffer_count();
+    let decoded_buffer_bytes = arena::decoded_buffer_bytes();
+    let (pooled_buffer_count, pooled_buffer_bytes) = buffer_pool::pooled();
+    let stats = with_quota(|quota| MemoryStats {
+        wasm_memory_pages: pages,
+        wasm_memory_bytes: pages as f64 * 65536.0,
+        quota_used_bytes: quota.used as f64,
+        quota_limit_bytes: quota.limit.map(|limit| limit as f64),
+        decoded_buffer_count,
+        decoded_buffer_bytes,
+        pooled_buffer_count,
+        pooled_buffer_bytes: pooled_buffer_bytes as f64,
+        active_stream_decoders: quota.stream_decoders,
+        stream_buffer_bytes: quota.stream_bytes as f64,
+        last_decode_peak_bytes: (quota.decode_peak - quota.decode_base) as f64,
+    });
+    guard::to_js(&stats)
+}
@@ -0,0 +1,164 @@
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AbortSignal, Response};
+
+use crate::abort;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::plain::PlainObject;
+use crate::seek::{self, BlockIndex};
+use crate::{codec, parse_header_fields, payload_len, quota, required_header_len, ContainerHeader};
+
+// 第一次请求的字节数，通常足以覆盖头部和块偏移表 (16K 个块)，不足时再补一次请求
+const HEADER_PROBE: usize = 64 * 1024;
+
+#[wasm_bindgen]
+extern "C" {
+    // 全局 fetch (窗口和 Worker 中均可用)
+    #[wasm_bindgen(catch, js_name = fetch)]
+    fn fetch_with_init(url: &str, init: &JsValue) -> Result<js_sys::Promise, JsValue>;
+}
+
+// 按 HTTP Range 请求读取远程的分块容器 (encode_fastdog_seekable 生成)
+//
+// open 只下载头部、块偏移表和末尾的原始长度字段，read 只下载覆盖请求区间的块并解压，
+// 数 GB 的远程资源可以按需逐段解码而无需完整下载。服务器必须支持 Range 请求 (返回 206)。
+// 与 decode_range 相同，只校验块长度，不校验整个载荷的 CRC32 / SHA-256
+#[wasm_bindgen]
+pub struct RangeFetcher {
+    remote: Rc<RemoteContainer>,
+}
+
+struct RemoteContainer {
+    url: String,
+    header: ContainerHeader,
+    index: BlockIndex,
+    original_len: u64,
+}
+
+#[wasm_bindgen]
+impl RangeFetcher {
+    // 读取远程容器的头部和块索引。容器没有块索引、HTTP 状态异常或服务器不支持 Range 请求时 Promise 拒绝
+    pub async fn open(url: String, signal: Option<AbortSignal>) -> Result<RangeFetcher, JsValue> {
+        let signal = signal.as_ref();
+        let mut prefix = fetch_range(&url, 0, HEADER_PROBE, signal).await?;
+
+        // 头部和块偏移表超出第一次请求的范围时补齐
+        let needed = required_header_len(&prefix);
+        if prefix.len() < needed && prefix.len() == HEADER_PROBE {
+            let rest = fetch_range(&url, prefix.len(), needed, signal).await?;
+            prefix.extend_from_slice(&rest);
+        }
+        let header = parse_header_fields(&prefix)?;
+        let index = header.block_index.clone().ok_or_else(|| {
+            DecodeError::new(DecodeErrorCode::InvalidInput, "容器没有块索引，无法按区间读取 (需要 encode_fastdog_seekable 编码)")
+        })?;
+
+        // 原始长度字段位于压缩数据之后
+        let trailer_offset = header.payload_offset + header.compressed_len;
+        let original_len = if prefix.len() >= header.total_len() {
+            header.read_original_len(&prefix[trailer_offset..])?
+        } else {
+            let trailer = fetch_range(&url, trailer_offset, header.total_len(), signal).await?;
+            header.read_original_len(&trailer).map_err(|e| e.context("缺少原始数据长度字段"))?
+        };
+        // 块数与原始长度不一致时在此报错，而不是等到第一次读取
+        index.spans(original_len, 0, 0)?;
+
+        Ok(RangeFetcher {
+            remote: Rc::new(RemoteContainer {
+                url,
+                header,
+                index,
+                original_len,
+            }),
+        })
+    }
+
+    // 读取未压缩载荷中 [start, start + len) 的数据，超出末尾的部分被截断 (start 可以超过 4GB)。
+    // 覆盖该区间的块在同一个 Range 请求中下载，Promise 解析为 Uint8Array
+    #[wasm_bindgen(unchecked_return_type = "Promise<Uint8Array>")]
+    pub fn read(&self, start: f64, len: f64, signal: Option<AbortSignal>) -> js_sys::Promise {
+        let remote = self.remote.clone();
+        wasm_bindgen_futures::future_to_promise(async move {
+            let bytes = remote.read(start, len, signal.as_ref()).await?;
+            Ok(js_sys::Uint8Array::from(&bytes[..]).into())
+        })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn url(&self) -> String {
+        self.remote.url.clone()
+    }
+
+    // 未压缩载荷长度 (2^53 以内精确)
+    #[wasm_bindgen(getter)]
+    pub fn original_len(&self) -> f64 {
+        self.remote.original_len as f64
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn block_size(&self) -> u32 {
+        self.remote.index.block_size
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn block_count(&self) -> u32 {
+        self.remote.index.ends.len() as u32
+    }
+}
+
+impl RemoteContainer {
+    async fn read(&self, start: f64, len: f64, signal: Option<&AbortSignal>) -> Result<Vec<u8>, JsValue> {
+        let (start, end) = seek::clamp_range(start, len, self.original_len);
+        let spans = self.index.spans(self.original_len, start, end)?;
+        let (Some(first), Some(last)) = (spans.first(), spans.last()) else {
+            return Ok(Vec::new());
+        };
+        let size = payload_len(end - start)?;
+        let _reservation = quota::reserve(size)?;
+
+        // 需要的块在压缩数据中是连续的
+        let range_start = self.header.payload_offset + first.compressed_start;
+        let range_end = self.header.payload_offset + last.compressed_end;
+        let compressed = fetch_range(&self.url, range_start, range_end, signal).await?;
+        if compressed.len() != range_end - range_start {
+            return Err(DecodeError::new(
+                DecodeErrorCode::Truncated,
+                format!("区间响应长度不匹配: 期望 {} 字节, 实际 {} 字节", range_end - range_start, compressed.len()),
+            )
+            .into());
+        }
+
+        let mut output = Vec::with_capacity(codec::initial_capacity(self.header.codec, compressed.len(), size));
+        for span in &spans {
+            let block = &compressed[span.compressed_start - first.compressed_start..span.compressed_end - first.compressed_start];
+            seek::decode_span(self.header.codec, block, span, &mut output)?;
+        }
+        Ok(output)
+    }
+}
+
+// 下载 [start, end) 字节，区间超出文件末尾时服务器返回的数据较短
+async fn fetch_range(url: &str, start: usize, end: usize, signal: Option<&AbortSignal>) -> Result<Vec<u8>, JsValue> {
+    abort::check(signal)?;
+    let headers = PlainObject::new().set("Range", format!("bytes={}-{}", start, end.saturating_sub(1)));
+    let init = PlainObject::new()
+        .set("headers", headers.build())
+        .set_opt("signal", signal.cloned())
+        .build();
+
+    // signal 触发时 fetch 以 AbortError 拒绝，统一转换为 Aborted 错误码
+    let aborted = |error: JsValue| abort::check(signal).map_or_else(JsValue::from, |_| error);
+    let response: Response = JsFuture::from(fetch_with_init(url, &init)?).await.map_err(aborted)?.unchecked_into();
+    match response.status() {
+        206 => {}
+        200 => return Err(JsValue::from_str("服务器不支持 Range 请求 (返回了完整内容)")),
+        status => {
+            return Err(JsValue::from_str(&format!("HTTP 请求失败: {} {}", status, response.status_text())));
+        }
+    }
+    let buffer = JsFuture::from(response.array_buffer()?).await.map_err(aborted)?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
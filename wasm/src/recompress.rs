@@ -0,0 +1,129 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::codec::{self, Codec};
+use crate::error::DecodeErrorCode;
+use crate::{buffer_pool, clock, decode_container, guard};
+
+// 重新压缩对比报告
+//
+// 解码后用每种编码的若干典型级别重新压缩载荷，记录压缩后大小、压缩和解压耗时，
+// 资源管线可按资源类别实测选择编码和级别。每个组合都会回解并与原载荷比较，
+// 避免把无法还原的组合推荐出去。brotli 11 对大载荷很慢，应在 Worker 中调用
+
+// 每种编码尝试的级别 (lz4 / store 忽略级别)
+const CANDIDATES: &[(Codec, &[u32])] = &[
+    (Codec::Zlib, &[1, 3, 6, 9]),
+    (Codec::Brotli, &[1, 5, 9, 11]),
+    (Codec::Lz4, &[0]),
+    (Codec::Store, &[0]),
+    (Codec::Zstd, &[1, 3, 9, 19]),
+];
+
+#[derive(Serialize, Default)]
+pub struct RecompressReport {
+    pub success: bool,
+    pub error: Option<String>,
+    pub error_code: Option<DecodeErrorCode>,
+    pub original_size: f64,
+    // 原容器的编码和压缩后大小
+    pub current_codec: Option<&'static str>,
+    pub current_size: f64,
+    pub results: Vec<RecompressResult>,
+    // 压缩后最小的组合，大小相同时取解压更快的
+    pub best: Option<RecompressResult>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct RecompressResult {
+    pub codec: &'static str,
+    pub level: u32,
+    pub compressed_size: f64,
+    // 原始大小 / 压缩后大小
+    pub compression_ratio: f64,
+    pub compress_time_ms: f64,
+    pub decompress_time_ms: f64,
+    // 回解结果与原载荷一致
+    pub verified: bool,
+    // 压缩失败时的原因，此时其余大小和耗时为 0
+    pub error: Option<String>,
+}
+
+fn try_candidate(codec: Codec, level: u32, payload: &[u8]) -> RecompressResult {
+    let mut result = RecompressResult {
+        codec: codec.name(),
+        level,
+        compressed_size: 0.0,
+        compression_ratio: 0.0,
+        compress_time_ms: 0.0,
+        decompress_time_ms: 0.0,
+        verified: false,
+        error: None,
+    };
+
+    let start = clock::now();
+    let compressed = match codec::compress(codec, payload, level, 0) {
+        Ok(compressed) => compressed,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+    result.compress_time_ms = clock::now() - start;
+    result.compressed_size = compressed.len() as f64;
+    if !compressed.is_empty() {
+        result.compression_ratio = payload.len() as f64 / compressed.len() as f64;
+    }
+
+    let start = clock::now();
+    match codec::decompress_sliced(codec, &compressed, payload.len(), |_| Ok(())) {
+        Ok(decompressed) => {
+            result.decompress_time_ms = clock::now() - start;
+            result.verified = decompressed == payload;
+            buffer_pool::recycle(decompressed);
+        }
+        Err(e) => result.error = Some(e.message),
+    }
+    result
+}
+
+// 用每种编码和级别重新压缩容器载荷，返回各组合的大小和耗时。
+// 加密容器返回 KeyRequired，应先用 decode_encrypted 解密后重新打包
+#[wasm_bindgen(unchecked_return_type = "RecompressReport")]
+pub fn recompress_report(data: &[u8]) -> JsValue {
+    let report = match decode_container(data) {
+        Ok((header, _, payload)) => {
+            let results: Vec<RecompressResult> = CANDIDATES
+                .iter()
+                .flat_map(|&(codec, levels)| levels.iter().map(move |&level| (codec, level)))
+                .map(|(codec, level)| try_candidate(codec, level, &payload))
+                .collect();
+            let best = results
+                .iter()
+                .filter(|result| result.verified)
+                .min_by(|a, b| {
+                    a.compressed_size
+                        .total_cmp(&b.compressed_size)
+                        .then(a.decompress_time_ms.total_cmp(&b.decompress_time_ms))
+                })
+                .cloned();
+            let report = RecompressReport {
+                success: true,
+                original_size: payload.len() as f64,
+                current_codec: Some(header.codec.name()),
+                current_size: header.compressed_len as f64,
+                results,
+                best,
+                ..Default::default()
+            };
+            buffer_pool::recycle(payload);
+            report
+        }
+        Err(e) => RecompressReport {
+            error: Some(e.message),
+            error_code: Some(e.code),
+            ..Default::default()
+        },
+    };
+    guard::to_js(&report)
+}
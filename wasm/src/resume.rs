@@ -0,0 +1,93 @@
+use wasm_bindgen::prelude::*;
+
+use crate::byte_reader::ByteReader;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::{clock, StreamDecoder};
+
+// 流式解码状态的序列化格式 (版本 1):
+// 魔数 "FDSTATE1"(8) | 版本(4) | 已处理块数(4) | 已接收字节数(4) | 原始字节 CRC32(4) | 已接收的原始字节
+//
+// 解压器的内部状态无法序列化，因此保存的是已接收的原始字节 (即 tee 模式保留的副本)，
+// 恢复时重新送入解压器。续传时从 total_received 处继续请求即可 (HTTP Range)
+const STATE_MAGIC: &[u8; 8] = b"FDSTATE1";
+const STATE_VERSION: u32 = 1;
+const STATE_HEADER_LEN: usize = 24;
+
+#[wasm_bindgen]
+impl StreamDecoder {
+    // 保存当前解码进度，可写入 IndexedDB，下载中断后用 restore() 恢复。
+    // 需要在接收数据前开启 tee 模式；解码已完成或已失败时无需保存
+    #[wasm_bindgen]
+    pub fn save_state(&self) -> Result<Vec<u8>, JsValue> {
+        if !self.tee {
+            return Err(DecodeError::new(
+                DecodeErrorCode::InvalidState,
+                "保存状态需要先开启 tee 模式，请在接收数据前调用 set_tee(true)",
+            )
+            .into());
+        }
+        if self.completed || self.failed {
+            return Err(DecodeError::new(DecodeErrorCode::InvalidState, "流式解码已结束，无需保存状态").into());
+        }
+
+        // 状态格式的字节数字段为 u32
+        let total_received = u32::try_from(self.total_received)
+            .map_err(|_| DecodeError::new(DecodeErrorCode::InvalidState, "已接收的数据超过 4GB，无法保存状态"))?;
+        let mut state = Vec::with_capacity(STATE_HEADER_LEN + self.original.len());
+        state.extend_from_slice(STATE_MAGIC);
+        state.extend_from_slice(&STATE_VERSION.to_le_bytes());
+        state.extend_from_slice(&self.chunks_processed.to_le_bytes());
+        state.extend_from_slice(&total_received.to_le_bytes());
+        state.extend_from_slice(&crc32fast::hash(&self.original).to_le_bytes());
+        state.extend_from_slice(&self.original);
+        Ok(state)
+    }
+
+    // 由 save_state() 的输出恢复解码器，恢复后 tee 模式保持开启，继续调用 add_chunk 即可。
+    // 回调不会随状态保存，需要时重新调用 set_header_callback
+    #[wasm_bindgen]
+    pub fn restore(state: &[u8]) -> Result<StreamDecoder, JsValue> {
+        Ok(restore_state(state)?)
+    }
+}
+
+fn restore_state(state: &[u8]) -> Result<StreamDecoder, DecodeError> {
+    let invalid = |message: String| DecodeError::new(DecodeErrorCode::InvalidInput, message);
+    if state.len() < STATE_HEADER_LEN || &state[..8] != STATE_MAGIC {
+        return Err(invalid("无效的流式解码状态".to_string()));
+    }
+    let header = ByteReader::new(state, "流式解码状态");
+    let version = header.u32_at(8)?;
+    if version != STATE_VERSION {
+        return Err(invalid(format!("不支持的状态版本: {}", version)));
+    }
+
+    let received = &state[STATE_HEADER_LEN..];
+    let total_received = header.u32_at(16)?;
+    if received.len() != total_received as usize {
+        return Err(invalid(format!(
+            "状态数据不完整: 期望 {} 字节, 实际 {} 字节",
+            total_received,
+            received.len()
+        )));
+    }
+    let expected = header.u32_at(20)?;
+    let actual = crc32fast::hash(received);
+    if actual != expected {
+        return Err(invalid(format!(
+            "状态数据 CRC32 校验失败: 期望 {:08x}, 实际 {:08x}",
+            expected, actual
+        )));
+    }
+
+    let mut decoder = StreamDecoder {
+        tee: true,
+        original: received.to_vec(),
+        chunks_processed: header.u32_at(12)?,
+        total_received: total_received as u64,
+        ..StreamDecoder::default()
+    };
+    decoder.process_chunk(received, clock::now())?;
+    decoder.reservation.resize(decoder.held_bytes())?;
+    Ok(decoder)
+}
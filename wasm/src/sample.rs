@@ -0,0 +1,138 @@
+use serde_json::json;
+use wasm_bindgen::prelude::*;
+
+use crate::bundle::BundleWriter;
+use crate::encoder::encode_container;
+use crate::payload_kind::PayloadKind;
+use crate::profiles::{resolve_profile, EncoderProfile};
+
+// 示例资源生成器
+//
+// 按需程序化生成合法的 JSON / GLB 载荷或打包文件，供下游应用编写集成测试和演示，
+// 无需在仓库中提交二进制样本。相同参数总是生成相同的字节。
+struct SampleRng(u64);
+
+impl SampleRng {
+    // xorshift64*
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // [-1, 1) 区间的浮点数
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+}
+
+const SAMPLE_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+// 生成示例文件: kind 为 "json" / "glb" / "archive"，size_hint 为载荷的大致大小 (字节)，
+// profile 省略时使用默认编码档案
+#[wasm_bindgen]
+pub fn make_sample(kind: &str, size_hint: u32, profile: Option<String>) -> Result<Vec<u8>, JsValue> {
+    let profile = resolve_profile(profile.as_deref()).map_err(|e| JsValue::from_str(&e))?;
+    build_sample(kind, size_hint as usize, &profile).map_err(|e| JsValue::from_str(&e))
+}
+
+fn build_sample(kind: &str, size_hint: usize, profile: &EncoderProfile) -> Result<Vec<u8>, String> {
+    let mut rng = SampleRng(SAMPLE_SEED);
+    match kind {
+        "json" => encode_container(&sample_json(&mut rng, size_hint), PayloadKind::Json, profile),
+        "glb" => encode_container(&sample_glb(&mut rng, size_hint), PayloadKind::Glb, profile),
+        "archive" => {
+            let mut writer = BundleWriter::default();
+            writer.add("scene.json", "json", &sample_json(&mut rng, size_hint / 2), profile)?;
+            writer.add("model.glb", "glb", &sample_glb(&mut rng, size_hint / 2), profile)?;
+            Ok(writer.finish()?)
+        }
+        other => Err(format!("不支持的示例类型: {} (可选 json / glb / archive)", other)),
+    }
+}
+
+// 场景描述风格的 JSON，对象数量随 size_hint 增长
+fn sample_json(rng: &mut SampleRng, size_hint: usize) -> Vec<u8> {
+    let mut objects = Vec::new();
+    let mut estimated = 64;
+    while estimated < size_hint || objects.is_empty() {
+        let id = objects.len();
+        let object = json!({
+            "id": id,
+            "name": format!("object-{}", id),
+            "position": [rng.next_f32(), rng.next_f32(), rng.next_f32()],
+            "scale": 1.0 + rng.next_f32().abs(),
+            "visible": !rng.next_u64().is_multiple_of(4),
+            "tags": ["sample", if id.is_multiple_of(2) { "even" } else { "odd" }],
+        });
+        estimated += object.to_string().len() + 1;
+        objects.push(object);
+    }
+
+    let document = json!({
+        "generator": "fastdog make_sample",
+        "version": 1,
+        "objects": objects,
+    });
+    serde_json::to_vec(&document).unwrap()
+}
+
+const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+
+// 只包含一个三角形列表网格 (POSITION 属性) 的 glTF 2.0 二进制文件
+fn sample_glb(rng: &mut SampleRng, size_hint: usize) -> Vec<u8> {
+    // 每个顶点 12 字节，顶点数取 3 的倍数
+    let triangles = (size_hint / 36).max(1);
+    let vertex_count = triangles * 3;
+
+    let mut bin = Vec::with_capacity(vertex_count * 12);
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    for _ in 0..vertex_count {
+        for axis in 0..3 {
+            let value = rng.next_f32();
+            min[axis] = min[axis].min(value);
+            max[axis] = max[axis].max(value);
+            bin.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    let gltf = json!({
+        "asset": { "version": "2.0", "generator": "fastdog make_sample" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "mesh": 0, "name": "sample" }],
+        "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 }, "mode": 4 }] }],
+        "accessors": [{
+            "bufferView": 0,
+            "componentType": 5126,
+            "count": vertex_count,
+            "type": "VEC3",
+            "min": min,
+            "max": max,
+        }],
+        "bufferViews": [{ "buffer": 0, "byteOffset": 0, "byteLength": bin.len(), "target": 34962 }],
+        "buffers": [{ "byteLength": bin.len() }],
+    });
+
+    // 块数据按 4 字节对齐: JSON 块用空格填充，BIN 块用 0 填充
+    let mut json_chunk = serde_json::to_vec(&gltf).unwrap();
+    json_chunk.resize(json_chunk.len().next_multiple_of(4), b' ');
+    bin.resize(bin.len().next_multiple_of(4), 0);
+
+    let total_len = 12 + 8 + json_chunk.len() + 8 + bin.len();
+    let mut glb = Vec::with_capacity(total_len);
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2u32.to_le_bytes());
+    glb.extend_from_slice(&(total_len as u32).to_le_bytes());
+    for (chunk_type, chunk) in [(GLB_CHUNK_JSON, &json_chunk), (GLB_CHUNK_BIN, &bin)] {
+        glb.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+        glb.extend_from_slice(&chunk_type.to_le_bytes());
+        glb.extend_from_slice(chunk);
+    }
+    glb
+}
@@ -0,0 +1,40 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::{codec, decompress_payload_prefix, locate_payload, payload_len, quota};
+
+pub(crate) use fastdog_core::seek::*;
+
+// 读取未压缩载荷中 [start, start + len) 的数据，超出末尾的部分被截断。
+// 带块索引的容器只解压覆盖该区间的块；没有块索引时解压到区间末尾为止 (不解压之后的数据)。
+// 块解压只校验块长度，载荷的 CRC32 / SHA-256 需要完整解码才能校验。
+// start / len 为 number，可以超过 4GB
+#[wasm_bindgen]
+pub fn decode_range(data: &[u8], start: f64, len: f64) -> Result<Vec<u8>, JsValue> {
+    let (header, compressed, original_len) = locate_payload(data)?;
+    let (start, end) = clamp_range(start, len, original_len);
+    let _reservation = quota::reserve(payload_len(end - start)?)?;
+
+    match &header.block_index {
+        Some(index) => {
+            let mut output = Vec::with_capacity(codec::initial_capacity(header.codec, compressed.len(), (end - start) as usize));
+            for span in index.spans(original_len, start, end)? {
+                decode_span(header.codec, &compressed[span.compressed_start..span.compressed_end], &span, &mut output)?;
+            }
+            Ok(output)
+        }
+        None => {
+            let end = payload_len(end)?;
+            let start = start as usize;
+            let prefix = decompress_payload_prefix(&header, compressed, original_len, end)?;
+            if prefix.len() < end {
+                return Err(DecodeError::new(
+                    DecodeErrorCode::Truncated,
+                    format!("解压数据不足: 需要 {} 字节, 实际 {} 字节", end, prefix.len()),
+                )
+                .into());
+            }
+            Ok(prefix[start..end].to_vec())
+        }
+    }
+}
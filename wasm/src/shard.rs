@@ -0,0 +1,104 @@
+use wasm_bindgen::prelude::*;
+
+use crate::clock;
+use crate::error::DecodeError;
+use crate::guard;
+use crate::plain::PlainObject;
+use crate::quota::Reservation;
+use crate::{decode_binary_internal, DecodeResult, DecodeStats};
+
+use fastdog_core::shard::ShardSet;
+
+// 分片重组器
+//
+// 分片可以按任意顺序到达，每个分片到达时校验 CRC32，全部到齐后 finish() 拼接并解码，
+// 返回与 decode_fastdog_binary 相同的结果。重复到达的相同分片被忽略
+#[wasm_bindgen]
+pub struct ShardAssembler {
+    shards: ShardSet,
+    // 已缓存分片占用的内存配额
+    reservation: Reservation,
+}
+
+#[wasm_bindgen]
+impl ShardAssembler {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ShardAssembler {
+        ShardAssembler {
+            shards: ShardSet::default(),
+            reservation: Reservation::empty(),
+        }
+    }
+
+    // 添加一个分片，返回是否已收齐全部分片。
+    // 分片损坏 (CRC32 不符) 或与之前的分片不一致 (总数、总长度、区间) 时抛出带错误码的异常，
+    // 已收到的分片不受影响，可以重新下载该分片后再次添加
+    pub fn add_shard(&mut self, shard: &[u8]) -> Result<bool, JsValue> {
+        let reservation = &mut self.reservation;
+        Ok(self.shards.add(shard, |received| reservation.grow_to(received))?)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.shards.is_complete()
+    }
+
+    // 尚未收到的分片序号 (收到第一个分片之前为空)
+    pub fn missing(&self) -> Vec<u32> {
+        self.shards.missing()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn shard_count(&self) -> u32 {
+        self.shards.shard_count()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn received_count(&self) -> u32 {
+        self.shards.received_count()
+    }
+
+    // 拼接全部分片并解码。分片未收齐、分片区间有空洞或重叠时返回失败结果；
+    // 调用后已缓存的分片被释放，重组器回到初始状态
+    #[wasm_bindgen(unchecked_return_type = "DecodeResult")]
+    pub fn finish(&mut self) -> JsValue {
+        let start_time = clock::now();
+        let result = self
+            .shards
+            .assemble()
+            .and_then(|data| decode_binary_internal(&data, start_time))
+            .unwrap_or_else(|error: DecodeError| DecodeResult {
+                success: false,
+                data: None,
+                error_code: Some(error.code),
+                error: Some(error.message),
+                stats: DecodeStats::failed(self.shards.total_len() as u64, clock::now() - start_time),
+            });
+        *self = ShardAssembler::new();
+        guard::to_js(&result)
+    }
+
+    // 当前进度的普通对象快照 (可结构化克隆)
+    #[wasm_bindgen(js_name = toPlainObject, unchecked_return_type = "ShardAssemblerSnapshot")]
+    pub fn to_plain_object(&self) -> JsValue {
+        PlainObject::new()
+            .set("shard_count", self.shard_count())
+            .set("received_count", self.received_count())
+            .set("received_bytes", self.shards.received_bytes() as u32)
+            .set("total_len", self.shards.total_len())
+            .set("is_complete", self.is_complete())
+            .build()
+    }
+}
+
+impl Default for ShardAssembler {
+    fn default() -> ShardAssembler {
+        ShardAssembler::new()
+    }
+}
+
+// 把 FastDog 文件切成 shard_count 个分片 (构建工具和测试使用，与 CDN 的切分方式一致)
+#[wasm_bindgen(unchecked_return_type = "Uint8Array[]")]
+pub fn split_shards(data: &[u8], shard_count: u32) -> Result<js_sys::Array, JsValue> {
+    let shards = fastdog_core::shard::split(data, shard_count).map_err(|e| JsValue::from_str(&e))?;
+    Ok(shards.iter().map(|shard| js_sys::Uint8Array::from(&shard[..])).collect())
+}
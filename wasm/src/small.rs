@@ -0,0 +1,81 @@
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+
+use crate::buffer_pool;
+use crate::codec::{self, Inflater};
+use crate::error::DecodeError;
+use crate::quota;
+use crate::{decode_container, locate_payload, verify_payload};
+
+// 小载荷快速路径
+//
+// 面向成批出现的小载荷 (如数千个配置片段)：复用线程内的解压器状态和输出缓冲区，
+// 不生成统计信息、不做 JSON 序列化，直接以 Uint8Array 返回解压后的字节，失败时抛出异常。
+// 超过 SMALL_PAYLOAD_LIMIT 的输入自动退回常规解码流程。
+const SMALL_PAYLOAD_LIMIT: usize = 64 * 1024;
+
+struct Scratch {
+    inflater: Inflater,
+    output: Vec<u8>,
+}
+
+thread_local! {
+    static SCRATCH: RefCell<Scratch> = RefCell::new(Scratch {
+        inflater: Inflater::new(),
+        output: Vec::with_capacity(SMALL_PAYLOAD_LIMIT),
+    });
+}
+
+// 解码单个载荷，返回解压后的字节
+#[wasm_bindgen]
+pub fn decode_fastdog_small(data: &[u8]) -> Result<js_sys::Uint8Array, JsValue> {
+    Ok(decode_small(data, |bytes| js_sys::Uint8Array::from(bytes))?)
+}
+
+// 批量解码，减少 JS/WASM 边界往返。返回与输入等长的数组，失败的条目为 null
+#[wasm_bindgen]
+pub fn decode_fastdog_small_batch(blobs: js_sys::Array) -> js_sys::Array {
+    let results = js_sys::Array::new_with_length(blobs.length());
+    for (index, blob) in blobs.iter().enumerate() {
+        let data = js_sys::Uint8Array::new(&blob).to_vec();
+        let decoded = decode_small(&data, |bytes| JsValue::from(js_sys::Uint8Array::from(bytes)));
+        results.set(index as u32, decoded.unwrap_or(JsValue::NULL));
+    }
+    results
+}
+
+// 解压到复用的缓冲区后交给 emit 复制出去
+fn decode_small<T>(data: &[u8], emit: impl FnOnce(&[u8]) -> T) -> Result<T, DecodeError> {
+    let (header, compressed, original_len) = locate_payload(data)?;
+    // 引用压缩字典的载荷不能复用 inflater，走常规解码流程
+    if original_len > SMALL_PAYLOAD_LIMIT as u64 || header.dict_id.is_some() {
+        let (_, _, decompressed) = decode_container(data)?;
+        let result = emit(&decompressed);
+        buffer_pool::recycle(decompressed);
+        return Ok(result);
+    }
+
+    let _reservation = quota::reserve(original_len as usize)?;
+    SCRATCH.with(|scratch| {
+        let mut scratch = scratch.borrow_mut();
+        let Scratch { inflater, output } = &mut *scratch;
+        output.clear();
+
+        #[cfg(feature = "chaos")]
+        crate::chaos::maybe_fail_inflate()?;
+
+        codec::decompress_into(header.codec, compressed, original_len as usize, output, inflater)?;
+
+        #[cfg(feature = "chaos")]
+        crate::chaos::maybe_corrupt(output);
+
+        verify_payload(&header, original_len, output)?;
+        let result = emit(output);
+
+        // 异常大的输出不保留，避免长期占用内存
+        if output.capacity() > SMALL_PAYLOAD_LIMIT * 4 {
+            *output = Vec::with_capacity(SMALL_PAYLOAD_LIMIT);
+        }
+        Ok(result)
+    })
+}
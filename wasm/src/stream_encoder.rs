@@ -0,0 +1,102 @@
+use wasm_bindgen::prelude::*;
+
+use crate::codec::StreamDeflater;
+use crate::encoder::{profile_provenance, write_fixed_header};
+use crate::payload_kind::PayloadKind;
+use crate::profiles::resolve_profile;
+use crate::{format, FLAG_FOOTER, FLAG_PROVENANCE};
+
+// 单遍流式编码器 (尾部帧格式，FLAG_FOOTER)
+//
+// 载荷分块写入，每次返回可以立即发送的容器字节，服务端无需预先知道载荷或压缩数据的长度。
+// 头部的压缩长度写为 0，压缩长度、原始长度和 CRC32 写在压缩数据之后的尾部。
+// 头部无法携带需要完整载荷的字段: 档案的 SHA-256 校验被忽略，CRC32 总是写入尾部
+#[wasm_bindgen]
+pub struct StreamEncoder {
+    // finish() 之后为 None
+    deflater: Option<StreamDeflater>,
+    // 尚未输出的头部，随第一次输出一起返回
+    header: Vec<u8>,
+    hasher: crc32fast::Hasher,
+    original_len: u64,
+    compressed_len: u64,
+}
+
+#[wasm_bindgen]
+impl StreamEncoder {
+    // content_type 与 encode_fastdog 相同，profile 省略时使用默认档案
+    #[wasm_bindgen(constructor)]
+    pub fn new(content_type: &str, profile: Option<String>) -> Result<StreamEncoder, JsValue> {
+        let kind = PayloadKind::from_name(content_type).map_err(|e| JsValue::from_str(&e))?;
+        let profile = resolve_profile(profile.as_deref()).map_err(|e| JsValue::from_str(&e))?;
+
+        let deflater = StreamDeflater::new(profile.codec, profile.level, profile.block_size)
+            .map_err(|e| JsValue::from_str(&e))?;
+
+        let provenance = profile_provenance(&profile);
+        let mut flags = FLAG_FOOTER;
+        if provenance.is_some() {
+            flags |= FLAG_PROVENANCE;
+        }
+        let mut header = Vec::with_capacity(format::v3_header_len(flags));
+        write_fixed_header(&mut header, profile.codec, kind, flags, 0);
+        if let Some(provenance) = &provenance {
+            provenance.write(&mut header);
+        }
+
+        Ok(StreamEncoder {
+            deflater: Some(deflater),
+            header,
+            hasher: crc32fast::Hasher::new(),
+            original_len: 0,
+            compressed_len: 0,
+        })
+    }
+
+    // 写入一段载荷，返回目前可以发送的容器字节 (第一次调用时包含头部，可能为空)
+    #[wasm_bindgen]
+    pub fn write(&mut self, chunk: &[u8]) -> Result<Vec<u8>, JsValue> {
+        let deflater = self.deflater.as_mut().ok_or_else(|| JsValue::from_str("流式编码已结束"))?;
+        deflater.write(chunk).map_err(|e| JsValue::from_str(&e))?;
+        self.hasher.update(chunk);
+        self.original_len += chunk.len() as u64;
+        let compressed = deflater.take_output();
+        Ok(self.emit(&compressed))
+    }
+
+    // 结束编码，返回剩余的压缩数据和尾部。之后不能再写入
+    #[wasm_bindgen]
+    pub fn finish(&mut self) -> Result<Vec<u8>, JsValue> {
+        let deflater = self.deflater.take().ok_or_else(|| JsValue::from_str("流式编码已结束"))?;
+        let compressed = deflater.finish().map_err(|e| JsValue::from_str(&e))?;
+        let mut output = self.emit(&compressed);
+
+        output.extend_from_slice(&self.compressed_len.to_le_bytes());
+        output.extend_from_slice(&self.original_len.to_le_bytes());
+        output.extend_from_slice(&std::mem::take(&mut self.hasher).finalize().to_le_bytes());
+        output.extend_from_slice(format::END_MAGIC);
+        Ok(output)
+    }
+
+    // 已写入的载荷字节数
+    #[wasm_bindgen(getter)]
+    pub fn bytes_in(&self) -> f64 {
+        self.original_len as f64
+    }
+
+    // 已输出的压缩数据字节数 (不含头部和尾部)
+    #[wasm_bindgen(getter)]
+    pub fn bytes_out(&self) -> f64 {
+        self.compressed_len as f64
+    }
+}
+
+impl StreamEncoder {
+    // 拼接尚未输出的头部和新的压缩数据
+    fn emit(&mut self, compressed: &[u8]) -> Vec<u8> {
+        self.compressed_len += compressed.len() as u64;
+        let mut output = std::mem::take(&mut self.header);
+        output.extend_from_slice(compressed);
+        output
+    }
+}
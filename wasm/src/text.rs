@@ -0,0 +1,52 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::plain::PlainObject;
+
+// 大 JSON 载荷的字符串转换
+//
+// String::from_utf8 先在 WASM 内完整校验一遍，wasm-bindgen 转为 JS 字符串时再由 JS 解码一遍。
+// 超过阈值的载荷直接把 WASM 内存上的视图交给 fatal 模式的 TextDecoder，由浏览器原生解码一次，
+// 省去 Rust 侧的校验和中间 String。没有 TextDecoder 的环境回退到原来的转换
+pub(crate) const TEXT_DECODER_THRESHOLD: usize = 64 * 1024;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = TextDecoder)]
+    type NativeTextDecoder;
+
+    #[wasm_bindgen(constructor, catch, js_class = "TextDecoder")]
+    fn new(label: &str, options: &JsValue) -> Result<NativeTextDecoder, JsValue>;
+
+    // 返回 JS 字符串本身，不转换为 Rust String
+    #[wasm_bindgen(method, catch, js_class = "TextDecoder")]
+    fn decode(this: &NativeTextDecoder, input: &js_sys::Uint8Array) -> Result<js_sys::JsString, JsValue>;
+}
+
+thread_local! {
+    // 无效的 UTF-8 抛出异常而不是替换为 U+FFFD
+    static DECODER: Option<NativeTextDecoder> =
+        NativeTextDecoder::new("utf-8", &PlainObject::new().set("fatal", true).build()).ok();
+}
+
+// UTF-8 字节转为 JS 字符串
+pub(crate) fn utf8_to_js(bytes: &[u8]) -> Result<JsValue, DecodeError> {
+    let decoded = DECODER.with(|decoder| {
+        let decoder = decoder.as_ref()?;
+        let result = if cfg!(target_feature = "atomics") {
+            // 共享内存 (多线程构建) 上的视图不能交给 TextDecoder，先复制到普通 ArrayBuffer
+            decoder.decode(&js_sys::Uint8Array::from(bytes))
+        } else {
+            // 视图只在本次调用中使用，期间不会分配 WASM 内存
+            decoder.decode(&unsafe { js_sys::Uint8Array::view(bytes) })
+        };
+        result.ok()
+    });
+    match decoded {
+        Some(text) => Ok(text.into()),
+        // 解码失败时由 Rust 侧给出包含偏移的错误信息
+        None => std::str::from_utf8(bytes)
+            .map(JsValue::from_str)
+            .map_err(|e| DecodeError::new(DecodeErrorCode::Utf8Error, format!("UTF-8 解码失败: {}", e))),
+    }
+}
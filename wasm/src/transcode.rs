@@ -0,0 +1,27 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::{buffer_pool, decode_container};
+
+pub(crate) use fastdog_core::transcode::*;
+
+// 转码为 target_version (1 / 2 / 3) 和 target_codec ("zlib" / "brotli" / "lz4" / "store" / "zstd")。
+// target_codec 省略时版本 3 使用档案的编码，版本 1/2 固定为 zlib; profile 省略时使用默认档案
+#[wasm_bindgen]
+pub fn transcode(
+    data: &[u8],
+    target_version: u32,
+    target_codec: Option<String>,
+    profile: Option<String>,
+) -> Result<Vec<u8>, JsValue> {
+    let codec = target_codec
+        .as_deref()
+        .map(parse_codec)
+        .transpose()
+        .map_err(|e| DecodeError::new(DecodeErrorCode::InvalidInput, e))?;
+    let (header, _, payload) = decode_container(data)?;
+    let output = transcode_payload(&header, &payload, target_version, codec, profile.as_deref());
+    buffer_pool::recycle(payload);
+    Ok(output?)
+}
+
@@ -0,0 +1,63 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::lazy::find_bytes;
+
+// 解码时的数据转换
+//
+// 在解压后、返回结果前对载荷执行，避免在 JS 中对数 MB 的字符串再做一遍处理。
+#[derive(Clone)]
+pub(crate) enum Transform {
+    // 内置: 字节级查找替换 (例如替换 JSON 中资源的基础 URL)
+    Replace { from: Vec<u8>, to: Vec<u8> },
+    // JS 回调: callback(bytes: Uint8Array, content_type: string) -> Uint8Array | string | undefined
+    Callback(js_sys::Function),
+}
+
+impl Transform {
+    fn apply(&self, data: Vec<u8>, content_type: &str) -> Result<Vec<u8>, String> {
+        match self {
+            Transform::Replace { from, to } => Ok(replace_all(&data, from, to)),
+            Transform::Callback(callback) => {
+                let input = js_sys::Uint8Array::from(&data[..]);
+                let output = callback
+                    .call2(&JsValue::NULL, &input, &JsValue::from_str(content_type))
+                    .map_err(|e| format!("转换回调执行失败: {:?}", e))?;
+
+                if output.is_undefined() || output.is_null() {
+                    Ok(data)
+                } else if let Some(text) = output.as_string() {
+                    Ok(text.into_bytes())
+                } else if let Some(bytes) = output.dyn_ref::<js_sys::Uint8Array>() {
+                    Ok(bytes.to_vec())
+                } else {
+                    Err("转换回调必须返回 Uint8Array、字符串或 undefined".to_string())
+                }
+            }
+        }
+    }
+}
+
+// 依次执行所有转换
+pub(crate) fn apply_transforms(transforms: &[Transform], mut data: Vec<u8>, content_type: &str) -> Result<Vec<u8>, String> {
+    for transform in transforms {
+        data = transform.apply(data, content_type)?;
+    }
+    Ok(data)
+}
+
+fn replace_all(data: &[u8], from: &[u8], to: &[u8]) -> Vec<u8> {
+    if from.is_empty() {
+        return data.to_vec();
+    }
+
+    let mut output = Vec::with_capacity(data.len());
+    let mut pos = 0;
+    while let Some(offset) = find_bytes(&data[pos..], from) {
+        output.extend_from_slice(&data[pos..pos + offset]);
+        output.extend_from_slice(to);
+        pos += offset + from.len();
+    }
+    output.extend_from_slice(&data[pos..]);
+    output
+}
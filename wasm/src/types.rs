@@ -0,0 +1,584 @@
+use wasm_bindgen::prelude::*;
+
+// 导出结构的 TypeScript 类型定义
+//
+// 结果结构经 serde_wasm_bindgen 转为普通 JS 对象，wasm-bindgen 只能把它们声明为 any。
+// 这里手写对应的 .d.ts 定义，导出函数通过 unchecked_return_type 引用。
+// 修改任何带 Serialize 的结果结构或 toPlainObject 字段时需同步更新此处。
+// Option 字段为 None 时序列化为 undefined，对应可选属性。
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES: &str = r#"
+export type DecodeErrorCode =
+  | "TooShort"
+  | "BadMagic"
+  | "UnsupportedVersion"
+  | "UnsupportedCodec"
+  | "UnsupportedContentType"
+  | "UnsupportedFlags"
+  | "Truncated"
+  | "LengthMismatch"
+  | "ChecksumMismatch"
+  | "InflateError"
+  | "MissingDictionary"
+  | "KeyRequired"
+  | "DecryptionFailed"
+  | "Utf8Error"
+  | "TransformError"
+  | "QuotaExceeded"
+  | "OutputLimitExceeded"
+  | "Timeout"
+  | "Aborted"
+  | "InvalidInput"
+  | "InvalidState"
+  | "Internal";
+
+export type CodecName = "zlib" | "brotli" | "lz4" | "store" | "zstd";
+
+export type ContentTypeName = "json" | "glb" | "cbor" | "image" | "pointcloud" | "msgpack" | "protobuf" | "custom";
+
+export interface DecodeStats {
+  original_size: number;
+  compressed_size: number;
+  decode_time_ms: number;
+  compression_ratio: number;
+  format_version: number;
+  codec: CodecName | "";
+  checksum_verified: boolean;
+  // 仅 decode_glb_accessors，应用过网格压缩解码时出现
+  mesh_codecs?: string[];
+}
+
+export interface DecodeResult {
+  success: boolean;
+  data?: string;
+  // 仅 DecoderOptions.output_format = "bytes"
+  bytes?: Uint8Array;
+  // 仅 DecoderOptions.output_format = "arraybuffer"，调用方独占的 ArrayBuffer，可直接转移 (transfer)
+  buffer?: ArrayBuffer;
+  // 仅 DecoderOptions.output_format = "object"，JSON / MessagePack / CBOR 载荷解析后的值
+  value?: unknown;
+  // 仅设置了 DecoderOptions.set_shared_output 时，载荷在 SharedArrayBuffer 中的区域
+  shared_output?: { byte_offset: number; byte_length: number };
+  error?: string;
+  error_code?: DecodeErrorCode;
+  stats: DecodeStats;
+}
+
+export interface ProtobufDecodeResult {
+  success: boolean;
+  // 解码时使用的消息类型 (参数或容器元数据 protobuf.message_type)
+  message_type?: string;
+  // 有消息类型时为 proto3 JSON 映射的对象，否则为各条消息的原始字节
+  messages?: (Record<string, unknown> | Uint8Array)[];
+  error?: string;
+  error_code?: DecodeErrorCode;
+  stats: DecodeStats;
+}
+
+export interface GlbDecodeResult {
+  success: boolean;
+  // glTF JSON 块解析后的对象
+  json?: Record<string, unknown>;
+  // BIN 块，GLB 没有 BIN 块时为空数组
+  bin?: Uint8Array;
+  error?: string;
+  error_code?: DecodeErrorCode;
+  stats: DecodeStats;
+}
+
+export interface ThreeGltfResult {
+  success: boolean;
+  // glTF JSON 字符串，可直接传给 GLTFLoader.parse
+  json?: string;
+  // BIN 块
+  buffer?: ArrayBuffer;
+  // GLTFLoader 按 uri 读取的资源 (缓冲区 0 的 uri 改写为 "fastdog:bin")
+  resources?: Record<string, ArrayBuffer>;
+  error?: string;
+  error_code?: DecodeErrorCode;
+  stats: DecodeStats;
+}
+
+export type GltfTypedArray = Int8Array | Uint8Array | Int16Array | Uint16Array | Uint32Array | Float32Array;
+
+export interface GltfPrimitiveArrays {
+  attributes: Record<string, GltfTypedArray>;
+  indices?: Uint8Array | Uint16Array | Uint32Array;
+  mode: number;
+}
+
+export interface GpuSlice {
+  // 相对 data_ptr 的偏移，按 256 字节对齐
+  offset: number;
+  // 字节数 (4 的倍数)
+  size: number;
+  stride: number;
+  count: number;
+  // WebGPU 顶点格式 (如 "float32x3") 或索引格式 ("uint16" / "uint32")，矩阵属性没有对应格式
+  format?: string;
+}
+
+export interface GpuPrimitiveLayout {
+  handle: number;
+  data_ptr: number;
+  data_len: number;
+  attributes: Record<string, GpuSlice>;
+  indices?: GpuSlice;
+  mode: number;
+}
+
+export interface Ktx2TextureInfo {
+  width: number;
+  height: number;
+  levels: number;
+  vk_format: number;
+  supercompression: "none" | "basislz" | "zstd" | "zlib" | "unknown";
+  // Basis Universal 纹理的编码类型，需要 Basis 转码器
+  basis?: "etc1s" | "uastc";
+  // 可直接输出的目标格式
+  format?: "rgba8" | "bc7" | "astc";
+  srgb: boolean;
+}
+
+export interface Ktx2Texture {
+  width: number;
+  height: number;
+  format: "rgba8" | "bc7" | "astc";
+  srgb: boolean;
+  // 级别 0 为最大尺寸
+  levels: Uint8Array[];
+}
+
+export interface BinaryDecodeResult {
+  success: boolean;
+  handle: number;
+  data_ptr: number;
+  data_len: number;
+  error?: string;
+  error_code?: DecodeErrorCode;
+  stats: DecodeStats;
+}
+
+export interface StreamHeaderInfo {
+  version: number;
+  content_type: ContentTypeName;
+  codec: CodecName;
+  compressed_size: number;
+  expected_size: number;
+}
+
+export interface StreamDecodeResult {
+  success: boolean;
+  data?: string;
+  error?: string;
+  error_code?: DecodeErrorCode;
+  progress: number;
+  is_complete: boolean;
+  chunks_processed: number;
+  total_received: number;
+  stats?: DecodeStats;
+}
+
+export interface ChunkedStepResult {
+  success: boolean;
+  data?: string;
+  error?: string;
+  error_code?: DecodeErrorCode;
+  progress: number;
+  is_complete: boolean;
+  steps: number;
+  decoded_bytes: number;
+  expected_bytes: number;
+  stats?: DecodeStats;
+}
+
+export interface TraceStep {
+  stage: string;
+  ok: boolean;
+  message: string;
+  elapsed_ms: number;
+}
+
+export interface DecodeTrace {
+  success: boolean;
+  error?: string;
+  error_code?: DecodeErrorCode;
+  version?: number;
+  codec?: CodecName;
+  content_type?: ContentTypeName;
+  compressed_size?: number;
+  original_size?: number;
+  total_time_ms: number;
+  steps: TraceStep[];
+  text: string;
+}
+
+export interface IntegrityReport {
+  valid: boolean;
+  has_digest: boolean;
+  checksum_verified: boolean;
+  digest?: string;
+  error?: string;
+  error_code?: DecodeErrorCode;
+}
+
+export interface ValidationReport {
+  valid: boolean;
+  version?: number;
+  codec?: CodecName;
+  content_type?: ContentTypeName;
+  compressed_size?: number;
+  original_size?: number;
+  probed_bytes: number;
+  fully_verified: boolean;
+  reasons: string[];
+}
+
+export interface JsonDiffEntry {
+  path: string;
+  value: unknown;
+}
+
+export interface JsonChange {
+  path: string;
+  from: unknown;
+  to: unknown;
+}
+
+export interface JsonDiffResult {
+  success: boolean;
+  error?: string;
+  added: JsonDiffEntry[];
+  removed: JsonDiffEntry[];
+  changed: JsonChange[];
+}
+
+export interface FieldChange {
+  name: string;
+  // A / B 中的值，不存在时为 null
+  from: string | null;
+  to: string | null;
+}
+
+export interface ContainerDiffResult {
+  success: boolean;
+  error?: string;
+  identical: boolean;
+  payload_identical: boolean;
+  header: FieldChange[];
+  metadata: FieldChange[];
+  payload?: {
+    mode: "json" | "binary";
+    size_a: number;
+    size_b: number;
+    added: JsonDiffEntry[];
+    removed: JsonDiffEntry[];
+    changed: JsonChange[];
+    ranges: { offset: number; length: number }[];
+    differing_bytes: number;
+    ranges_truncated: boolean;
+  };
+}
+
+export interface RecompressResult {
+  codec: CodecName;
+  level: number;
+  compressed_size: number;
+  compression_ratio: number;
+  compress_time_ms: number;
+  decompress_time_ms: number;
+  // 回解结果与原载荷一致
+  verified: boolean;
+  error?: string;
+}
+
+export interface RecompressReport {
+  success: boolean;
+  error?: string;
+  error_code?: DecodeErrorCode;
+  original_size: number;
+  current_codec?: CodecName;
+  current_size: number;
+  results: RecompressResult[];
+  // 压缩后最小且回解一致的组合
+  best?: RecompressResult;
+}
+
+export interface Capabilities {
+  library_version: string;
+  container_versions: number[];
+  codecs: CodecName[];
+  payload_kinds: ContentTypeName[];
+  flags: string[];
+  // 模块内解码的网格压缩扩展 (目前只有 EXT_meshopt_compression)
+  mesh_codecs: string[];
+  threads: boolean;
+  simd: boolean;
+  chaos: boolean;
+  limits: {
+    max_payload_size: number;
+    // 未设置内存配额时为 null
+    memory_quota: number | null;
+    max_nesting_depth: number;
+    max_kdf_iterations: number;
+  };
+}
+
+export interface FormatInfo {
+  valid: boolean;
+  magic: string;
+  version: number;
+  codec: CodecName | "";
+  compressed_size: number;
+  original_size: number;
+  total_size: number;
+  // Unix 毫秒时间戳，0 表示未知
+  created_at?: number;
+  producer?: string;
+}
+
+export interface InspectionField {
+  name: string;
+  offset: number;
+  size: number;
+  // 数据不完整时缺省
+  value?: string;
+}
+
+export interface InspectionSection {
+  name: "header" | "extensions" | "block_table" | "metadata" | "payload" | "trailer" | "trailing";
+  offset: number;
+  size: number;
+}
+
+export interface Inspection {
+  valid: boolean;
+  error?: string;
+  error_code?: DecodeErrorCode;
+  total_size: number;
+  header: {
+    magic?: string;
+    version?: number;
+    size?: number;
+    flags?: number;
+    flag_names: string[];
+    fields: InspectionField[];
+  };
+  codec?: CodecName;
+  content_type?: ContentTypeName;
+  payload?: {
+    offset: number;
+    compressed_size: number;
+    original_size?: number;
+    compression_ratio?: number;
+    encrypted: boolean;
+    kdf_iterations?: number;
+    dictionary_id?: number;
+    block_size?: number;
+    block_count?: number;
+  };
+  checksums: { crc32?: string; sha256?: string };
+  metadata: Record<string, string>;
+  provenance?: { created_at: number; producer: string };
+  trailer_kind?: "original_len" | "footer";
+  trailing_bytes: number;
+  sections: InspectionSection[];
+}
+
+export interface BenchmarkResult {
+  iterations: number;
+  warmup_iterations: number;
+  total_time_ms: number;
+  avg_time_ms: number;
+  min_time_ms: number;
+  max_time_ms: number;
+  p50_time_ms: number;
+  p95_time_ms: number;
+  p99_time_ms: number;
+  std_dev_ms: number;
+  original_size: number;
+  throughput_mb_s: number;
+  success_rate: number;
+}
+
+export interface MemoryStats {
+  wasm_memory_pages: number;
+  wasm_memory_bytes: number;
+  quota_used_bytes: number;
+  quota_limit_bytes?: number;
+  decoded_buffer_count: number;
+  decoded_buffer_bytes: number;
+  pooled_buffer_count: number;
+  pooled_buffer_bytes: number;
+  active_stream_decoders: number;
+  stream_buffer_bytes: number;
+  last_decode_peak_bytes: number;
+}
+
+export interface AllocatorStats {
+  allocator: "dlmalloc" | "system" | "wee_alloc";
+  allocations: number;
+  deallocations: number;
+  reallocations: number;
+  current_bytes: number;
+  peak_bytes: number;
+}
+
+export interface FormatField {
+  name: string;
+  offset: number;
+  size: number;
+  type: "bytes" | "u8" | "u16" | "u32" | "u64";
+  description: string;
+}
+
+export interface FormatExtension {
+  flag: number;
+  name: string;
+  size: number;
+  type: "bytes" | "u8" | "u16" | "u32" | "u64";
+  description: string;
+}
+
+export interface FormatDescription {
+  version: number;
+  byte_order: "little-endian";
+  header: FormatField[];
+  extensions: FormatExtension[];
+  extensions_offset?: number;
+  payload: string;
+  trailer: FormatField[];
+  footer: FormatField[];
+  codecs: { id: number; name: CodecName }[];
+  content_types: { id: number; name: ContentTypeName }[];
+}
+
+export interface EncoderProfile {
+  name: string;
+  codec: CodecName;
+  level?: number;
+  block_size?: number;
+  checksum?: "none" | "crc32" | "sha256" | "all";
+  deterministic?: boolean;
+  producer?: string;
+}
+
+export interface BundleEntry {
+  name: string;
+  content_type: string;
+  codec: CodecName;
+  offset: number;
+  compressed_size: number;
+  original_size: number;
+  crc32: number;
+}
+
+export interface BundleInput {
+  name: string;
+  data: Uint8Array;
+  content_type?: string;
+}
+
+export interface DecodedBundleEntry {
+  name: string;
+  content_type: string;
+  codec: CodecName;
+  compressed_size: number;
+  original_size: number;
+  crc32: number;
+  data: Uint8Array;
+}
+
+export interface ArchiveChange {
+  name: string;
+  data: Uint8Array | null;
+  content_type?: string;
+}
+
+export interface StreamDecoderSnapshot {
+  progress: number;
+  buffer_size: number;
+  expected_size?: number;
+  compressed_size?: number;
+  original_size?: number;
+  version?: number;
+  codec?: CodecName;
+  content_type?: ContentTypeName;
+  chunks_processed: number;
+  total_received: number;
+  tee: boolean;
+  original_bytes?: Uint8Array;
+}
+
+export interface ChunkedDecoderSnapshot {
+  progress: number;
+  is_complete: boolean;
+  steps: number;
+  decoded_bytes?: number;
+  expected_bytes: number;
+  version: number;
+  codec: CodecName;
+}
+
+export interface DecodedBufferSnapshot {
+  handle: number;
+  stats: DecodeStats;
+  data: Uint8Array | null;
+}
+
+export interface DecodeBufferRegion {
+  handle: number;
+  data_ptr: number;
+  data_len: number;
+}
+
+export interface LazyPayloadSnapshot {
+  version: number;
+  content_type: ContentTypeName;
+  length: number;
+  data: Uint8Array;
+}
+
+export interface DecoderOptionsSnapshot {
+  max_decode_ms?: number;
+  max_output_bytes?: number;
+  max_ratio?: number;
+  output_format: "auto" | "text" | "base64" | "bytes" | "object" | "arraybuffer";
+  strict: boolean;
+  log: boolean;
+  custom_clock: boolean;
+  shared_output_offset?: number;
+  replace_transforms: { from: string; to: string }[];
+  callback_transforms: number;
+}
+
+export interface DecoderPoolSnapshot {
+  size: number;
+  pending: number;
+}
+
+export interface ShardAssemblerSnapshot {
+  shard_count: number;
+  received_count: number;
+  received_bytes: number;
+  total_len: number;
+  is_complete: boolean;
+}
+
+export interface LodLevelEvent {
+  level: number;
+  level_count: number;
+  // 该级完整的 GLB
+  glb: Uint8Array;
+  // 由上一级得到本级的补丁 (apply_patch 格式)，级别 0 没有
+  patch?: Uint8Array;
+}
+
+export type LogCallback = (level: "error" | "warn" | "info" | "debug", message: string) => void;
+export type StreamHeaderCallback = (header: StreamHeaderInfo) => void;
+export type StreamResultCallback = (result: StreamDecodeResult) => void;
+export type LodLevelCallback = (level: LodLevelEvent) => void;
+
+// StreamDecoder.set_json_element_callback: 顶层数组的元素 (已解析) 和序号
+export type JsonElementCallback = (element: unknown, index: number) => void;
+"#;
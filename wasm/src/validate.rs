@@ -0,0 +1,23 @@
+use wasm_bindgen::prelude::*;
+
+use crate::guard;
+use crate::quota::Reservation;
+
+pub use fastdog_core::validate::ValidationReport;
+
+// 严格格式校验，适合在上传表单和边缘 Worker 中使用
+//
+// 比 validate_fastdog_format 多检查头部字段、长度一致性、压缩比是否可信以及载荷开头的内容，
+// 但最多只解压 64KB，不做完整解码
+#[wasm_bindgen(unchecked_return_type = "ValidationReport")]
+pub fn validate_strict(data: &[u8]) -> JsValue {
+    guard::to_js(&fastdog_core::validate::validate_strict(data))
+}
+
+// 深度校验: 完整解压并校验长度、CRC32 和 SHA-256，JSON 载荷完整解析，GLB 载荷检查头部和块表。
+// 比 validate_strict 慢得多 (需要完整解码)，适合导入前的离线检查。解压输出计入内存配额
+#[wasm_bindgen(unchecked_return_type = "ValidationReport")]
+pub fn validate_deep(data: &[u8]) -> JsValue {
+    let mut reservation = Reservation::empty();
+    guard::to_js(&fastdog_core::validate::validate_deep(data, |len| reservation.grow_to(len)))
+}
@@ -0,0 +1,286 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    AbortSignal, Blob, ReadableStream, ReadableStreamDefaultReader, ReadableStreamReadResult, Response, TransformStream,
+    TransformStreamDefaultController,
+};
+
+use crate::abort::{self, OnAbort};
+use crate::byte_reader::ByteReader;
+use crate::codec::StreamInflater;
+use crate::error::{DecodeError, DecodeErrorCode};
+use crate::{
+    bound_stream_output, header_available, open_stream_inflater, parse_header_fields, payload_len, split_footer_stream,
+    ContainerHeader, StreamDecoder,
+};
+
+// Web Streams 集成: 直接从 ReadableStream 读取数据并驱动 StreamDecoder
+#[wasm_bindgen]
+impl StreamDecoder {
+    // 读取整个数据流并逐块解码，Promise 解析为最终的 StreamDecodeResult (失败时 success 为 false)，
+    // 读取数据流本身出错时 Promise 拒绝。构造时注册的回调照常触发。
+    // 调用后解码器被消费，JS 侧对象不可再使用。
+    // signal 触发时取消数据流并返回 Aborted 错误码，已缓存的数据随解码器一起释放
+    #[wasm_bindgen(unchecked_return_type = "StreamDecodeResult")]
+    pub async fn consume(mut self, stream: ReadableStream, signal: Option<AbortSignal>) -> Result<JsValue, JsValue> {
+        let reader: ReadableStreamDefaultReader = stream.get_reader().unchecked_into();
+        // 取消数据流使等待中的读取立即结束，而不是等到下一个数据块到达
+        let cancel_reader = reader.clone();
+        let _on_abort = OnAbort::new(signal.as_ref(), move || {
+            let _ = cancel_reader.cancel();
+        });
+
+        loop {
+            if let Err(e) = abort::check(signal.as_ref()) {
+                let _ = reader.cancel();
+                return Ok(self.fail(e));
+            }
+            let chunk: ReadableStreamReadResult = JsFuture::from(reader.read()).await?.unchecked_into();
+            if chunk.get_done().unwrap_or(false) {
+                if let Err(e) = abort::check(signal.as_ref()) {
+                    return Ok(self.fail(e));
+                }
+                break;
+            }
+
+            let result = self.add_chunk(&js_sys::Uint8Array::new(&chunk.get_value()).to_vec());
+            if self.completed || self.failed {
+                // 解码已结束，剩余数据不再需要
+                let _ = reader.cancel();
+                return Ok(result);
+            }
+        }
+
+        reader.release_lock();
+        Ok(self.end_of_input())
+    }
+}
+
+impl StreamDecoder {
+    // 输入在解码完成前结束，按失败处理
+    fn end_of_input(&mut self) -> JsValue {
+        let error = DecodeError::new(
+            DecodeErrorCode::Truncated,
+            format!(
+                "数据流在解码完成前结束: 已接收 {} 字节, 预期 {} 字节",
+                self.total_received,
+                self.expected_size.map_or("未知".to_string(), |size| size.to_string())
+            ),
+        );
+        self.fail(error)
+    }
+
+    fn fail(&mut self, error: DecodeError) -> JsValue {
+        self.failed = true;
+        self.emit(self.error_result(error))
+    }
+}
+
+// 边下载边解码 fetch 响应，无需先把整个响应缓存为 ArrayBuffer。
+// on_progress 在每个数据块处理后以 StreamDecodeResult 调用 (progress 同时反映下载和解码进度)，
+// Promise 解析为最终的 StreamDecodeResult；HTTP 状态异常或网络读取出错时 Promise 拒绝。
+// signal 通常与传给 fetch 的相同，触发后返回 Aborted 错误码
+#[wasm_bindgen(unchecked_return_type = "StreamDecodeResult")]
+pub async fn decode_from_response(
+    response: Response,
+    #[wasm_bindgen(unchecked_param_type = "StreamResultCallback | null")] on_progress: Option<js_sys::Function>,
+    signal: Option<AbortSignal>,
+) -> Result<JsValue, JsValue> {
+    if !response.ok() {
+        return Err(JsValue::from_str(&format!(
+            "HTTP 请求失败: {} {}",
+            response.status(),
+            response.status_text()
+        )));
+    }
+    let body = response.body().ok_or_else(|| JsValue::from_str("响应没有数据体"))?;
+    StreamDecoder::new(None, on_progress, None, None).consume(body, signal).await
+}
+
+// 从 Blob 读取数据的分片大小
+const BLOB_SLICE_SIZE: f64 = 1024.0 * 1024.0;
+
+// 解码 File/Blob (例如 <input type=file> 打开的本地 .fastdog 文件)。
+// 按分片读取并逐片解码，JS 侧无需先读出完整的 ArrayBuffer；on_progress 与 decode_from_response 相同。
+// Promise 解析为最终的 StreamDecodeResult，读取文件出错时 Promise 拒绝。
+// signal 触发后在下一个分片之前中止，返回 Aborted 错误码
+#[wasm_bindgen(unchecked_return_type = "StreamDecodeResult")]
+pub async fn decode_from_blob(
+    blob: Blob,
+    #[wasm_bindgen(unchecked_param_type = "StreamResultCallback | null")] on_progress: Option<js_sys::Function>,
+    signal: Option<AbortSignal>,
+) -> Result<JsValue, JsValue> {
+    let mut decoder = StreamDecoder::new(None, on_progress, None, None);
+    let size = blob.size();
+    let mut offset = 0.0;
+
+    while offset < size {
+        if let Err(e) = abort::check(signal.as_ref()) {
+            return Ok(decoder.fail(e));
+        }
+        let end = (offset + BLOB_SLICE_SIZE).min(size);
+        let buffer = JsFuture::from(blob.slice_with_f64_and_f64(offset, end)?.array_buffer()).await?;
+        let result = decoder.add_chunk(&js_sys::Uint8Array::new(&buffer).to_vec());
+        if decoder.completed || decoder.failed {
+            return Ok(result);
+        }
+        offset = end;
+    }
+
+    Ok(decoder.end_of_input())
+}
+
+// 解码 TransformStream 的内部状态: 输入 FastDog 容器字节，输出解压后的载荷字节
+#[derive(Default)]
+struct DecodeTransform {
+    header_buffer: Vec<u8>,
+    header: Option<ContainerHeader>,
+    inflater: Option<StreamInflater>,
+    compressed_received: usize,
+    trailer: Vec<u8>,
+    // 已输出的字节数和 CRC32，用于结束时校验 (载荷可能超过 4GB)
+    output_len: u64,
+    hasher: crc32fast::Hasher,
+}
+
+impl DecodeTransform {
+    // 处理一个输入块，返回目前可以输出的解压数据
+    fn transform(&mut self, chunk: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        if self.header.is_some() {
+            self.feed(chunk)?;
+        } else {
+            self.header_buffer.extend_from_slice(chunk);
+            if !header_available(&self.header_buffer) {
+                return Ok(Vec::new());
+            }
+            let header = parse_header_fields(&self.header_buffer).map_err(|e| e.context("头部解析失败"))?;
+            let buffered = std::mem::take(&mut self.header_buffer);
+            let payload_offset = header.payload_offset;
+            self.inflater = Some(open_stream_inflater(&header)?);
+            self.header = Some(header);
+            self.feed(ByteReader::new(&buffered, "容器头部").rest_from(payload_offset)?)?;
+        }
+        Ok(self.take_output())
+    }
+
+    // 压缩数据送入解压器，之后的 4 或 8 字节作为原始长度字段 (尾部帧格式为尾部)，多余的数据忽略
+    fn feed(&mut self, bytes: &[u8]) -> Result<(), DecodeError> {
+        if let Some(header) = self.header.as_mut().filter(|header| header.footer) {
+            let compressed = split_footer_stream(header, &mut self.trailer, self.compressed_received, bytes);
+            if let Some(inflater) = self.inflater.as_mut() {
+                bound_stream_output(header, &self.trailer, inflater)?;
+                inflater.write(&compressed)?;
+            }
+            self.compressed_received += compressed.len();
+            return Ok(());
+        }
+
+        // 原始长度字段先于压缩数据写入解压器，同一数据块中到达时按声明的长度限制输出
+        let compressed_len = self.header.as_ref().map_or(0, |header| header.compressed_len);
+        let take = compressed_len.saturating_sub(self.compressed_received).min(bytes.len());
+        let rest = &bytes[take..];
+        let trailer_len = self.header.as_ref().map_or(0, ContainerHeader::trailer_len);
+        let need = trailer_len.saturating_sub(self.trailer.len()).min(rest.len());
+        self.trailer.extend_from_slice(&rest[..need]);
+
+        if let (Some(header), Some(inflater)) = (self.header.as_ref(), self.inflater.as_mut()) {
+            bound_stream_output(header, &self.trailer, inflater)?;
+            inflater.write(&bytes[..take])?;
+        }
+        self.compressed_received += take;
+        Ok(())
+    }
+
+    fn take_output(&mut self) -> Vec<u8> {
+        let output = self.inflater.as_mut().map(|inflater| inflater.take_output()).unwrap_or_default();
+        self.record(&output);
+        output
+    }
+
+    fn record(&mut self, output: &[u8]) {
+        self.output_len += output.len() as u64;
+        self.hasher.update(output);
+    }
+
+    // 输入结束: 返回剩余输出并校验长度和 CRC32
+    fn flush(&mut self) -> Result<Vec<u8>, DecodeError> {
+        let (Some(header), Some(inflater)) = (self.header.take(), self.inflater.take()) else {
+            return Err(DecodeError::new(DecodeErrorCode::Truncated, "数据流在头部完整之前结束"));
+        };
+        if header.footer && self.trailer.len() < header.trailer_len() {
+            return Err(DecodeError::new(DecodeErrorCode::Truncated, "数据流在容器尾部之前结束"));
+        }
+        if self.compressed_received < header.compressed_len || self.trailer.len() < header.trailer_len() {
+            return Err(DecodeError::new(
+                DecodeErrorCode::Truncated,
+                format!(
+                    "数据流在解码完成前结束: 已接收 {} 字节, 预期 {} 字节",
+                    header.payload_offset + self.compressed_received + self.trailer.len(),
+                    header.total_len()
+                ),
+            ));
+        }
+
+        // 只有尚未输出的部分需要放入内存
+        let original_len = header.read_original_len(&self.trailer)?;
+        let output = inflater.finish(payload_len(original_len.saturating_sub(self.output_len))?)?;
+        self.record(&output);
+
+        if self.output_len != original_len {
+            return Err(DecodeError::new(
+                DecodeErrorCode::LengthMismatch,
+                format!("解压后数据长度不匹配: 期望 {}, 实际 {}", original_len, self.output_len),
+            ));
+        }
+        if let Some(expected) = header.crc32 {
+            let actual = std::mem::take(&mut self.hasher).finalize();
+            if actual != expected {
+                return Err(DecodeError::new(
+                    DecodeErrorCode::ChecksumMismatch,
+                    format!("CRC32 校验失败: 期望 {:08x}, 实际 {:08x}", expected, actual),
+                ));
+            }
+        }
+        Ok(output)
+    }
+}
+
+// 创建解码 TransformStream，可直接接在 fetch 响应后面:
+// `resp.body.pipeThrough(create_decode_transform())`。
+// 下游在数据到达后即可收到解压后的载荷字节 (Uint8Array)；数据不完整、解压失败或
+// 长度/CRC32 校验失败时数据流以错误结束。注意校验在流结束时才完成，下游应等待流正常关闭
+#[wasm_bindgen]
+pub fn create_decode_transform() -> Result<TransformStream, JsValue> {
+    let state = Rc::new(RefCell::new(DecodeTransform::default()));
+
+    let transform_state = state.clone();
+    let transform = Closure::<dyn FnMut(JsValue, TransformStreamDefaultController) -> Result<(), JsValue>>::new(
+        move |chunk: JsValue, controller: TransformStreamDefaultController| {
+            let chunk = js_sys::Uint8Array::new(&chunk).to_vec();
+            let output = transform_state.borrow_mut().transform(&chunk)?;
+            enqueue(&controller, &output)
+        },
+    );
+    let flush = Closure::<dyn FnMut(TransformStreamDefaultController) -> Result<(), JsValue>>::new(
+        move |controller: TransformStreamDefaultController| {
+            let output = state.borrow_mut().flush()?;
+            enqueue(&controller, &output)
+        },
+    );
+
+    // 闭包交给 JS 垃圾回收管理，随 TransformStream 一起释放
+    let transformer = js_sys::Object::new();
+    js_sys::Reflect::set(&transformer, &"transform".into(), &transform.into_js_value())?;
+    js_sys::Reflect::set(&transformer, &"flush".into(), &flush.into_js_value())?;
+    TransformStream::new_with_transformer(&transformer)
+}
+
+fn enqueue(controller: &TransformStreamDefaultController, output: &[u8]) -> Result<(), JsValue> {
+    if output.is_empty() {
+        return Ok(());
+    }
+    controller.enqueue_with_chunk(&js_sys::Uint8Array::from(output))
+}